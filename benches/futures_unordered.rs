@@ -4,8 +4,8 @@ extern crate futures;
 extern crate test;
 
 use futures::*;
-use futures::stream::FuturesUnordered;
-use futures::sync::oneshot;
+use futures::stream::{FuturesUnordered, StreamUnordered};
+use futures::sync::{mpsc, oneshot};
 
 use test::Bencher;
 
@@ -41,3 +41,35 @@ fn oneshots(b: &mut Bencher) {
         }).wait().unwrap();
     });
 }
+
+#[bench]
+fn streams(b: &mut Bencher) {
+    const NUM: usize = 10_000;
+
+    b.iter(|| {
+        let mut txs = Vec::with_capacity(NUM);
+        let mut rxs = StreamUnordered::new();
+
+        for _ in 0..NUM {
+            let (tx, rx) = mpsc::unbounded();
+            txs.push(tx);
+            rxs.push(rx);
+        }
+
+        thread::spawn(move || {
+            for _ in 0..10 {
+                for tx in &txs {
+                    let _ = tx.unbounded_send("hello");
+                }
+            }
+        });
+
+        future::lazy(move || {
+            loop {
+                if let Ok(Async::Ready(None)) = rxs.poll() {
+                    return Ok::<(), ()>(());
+                }
+            }
+        }).wait().unwrap();
+    });
+}
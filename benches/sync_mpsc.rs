@@ -15,6 +15,8 @@ use futures::sync::mpsc::channel;
 use futures::sync::mpsc::Sender;
 use futures::sync::mpsc::UnboundedSender;
 
+use std::thread;
+
 
 use test::Bencher;
 
@@ -89,6 +91,48 @@ fn unbounded_uncontended(b: &mut Bencher) {
     })
 }
 
+/// Fill the channel, then drain it, so that the receiver walks through many
+/// queued messages in a row
+#[bench]
+fn unbounded_drain(b: &mut Bencher) {
+    b.iter(|| {
+        let (tx, mut rx) = unbounded();
+
+        for i in 0..1000 {
+            UnboundedSender::send(&tx, i).expect("send");
+        }
+
+        for i in 0..1000 {
+            assert_eq!(Ok(Async::Ready(Some(i))), rx.poll());
+        }
+    })
+}
+
+/// 4 producer threads, single consumer draining everything they send
+#[bench]
+fn unbounded_4_threads_drain(b: &mut Bencher) {
+    b.iter(|| {
+        let (tx, rx) = unbounded::<u32>();
+
+        let threads: Vec<_> = (0..4).map(|_| {
+            let tx = tx.clone();
+            thread::spawn(move || {
+                for i in 0..250 {
+                    UnboundedSender::send(&tx, i).expect("send");
+                }
+            })
+        }).collect();
+        drop(tx);
+
+        // 1000 messages in total, result should be divided by 1000
+        assert_eq!(rx.wait().count(), 1000);
+
+        for thread in threads {
+            thread.join().unwrap();
+        }
+    })
+}
+
 
 /// A Stream that continuously sends incrementing number of the queue
 struct TestSender {
@@ -39,6 +39,7 @@
 extern crate futures;
 extern crate num_cpus;
 
+use std::cell::RefCell;
 use std::panic::{self, AssertUnwindSafe};
 use std::sync::{Arc, Mutex};
 use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
@@ -82,6 +83,7 @@ pub struct Builder {
     name_prefix: Option<String>,
     after_start: Option<Arc<Fn() + Send + Sync>>,
     before_stop: Option<Arc<Fn() + Send + Sync>>,
+    lifo_slot: bool,
 }
 
 struct MySender<F, T> {
@@ -100,8 +102,23 @@ struct Inner {
     size: usize,
     after_start: Option<Arc<Fn() + Send + Sync>>,
     before_stop: Option<Arc<Fn() + Send + Sync>>,
+    lifo_slot: bool,
 }
 
+// The number of tasks a worker runs in a row from its LIFO slot before taking
+// the next one from the shared queue, so that two tasks waking each other up
+// can't starve the rest of the pool.
+const MAX_LIFO_RUNS: usize = 3;
+
+// The LIFO slot of the pool worker running on this thread, if any.
+struct Worker {
+    pool: *const Inner,
+    slot: Option<Run>,
+    runs: usize,
+}
+
+thread_local!(static WORKER: RefCell<Option<Worker>> = RefCell::new(None));
+
 impl fmt::Debug for CpuPool {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         f.debug_struct("CpuPool")
@@ -240,16 +257,67 @@ impl Inner {
     }
 
     fn work(&self) {
+        // Blocking on a future from within a task would tie up the worker,
+        // so flag this thread as running an executor for `Future::wait`.
+        let _enter = executor::enter().unwrap();
         self.after_start.as_ref().map(|fun| fun());
+        if self.lifo_slot {
+            WORKER.with(|w| {
+                *w.borrow_mut() = Some(Worker {
+                    pool: self,
+                    slot: None,
+                    runs: 0,
+                });
+            });
+        }
         loop {
-            let msg = self.rx.lock().unwrap().recv().unwrap();
+            let msg = match self.take_lifo() {
+                Some(r) => Message::Run(r),
+                None => self.rx.lock().unwrap().recv().unwrap(),
+            };
             match msg {
                 Message::Run(r) => r.run(),
                 Message::Close => break,
             }
         }
+        WORKER.with(|w| *w.borrow_mut() = None);
         self.before_stop.as_ref().map(|fun| fun());
     }
+
+    // Takes the task in the LIFO slot of this worker, unless it has run too
+    // many tasks from there in a row, in which case the task is moved to the
+    // back of the shared queue instead.
+    fn take_lifo(&self) -> Option<Run> {
+        let run = WORKER.with(|w| {
+            let mut w = w.borrow_mut();
+            let w = match *w {
+                Some(ref mut w) => w,
+                None => return None,
+            };
+            match w.slot.take() {
+                Some(run) if w.runs < MAX_LIFO_RUNS => {
+                    w.runs += 1;
+                    Some(Ok(run))
+                }
+                Some(run) => {
+                    w.runs = 0;
+                    Some(Err(run))
+                }
+                None => {
+                    w.runs = 0;
+                    None
+                }
+            }
+        });
+        match run {
+            Some(Ok(run)) => Some(run),
+            Some(Err(run)) => {
+                self.send(Message::Run(run));
+                None
+            }
+            None => None,
+        }
+    }
 }
 
 impl Clone for CpuPool {
@@ -271,7 +339,18 @@ impl Drop for CpuPool {
 
 impl OldExecutor for Inner {
     fn execute(&self, run: Run) {
-        self.send(Message::Run(run))
+        // Tasks woken up by a worker of this pool go into its LIFO slot,
+        // pushing out the task which was there.
+        let me: *const Inner = self;
+        let run = WORKER.with(|w| {
+            match *w.borrow_mut() {
+                Some(ref mut w) if w.pool == me => w.slot.replace(run),
+                _ => Some(run),
+            }
+        });
+        if let Some(run) = run {
+            self.send(Message::Run(run))
+        }
     }
 }
 
@@ -334,6 +413,7 @@ impl Builder {
             name_prefix: None,
             after_start: None,
             before_stop: None,
+            lifo_slot: false,
         }
     }
 
@@ -375,6 +455,25 @@ impl Builder {
         self
     }
 
+    /// Set whether the workers of a future CpuPool have a LIFO slot
+    ///
+    /// With the LIFO slot, which is disabled by default, a task woken up by
+    /// a worker thread is the next one run on that thread rather than going
+    /// to the back of the shared queue. This improves cache locality and
+    /// latency when tasks pass messages back and forth, at the expense of
+    /// fairness: a worker may run a few tasks from its slot before getting
+    /// back to the tasks which have been waiting in the queue.
+    ///
+    /// The slot also can't be taken over by other workers. A task waiting in
+    /// it runs only once the task running on its worker is done, even while
+    /// the other workers are idle. This suits pools of short tasks waking
+    /// each other up, but hurts pools running long CPU-bound closures, which
+    /// is why it has to be enabled explicitly.
+    pub fn lifo_slot(&mut self, enabled: bool) -> &mut Self {
+        self.lifo_slot = enabled;
+        self
+    }
+
     /// Create CpuPool with configured parameters
     ///
     /// # Panics
@@ -390,6 +489,7 @@ impl Builder {
                 size: self.pool_size,
                 after_start: self.after_start.clone(),
                 before_stop: self.before_stop.clone(),
+                lifo_slot: self.lifo_slot,
             }),
         };
         assert!(self.pool_size > 0);
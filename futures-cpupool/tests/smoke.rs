@@ -1,11 +1,13 @@
 extern crate futures;
 extern crate futures_cpupool;
 
+use std::sync::{mpsc, Arc, Mutex};
 use std::sync::atomic::{AtomicUsize, Ordering, ATOMIC_USIZE_INIT};
 use std::thread;
 use std::time::Duration;
 
-use futures::future::{Future, BoxFuture};
+use futures::future::{self, Future, BoxFuture};
+use futures::sync::oneshot;
 use futures_cpupool::{CpuPool, Builder};
 
 fn done<T: Send + 'static>(t: T) -> BoxFuture<T, ()> {
@@ -108,3 +110,55 @@ fn thread_name() {
     });
     let _ = future.wait();
 }
+
+// Runs a task waking up another one on a single worker while two more tasks
+// are queued, returning the order in which they ran.
+fn wake_order(builder: &mut Builder) -> Vec<&'static str> {
+    let pool = builder.pool_size(1).create();
+    let log = Arc::new(Mutex::new(Vec::new()));
+    let (started_tx, started_rx) = mpsc::channel();
+    let (gate_tx, gate_rx) = mpsc::channel::<()>();
+    let (wake_tx, wake_rx) = oneshot::channel::<()>();
+
+    let log2 = log.clone();
+    let woken = pool.spawn(future::lazy(move || {
+        started_tx.send(()).unwrap();
+        wake_rx.map(move |()| log2.lock().unwrap().push("woken"))
+    }));
+    started_rx.recv().unwrap();
+
+    let log2 = log.clone();
+    let waker = pool.spawn_fn(move || {
+        gate_rx.recv().unwrap();
+        wake_tx.send(()).unwrap();
+        log2.lock().unwrap().push("waker");
+        Ok::<(), oneshot::Canceled>(())
+    });
+    let queued = ["a", "b"].iter().map(|&name| {
+        let log = log.clone();
+        pool.spawn_fn(move || {
+            log.lock().unwrap().push(name);
+            Ok::<(), oneshot::Canceled>(())
+        })
+    }).collect::<Vec<_>>();
+
+    gate_tx.send(()).unwrap();
+    woken.join(waker).join(future::join_all(queued)).wait().unwrap();
+    let log = log.lock().unwrap().clone();
+    log
+}
+
+#[test]
+fn lifo_slot() {
+    assert_eq!(wake_order(Builder::new().lifo_slot(true)), ["waker", "woken", "a", "b"]);
+    assert_eq!(wake_order(Builder::new().lifo_slot(false)), ["waker", "a", "b", "woken"]);
+    // Disabled by default.
+    assert_eq!(wake_order(&mut Builder::new()), ["waker", "a", "b", "woken"]);
+}
+
+#[test]
+fn workers_are_entered() {
+    let pool = CpuPool::new(1);
+    let entered = pool.spawn_fn(|| Ok::<_, ()>(futures::executor::enter().is_err()));
+    assert_eq!(entered.wait(), Ok(true));
+}
@@ -0,0 +1,135 @@
+use std::fmt;
+
+use {Poll, Sink, StartSend, Stream};
+use codec::{Decoder, Encoder};
+use codec::framed_read::{self, ReadState};
+use codec::framed_write::{self, WriteState};
+use io::{AsyncRead, AsyncWrite};
+
+/// A `Stream` and `Sink` of frames over an object which is both an
+/// `AsyncRead` and an `AsyncWrite`.
+///
+/// This combines `FramedRead` and `FramedWrite` with a single codec, which
+/// is both a `Decoder` and an `Encoder`. The halves can be used separately
+/// through `Stream::split`.
+///
+/// # Examples
+///
+/// ```
+/// use std::io::Cursor;
+///
+/// use futures::prelude::*;
+/// use futures::codec::{Framed, LinesCodec};
+///
+/// let framed = Framed::new(Cursor::new(Vec::new()), LinesCodec::new());
+/// let framed = framed.send("ping".to_string()).wait().unwrap();
+/// assert_eq!(framed.get_ref().get_ref(), b"ping\n");
+/// ```
+#[must_use = "streams do nothing unless polled"]
+pub struct Framed<T, U> {
+    inner: T,
+    codec: U,
+    read: ReadState,
+    write: WriteState,
+}
+
+impl<T, U> Framed<T, U>
+    where T: AsyncRead + AsyncWrite,
+          U: Decoder + Encoder,
+{
+    /// Creates a new stream and sink of frames over `inner`, using `codec`
+    /// for both decoding and encoding.
+    pub fn new(inner: T, codec: U) -> Framed<T, U> {
+        Framed {
+            inner: inner,
+            codec: codec,
+            read: ReadState::new(),
+            write: WriteState::new(),
+        }
+    }
+}
+
+impl<T, U> Framed<T, U> {
+    /// Returns a reference to the underlying object.
+    pub fn get_ref(&self) -> &T {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the underlying object.
+    ///
+    /// Reading from or writing to it directly would likely corrupt the
+    /// frames.
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self.inner
+    }
+
+    /// Consumes this stream and sink, returning the underlying object.
+    ///
+    /// Buffered bytes which were not decoded or written yet are lost.
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+
+    /// Returns a reference to the codec.
+    pub fn codec(&self) -> &U {
+        &self.codec
+    }
+
+    /// Returns a mutable reference to the codec.
+    pub fn codec_mut(&mut self) -> &mut U {
+        &mut self.codec
+    }
+
+    /// Returns the bytes which were read but not decoded yet.
+    pub fn read_buffer(&self) -> &[u8] {
+        self.read.buffer()
+    }
+
+    /// Returns the bytes which were encoded but not written yet.
+    pub fn write_buffer(&self) -> &[u8] {
+        self.write.buffer()
+    }
+}
+
+impl<T, U> Stream for Framed<T, U>
+    where T: AsyncRead,
+          U: Decoder,
+{
+    type Item = U::Item;
+    type Error = U::Error;
+
+    fn poll(&mut self) -> Poll<Option<U::Item>, U::Error> {
+        framed_read::poll_frame(&mut self.inner, &mut self.codec, &mut self.read)
+    }
+}
+
+impl<T, U> Sink for Framed<T, U>
+    where T: AsyncWrite,
+          U: Encoder,
+{
+    type SinkItem = U::Item;
+    type SinkError = U::Error;
+
+    fn start_send(&mut self, item: U::Item) -> StartSend<U::Item, U::Error> {
+        framed_write::start_send(&mut self.inner, &mut self.codec, &mut self.write, item)
+    }
+
+    fn poll_complete(&mut self) -> Poll<(), U::Error> {
+        framed_write::poll_complete::<T, U>(&mut self.inner, &mut self.write)
+    }
+
+    fn close(&mut self) -> Poll<(), U::Error> {
+        framed_write::close::<T, U>(&mut self.inner, &mut self.write)
+    }
+}
+
+impl<T: fmt::Debug, U: fmt::Debug> fmt::Debug for Framed<T, U> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("Framed")
+            .field("inner", &self.inner)
+            .field("codec", &self.codec)
+            .field("read_buffered", &self.read.buffer().len())
+            .field("write_buffered", &self.write.buffer().len())
+            .finish()
+    }
+}
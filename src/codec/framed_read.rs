@@ -0,0 +1,166 @@
+use std::fmt;
+use std::prelude::v1::*;
+
+use {Async, Poll, Stream};
+use codec::Decoder;
+use io::AsyncRead;
+
+// How many more bytes are read into the buffer at a time.
+const READ_SIZE: usize = 8 * 1024;
+
+/// A `Stream` of frames decoded from an `AsyncRead`.
+///
+/// Bytes are read from the underlying object into a buffer, out of which
+/// the `Decoder` parses frames. Errors of the underlying object are
+/// converted into the errors of the decoder. The stream ends once the end
+/// of the underlying object is reached and `Decoder::decode_eof` doesn't
+/// produce any more frames.
+///
+/// # Examples
+///
+/// ```
+/// use futures::prelude::*;
+/// use futures::codec::{FramedRead, LinesCodec};
+///
+/// let lines = FramedRead::new(&b"hello\nworld\n"[..], LinesCodec::new());
+/// assert_eq!(lines.collect().wait().unwrap(), ["hello", "world"]);
+/// ```
+#[must_use = "streams do nothing unless polled"]
+pub struct FramedRead<T, D> {
+    inner: T,
+    decoder: D,
+    state: ReadState,
+}
+
+pub struct ReadState {
+    buf: Vec<u8>,
+    eof: bool,
+    is_readable: bool,
+}
+
+impl ReadState {
+    pub fn new() -> ReadState {
+        ReadState {
+            buf: Vec::new(),
+            eof: false,
+            is_readable: false,
+        }
+    }
+
+    pub fn buffer(&self) -> &[u8] {
+        &self.buf
+    }
+}
+
+// Drives the reading half of `FramedRead` and `Framed`.
+pub fn poll_frame<T, D>(inner: &mut T, decoder: &mut D, state: &mut ReadState)
+    -> Poll<Option<D::Item>, D::Error>
+    where T: AsyncRead,
+          D: Decoder,
+{
+    loop {
+        // Frames are decoded out of whatever is buffered before reading
+        // anything more.
+        if state.is_readable {
+            if state.eof {
+                return Ok(Async::Ready(decoder.decode_eof(&mut state.buf)?))
+            }
+            if let Some(frame) = decoder.decode(&mut state.buf)? {
+                return Ok(Async::Ready(Some(frame)))
+            }
+            state.is_readable = false;
+        }
+
+        let len = state.buf.len();
+        state.buf.resize(len + READ_SIZE, 0);
+        let n = match inner.poll_read(&mut state.buf[len..]) {
+            Ok(Async::Ready(n)) => n,
+            Ok(Async::NotReady) => {
+                state.buf.truncate(len);
+                return Ok(Async::NotReady)
+            }
+            Err(e) => {
+                state.buf.truncate(len);
+                return Err(e.into())
+            }
+        };
+        state.buf.truncate(len + n);
+        if n == 0 {
+            state.eof = true;
+        }
+        state.is_readable = true;
+    }
+}
+
+impl<T, D> FramedRead<T, D>
+    where T: AsyncRead,
+          D: Decoder,
+{
+    /// Creates a new stream decoding the bytes of `inner` with `decoder`.
+    pub fn new(inner: T, decoder: D) -> FramedRead<T, D> {
+        FramedRead {
+            inner: inner,
+            decoder: decoder,
+            state: ReadState::new(),
+        }
+    }
+}
+
+impl<T, D> FramedRead<T, D> {
+    /// Returns a reference to the underlying object.
+    pub fn get_ref(&self) -> &T {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the underlying object.
+    ///
+    /// Reading from it directly would likely corrupt the stream of frames.
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self.inner
+    }
+
+    /// Consumes this stream, returning the underlying object.
+    ///
+    /// Bytes which were read but not decoded yet are lost.
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+
+    /// Returns a reference to the decoder.
+    pub fn decoder(&self) -> &D {
+        &self.decoder
+    }
+
+    /// Returns a mutable reference to the decoder.
+    pub fn decoder_mut(&mut self) -> &mut D {
+        &mut self.decoder
+    }
+
+    /// Returns the bytes which were read but not decoded yet.
+    pub fn read_buffer(&self) -> &[u8] {
+        self.state.buffer()
+    }
+}
+
+impl<T, D> Stream for FramedRead<T, D>
+    where T: AsyncRead,
+          D: Decoder,
+{
+    type Item = D::Item;
+    type Error = D::Error;
+
+    fn poll(&mut self) -> Poll<Option<D::Item>, D::Error> {
+        poll_frame(&mut self.inner, &mut self.decoder, &mut self.state)
+    }
+}
+
+impl<T: fmt::Debug, D: fmt::Debug> fmt::Debug for FramedRead<T, D> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("FramedRead")
+            .field("inner", &self.inner)
+            .field("decoder", &self.decoder)
+            .field("buffered", &self.state.buf.len())
+            .field("eof", &self.state.eof)
+            .finish()
+    }
+}
@@ -0,0 +1,171 @@
+use std::fmt;
+use std::io;
+use std::prelude::v1::*;
+
+use {Async, AsyncSink, Poll, Sink, StartSend};
+use codec::Encoder;
+use io::AsyncWrite;
+
+// How many encoded bytes are buffered before `start_send` tries to write
+// them out.
+const BACKPRESSURE_BOUNDARY: usize = 8 * 1024;
+
+/// A `Sink` of frames encoded into an `AsyncWrite`.
+///
+/// Frames are encoded by the `Encoder` into a buffer, which is written to
+/// the underlying object when the sink is flushed, or when it gets too
+/// large. Errors of the underlying object are converted into the errors of
+/// the encoder. Closing the sink flushes it and then shuts down the
+/// underlying object.
+///
+/// # Examples
+///
+/// ```
+/// use futures::prelude::*;
+/// use futures::codec::{FramedWrite, LinesCodec};
+///
+/// let sink = FramedWrite::new(Vec::new(), LinesCodec::new());
+/// let sink = sink.send("hello".to_string()).wait().unwrap();
+/// assert_eq!(sink.get_ref(), b"hello\n");
+/// ```
+#[must_use = "sinks do nothing unless polled"]
+pub struct FramedWrite<T, E> {
+    inner: T,
+    encoder: E,
+    state: WriteState,
+}
+
+pub struct WriteState {
+    buf: Vec<u8>,
+}
+
+impl WriteState {
+    pub fn new() -> WriteState {
+        WriteState { buf: Vec::new() }
+    }
+
+    pub fn buffer(&self) -> &[u8] {
+        &self.buf
+    }
+}
+
+// Drives the writing half of `FramedWrite` and `Framed`.
+pub fn start_send<T, E>(inner: &mut T, encoder: &mut E, state: &mut WriteState, item: E::Item)
+    -> StartSend<E::Item, E::Error>
+    where T: AsyncWrite,
+          E: Encoder,
+{
+    // Try to make room before buffering even more bytes.
+    if state.buf.len() >= BACKPRESSURE_BOUNDARY {
+        poll_complete::<T, E>(inner, state)?;
+        if state.buf.len() >= BACKPRESSURE_BOUNDARY {
+            return Ok(AsyncSink::NotReady(item))
+        }
+    }
+    encoder.encode(item, &mut state.buf)?;
+    Ok(AsyncSink::Ready)
+}
+
+pub fn poll_complete<T, E>(inner: &mut T, state: &mut WriteState) -> Poll<(), E::Error>
+    where T: AsyncWrite,
+          E: Encoder,
+{
+    while !state.buf.is_empty() {
+        let n = try_ready!(inner.poll_write(&state.buf));
+        if n == 0 {
+            return Err(io::Error::new(io::ErrorKind::WriteZero,
+                                      "failed to write frame to transport").into())
+        }
+        state.buf.drain(..n);
+    }
+    try_ready!(inner.poll_flush());
+    Ok(Async::Ready(()))
+}
+
+pub fn close<T, E>(inner: &mut T, state: &mut WriteState) -> Poll<(), E::Error>
+    where T: AsyncWrite,
+          E: Encoder,
+{
+    try_ready!(poll_complete::<T, E>(inner, state));
+    Ok(inner.poll_shutdown()?)
+}
+
+impl<T, E> FramedWrite<T, E>
+    where T: AsyncWrite,
+          E: Encoder,
+{
+    /// Creates a new sink encoding frames into `inner` with `encoder`.
+    pub fn new(inner: T, encoder: E) -> FramedWrite<T, E> {
+        FramedWrite {
+            inner: inner,
+            encoder: encoder,
+            state: WriteState::new(),
+        }
+    }
+}
+
+impl<T, E> FramedWrite<T, E> {
+    /// Returns a reference to the underlying object.
+    pub fn get_ref(&self) -> &T {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the underlying object.
+    ///
+    /// Writing to it directly would likely corrupt the stream of frames.
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self.inner
+    }
+
+    /// Consumes this sink, returning the underlying object.
+    ///
+    /// Bytes which were encoded but not written yet are lost.
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+
+    /// Returns a reference to the encoder.
+    pub fn encoder(&self) -> &E {
+        &self.encoder
+    }
+
+    /// Returns a mutable reference to the encoder.
+    pub fn encoder_mut(&mut self) -> &mut E {
+        &mut self.encoder
+    }
+
+    /// Returns the bytes which were encoded but not written yet.
+    pub fn write_buffer(&self) -> &[u8] {
+        self.state.buffer()
+    }
+}
+
+impl<T, E> Sink for FramedWrite<T, E>
+    where T: AsyncWrite,
+          E: Encoder,
+{
+    type SinkItem = E::Item;
+    type SinkError = E::Error;
+
+    fn start_send(&mut self, item: E::Item) -> StartSend<E::Item, E::Error> {
+        start_send(&mut self.inner, &mut self.encoder, &mut self.state, item)
+    }
+
+    fn poll_complete(&mut self) -> Poll<(), E::Error> {
+        poll_complete::<T, E>(&mut self.inner, &mut self.state)
+    }
+
+    fn close(&mut self) -> Poll<(), E::Error> {
+        close::<T, E>(&mut self.inner, &mut self.state)
+    }
+}
+
+impl<T: fmt::Debug, E: fmt::Debug> fmt::Debug for FramedWrite<T, E> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("FramedWrite")
+            .field("inner", &self.inner)
+            .field("encoder", &self.encoder)
+            .field("buffered", &self.state.buf.len())
+            .finish()
+    }
+}
@@ -0,0 +1,80 @@
+use std::io;
+use std::prelude::v1::*;
+
+use codec::{Decoder, Encoder};
+
+// The length of a frame is a big endian `u32`.
+const HEADER_LEN: usize = 4;
+
+/// A codec for frames of bytes prefixed by their length.
+///
+/// Every frame starts with its length in bytes, as a big endian 32-bit
+/// unsigned integer, which is followed by that many bytes. Decoded frames
+/// don't include the length.
+#[derive(Clone, Debug)]
+pub struct LengthDelimitedCodec {
+    max_frame_length: usize,
+}
+
+impl LengthDelimitedCodec {
+    /// Creates a new codec with a maximum frame length of 8 MiB.
+    pub fn new() -> LengthDelimitedCodec {
+        LengthDelimitedCodec::with_max_frame_length(8 * 1024 * 1024)
+    }
+
+    /// Creates a new codec failing on frames longer than `max` bytes, not
+    /// counting the length.
+    ///
+    /// The length of a frame is checked before any of its bytes are
+    /// buffered, so this bounds how much memory the decoder uses.
+    pub fn with_max_frame_length(max: usize) -> LengthDelimitedCodec {
+        LengthDelimitedCodec { max_frame_length: max }
+    }
+
+    /// Returns the maximum frame length of this codec.
+    pub fn max_frame_length(&self) -> usize {
+        self.max_frame_length
+    }
+}
+
+fn too_long() -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, "frame too long")
+}
+
+impl Decoder for LengthDelimitedCodec {
+    type Item = Vec<u8>;
+    type Error = io::Error;
+
+    fn decode(&mut self, src: &mut Vec<u8>) -> Result<Option<Vec<u8>>, io::Error> {
+        if src.len() < HEADER_LEN {
+            return Ok(None)
+        }
+        let len = src[..HEADER_LEN].iter().fold(0, |n, b| (n << 8) | *b as usize);
+        if len > self.max_frame_length {
+            return Err(too_long())
+        }
+        if src.len() < HEADER_LEN + len {
+            src.reserve(HEADER_LEN + len - src.len());
+            return Ok(None)
+        }
+        let frame = src[HEADER_LEN..HEADER_LEN + len].to_vec();
+        src.drain(..HEADER_LEN + len);
+        Ok(Some(frame))
+    }
+}
+
+impl Encoder for LengthDelimitedCodec {
+    type Item = Vec<u8>;
+    type Error = io::Error;
+
+    fn encode(&mut self, frame: Vec<u8>, dst: &mut Vec<u8>) -> Result<(), io::Error> {
+        if frame.len() > self.max_frame_length || frame.len() > u32::max_value() as usize {
+            return Err(too_long())
+        }
+        let len = frame.len() as u32;
+        dst.reserve(HEADER_LEN + frame.len());
+        dst.extend_from_slice(&[(len >> 24) as u8, (len >> 16) as u8, (len >> 8) as u8, len as u8]);
+        dst.extend_from_slice(&frame);
+        Ok(())
+    }
+}
@@ -0,0 +1,103 @@
+use std::io;
+use std::mem;
+use std::prelude::v1::*;
+
+use codec::{Decoder, Encoder};
+
+/// A codec for newline separated lines of UTF-8 text.
+///
+/// Decoded lines don't include their `\n`, nor a `\r` right before it. At
+/// the end of the transport, bytes after the last newline make up a last
+/// line. Encoding a line appends a `\n` to it.
+#[derive(Clone, Debug)]
+pub struct LinesCodec {
+    max_length: usize,
+    // How far the buffer has already been searched for a newline.
+    next_index: usize,
+}
+
+impl LinesCodec {
+    /// Creates a new codec with no limit on the length of lines.
+    pub fn new() -> LinesCodec {
+        LinesCodec::with_max_length(usize::max_value())
+    }
+
+    /// Creates a new codec failing on lines longer than `max_length` bytes,
+    /// not counting the newline.
+    ///
+    /// Without a limit, a peer never sending a newline makes the decoder
+    /// buffer everything it sends.
+    pub fn with_max_length(max_length: usize) -> LinesCodec {
+        LinesCodec {
+            max_length: max_length,
+            next_index: 0,
+        }
+    }
+
+    /// Returns the maximum length of the lines of this codec.
+    pub fn max_length(&self) -> usize {
+        self.max_length
+    }
+}
+
+fn too_long() -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, "line too long")
+}
+
+fn to_string(mut line: Vec<u8>) -> Result<String, io::Error> {
+    if line.last() == Some(&b'\r') {
+        line.pop();
+    }
+    String::from_utf8(line).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+impl Decoder for LinesCodec {
+    type Item = String;
+    type Error = io::Error;
+
+    fn decode(&mut self, src: &mut Vec<u8>) -> Result<Option<String>, io::Error> {
+        let start = self.next_index.min(src.len());
+        match src[start..].iter().position(|b| *b == b'\n') {
+            Some(offset) => {
+                let end = start + offset;
+                self.next_index = 0;
+                if end > self.max_length {
+                    return Err(too_long())
+                }
+                let line = src.drain(..end + 1).take(end).collect();
+                to_string(line).map(Some)
+            }
+            None => {
+                if src.len() > self.max_length {
+                    return Err(too_long())
+                }
+                self.next_index = src.len();
+                Ok(None)
+            }
+        }
+    }
+
+    fn decode_eof(&mut self, src: &mut Vec<u8>) -> Result<Option<String>, io::Error> {
+        match self.decode(src)? {
+            Some(line) => Ok(Some(line)),
+            None if src.is_empty() => Ok(None),
+            None => {
+                self.next_index = 0;
+                let line = mem::replace(src, Vec::new());
+                to_string(line).map(Some)
+            }
+        }
+    }
+}
+
+impl Encoder for LinesCodec {
+    type Item = String;
+    type Error = io::Error;
+
+    fn encode(&mut self, line: String, dst: &mut Vec<u8>) -> Result<(), io::Error> {
+        dst.reserve(line.len() + 1);
+        dst.extend_from_slice(line.as_bytes());
+        dst.push(b'\n');
+        Ok(())
+    }
+}
@@ -0,0 +1,77 @@
+//! Conversion between byte transports and streams and sinks of frames.
+//!
+//! An `AsyncRead` or `AsyncWrite` only deals with bytes, while most
+//! protocols deal with messages, or frames. The `Decoder` and `Encoder`
+//! traits describe how to parse frames out of a buffer of bytes and how to
+//! write them into one. Given a codec implementing them, `FramedRead` turns
+//! an `AsyncRead` into a `Stream` of frames, `FramedWrite` turns an
+//! `AsyncWrite` into a `Sink` of frames, and `Framed` does both at once for
+//! an object which is both.
+//!
+//! Two codecs are built in: `LinesCodec` for newline separated text, and
+//! `LengthDelimitedCodec` for frames prefixed by their length.
+//!
+//! This module is only available when the `use_std` feature of this library
+//! is activated, and it is activated by default.
+
+use std::io;
+use std::prelude::v1::*;
+
+mod framed;
+mod framed_read;
+mod framed_write;
+mod length_delimited;
+mod lines;
+
+pub use self::framed::Framed;
+pub use self::framed_read::FramedRead;
+pub use self::framed_write::FramedWrite;
+pub use self::length_delimited::LengthDelimitedCodec;
+pub use self::lines::LinesCodec;
+
+/// Parses frames out of a buffer of bytes.
+pub trait Decoder {
+    /// The type of frames produced by this decoder.
+    type Item;
+
+    /// The type of errors produced by this decoder.
+    ///
+    /// Errors of the underlying transport are converted into it.
+    type Error: From<io::Error>;
+
+    /// Attempts to decode a frame from the start of `src`.
+    ///
+    /// If `src` holds a whole frame then its bytes are removed from `src`
+    /// and the frame is returned. Otherwise this returns `Ok(None)`, and is
+    /// called again once more bytes have been read into `src`.
+    fn decode(&mut self, src: &mut Vec<u8>) -> Result<Option<Self::Item>, Self::Error>;
+
+    /// Attempts to decode a frame once the end of the transport has been
+    /// reached, and no more bytes will be read into `src`.
+    ///
+    /// Returning `Ok(None)` ends the stream of frames. The default
+    /// implementation calls `decode`, and fails if that leaves bytes in
+    /// `src` without producing a frame.
+    fn decode_eof(&mut self, src: &mut Vec<u8>) -> Result<Option<Self::Item>, Self::Error> {
+        match self.decode(src)? {
+            Some(frame) => Ok(Some(frame)),
+            None if src.is_empty() => Ok(None),
+            None => Err(io::Error::new(io::ErrorKind::UnexpectedEof,
+                                       "bytes remaining on stream").into()),
+        }
+    }
+}
+
+/// Writes frames into a buffer of bytes.
+pub trait Encoder {
+    /// The type of frames consumed by this encoder.
+    type Item;
+
+    /// The type of errors produced by this encoder.
+    ///
+    /// Errors of the underlying transport are converted into it.
+    type Error: From<io::Error>;
+
+    /// Appends the bytes of `item` to `dst`.
+    fn encode(&mut self, item: Self::Item, dst: &mut Vec<u8>) -> Result<(), Self::Error>;
+}
@@ -0,0 +1,323 @@
+//! Compatibility with `std::future::Future`.
+//!
+//! This module converts between the futures of this crate, which resolve to
+//! an `Item` or an `Error` and are notified through `task::current`, and the
+//! futures of the standard library, which resolve to an `Output` and are
+//! notified through the `Waker` they're given. This allows migrating a code
+//! base from one to the other piece by piece.
+//!
+//! * `Compat`, created with `CompatExt::compat`, turns a future of this
+//!   crate into a `std::future::Future` resolving to a `Result`. It also
+//!   turns a stream into something with a `poll_next` method.
+//! * `FromStd`, created with `from_std`, turns a `std::future::Future`
+//!   resolving to a `Result` into a future of this crate.
+//! * `StdExecutor` spawns `std::future::Future`s onto any `Executor` of this
+//!   crate.
+//! * The `waker` module bridges between tasks of this crate and wake handles
+//!   made of plain function pointers, as used by `Waker`-style event sources
+//!   and other languages. It re-exports the wake handles of `ffi`.
+//!
+//! This module is only available with the `compat` feature, as it needs a
+//! compiler providing `std::future` and `std::task::Wake`.
+
+use std::fmt;
+use std::future::Future as StdFuture;
+use std::pin::Pin;
+use std::prelude::v1::*;
+use std::sync::Arc;
+use std::task::{Context, Poll as StdPoll, Wake, Waker};
+
+use {Async, Future, Poll, Stream};
+use executor::{self, Notify, Spawn};
+use future::{Executor, ExecuteError};
+use task::{self, Task};
+
+pub mod waker;
+
+/// Extension trait converting futures and streams of this crate into their
+/// `std` counterparts.
+pub trait CompatExt: Sized {
+    /// Wraps this future or stream into a `Compat`, which implements
+    /// `std::future::Future` for futures, and provides `poll_next` for
+    /// streams.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::future::Future;
+    /// use std::pin::Pin;
+    /// use std::sync::Arc;
+    /// use std::task::{Context, Poll, Wake};
+    ///
+    /// use futures::future;
+    /// use futures::compat::CompatExt;
+    ///
+    /// struct Noop;
+    ///
+    /// impl Wake for Noop {
+    ///     fn wake(self: Arc<Self>) {}
+    /// }
+    ///
+    /// let waker = Arc::new(Noop).into();
+    /// let mut cx = Context::from_waker(&waker);
+    /// let mut f = future::ok::<u32, ()>(1).compat();
+    /// assert_eq!(Pin::new(&mut f).poll(&mut cx), Poll::Ready(Ok(1)));
+    /// ```
+    fn compat(self) -> Compat<Self> {
+        Compat {
+            inner: executor::spawn(self),
+            notify: None,
+        }
+    }
+}
+
+impl<T> CompatExt for T {}
+
+/// A future or stream of this crate usable as its `std` counterpart.
+///
+/// This is created by the `CompatExt::compat` method. Polling it polls the
+/// wrapped future or stream within a task of its own, which wakes up the
+/// `Waker` of the last poll when notified.
+#[must_use = "futures do nothing unless polled"]
+pub struct Compat<T> {
+    inner: Spawn<T>,
+    notify: Option<Arc<WakerNotify>>,
+}
+
+// Futures of this crate don't care about being moved around.
+impl<T> Unpin for Compat<T> {}
+
+struct WakerNotify(Waker);
+
+impl Notify for WakerNotify {
+    fn notify(&self, _id: usize) {
+        self.0.wake_by_ref()
+    }
+}
+
+impl<T> Compat<T> {
+    /// Returns a reference to the wrapped future or stream.
+    pub fn get_ref(&self) -> &T {
+        self.inner.get_ref()
+    }
+
+    /// Returns a mutable reference to the wrapped future or stream.
+    pub fn get_mut(&mut self) -> &mut T {
+        self.inner.get_mut()
+    }
+
+    /// Consumes this wrapper, returning the wrapped future or stream.
+    pub fn into_inner(self) -> T {
+        self.inner.into_inner()
+    }
+
+    // Reuses the notifier of the previous poll as long as it wakes up the
+    // same task, to save an allocation per poll.
+    fn notify(&mut self, cx: &Context) -> Arc<WakerNotify> {
+        match self.notify {
+            Some(ref notify) if notify.0.will_wake(cx.waker()) => return notify.clone(),
+            _ => {}
+        }
+        let notify = Arc::new(WakerNotify(cx.waker().clone()));
+        self.notify = Some(notify.clone());
+        notify
+    }
+}
+
+impl<T: Stream> Compat<T> {
+    /// Polls the wrapped stream for its next item, in the way the standard
+    /// library polls futures.
+    ///
+    /// The standard library doesn't have a `Stream` trait, this has the
+    /// shape stream traits built on top of `std::future` commonly use.
+    pub fn poll_next(&mut self, cx: &mut Context) -> StdPoll<Option<Result<T::Item, T::Error>>> {
+        let notify = self.notify(cx);
+        match self.inner.poll_stream_notify(&notify, 0) {
+            Ok(Async::Ready(Some(t))) => StdPoll::Ready(Some(Ok(t))),
+            Ok(Async::Ready(None)) => StdPoll::Ready(None),
+            Ok(Async::NotReady) => StdPoll::Pending,
+            Err(e) => StdPoll::Ready(Some(Err(e))),
+        }
+    }
+}
+
+impl<T: Future> StdFuture for Compat<T> {
+    type Output = Result<T::Item, T::Error>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context) -> StdPoll<Self::Output> {
+        let me = self.get_mut();
+        let notify = me.notify(cx);
+        match me.inner.poll_future_notify(&notify, 0) {
+            Ok(Async::Ready(t)) => StdPoll::Ready(Ok(t)),
+            Ok(Async::NotReady) => StdPoll::Pending,
+            Err(e) => StdPoll::Ready(Err(e)),
+        }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Compat<T> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("Compat")
+            .field("inner", self.inner.get_ref())
+            .finish()
+    }
+}
+
+/// A `std::future::Future` usable as a future of this crate.
+///
+/// This is created by the `from_std` function.
+#[must_use = "futures do nothing unless polled"]
+pub struct FromStd<F> {
+    future: Pin<Box<F>>,
+    waker: Option<(Task, Waker)>,
+}
+
+/// Converts a `std::future::Future` resolving to a `Result` into a future of
+/// this crate.
+///
+/// The future must be polled from within a task, whose handle is passed to
+/// the wrapped future as its `Waker`.
+///
+/// # Examples
+///
+/// ```
+/// use futures::prelude::*;
+/// use futures::compat;
+///
+/// let f = compat::from_std(std::future::ready(Ok::<u32, ()>(1)));
+/// assert_eq!(f.map(|i| i + 1).wait(), Ok(2));
+/// ```
+pub fn from_std<F, T, E>(future: F) -> FromStd<F>
+    where F: StdFuture<Output = Result<T, E>>,
+{
+    FromStd {
+        future: Box::pin(future),
+        waker: None,
+    }
+}
+
+struct TaskWaker(Task);
+
+impl Wake for TaskWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.notify()
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.0.notify()
+    }
+}
+
+// Returns a `Waker` notifying the current task, reusing `cache` if it was
+// created for the same task.
+fn current_waker(cache: &mut Option<(Task, Waker)>) -> Waker {
+    match *cache {
+        Some((ref task, ref waker)) if task.will_notify_current() => return waker.clone(),
+        _ => {}
+    }
+    let task = task::current();
+    let waker = Waker::from(Arc::new(TaskWaker(task.clone())));
+    *cache = Some((task, waker.clone()));
+    waker
+}
+
+impl<F, T, E> Future for FromStd<F>
+    where F: StdFuture<Output = Result<T, E>>,
+{
+    type Item = T;
+    type Error = E;
+
+    fn poll(&mut self) -> Poll<T, E> {
+        let waker = current_waker(&mut self.waker);
+        let mut cx = Context::from_waker(&waker);
+        match self.future.as_mut().poll(&mut cx) {
+            StdPoll::Ready(Ok(t)) => Ok(Async::Ready(t)),
+            StdPoll::Ready(Err(e)) => Err(e),
+            StdPoll::Pending => Ok(Async::NotReady),
+        }
+    }
+}
+
+impl<F> fmt::Debug for FromStd<F> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("FromStd").finish()
+    }
+}
+
+/// An executor spawning `std::future::Future`s onto an executor of this
+/// crate.
+///
+/// # Examples
+///
+/// ```
+/// use futures::compat::StdExecutor;
+/// use futures::executor::LocalPool;
+///
+/// let mut pool = LocalPool::new();
+/// let executor = StdExecutor::new(pool.spawner());
+/// executor.spawn(std::future::ready(())).unwrap();
+/// pool.run();
+/// ```
+#[derive(Clone, Debug)]
+pub struct StdExecutor<E> {
+    inner: E,
+}
+
+/// The task spawned by a `StdExecutor`, running a `std::future::Future`
+/// resolving to `()`.
+#[must_use = "futures do nothing unless polled"]
+pub struct StdTask<F> {
+    future: Pin<Box<F>>,
+    waker: Option<(Task, Waker)>,
+}
+
+impl<E> StdExecutor<E> {
+    /// Creates a new executor spawning futures onto `inner`.
+    pub fn new(inner: E) -> StdExecutor<E> {
+        StdExecutor { inner: inner }
+    }
+
+    /// Spawns `future` onto the underlying executor.
+    ///
+    /// If the executor rejects the task then it's handed back through the
+    /// returned `ExecuteError`.
+    pub fn spawn<F>(&self, future: F) -> Result<(), ExecuteError<StdTask<F>>>
+        where F: StdFuture<Output = ()>,
+              E: Executor<StdTask<F>>,
+    {
+        self.inner.execute(StdTask {
+            future: Box::pin(future),
+            waker: None,
+        })
+    }
+
+    /// Returns a reference to the underlying executor.
+    pub fn get_ref(&self) -> &E {
+        &self.inner
+    }
+
+    /// Consumes this wrapper, returning the underlying executor.
+    pub fn into_inner(self) -> E {
+        self.inner
+    }
+}
+
+impl<F: StdFuture<Output = ()>> Future for StdTask<F> {
+    type Item = ();
+    type Error = ();
+
+    fn poll(&mut self) -> Poll<(), ()> {
+        let waker = current_waker(&mut self.waker);
+        let mut cx = Context::from_waker(&waker);
+        match self.future.as_mut().poll(&mut cx) {
+            StdPoll::Ready(()) => Ok(Async::Ready(())),
+            StdPoll::Pending => Ok(Async::NotReady),
+        }
+    }
+}
+
+impl<F> fmt::Debug for StdTask<F> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("StdTask").finish()
+    }
+}
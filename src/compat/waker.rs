@@ -0,0 +1,9 @@
+//! Wake handles made of plain function pointers.
+//!
+//! The types of this module bridge between tasks of this crate and
+//! `Waker`-style event sources, including ones written in other languages.
+//! They don't depend on `std::future` themselves, and live in the `ffi`
+//! module, which is available without the `compat` feature. They're
+//! re-exported here for code working with the rest of `compat`.
+
+pub use ffi::{RawWakeHandle, WakeHandle, WakeVtable};
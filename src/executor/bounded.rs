@@ -0,0 +1,247 @@
+use std::fmt;
+use std::mem;
+use std::prelude::v1::*;
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use {Async, Future, Poll};
+use future::{Executor, ExecuteError, ExecuteErrorKind};
+use task::{self, Task};
+use super::{ExecutePriority, Priority};
+
+/// An executor wrapper limiting the number of futures it has in flight.
+///
+/// Every future spawned through this wrapper counts against the limit until
+/// it completes or is dropped by the underlying executor. Once the limit is
+/// reached, spawning fails with `ExecuteErrorKind::NoCapacity` and hands the
+/// future back, so that callers can shed load or retry later. Callers which
+/// would rather wait for capacity can do so with `spawn_ready`.
+///
+/// Clones of a `Bounded` share the same limit.
+///
+/// # Examples
+///
+/// ```
+/// use futures::prelude::*;
+/// use futures::future::{self, Executor, ExecuteErrorKind};
+/// use futures::executor::{Bounded, LocalPool};
+///
+/// let mut pool = LocalPool::new();
+/// let bounded = Bounded::new(pool.spawner(), 2);
+///
+/// bounded.execute(future::ok(())).unwrap();
+/// bounded.execute(future::ok(())).unwrap();
+/// let err = bounded.execute(future::ok(())).unwrap_err();
+/// assert_eq!(err.kind(), ExecuteErrorKind::NoCapacity);
+///
+/// pool.run();
+/// assert_eq!(bounded.in_flight(), 0);
+/// bounded.execute(future::ok(())).unwrap();
+/// ```
+#[derive(Clone)]
+pub struct Bounded<E> {
+    inner: E,
+    limit: Arc<Limit>,
+}
+
+/// The task spawned onto the underlying executor of a `Bounded`, releasing
+/// its slot once the future completes or is dropped.
+#[must_use = "futures do nothing unless polled"]
+pub struct BoundedTask<F> {
+    future: F,
+    _slot: Slot,
+}
+
+/// A future resolving once a `Bounded` executor has capacity for another
+/// future.
+///
+/// This is created by the `Bounded::spawn_ready` method.
+#[must_use = "futures do nothing unless polled"]
+pub struct SpawnReady {
+    limit: Arc<Limit>,
+}
+
+struct Limit {
+    in_flight: AtomicUsize,
+    max: usize,
+    waiters: Mutex<Vec<Task>>,
+}
+
+struct Slot {
+    limit: Arc<Limit>,
+}
+
+impl<E> Bounded<E> {
+    /// Creates a new executor spawning futures onto `inner`, with at most
+    /// `max_in_flight` of them in flight at a time.
+    pub fn new(inner: E, max_in_flight: usize) -> Bounded<E> {
+        Bounded {
+            inner: inner,
+            limit: Arc::new(Limit {
+                in_flight: AtomicUsize::new(0),
+                max: max_in_flight,
+                waiters: Mutex::new(Vec::new()),
+            }),
+        }
+    }
+
+    /// Returns the number of futures spawned through this executor which
+    /// haven't completed yet.
+    pub fn in_flight(&self) -> usize {
+        self.limit.in_flight.load(Ordering::SeqCst)
+    }
+
+    /// Returns the maximum number of futures in flight.
+    pub fn max_in_flight(&self) -> usize {
+        self.limit.max
+    }
+
+    /// Returns a future which resolves once this executor has capacity for
+    /// another future.
+    ///
+    /// The capacity isn't reserved, so spawning may still fail if someone
+    /// else spawns first.
+    pub fn spawn_ready(&self) -> SpawnReady {
+        SpawnReady { limit: self.limit.clone() }
+    }
+
+    /// Returns a reference to the underlying executor.
+    pub fn get_ref(&self) -> &E {
+        &self.inner
+    }
+
+    /// Consumes this wrapper, returning the underlying executor.
+    pub fn into_inner(self) -> E {
+        self.inner
+    }
+
+    fn try_spawn<F, G>(&self, future: F, spawn: G) -> Result<(), ExecuteError<F>>
+        where G: FnOnce(BoundedTask<F>) -> Result<(), ExecuteError<BoundedTask<F>>>,
+    {
+        let slot = match acquire(&self.limit) {
+            Some(slot) => slot,
+            None => return Err(ExecuteError::new(ExecuteErrorKind::NoCapacity, future)),
+        };
+        let task = BoundedTask {
+            future: future,
+            _slot: slot,
+        };
+        spawn(task).map_err(|e| {
+            let kind = e.kind();
+            ExecuteError::new(kind, e.into_future().future)
+        })
+    }
+}
+
+impl<E, F> Executor<F> for Bounded<E>
+    where E: Executor<BoundedTask<F>>,
+          F: Future<Item = (), Error = ()>,
+{
+    fn execute(&self, future: F) -> Result<(), ExecuteError<F>> {
+        self.try_spawn(future, |task| self.inner.execute(task))
+    }
+}
+
+impl<E, F> ExecutePriority<F> for Bounded<E>
+    where E: ExecutePriority<BoundedTask<F>>,
+          F: Future<Item = (), Error = ()>,
+{
+    fn execute_priority(&self, future: F, priority: Priority)
+                        -> Result<(), ExecuteError<F>> {
+        self.try_spawn(future, |task| self.inner.execute_priority(task, priority))
+    }
+}
+
+impl<E: fmt::Debug> fmt::Debug for Bounded<E> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("Bounded")
+            .field("inner", &self.inner)
+            .field("in_flight", &self.in_flight())
+            .field("max_in_flight", &self.limit.max)
+            .finish()
+    }
+}
+
+fn acquire(limit: &Arc<Limit>) -> Option<Slot> {
+    let mut cur = limit.in_flight.load(Ordering::SeqCst);
+    loop {
+        if cur >= limit.max {
+            return None
+        }
+        match limit.in_flight.compare_exchange(cur, cur + 1,
+                                               Ordering::SeqCst,
+                                               Ordering::SeqCst) {
+            Ok(_) => return Some(Slot { limit: limit.clone() }),
+            Err(actual) => cur = actual,
+        }
+    }
+}
+
+impl Limit {
+    fn has_capacity(&self) -> bool {
+        self.in_flight.load(Ordering::SeqCst) < self.max
+    }
+}
+
+impl Drop for Slot {
+    fn drop(&mut self) {
+        self.limit.in_flight.fetch_sub(1, Ordering::SeqCst);
+        let waiters = {
+            let mut waiters = self.limit.waiters.lock().unwrap();
+            if waiters.is_empty() {
+                return
+            }
+            mem::replace(&mut *waiters, Vec::new())
+        };
+        for task in waiters {
+            task.notify();
+        }
+    }
+}
+
+impl<F: Future<Item = (), Error = ()>> Future for BoundedTask<F> {
+    type Item = ();
+    type Error = ();
+
+    fn poll(&mut self) -> Poll<(), ()> {
+        self.future.poll()
+    }
+}
+
+impl<F: fmt::Debug> fmt::Debug for BoundedTask<F> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("BoundedTask")
+            .field("future", &self.future)
+            .finish()
+    }
+}
+
+impl Future for SpawnReady {
+    type Item = ();
+    type Error = ();
+
+    fn poll(&mut self) -> Poll<(), ()> {
+        if self.limit.has_capacity() {
+            return Ok(Async::Ready(()))
+        }
+        {
+            let mut waiters = self.limit.waiters.lock().unwrap();
+            if !waiters.iter().any(|t| t.will_notify_current()) {
+                waiters.push(task::current());
+            }
+        }
+
+        // A slot may have been released before the task was registered.
+        if self.limit.has_capacity() {
+            Ok(Async::Ready(()))
+        } else {
+            Ok(Async::NotReady)
+        }
+    }
+}
+
+impl fmt::Debug for SpawnReady {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("SpawnReady").finish()
+    }
+}
@@ -0,0 +1,118 @@
+use std::any::Any;
+use std::fmt;
+use std::prelude::v1::*;
+use std::sync::Arc;
+
+use {Future, Poll};
+use future::{Executor, ExecuteError};
+
+/// A type-erased handle to an executor.
+///
+/// This can spawn any `Send + 'static` future, whatever the type of the
+/// executor behind it, which makes it possible to store "some executor" in
+/// a configuration struct or to pass it around without making everything
+/// generic over the executor type. The futures are boxed on their way to the
+/// executor, which receives them as `BoxedFuture`s.
+///
+/// Cloning the handle is cheap and the clones refer to the same executor.
+/// Futures rejected by the executor are handed back unboxed, just like with
+/// the executor itself.
+///
+/// # Examples
+///
+/// ```
+/// use futures::prelude::*;
+/// use futures::future::{self, Executor};
+/// use futures::executor::BoxExecutor;
+///
+/// struct Config {
+///     executor: BoxExecutor,
+/// }
+///
+/// // An executor simply running the futures it's given to completion.
+/// struct Inline;
+///
+/// impl<F: Future<Item = (), Error = ()>> Executor<F> for Inline {
+///     fn execute(&self, f: F) -> Result<(), future::ExecuteError<F>> {
+///         let _ = f.wait();
+///         Ok(())
+///     }
+/// }
+///
+/// let config = Config { executor: BoxExecutor::new(Inline) };
+/// config.executor.execute(future::lazy(|| Ok(()))).unwrap();
+/// ```
+#[derive(Clone)]
+pub struct BoxExecutor {
+    inner: Arc<Executor<BoxedFuture> + Send + Sync>,
+}
+
+/// A boxed future spawned through a `BoxExecutor`.
+#[must_use = "futures do nothing unless polled"]
+pub struct BoxedFuture {
+    inner: Box<Erased>,
+}
+
+// A future which can be turned back into its original type.
+trait Erased: Send {
+    fn poll(&mut self) -> Poll<(), ()>;
+
+    fn into_any(self: Box<Self>) -> Box<Any>;
+}
+
+impl<F> Erased for F
+    where F: Future<Item = (), Error = ()> + Send + 'static,
+{
+    fn poll(&mut self) -> Poll<(), ()> {
+        Future::poll(self)
+    }
+
+    fn into_any(self: Box<Self>) -> Box<Any> {
+        self
+    }
+}
+
+impl BoxExecutor {
+    /// Erases the type of `executor`.
+    pub fn new<E>(executor: E) -> BoxExecutor
+        where E: Executor<BoxedFuture> + Send + Sync + 'static,
+    {
+        BoxExecutor { inner: Arc::new(executor) }
+    }
+}
+
+impl<F> Executor<F> for BoxExecutor
+    where F: Future<Item = (), Error = ()> + Send + 'static,
+{
+    fn execute(&self, future: F) -> Result<(), ExecuteError<F>> {
+        let boxed = BoxedFuture { inner: Box::new(future) };
+        self.inner.execute(boxed).map_err(|e| {
+            let kind = e.kind();
+            match e.into_future().inner.into_any().downcast::<F>() {
+                Ok(future) => ExecuteError::new(kind, *future),
+                Err(_) => unreachable!(),
+            }
+        })
+    }
+}
+
+impl fmt::Debug for BoxExecutor {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("BoxExecutor").finish()
+    }
+}
+
+impl Future for BoxedFuture {
+    type Item = ();
+    type Error = ();
+
+    fn poll(&mut self) -> Poll<(), ()> {
+        self.inner.poll()
+    }
+}
+
+impl fmt::Debug for BoxedFuture {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("BoxedFuture").finish()
+    }
+}
@@ -0,0 +1,167 @@
+use std::fmt;
+use std::mem;
+use std::prelude::v1::*;
+
+use {Async, Future, Poll, Stream};
+use future::{Executor, ExecuteError, JoinError, JoinHandle, JoinTask, SpawnExt};
+use stream::FuturesUnordered;
+
+/// A group of futures spawned onto executors, whose results are collected
+/// as they complete.
+///
+/// Futures are spawned with `spawn`, which works with any executor able to
+/// run the `JoinTask`s of `SpawnExt::spawn_with_handle`. The set is then a
+/// stream of their results in the order they complete, each of them as the
+/// corresponding `JoinHandle` would resolve. Polling an empty set returns
+/// `Ok(Async::Ready(None))`, and more futures can be spawned at any time.
+///
+/// For shutting down, `abort_all` aborts all of the futures which haven't
+/// completed yet, and `join_all` waits for all of them to finish. Dropping
+/// the set detaches the futures in it, which keep running.
+///
+/// # Examples
+///
+/// ```
+/// use futures::prelude::*;
+/// use futures::future;
+/// use futures::executor::{JoinSet, LocalPool};
+///
+/// let mut pool = LocalPool::new();
+/// let mut set = JoinSet::named("workers");
+/// for i in 0..3 {
+///     set.spawn(&pool.spawner(), future::ok::<_, ()>(i)).unwrap();
+/// }
+///
+/// let results = pool.run_until(set.join_all()).unwrap();
+/// let mut results = results.into_iter().map(Result::unwrap).collect::<Vec<_>>();
+/// results.sort();
+/// assert_eq!(results, [0, 1, 2]);
+/// ```
+#[must_use = "streams do nothing unless polled"]
+pub struct JoinSet<T, E> {
+    name: Option<String>,
+    handles: FuturesUnordered<JoinHandle<T, E>>,
+}
+
+/// A future waiting for all of the futures of a `JoinSet` to finish.
+///
+/// This is created by the `JoinSet::join_all` method.
+#[must_use = "futures do nothing unless polled"]
+pub struct JoinSetAll<T, E> {
+    set: JoinSet<T, E>,
+    results: Vec<Result<T, JoinError<E>>>,
+}
+
+impl<T, E> JoinSet<T, E> {
+    /// Creates a new, empty set.
+    pub fn new() -> JoinSet<T, E> {
+        JoinSet {
+            name: None,
+            handles: FuturesUnordered::new(),
+        }
+    }
+
+    /// Creates a new, empty set with a name, which shows up in its `Debug`
+    /// output.
+    pub fn named<S: Into<String>>(name: S) -> JoinSet<T, E> {
+        JoinSet {
+            name: Some(name.into()),
+            handles: FuturesUnordered::new(),
+        }
+    }
+
+    /// Returns the name of this set, if it has one.
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_ref().map(|s| &s[..])
+    }
+
+    /// Spawns `future` onto `executor` as part of this set.
+    ///
+    /// If the executor rejects the task then the original future is handed
+    /// back through the returned `ExecuteError`.
+    pub fn spawn<X, F>(&mut self, executor: &X, future: F) -> Result<(), ExecuteError<F>>
+        where X: Executor<JoinTask<F>>,
+              F: Future<Item = T, Error = E>,
+    {
+        let handle = executor.spawn_with_handle(future)?;
+        self.handles.push(handle);
+        Ok(())
+    }
+
+    /// Aborts all of the futures in this set which haven't completed yet.
+    ///
+    /// As with `JoinHandle::abort`, the futures are dropped by their
+    /// executors, and the set then yields `JoinError::Aborted` for each of
+    /// them.
+    pub fn abort_all(&self) {
+        for handle in self.handles.iter() {
+            handle.abort();
+        }
+    }
+
+    /// Returns a future waiting for all of the futures in this set to
+    /// finish, resolving to their results in the order they completed.
+    pub fn join_all(self) -> JoinSetAll<T, E> {
+        JoinSetAll {
+            set: self,
+            results: Vec::new(),
+        }
+    }
+
+    /// Returns the number of futures in this set whose results haven't been
+    /// taken out of it yet.
+    pub fn len(&self) -> usize {
+        self.handles.len()
+    }
+
+    /// Returns `true` if the set contains no futures.
+    pub fn is_empty(&self) -> bool {
+        self.handles.is_empty()
+    }
+}
+
+impl<T, E> Stream for JoinSet<T, E> {
+    type Item = T;
+    type Error = JoinError<E>;
+
+    fn poll(&mut self) -> Poll<Option<T>, JoinError<E>> {
+        self.handles.poll()
+    }
+}
+
+impl<T, E> fmt::Debug for JoinSet<T, E> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("JoinSet")
+            .field("name", &self.name)
+            .field("len", &self.handles.len())
+            .finish()
+    }
+}
+
+impl<T, E> Future for JoinSetAll<T, E> {
+    type Item = Vec<Result<T, JoinError<E>>>;
+    type Error = ();
+
+    fn poll(&mut self) -> Poll<Self::Item, ()> {
+        loop {
+            let res = match self.set.poll() {
+                Ok(Async::Ready(Some(t))) => Ok(t),
+                Ok(Async::Ready(None)) => {
+                    return Ok(Async::Ready(mem::replace(&mut self.results, Vec::new())))
+                }
+                Ok(Async::NotReady) => return Ok(Async::NotReady),
+                Err(e) => Err(e),
+            };
+            self.results.push(res);
+        }
+    }
+}
+
+impl<T, E> fmt::Debug for JoinSetAll<T, E> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("JoinSetAll")
+            .field("set", &self.set)
+            .field("finished", &self.results.len())
+            .finish()
+    }
+}
@@ -0,0 +1,657 @@
+use std::cell::RefCell;
+use std::collections::{HashSet, VecDeque};
+use std::fmt;
+use std::mem;
+use std::panic::{self, AssertUnwindSafe};
+use std::prelude::v1::*;
+use std::process;
+use std::rc::{Rc, Weak};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use {Async, Future};
+use future::{Executor, ExecuteError, ExecuteErrorKind};
+use task_impl::{self, Named, Notify, Spawn};
+use super::{ExecutePriority, Priority};
+
+type LocalFuture = Box<Future<Item = (), Error = ()>>;
+
+thread_local!(static CURRENT: RefCell<Option<LocalSpawner>> = RefCell::new(None));
+
+// The notification id of the future passed to `run_until`, which doesn't live
+// in the task list.
+const MAIN: usize = usize::MAX;
+
+/// A single-threaded executor running a set of tasks on the current thread.
+///
+/// Futures are spawned onto the pool through a `LocalSpawner`, which is
+/// obtained from the `spawner` method. As all tasks run on the thread owning
+/// the pool, the futures don't need to be `Send`.
+///
+/// Tasks only make progress while the pool is being run with `run` or
+/// `run_until`. When none of the tasks can make progress the thread is
+/// parked until one of them is notified, which may happen from any thread.
+///
+/// While the pool is running it is the thread-default executor, so futures
+/// can also be spawned onto it through `DefaultSpawner`.
+///
+/// Tasks can be spawned with a `Priority` through `ExecutePriority`. Ready
+/// tasks of a higher priority are always polled before those of a lower
+/// priority, the future passed to `run_until` has `Priority::Normal`.
+///
+/// # Examples
+///
+/// ```
+/// use std::rc::Rc;
+/// use std::cell::Cell;
+///
+/// use futures::future::{self, Executor};
+/// use futures::executor::LocalPool;
+///
+/// let mut pool = LocalPool::new();
+/// let spawner = pool.spawner();
+/// let count = Rc::new(Cell::new(0));
+///
+/// for _ in 0..3 {
+///     let count = count.clone();
+///     spawner.execute(future::lazy(move || {
+///         count.set(count.get() + 1);
+///         Ok(())
+///     })).unwrap();
+/// }
+///
+/// pool.run();
+/// assert_eq!(count.get(), 3);
+/// ```
+pub struct LocalPool {
+    tasks: Vec<Option<LocalTask>>,
+    free: Vec<usize>,
+    live: usize,
+    // The ids of the tasks ready to be polled, indexed by `rank`.
+    queues: [VecDeque<usize>; 3],
+    incoming: Rc<RefCell<Vec<(LocalFuture, Priority)>>>,
+    notify: Arc<PoolNotify>,
+    created: Instant,
+    polls: u64,
+    busy: Duration,
+    reporter: Option<Reporter>,
+    panic_policy: PanicPolicy,
+}
+
+struct Reporter {
+    every: Duration,
+    last: Instant,
+    f: Box<FnMut(&PoolMetrics)>,
+}
+
+struct LocalTask {
+    spawn: Spawn<LocalFuture>,
+    priority: Priority,
+}
+
+/// A handle used to spawn futures onto a `LocalPool`.
+///
+/// This implements `Executor` for all futures which are `'static`, even if
+/// they aren't `Send`. Spawning fails with `ExecuteErrorKind::Shutdown` once
+/// the pool has been dropped.
+///
+/// This structure is produced by the `LocalPool::spawner` method.
+#[derive(Clone)]
+pub struct LocalSpawner {
+    incoming: Weak<RefCell<Vec<(LocalFuture, Priority)>>>,
+}
+
+// Queues the ids of notified tasks and wakes up the thread running the pool.
+struct PoolNotify {
+    ready: Mutex<Ready>,
+    thread: thread::Thread,
+}
+
+struct Ready {
+    ids: VecDeque<usize>,
+    // The ids of the tasks which are queued to be polled, here or in the run
+    // queues of the pool, so that a task notified several times before it's
+    // polled is only queued once.
+    queued: HashSet<usize>,
+}
+
+impl Notify for PoolNotify {
+    fn notify(&self, id: usize) {
+        let mut ready = self.ready.lock().unwrap();
+        if ready.queued.insert(id) {
+            ready.ids.push_back(id);
+            self.thread.unpark();
+        }
+    }
+}
+
+impl PoolNotify {
+    // Called right before polling the task `id`, notifications from then on
+    // queue it again.
+    fn dequeue(&self, id: usize) {
+        self.ready.lock().unwrap().queued.remove(&id);
+    }
+}
+
+impl LocalPool {
+    /// Creates a new pool without any tasks, to be run on the current
+    /// thread.
+    pub fn new() -> LocalPool {
+        LocalPool {
+            tasks: Vec::new(),
+            free: Vec::new(),
+            live: 0,
+            queues: [VecDeque::new(), VecDeque::new(), VecDeque::new()],
+            incoming: Rc::new(RefCell::new(Vec::new())),
+            notify: Arc::new(PoolNotify {
+                ready: Mutex::new(Ready {
+                    ids: VecDeque::new(),
+                    queued: HashSet::new(),
+                }),
+                thread: thread::current(),
+            }),
+            created: Instant::now(),
+            polls: 0,
+            busy: Duration::from_secs(0),
+            reporter: None,
+            panic_policy: PanicPolicy::Propagate,
+        }
+    }
+
+    /// Returns a handle which can be used to spawn futures onto this pool.
+    pub fn spawner(&self) -> LocalSpawner {
+        LocalSpawner { incoming: Rc::downgrade(&self.incoming) }
+    }
+
+    /// Runs all tasks of this pool until they have completed.
+    ///
+    /// This includes tasks spawned while the pool is running. Note that this
+    /// never returns if one of the tasks never completes.
+    ///
+    /// # Panics
+    ///
+    /// This panics if it's called from within a task or while another
+    /// executor is running on the current thread, see `executor::enter`.
+    pub fn run(&mut self) {
+        let _enter = self.enter("LocalPool::run");
+        loop {
+            match self.next_ready() {
+                Some(id) => {
+                    self.poll_task(id);
+                }
+                None if self.live == 0 => return,
+                None => thread::park(),
+            }
+        }
+    }
+
+    /// Runs the tasks of this pool until `f` has completed, returning its
+    /// result.
+    ///
+    /// The future `f` is run on the current thread alongside the tasks of the
+    /// pool, and may spawn more tasks through a `LocalSpawner`. Tasks which
+    /// haven't completed by the time `f` resolves are kept, and will continue
+    /// to run the next time the pool is run.
+    ///
+    /// # Panics
+    ///
+    /// This panics if it's called from within a task or while another
+    /// executor is running on the current thread, see `executor::enter`.
+    pub fn run_until<F: Future>(&mut self, f: F) -> Result<F::Item, F::Error> {
+        let _enter = self.enter("LocalPool::run_until");
+        let mut main = task_impl::spawn(f);
+        let mut main_ready = true;
+        loop {
+            if main_ready {
+                main_ready = false;
+                self.notify.dequeue(MAIN);
+                let start = Instant::now();
+                let res = main.poll_future_notify(&self.notify, MAIN);
+                self.record(start);
+                if let Async::Ready(e) = res? {
+                    return Ok(e)
+                }
+            }
+            match self.next_ready() {
+                Some(MAIN) => main_ready = true,
+                Some(id) => {
+                    self.poll_task(id);
+                }
+                None => thread::park(),
+            }
+        }
+    }
+
+    /// Returns a snapshot of the metrics of this pool.
+    pub fn metrics(&self) -> PoolMetrics {
+        let queued = self.queues.iter().map(|q| q.len()).sum::<usize>() +
+            self.notify.ready.lock().unwrap().ids.len() +
+            self.incoming.borrow().len();
+        PoolMetrics {
+            tasks: self.live + self.incoming.borrow().len(),
+            queued: queued,
+            polls: self.polls,
+            busy: self.busy,
+            uptime: self.created.elapsed(),
+        }
+    }
+
+    /// Registers a callback which is passed the metrics of this pool about
+    /// every `every` while it's running, replacing any previous one.
+    ///
+    /// The callback runs on the thread running the pool, between polls of
+    /// its tasks.
+    pub fn on_metrics<F>(&mut self, every: Duration, f: F)
+        where F: FnMut(&PoolMetrics) + 'static,
+    {
+        self.reporter = Some(Reporter {
+            every: every,
+            last: Instant::now(),
+            f: Box::new(f),
+        });
+    }
+
+    /// Sets what happens when one of the tasks of this pool panics.
+    ///
+    /// This doesn't affect the future passed to `run_until`, whose panics
+    /// are always propagated to the caller.
+    pub fn set_panic_policy(&mut self, policy: PanicPolicy) {
+        self.panic_policy = policy;
+    }
+
+    /// Shuts the pool down, giving the remaining tasks up to `timeout` to
+    /// complete.
+    ///
+    /// Spawning onto the pool fails with `ExecuteErrorKind::Shutdown` from
+    /// the moment this is called, including from within the remaining tasks,
+    /// while futures which were spawned before still become tasks. The pool
+    /// then runs until all of the tasks have completed or the deadline has
+    /// passed, and drops the tasks which are still pending.
+    ///
+    /// This lets tasks flush buffered data or send acknowledgments before
+    /// the program exits, rather than losing them as when the pool is just
+    /// dropped. The returned report says how many tasks were abandoned.
+    pub fn shutdown(mut self, timeout: Duration) -> ShutdownReport {
+        let deadline = Instant::now() + timeout;
+
+        // Nothing is the thread-default executor while shutting down, and
+        // replacing `incoming` disconnects all of the existing spawners.
+        let _enter = task_impl::enter_blocking("LocalPool::shutdown");
+        let _reset = Reset(CURRENT.with(|c| c.borrow_mut().take()));
+        let incoming = mem::replace(&mut *self.incoming.borrow_mut(), Vec::new());
+        self.incoming = Rc::new(RefCell::new(incoming));
+        let mut completed = 0;
+        loop {
+            let now = Instant::now();
+            if now >= deadline {
+                break
+            }
+            match self.next_ready() {
+                Some(id) => {
+                    if self.poll_task(id) {
+                        completed += 1;
+                    }
+                }
+                None if self.live == 0 => break,
+                None => thread::park_timeout(deadline - now),
+            }
+        }
+
+        // Also count the futures which never got to become tasks.
+        let abandoned = self.live + self.incoming.borrow().len();
+        self.tasks.clear();
+        self.incoming.borrow_mut().clear();
+        ShutdownReport {
+            completed: completed,
+            abandoned: abandoned,
+        }
+    }
+
+    // Marks the thread as running an executor, and makes this pool the
+    // thread-default executor until the returned guards are dropped.
+    fn enter(&self, what: &str) -> (task_impl::Enter, Reset) {
+        let enter = task_impl::enter_blocking(what);
+        let prev = CURRENT.with(|c| c.borrow_mut().replace(self.spawner()));
+        (enter, Reset(prev))
+    }
+
+    // Moves newly spawned futures into the task list and queues all of the
+    // notified tasks, then returns the ready task of the highest priority.
+    fn next_ready(&mut self) -> Option<usize> {
+        let incoming = mem::replace(&mut *self.incoming.borrow_mut(), Vec::new());
+        for (future, priority) in incoming {
+            let task = Some(LocalTask {
+                spawn: task_impl::spawn(future),
+                priority: priority,
+            });
+            let id = match self.free.pop() {
+                Some(id) => {
+                    self.tasks[id] = task;
+                    id
+                }
+                None => {
+                    self.tasks.push(task);
+                    self.tasks.len() - 1
+                }
+            };
+            self.live += 1;
+            self.queues[rank(priority)].push_back(id);
+        }
+
+        let notified = self.notify.ready.lock().unwrap()
+            .ids
+            .drain(..)
+            .collect::<Vec<_>>();
+        for id in notified {
+            // Notifications of `MAIN` and of completed tasks fall back to the
+            // normal priority.
+            let priority = match self.tasks.get(id) {
+                Some(&Some(ref task)) => task.priority,
+                _ => Priority::Normal,
+            };
+            self.queues[rank(priority)].push_back(id);
+        }
+
+        self.queues.iter_mut().filter_map(|q| q.pop_front()).next()
+    }
+
+    // Polls the task `id`, returning whether it has completed.
+    fn poll_task(&mut self, id: usize) -> bool {
+        // Also for completed tasks, as their id may be reused.
+        self.notify.dequeue(id);
+        let start = Instant::now();
+        let res = match self.tasks.get_mut(id) {
+            Some(&mut Some(ref mut task)) => {
+                let notify = &self.notify;
+                match self.panic_policy {
+                    PanicPolicy::Propagate => {
+                        Ok(task.spawn.poll_future_notify(notify, id))
+                    }
+                    _ => {
+                        panic::catch_unwind(AssertUnwindSafe(|| {
+                            task.spawn.poll_future_notify(notify, id)
+                        }))
+                    }
+                }
+            }
+            // Notifications of tasks which have already completed.
+            _ => return false,
+        };
+        self.record(start);
+        match res {
+            Ok(Ok(Async::NotReady)) => false,
+            Ok(Ok(Async::Ready(()))) | Ok(Err(())) => {
+                self.remove(id);
+                true
+            }
+            Err(payload) => {
+                match self.panic_policy {
+                    PanicPolicy::Abort => process::abort(),
+                    PanicPolicy::Forward => {
+                        task_impl::forward_panic(payload, || self.remove(id));
+                    }
+                    _ => self.remove(id),
+                }
+                true
+            }
+        }
+    }
+
+    fn remove(&mut self, id: usize) {
+        self.tasks[id] = None;
+        self.free.push(id);
+        self.live -= 1;
+    }
+
+    // Accounts for a poll which started at `start`, and reports the metrics
+    // if it's time to.
+    fn record(&mut self, start: Instant) {
+        let now = Instant::now();
+        self.polls += 1;
+        self.busy += now - start;
+        let due = match self.reporter {
+            Some(ref reporter) => now - reporter.last >= reporter.every,
+            None => false,
+        };
+        if due {
+            let metrics = self.metrics();
+            let reporter = self.reporter.as_mut().unwrap();
+            reporter.last = now;
+            (reporter.f)(&metrics);
+        }
+    }
+}
+
+/// A snapshot of the metrics of a `LocalPool`, as returned by
+/// `LocalPool::metrics`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PoolMetrics {
+    /// The number of tasks which haven't completed yet.
+    pub tasks: usize,
+
+    /// The number of tasks waiting in the run queue to be polled.
+    pub queued: usize,
+
+    /// The number of polls since the pool was created.
+    pub polls: u64,
+
+    /// The total time spent polling since the pool was created.
+    pub busy: Duration,
+
+    /// The time elapsed since the pool was created.
+    pub uptime: Duration,
+}
+
+impl PoolMetrics {
+    /// Returns the average number of polls per second since the pool was
+    /// created.
+    pub fn polls_per_second(&self) -> f64 {
+        let secs = self.uptime.as_secs() as f64 +
+            self.uptime.subsec_nanos() as f64 / 1e9;
+        if secs == 0.0 {
+            0.0
+        } else {
+            self.polls as f64 / secs
+        }
+    }
+
+    /// Returns the mean duration of a poll, or `None` if nothing was polled
+    /// yet.
+    pub fn mean_poll_duration(&self) -> Option<Duration> {
+        if self.polls == 0 {
+            return None
+        }
+        let nanos = (self.busy.as_secs() * 1_000_000_000 +
+                     self.busy.subsec_nanos() as u64) / self.polls;
+        Some(Duration::new(nanos / 1_000_000_000, (nanos % 1_000_000_000) as u32))
+    }
+}
+
+/// What an executor does when one of its tasks panics.
+///
+/// This is set for a `LocalPool` with `LocalPool::set_panic_policy`. With
+/// all policies but `Propagate` the executor keeps running the other tasks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PanicPolicy {
+    /// The panic is propagated out of the executor, to whoever is running
+    /// it. This is the default.
+    Propagate,
+
+    /// The process is aborted.
+    Abort,
+
+    /// The task which panicked is dropped, the panic has already been
+    /// reported by the panic hook.
+    DropTask,
+
+    /// The task which panicked is dropped, and the panic is forwarded to its
+    /// `JoinHandle` if it was spawned through `SpawnExt::spawn_with_handle`.
+    /// Polling the handle then resumes the panic. Other tasks are dropped as
+    /// with `DropTask`.
+    Forward,
+}
+
+impl Default for PanicPolicy {
+    fn default() -> PanicPolicy {
+        PanicPolicy::Propagate
+    }
+}
+
+/// What happened to the tasks of a `LocalPool` which was shut down, as
+/// returned by `LocalPool::shutdown`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ShutdownReport {
+    /// The number of tasks which completed during the shutdown.
+    pub completed: usize,
+
+    /// The number of tasks which were still pending at the deadline, and
+    /// were dropped.
+    pub abandoned: usize,
+}
+
+struct Reset(Option<LocalSpawner>);
+
+impl Drop for Reset {
+    fn drop(&mut self) {
+        let prev = self.0.take();
+        CURRENT.with(|c| *c.borrow_mut() = prev);
+    }
+}
+
+// The index of the run queue of tasks with `priority`.
+fn rank(priority: Priority) -> usize {
+    match priority {
+        Priority::High => 0,
+        Priority::Normal => 1,
+        Priority::Low => 2,
+    }
+}
+
+impl Default for LocalPool {
+    fn default() -> LocalPool {
+        LocalPool::new()
+    }
+}
+
+impl fmt::Debug for LocalPool {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("LocalPool")
+            .field("tasks", &self.live)
+            .field("polls", &self.polls)
+            .finish()
+    }
+}
+
+impl LocalSpawner {
+    /// Spawns `future` onto the pool as a task named `name`.
+    ///
+    /// The name is reported by `task::name` while the task runs, see
+    /// `task::named` for more information.
+    pub fn spawn_named<N, F>(&self, name: N, future: F)
+                             -> Result<(), ExecuteError<Named<F>>>
+        where N: Into<String>,
+              F: Future<Item = (), Error = ()> + 'static,
+    {
+        self.execute(task_impl::named(name, future))
+    }
+}
+
+impl<F> Executor<F> for LocalSpawner
+    where F: Future<Item = (), Error = ()> + 'static,
+{
+    fn execute(&self, future: F) -> Result<(), ExecuteError<F>> {
+        self.execute_priority(future, Priority::Normal)
+    }
+}
+
+impl<F> ExecutePriority<F> for LocalSpawner
+    where F: Future<Item = (), Error = ()> + 'static,
+{
+    fn execute_priority(&self, future: F, priority: Priority)
+                        -> Result<(), ExecuteError<F>> {
+        match self.incoming.upgrade() {
+            Some(incoming) => {
+                incoming.borrow_mut().push((Box::new(future), priority));
+                Ok(())
+            }
+            None => Err(ExecuteError::new(ExecuteErrorKind::Shutdown, future)),
+        }
+    }
+}
+
+impl fmt::Debug for LocalSpawner {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("LocalSpawner").finish()
+    }
+}
+
+/// An executor spawning futures onto the `LocalPool` currently running on
+/// this thread.
+///
+/// This makes it possible to spawn tasks without passing a `LocalSpawner`
+/// around, for example from within a future run by `block_on_all`. Spawning
+/// fails with `ExecuteErrorKind::Shutdown` if no pool is running on the
+/// current thread.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DefaultSpawner;
+
+impl<F> Executor<F> for DefaultSpawner
+    where F: Future<Item = (), Error = ()> + 'static,
+{
+    fn execute(&self, future: F) -> Result<(), ExecuteError<F>> {
+        self.execute_priority(future, Priority::Normal)
+    }
+}
+
+impl<F> ExecutePriority<F> for DefaultSpawner
+    where F: Future<Item = (), Error = ()> + 'static,
+{
+    fn execute_priority(&self, future: F, priority: Priority)
+                        -> Result<(), ExecuteError<F>> {
+        match CURRENT.with(|c| c.borrow().clone()) {
+            Some(spawner) => spawner.execute_priority(future, priority),
+            None => Err(ExecuteError::new(ExecuteErrorKind::Shutdown, future)),
+        }
+    }
+}
+
+/// Runs `f` to completion on the current thread, along with all of the tasks
+/// spawned onto the thread-default executor while doing so.
+///
+/// This runs a fresh `LocalPool` until `f` has completed, with tasks spawned
+/// through `DefaultSpawner` running alongside `f`, and then keeps running the
+/// pool until all of those tasks have completed as well. The result of `f`
+/// is returned once everything is complete.
+///
+/// # Examples
+///
+/// ```
+/// use std::cell::Cell;
+/// use std::rc::Rc;
+///
+/// use futures::prelude::*;
+/// use futures::future::{self, Executor};
+/// use futures::executor::{self, DefaultSpawner};
+///
+/// let count = Rc::new(Cell::new(0));
+/// let count2 = count.clone();
+/// let res = executor::block_on_all(future::lazy(move || {
+///     for _ in 0..3 {
+///         let count = count2.clone();
+///         DefaultSpawner.execute(future::lazy(move || {
+///             count.set(count.get() + 1);
+///             Ok(())
+///         })).unwrap();
+///     }
+///     Ok::<_, ()>("done")
+/// }));
+/// assert_eq!(res, Ok("done"));
+/// assert_eq!(count.get(), 3);
+/// ```
+pub fn block_on_all<F: Future>(f: F) -> Result<F::Item, F::Error> {
+    let mut pool = LocalPool::new();
+    let res = pool.run_until(f);
+    pool.run();
+    res
+}
@@ -0,0 +1,37 @@
+//! Executors
+//!
+//! This module contains tools for managing the raw execution of futures,
+//! which is needed when building *executors* (places where futures can run).
+//!
+//! More information about executors can be [found online at tokio.rs][online].
+//!
+//! [online]: https://tokio.rs/docs/going-deeper-futures/tasks/
+
+#[allow(deprecated)]
+#[cfg(feature = "use_std")]
+pub use task_impl::{Unpark, Executor, Run};
+
+pub use task_impl::{Spawn, spawn, Notify, with_notify};
+
+pub use task_impl::{UnsafeNotify, NotifyHandle};
+
+mod priority;
+pub use self::priority::{Priority, ExecutePriority, PriorityExecutor};
+
+if_std! {
+    pub use task_impl::{ArcNotify, NotifyStats, PollObserver, set_poll_observer};
+    pub use task_impl::{Enter, EnterError, ThreadParker, enter};
+
+    mod box_executor;
+    pub use self::box_executor::{BoxExecutor, BoxedFuture};
+
+    mod join_set;
+    pub use self::join_set::{JoinSet, JoinSetAll};
+
+    mod bounded;
+    pub use self::bounded::{Bounded, BoundedTask, SpawnReady};
+
+    mod local_pool;
+    pub use self::local_pool::{LocalPool, LocalSpawner, DefaultSpawner, PoolMetrics, ShutdownReport};
+    pub use self::local_pool::{PanicPolicy, block_on_all};
+}
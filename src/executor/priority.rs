@@ -0,0 +1,121 @@
+use Future;
+use future::{Executor, ExecuteError};
+
+/// The priority of a task, deciding the order in which an executor runs the
+/// tasks which are ready to make progress.
+///
+/// Ready tasks of a higher priority are always run before ready tasks of a
+/// lower priority, tasks of the same priority are run in the order they were
+/// notified.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Priority {
+    /// Bulk work which may be delayed by everything else.
+    Low,
+
+    /// The priority of tasks spawned through `Executor::execute`.
+    Normal,
+
+    /// Latency sensitive work, run ahead of all other tasks.
+    High,
+}
+
+impl Default for Priority {
+    fn default() -> Priority {
+        Priority::Normal
+    }
+}
+
+/// An `Executor` which is able to run futures with a `Priority`.
+///
+/// This is implemented by the executors of this crate, such as
+/// `LocalSpawner`. Spawning through `Executor::execute` uses
+/// `Priority::Normal`.
+pub trait ExecutePriority<F>: Executor<F>
+    where F: Future<Item = (), Error = ()>,
+{
+    /// Spawns `future` to run on this executor with the given `priority`.
+    ///
+    /// This behaves like `Executor::execute` otherwise.
+    fn execute_priority(&self, future: F, priority: Priority)
+                        -> Result<(), ExecuteError<F>>;
+}
+
+/// An executor wrapper spawning all futures with a fixed priority.
+///
+/// This makes it possible to hand an executor to code which only knows about
+/// `Executor`, such as a library spawning background work, while still
+/// controlling the priority of the tasks it spawns.
+///
+/// # Examples
+///
+/// ```
+/// use std::cell::RefCell;
+/// use std::rc::Rc;
+///
+/// use futures::future::{self, Executor};
+/// use futures::executor::{LocalPool, Priority, PriorityExecutor};
+///
+/// let mut pool = LocalPool::new();
+/// let bulk = PriorityExecutor::new(pool.spawner(), Priority::Low);
+/// let control = PriorityExecutor::new(pool.spawner(), Priority::High);
+/// let order = Rc::new(RefCell::new(Vec::new()));
+///
+/// let order2 = order.clone();
+/// bulk.execute(future::lazy(move || Ok(order2.borrow_mut().push("bulk"))))
+///     .unwrap();
+/// let order2 = order.clone();
+/// control.execute(future::lazy(move || Ok(order2.borrow_mut().push("control"))))
+///     .unwrap();
+///
+/// pool.run();
+/// assert_eq!(*order.borrow(), ["control", "bulk"]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct PriorityExecutor<E> {
+    inner: E,
+    priority: Priority,
+}
+
+impl<E> PriorityExecutor<E> {
+    /// Creates a new executor spawning futures onto `inner` with `priority`.
+    pub fn new(inner: E, priority: Priority) -> PriorityExecutor<E> {
+        PriorityExecutor {
+            inner: inner,
+            priority: priority,
+        }
+    }
+
+    /// Returns the priority futures are spawned with.
+    pub fn priority(&self) -> Priority {
+        self.priority
+    }
+
+    /// Returns a reference to the underlying executor.
+    pub fn get_ref(&self) -> &E {
+        &self.inner
+    }
+
+    /// Consumes this wrapper, returning the underlying executor.
+    pub fn into_inner(self) -> E {
+        self.inner
+    }
+}
+
+impl<E, F> Executor<F> for PriorityExecutor<E>
+    where E: ExecutePriority<F>,
+          F: Future<Item = (), Error = ()>,
+{
+    fn execute(&self, future: F) -> Result<(), ExecuteError<F>> {
+        self.inner.execute_priority(future, self.priority)
+    }
+}
+
+impl<E, F> ExecutePriority<F> for PriorityExecutor<E>
+    where E: ExecutePriority<F>,
+          F: Future<Item = (), Error = ()>,
+{
+    fn execute_priority(&self, future: F, priority: Priority)
+                        -> Result<(), ExecuteError<F>> {
+        self.inner.execute_priority(future, priority)
+    }
+}
@@ -0,0 +1,266 @@
+//! A C representation of boxed futures, for driving futures from C or C++
+//! event loops embedding Rust, and the other way around.
+//!
+//! An `FfiFuture` is a future spelled out as a pointer to its state and a
+//! table of `extern "C"` functions to poll and drop it. `FfiFuture::new`
+//! turns any Rust future into one, which can then be handed to C and polled
+//! there. In the other direction an `FfiFuture` implemented in C can be
+//! polled by Rust like any other future, as it implements `Future` itself.
+//!
+//! Instead of a task, poll functions are passed an `FfiWake`: a callback to
+//! invoke once the future should be polled again, along with the data to
+//! invoke it with and a function releasing that data. Futures keep the
+//! `FfiWake` around for as long as they need it, and call its drop function
+//! once they're done with it.
+//!
+//! On the C side the types of this module look like this:
+//!
+//! ```c
+//! typedef enum {
+//!     FFI_POLL_READY = 0,
+//!     FFI_POLL_NOT_READY = 1,
+//!     FFI_POLL_ERROR = 2,
+//!     FFI_POLL_PANICKED = 3,
+//! } ffi_poll;
+//!
+//! typedef struct {
+//!     void *data;
+//!     void (*wake)(void *data);
+//!     void (*drop)(void *data);
+//! } ffi_wake;
+//!
+//! typedef struct {
+//!     void *data;
+//!     ffi_poll (*poll)(void *data, ffi_wake wake);
+//!     void (*drop)(void *data);
+//! } ffi_future;
+//! ```
+//!
+//! For event sources which need to hold on to several handles for the same
+//! task, the `WakeHandle` of this module adds a clone function to the wake
+//! and drop functions of an `FfiWake`, and converts to and from one.
+//!
+//! Futures crossing the boundary resolve to `()` and fail with `()`, as C
+//! has no way of receiving Rust values generically. Results can be passed on
+//! through a side channel the future writes to, for example with
+//! `Future::then`.
+//!
+//! This module is only available when the `use_std` feature of this library
+//! is activated, and it is activated by default.
+
+use std::boxed::Box;
+use std::fmt;
+use std::os::raw::c_void;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::Arc;
+
+use {Async, Future, Poll};
+use executor::{self, Notify, Spawn};
+use task::{self, Task};
+
+mod waker;
+pub use self::waker::{WakeHandle, RawWakeHandle, WakeVtable};
+
+/// The result of polling an `FfiFuture` through its poll function.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FfiPoll {
+    /// The future resolved successfully.
+    Ready = 0,
+
+    /// The future isn't done yet, and will invoke the `FfiWake` it was
+    /// passed once it should be polled again.
+    NotReady = 1,
+
+    /// The future failed.
+    Error = 2,
+
+    /// Polling the future panicked. The panic was caught at the boundary,
+    /// as it can't unwind into C, and the future shouldn't be polled again.
+    Panicked = 3,
+}
+
+/// A callback waking up whoever is polling an `FfiFuture`, the C counterpart
+/// of a `Task`.
+///
+/// The drop function, if any, is called when this value is dropped.
+#[repr(C)]
+pub struct FfiWake {
+    data: *mut c_void,
+    wake: unsafe extern "C" fn(*mut c_void),
+    drop: Option<unsafe extern "C" fn(*mut c_void)>,
+}
+
+unsafe impl Send for FfiWake {}
+unsafe impl Sync for FfiWake {}
+
+/// A boxed future in a representation which can cross the boundary to C.
+///
+/// The future is dropped through its drop function when this value is
+/// dropped.
+#[repr(C)]
+#[must_use = "futures do nothing unless polled"]
+pub struct FfiFuture {
+    data: *mut c_void,
+    poll: unsafe extern "C" fn(*mut c_void, FfiWake) -> FfiPoll,
+    drop: unsafe extern "C" fn(*mut c_void),
+}
+
+unsafe impl Send for FfiFuture {}
+
+impl FfiWake {
+    /// Creates an `FfiWake` out of its raw parts.
+    ///
+    /// # Safety
+    ///
+    /// `wake` must be safe to call with `data`, from any thread and any
+    /// number of times, until `drop` is called with `data`, which happens
+    /// exactly once.
+    pub unsafe fn from_raw(data: *mut c_void,
+                           wake: unsafe extern "C" fn(*mut c_void),
+                           drop: Option<unsafe extern "C" fn(*mut c_void)>)
+                           -> FfiWake
+    {
+        FfiWake {
+            data: data,
+            wake: wake,
+            drop: drop,
+        }
+    }
+
+    /// Creates an `FfiWake` notifying `task`.
+    pub fn from_task(task: Task) -> FfiWake {
+        unsafe {
+            FfiWake::from_raw(Box::into_raw(Box::new(task)) as *mut c_void,
+                              wake_task,
+                              Some(drop_task))
+        }
+    }
+
+    /// Invokes the wake callback.
+    pub fn wake(&self) {
+        unsafe { (self.wake)(self.data) }
+    }
+}
+
+unsafe extern "C" fn wake_task(data: *mut c_void) {
+    (*(data as *const Task)).notify();
+}
+
+unsafe extern "C" fn drop_task(data: *mut c_void) {
+    drop(Box::from_raw(data as *mut Task));
+}
+
+impl Drop for FfiWake {
+    fn drop(&mut self) {
+        if let Some(drop) = self.drop {
+            unsafe { drop(self.data) }
+        }
+    }
+}
+
+impl fmt::Debug for FfiWake {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("FfiWake")
+            .field("data", &self.data)
+            .finish()
+    }
+}
+
+impl Notify for FfiWake {
+    fn notify(&self, _id: usize) {
+        self.wake();
+    }
+}
+
+impl FfiFuture {
+    /// Boxes up `future` so that it can be polled from C.
+    ///
+    /// Panics while polling the future are caught and reported as
+    /// `FfiPoll::Panicked`, since they can't unwind into C.
+    pub fn new<F>(future: F) -> FfiFuture
+        where F: Future<Item = (), Error = ()> + Send + 'static,
+    {
+        let spawn = Box::new(executor::spawn(future));
+        unsafe {
+            FfiFuture::from_raw(Box::into_raw(spawn) as *mut c_void,
+                                poll_spawn::<F>,
+                                drop_spawn::<F>)
+        }
+    }
+
+    /// Creates an `FfiFuture` out of its raw parts.
+    ///
+    /// # Safety
+    ///
+    /// `poll` must be safe to call with `data` from any thread, though not
+    /// concurrently, until `drop` is called with `data`, which happens
+    /// exactly once.
+    pub unsafe fn from_raw(data: *mut c_void,
+                           poll: unsafe extern "C" fn(*mut c_void, FfiWake) -> FfiPoll,
+                           drop: unsafe extern "C" fn(*mut c_void))
+                           -> FfiFuture
+    {
+        FfiFuture {
+            data: data,
+            poll: poll,
+            drop: drop,
+        }
+    }
+
+    /// Polls this future through its poll function, handing it `wake` to
+    /// invoke once it should be polled again.
+    pub fn poll_ffi(&mut self, wake: FfiWake) -> FfiPoll {
+        unsafe { (self.poll)(self.data, wake) }
+    }
+}
+
+unsafe extern "C" fn poll_spawn<F>(data: *mut c_void, wake: FfiWake) -> FfiPoll
+    where F: Future<Item = (), Error = ()>,
+{
+    let spawn = &mut *(data as *mut Spawn<F>);
+    let wake = Arc::new(wake);
+    let res = panic::catch_unwind(AssertUnwindSafe(|| {
+        spawn.poll_future_notify(&wake, 0)
+    }));
+    match res {
+        Ok(Ok(Async::Ready(()))) => FfiPoll::Ready,
+        Ok(Ok(Async::NotReady)) => FfiPoll::NotReady,
+        Ok(Err(())) => FfiPoll::Error,
+        Err(_) => FfiPoll::Panicked,
+    }
+}
+
+unsafe extern "C" fn drop_spawn<F>(data: *mut c_void) {
+    // The destructor of the future can panic as well.
+    let spawn = Box::from_raw(data as *mut Spawn<F>);
+    drop(panic::catch_unwind(AssertUnwindSafe(move || drop(spawn))));
+}
+
+impl Future for FfiFuture {
+    type Item = ();
+    type Error = ();
+
+    fn poll(&mut self) -> Poll<(), ()> {
+        match self.poll_ffi(FfiWake::from_task(task::current())) {
+            FfiPoll::Ready => Ok(Async::Ready(())),
+            FfiPoll::NotReady => Ok(Async::NotReady),
+            FfiPoll::Error => Err(()),
+            FfiPoll::Panicked => panic!("future polled through ffi panicked"),
+        }
+    }
+}
+
+impl Drop for FfiFuture {
+    fn drop(&mut self) {
+        unsafe { (self.drop)(self.data) }
+    }
+}
+
+impl fmt::Debug for FfiFuture {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("FfiFuture")
+            .field("data", &self.data)
+            .finish()
+    }
+}
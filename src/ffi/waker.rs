@@ -0,0 +1,216 @@
+//! Wake handles made of plain function pointers.
+//!
+//! A `WakeHandle` is a pointer to some data along with a table of
+//! `extern "C"` functions to clone it, wake it and drop it, like the
+//! `RawWaker` behind a `std::task::Waker`. As it doesn't involve any trait
+//! objects it can be passed to event sources written against waker-style
+//! designs, including ones written in other languages, which store and wake
+//! the handle without knowing anything about the tasks of this crate.
+//!
+//! * `WakeHandle::from_task` turns a `Task` into a handle. The task is put
+//!   behind a reference count once, and the clones of the handle made for
+//!   every registration share it.
+//! * `WakeHandle` converts into a `NotifyHandle`, so that tasks of this crate
+//!   can be polled under a handle obtained from elsewhere with
+//!   `Spawn::poll_future_notify` and the like. Notifying such a task wakes
+//!   the handle.
+//! * `WakeHandle` and `FfiWake` convert into each other, for C code written
+//!   against the simpler `ffi_wake`, which can't be cloned.
+
+use std::boxed::Box;
+use std::fmt;
+use std::os::raw::c_void;
+use std::sync::Arc;
+
+use executor::{Notify, NotifyHandle};
+use ffi::FfiWake;
+use task::Task;
+
+/// The functions operating on the data of a `WakeHandle`.
+#[repr(C)]
+#[derive(Debug)]
+pub struct WakeVtable {
+    /// Creates another handle to the same data, which has to be dropped
+    /// separately.
+    pub clone: unsafe extern "C" fn(*const c_void) -> RawWakeHandle,
+
+    /// Wakes the handle up, without consuming it.
+    pub wake: unsafe extern "C" fn(*const c_void),
+
+    /// Releases the handle.
+    pub drop: unsafe extern "C" fn(*const c_void),
+}
+
+/// The raw parts of a `WakeHandle`, as passed across an FFI boundary.
+#[repr(C)]
+#[derive(Debug)]
+pub struct RawWakeHandle {
+    /// The data passed to the functions of `vtable`.
+    pub data: *const c_void,
+
+    /// The functions operating on `data`.
+    pub vtable: &'static WakeVtable,
+}
+
+/// An owned handle which can be woken up any number of times, from any
+/// thread.
+///
+/// Cloning and dropping the handle go through its `WakeVtable`.
+pub struct WakeHandle {
+    raw: RawWakeHandle,
+}
+
+unsafe impl Send for WakeHandle {}
+unsafe impl Sync for WakeHandle {}
+
+static TASK_VTABLE: WakeVtable = WakeVtable {
+    clone: clone_task,
+    wake: wake_task,
+    drop: drop_task,
+};
+
+// The data of these handles is a boxed `Arc`, rather than the pointer of
+// `Arc::into_raw`, which isn't available on all of the compilers this crate
+// supports.
+unsafe extern "C" fn clone_task(data: *const c_void) -> RawWakeHandle {
+    let task = (*(data as *const Arc<Task>)).clone();
+    RawWakeHandle {
+        data: Box::into_raw(Box::new(task)) as *const c_void,
+        vtable: &TASK_VTABLE,
+    }
+}
+
+unsafe extern "C" fn wake_task(data: *const c_void) {
+    (*(data as *const Arc<Task>)).notify();
+}
+
+unsafe extern "C" fn drop_task(data: *const c_void) {
+    drop(Box::from_raw(data as *mut Arc<Task>));
+}
+
+impl WakeHandle {
+    /// Creates a handle notifying `task` whenever it's woken up.
+    pub fn from_task(task: Task) -> WakeHandle {
+        WakeHandle {
+            raw: RawWakeHandle {
+                data: Box::into_raw(Box::new(Arc::new(task))) as *const c_void,
+                vtable: &TASK_VTABLE,
+            },
+        }
+    }
+
+    /// Creates a handle out of its raw parts, taking ownership of them.
+    ///
+    /// # Safety
+    ///
+    /// The functions of the vtable must be safe to call with the data from
+    /// any thread, and the data has to stay valid until it's dropped through
+    /// the vtable, which happens exactly once for the handle and once for
+    /// every clone of it.
+    pub unsafe fn from_raw(raw: RawWakeHandle) -> WakeHandle {
+        WakeHandle { raw: raw }
+    }
+
+    /// Consumes this handle, returning its raw parts.
+    ///
+    /// Whoever gets the raw parts is responsible for dropping them through
+    /// the vtable eventually.
+    pub fn into_raw(self) -> RawWakeHandle {
+        let raw = RawWakeHandle {
+            data: self.raw.data,
+            vtable: self.raw.vtable,
+        };
+        ::std::mem::forget(self);
+        raw
+    }
+
+    /// Wakes this handle up.
+    pub fn wake(&self) {
+        unsafe { (self.raw.vtable.wake)(self.raw.data) }
+    }
+}
+
+impl Clone for WakeHandle {
+    fn clone(&self) -> WakeHandle {
+        WakeHandle { raw: unsafe { (self.raw.vtable.clone)(self.raw.data) } }
+    }
+}
+
+impl Drop for WakeHandle {
+    fn drop(&mut self) {
+        unsafe { (self.raw.vtable.drop)(self.raw.data) }
+    }
+}
+
+impl fmt::Debug for WakeHandle {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("WakeHandle")
+            .field("data", &self.raw.data)
+            .finish()
+    }
+}
+
+impl Notify for WakeHandle {
+    fn notify(&self, _id: usize) {
+        self.wake();
+    }
+}
+
+impl From<WakeHandle> for NotifyHandle {
+    fn from(handle: WakeHandle) -> NotifyHandle {
+        NotifyHandle::from(Arc::new(handle))
+    }
+}
+
+impl From<WakeHandle> for FfiWake {
+    fn from(handle: WakeHandle) -> FfiWake {
+        unsafe {
+            FfiWake::from_raw(Box::into_raw(Box::new(handle)) as *mut c_void,
+                              wake_handle,
+                              Some(drop_handle))
+        }
+    }
+}
+
+unsafe extern "C" fn wake_handle(data: *mut c_void) {
+    (*(data as *const WakeHandle)).wake();
+}
+
+unsafe extern "C" fn drop_handle(data: *mut c_void) {
+    drop(Box::from_raw(data as *mut WakeHandle));
+}
+
+// An `FfiWake` can't be cloned, so all of the clones of a `WakeHandle` made
+// out of one share it behind a boxed `Arc` instead, like the ones of a task.
+static FFI_VTABLE: WakeVtable = WakeVtable {
+    clone: clone_ffi,
+    wake: wake_ffi,
+    drop: drop_ffi,
+};
+
+unsafe extern "C" fn clone_ffi(data: *const c_void) -> RawWakeHandle {
+    let wake = (*(data as *const Arc<FfiWake>)).clone();
+    RawWakeHandle {
+        data: Box::into_raw(Box::new(wake)) as *const c_void,
+        vtable: &FFI_VTABLE,
+    }
+}
+
+unsafe extern "C" fn wake_ffi(data: *const c_void) {
+    (*(data as *const Arc<FfiWake>)).wake();
+}
+
+unsafe extern "C" fn drop_ffi(data: *const c_void) {
+    drop(Box::from_raw(data as *mut Arc<FfiWake>));
+}
+
+impl From<FfiWake> for WakeHandle {
+    fn from(wake: FfiWake) -> WakeHandle {
+        WakeHandle {
+            raw: RawWakeHandle {
+                data: Box::into_raw(Box::new(Arc::new(wake))) as *const c_void,
+                vtable: &FFI_VTABLE,
+            },
+        }
+    }
+}
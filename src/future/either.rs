@@ -1,7 +1,7 @@
-use {Future, Poll};
+use {Future, Poll, Stream, Sink, StartSend};
 
-/// Combines two different futures yielding the same item and error
-/// types into a single type.
+/// Combines two different futures, streams, or sinks having the same item and
+/// error types into a single type.
 #[derive(Debug)]
 pub enum Either<A, B> {
     /// First branch of the type
@@ -37,3 +37,54 @@ impl<A, B> Future for Either<A, B>
         }
     }
 }
+
+impl<A, B> Stream for Either<A, B>
+    where A: Stream,
+          B: Stream<Item = A::Item, Error = A::Error>
+{
+    type Item = A::Item;
+    type Error = A::Error;
+
+    fn poll(&mut self) -> Poll<Option<A::Item>, A::Error> {
+        match *self {
+            Either::A(ref mut a) => a.poll(),
+            Either::B(ref mut b) => b.poll(),
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match *self {
+            Either::A(ref a) => a.size_hint(),
+            Either::B(ref b) => b.size_hint(),
+        }
+    }
+}
+
+impl<A, B> Sink for Either<A, B>
+    where A: Sink,
+          B: Sink<SinkItem = A::SinkItem, SinkError = A::SinkError>
+{
+    type SinkItem = A::SinkItem;
+    type SinkError = A::SinkError;
+
+    fn start_send(&mut self, item: A::SinkItem) -> StartSend<A::SinkItem, A::SinkError> {
+        match *self {
+            Either::A(ref mut a) => a.start_send(item),
+            Either::B(ref mut b) => b.start_send(item),
+        }
+    }
+
+    fn poll_complete(&mut self) -> Poll<(), A::SinkError> {
+        match *self {
+            Either::A(ref mut a) => a.poll_complete(),
+            Either::B(ref mut b) => b.poll_complete(),
+        }
+    }
+
+    fn close(&mut self) -> Poll<(), A::SinkError> {
+        match *self {
+            Either::A(ref mut a) => a.close(),
+            Either::B(ref mut b) => b.close(),
+        }
+    }
+}
@@ -7,12 +7,7 @@ use std::fmt;
 use std::mem;
 
 use {Future, IntoFuture, Poll, Async};
-
-#[derive(Debug)]
-enum ElemState<T> where T: Future {
-    Pending(T),
-    Done(T::Item),
-}
+use stream::{FuturesUnordered, Stream};
 
 /// A future which takes a list of futures and resolves with a vector of the
 /// completed values.
@@ -23,7 +18,25 @@ pub struct JoinAll<I>
     where I: IntoIterator,
           I::Item: IntoFuture,
 {
-    elems: Vec<ElemState<<I::Item as IntoFuture>::Future>>,
+    pending: FuturesUnordered<Indexed<<I::Item as IntoFuture>::Future>>,
+    results: Vec<Option<<I::Item as IntoFuture>::Item>>,
+}
+
+// A child future along with its position in the input, so that results can
+// be put in order.
+struct Indexed<F> {
+    future: F,
+    index: usize,
+}
+
+impl<F: Future> Future for Indexed<F> {
+    type Item = (usize, F::Item);
+    type Error = F::Error;
+
+    fn poll(&mut self) -> Poll<Self::Item, Self::Error> {
+        let item = try_ready!(self.future.poll());
+        Ok(Async::Ready((self.index, item)))
+    }
 }
 
 impl<I> fmt::Debug for JoinAll<I>
@@ -34,7 +47,8 @@ impl<I> fmt::Debug for JoinAll<I>
 {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
         fmt.debug_struct("JoinAll")
-            .field("elems", &self.elems)
+            .field("pending", &self.pending.len())
+            .field("results", &self.results)
             .finish()
     }
 }
@@ -49,6 +63,11 @@ impl<I> fmt::Debug for JoinAll<I>
 /// successfully, however, then the returned future will succeed with a `Vec` of
 /// all the successful results.
 ///
+/// Each of the futures gets notifications of its own, so that only the
+/// futures which made progress are polled again. This keeps joining a large
+/// number of futures cheap even though any one of them may wake up the
+/// returned future.
+///
 /// # Examples
 ///
 /// ```
@@ -77,10 +96,18 @@ pub fn join_all<I>(i: I) -> JoinAll<I>
     where I: IntoIterator,
           I::Item: IntoFuture,
 {
-    let elems = i.into_iter().map(|f| {
-        ElemState::Pending(f.into_future())
-    }).collect();
-    JoinAll { elems: elems }
+    let mut pending = FuturesUnordered::new();
+    for (index, f) in i.into_iter().enumerate() {
+        pending.push(Indexed {
+            future: f.into_future(),
+            index: index,
+        });
+    }
+    let results = (0..pending.len()).map(|_| None).collect();
+    JoinAll {
+        pending: pending,
+        results: results,
+    }
 }
 
 impl<I> Future for JoinAll<I>
@@ -92,45 +119,24 @@ impl<I> Future for JoinAll<I>
 
 
     fn poll(&mut self) -> Poll<Self::Item, Self::Error> {
-        let mut all_done = true;
-
-        for idx in 0 .. self.elems.len() {
-            let done_val = match self.elems[idx] {
-                ElemState::Pending(ref mut t) => {
-                    match t.poll() {
-                        Ok(Async::Ready(v)) => Ok(v),
-                        Ok(Async::NotReady) => {
-                            all_done = false;
-                            continue
-                        }
-                        Err(e) => Err(e),
-                    }
-                }
-                ElemState::Done(ref mut _v) => continue,
-            };
-
-            match done_val {
-                Ok(v) => self.elems[idx] = ElemState::Done(v),
+        // Each child is notified on its own, so only the children which made
+        // progress are polled here.
+        loop {
+            match self.pending.poll() {
+                Ok(Async::Ready(Some((index, v)))) => self.results[index] = Some(v),
+                Ok(Async::Ready(None)) => break,
+                Ok(Async::NotReady) => return Ok(Async::NotReady),
                 Err(e) => {
                     // On completion drop all our associated resources
                     // ASAP.
-                    self.elems = Vec::new();
+                    self.pending = FuturesUnordered::new();
+                    self.results = Vec::new();
                     return Err(e)
                 }
             }
         }
 
-        if all_done {
-            let elems = mem::replace(&mut self.elems, Vec::new());
-            let result = elems.into_iter().map(|e| {
-                match e {
-                    ElemState::Done(t) => t,
-                    _ => unreachable!(),
-                }
-            }).collect();
-            Ok(Async::Ready(result))
-        } else {
-            Ok(Async::NotReady)
-        }
+        let results = mem::replace(&mut self.results, Vec::new());
+        Ok(Async::Ready(results.into_iter().map(|v| v.unwrap()).collect()))
     }
 }
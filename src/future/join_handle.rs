@@ -0,0 +1,321 @@
+use std::prelude::v1::*;
+
+use std::any::Any;
+use std::error::Error;
+use std::fmt;
+use std::panic;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use {Future, Poll, Async};
+use future::{Executor, ExecuteError};
+use task::{self, Task};
+use task_impl;
+
+/// Extension trait for executors, adding a way to get results back from
+/// spawned futures.
+///
+/// This is implemented for all types, but `spawn_with_handle` can only be
+/// called on `Executor`s able to spawn the `JoinTask`s it creates.
+pub trait SpawnExt {
+    /// Spawns `future` onto this executor, returning a `JoinHandle` which
+    /// resolves to the output of `future`.
+    ///
+    /// Unlike with `oneshot::spawn`, dropping the returned handle doesn't
+    /// cancel the spawned future, and the handle never panics if the future
+    /// doesn't run to completion. Instead it resolves to a `JoinError`
+    /// describing why the output is missing. The future can be canceled
+    /// through `JoinHandle::abort`, or when the handle is dropped after
+    /// calling `JoinHandle::abort_on_drop`.
+    ///
+    /// If the executor rejects the task then the original future is handed
+    /// back through the returned `ExecuteError`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use futures::prelude::*;
+    /// use futures::future::{self, SpawnExt};
+    /// use futures::executor::LocalPool;
+    ///
+    /// let mut pool = LocalPool::new();
+    /// let handle = pool.spawner()
+    ///     .spawn_with_handle(future::ok::<_, ()>(1).map(|i| i + 1))
+    ///     .unwrap();
+    /// assert_eq!(pool.run_until(handle).ok(), Some(2));
+    /// ```
+    fn spawn_with_handle<F>(&self, future: F)
+                            -> Result<JoinHandle<F::Item, F::Error>, ExecuteError<F>>
+        where F: Future,
+              Self: Executor<JoinTask<F>>,
+    {
+        let inner = Arc::new(Inner {
+            state: Mutex::new(State {
+                result: None,
+                panic: None,
+                complete: false,
+                aborted: false,
+                joiner: None,
+                runner: None,
+            }),
+        });
+        let task = JoinTask {
+            future: Some(future),
+            inner: inner.clone(),
+        };
+        match self.execute(task) {
+            Ok(()) => {
+                Ok(JoinHandle {
+                    inner: inner,
+                    abort_on_drop: false,
+                })
+            }
+            Err(e) => {
+                let kind = e.kind();
+                let mut task = e.into_future();
+                Err(ExecuteError::new(kind, task.future.take().unwrap()))
+            }
+        }
+    }
+}
+
+impl<T: ?Sized> SpawnExt for T {}
+
+/// A handle to a future spawned with `SpawnExt::spawn_with_handle`.
+///
+/// This is a future resolving to the output of the spawned future, or to a
+/// `JoinError` if the spawned future failed or didn't run to completion. If
+/// the spawned future panicked on an executor forwarding panics, such as a
+/// `LocalPool` with `PanicPolicy::Forward`, then polling the handle resumes
+/// the panic.
+///
+/// By default dropping the handle detaches the spawned future, which keeps
+/// running. With `abort_on_drop` dropping the handle aborts it instead, the
+/// same way on every executor.
+#[must_use = "futures do nothing unless polled"]
+pub struct JoinHandle<T, E> {
+    inner: Arc<Inner<T, E>>,
+    abort_on_drop: bool,
+}
+
+/// The task spawned by `SpawnExt::spawn_with_handle`, running a future and
+/// passing its output to the corresponding `JoinHandle`.
+#[must_use = "futures do nothing unless polled"]
+pub struct JoinTask<F: Future> {
+    future: Option<F>,
+    inner: Arc<Inner<F::Item, F::Error>>,
+}
+
+/// The error produced by a `JoinHandle` when the spawned future didn't
+/// produce a value.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum JoinError<E> {
+    /// The spawned future resolved to an error.
+    Failed(E),
+
+    /// The spawned future was canceled through `JoinHandle::abort`.
+    Aborted,
+
+    /// The spawned future was dropped by its executor before completing,
+    /// for example because the executor shut down.
+    Canceled,
+}
+
+struct Inner<T, E> {
+    state: Mutex<State<T, E>>,
+}
+
+struct State<T, E> {
+    result: Option<Result<T, E>>,
+    panic: Option<Box<Any + Send>>,
+    complete: bool,
+    aborted: bool,
+    joiner: Option<Task>,
+    runner: Option<Task>,
+}
+
+impl<T, E> Inner<T, E> {
+    fn lock<'a>(&'a self) -> MutexGuard<'a, State<T, E>> {
+        // Neither side panics while holding the lock, so the state is always
+        // consistent.
+        match self.state.lock() {
+            Ok(state) => state,
+            Err(e) => e.into_inner(),
+        }
+    }
+
+    fn complete(&self,
+                result: Option<Result<T, E>>,
+                panic: Option<Box<Any + Send>>) {
+        let joiner = {
+            let mut state = self.lock();
+            state.result = result;
+            state.panic = panic;
+            state.complete = true;
+            state.runner = None;
+            state.joiner.take()
+        };
+        if let Some(task) = joiner {
+            task.notify();
+        }
+    }
+}
+
+impl<T, E> JoinHandle<T, E> {
+    /// Requests the spawned future to be canceled.
+    ///
+    /// The spawned future is dropped the next time its task is polled, and
+    /// this handle then resolves to `JoinError::Aborted`. The task is
+    /// notified so this happens even if the spawned future is blocked on
+    /// something else. Nothing happens if the spawned future has already
+    /// completed.
+    pub fn abort(&self) {
+        let runner = {
+            let mut state = self.inner.lock();
+            if state.complete {
+                return
+            }
+            state.aborted = true;
+            state.runner.take()
+        };
+        if let Some(task) = runner {
+            task.notify();
+        }
+    }
+
+    /// Makes dropping this handle abort the spawned future, rather than
+    /// detaching it.
+    ///
+    /// This is handy to tie background work to the lifetime of whatever
+    /// holds the handle, so that it doesn't outlive it when shutting down.
+    pub fn abort_on_drop(mut self) -> JoinHandle<T, E> {
+        self.abort_on_drop = true;
+        self
+    }
+
+    /// Returns whether the spawned future has finished, in which case this
+    /// handle resolves right away.
+    ///
+    /// A future which was aborted or dropped by its executor counts as
+    /// finished as well.
+    pub fn is_finished(&self) -> bool {
+        self.inner.lock().complete
+    }
+}
+
+impl<T, E> Future for JoinHandle<T, E> {
+    type Item = T;
+    type Error = JoinError<E>;
+
+    fn poll(&mut self) -> Poll<T, JoinError<E>> {
+        let mut state = self.inner.lock();
+        if !state.complete {
+            state.joiner = Some(task::current());
+            return Ok(Async::NotReady)
+        }
+        if let Some(payload) = state.panic.take() {
+            drop(state);
+            panic::resume_unwind(payload)
+        }
+        match state.result.take() {
+            Some(Ok(t)) => Ok(Async::Ready(t)),
+            Some(Err(e)) => Err(JoinError::Failed(e)),
+            None if state.aborted => Err(JoinError::Aborted),
+            None => Err(JoinError::Canceled),
+        }
+    }
+}
+
+impl<T, E> fmt::Debug for JoinHandle<T, E> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("JoinHandle")
+            .field("finished", &self.is_finished())
+            .field("abort_on_drop", &self.abort_on_drop)
+            .finish()
+    }
+}
+
+impl<T, E> Drop for JoinHandle<T, E> {
+    fn drop(&mut self) {
+        if self.abort_on_drop {
+            self.abort();
+        }
+    }
+}
+
+impl<F: Future> Future for JoinTask<F> {
+    type Item = ();
+    type Error = ();
+
+    fn poll(&mut self) -> Poll<(), ()> {
+        {
+            let mut state = self.inner.lock();
+            if state.aborted {
+                drop(state);
+                self.future = None;
+                self.inner.complete(None, None);
+                return Ok(Async::Ready(()))
+            }
+            let current = match state.runner {
+                Some(ref task) => task.will_notify_current(),
+                None => false,
+            };
+            if !current {
+                state.runner = Some(task::current());
+            }
+        }
+
+        let result = match self.future.as_mut().expect("cannot poll JoinTask twice").poll() {
+            Ok(Async::NotReady) => return Ok(Async::NotReady),
+            Ok(Async::Ready(t)) => Ok(t),
+            Err(e) => Err(e),
+        };
+        self.future = None;
+        self.inner.complete(Some(result), None);
+        Ok(Async::Ready(()))
+    }
+}
+
+impl<F: Future + fmt::Debug> fmt::Debug for JoinTask<F> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("JoinTask")
+            .field("future", &self.future)
+            .finish()
+    }
+}
+
+impl<F: Future> Drop for JoinTask<F> {
+    fn drop(&mut self) {
+        // Executors forward the panic of the task being dropped with
+        // `PanicPolicy::Forward`.
+        if self.future.is_some() {
+            self.inner.complete(None, task_impl::take_forwarded_panic());
+        }
+    }
+}
+
+impl<E: fmt::Display> fmt::Display for JoinError<E> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            JoinError::Failed(ref e) => write!(fmt, "spawned future failed: {}", e),
+            JoinError::Aborted => write!(fmt, "spawned future was aborted"),
+            JoinError::Canceled => write!(fmt, "spawned future was canceled"),
+        }
+    }
+}
+
+impl<E: Error> Error for JoinError<E> {
+    fn description(&self) -> &str {
+        match *self {
+            JoinError::Failed(_) => "spawned future failed",
+            JoinError::Aborted => "spawned future was aborted",
+            JoinError::Canceled => "spawned future was canceled",
+        }
+    }
+
+    fn cause(&self) -> Option<&Error> {
+        match *self {
+            JoinError::Failed(ref e) => Some(e),
+            _ => None,
+        }
+    }
+}
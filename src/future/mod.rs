@@ -81,13 +81,19 @@ if_std! {
     mod catch_unwind;
     mod join_all;
     mod select_all;
-    mod select_ok;
+    mod select_set;
     mod shared;
+    mod join_handle;
+    mod with_local;
+    mod timeout;
     pub use self::catch_unwind::CatchUnwind;
     pub use self::join_all::{join_all, JoinAll};
     pub use self::select_all::{SelectAll, SelectAllNext, select_all};
-    pub use self::select_ok::{SelectOk, select_ok};
+    pub use self::select_set::{SelectSet, select_set};
     pub use self::shared::{Shared, SharedItem, SharedError};
+    pub use self::with_local::WithLocal;
+    pub use self::timeout::{Timeout, TimeoutError};
+    pub use self::join_handle::{SpawnExt, JoinHandle, JoinTask, JoinError};
 
     #[doc(hidden)]
     #[deprecated(since = "0.1.4", note = "use join_all instead")]
@@ -97,15 +103,20 @@ if_std! {
     #[deprecated(since = "0.1.4", note = "use JoinAll instead")]
     #[cfg(feature = "with-deprecated")]
     pub use self::join_all::JoinAll as Collect;
+}
+
+if_alloc! {
+    mod select_ok;
+    pub use self::select_ok::{SelectOk, select_ok};
 
     /// A type alias for `Box<Future + Send>`
     #[doc(hidden)]
     #[deprecated(note = "removed without replacement, recommended to use a \
                          local extension trait or function if needed, more \
                          details in #228")]
-    pub type BoxFuture<T, E> = ::std::boxed::Box<Future<Item = T, Error = E> + Send>;
+    pub type BoxFuture<T, E> = ::alloc::boxed::Box<Future<Item = T, Error = E> + Send>;
 
-    impl<F: ?Sized + Future> Future for ::std::boxed::Box<F> {
+    impl<F: ?Sized + Future> Future for ::alloc::boxed::Box<F> {
         type Item = F::Item;
         type Error = F::Error;
 
@@ -113,9 +124,31 @@ if_std! {
             (**self).poll()
         }
     }
+
+    impl<E, F> Executor<F> for ::alloc::boxed::Box<E>
+        where E: ?Sized + Executor<F>,
+              F: Future<Item = (), Error = ()>,
+    {
+        fn execute(&self, future: F) -> Result<(), ExecuteError<F>> {
+            (**self).execute(future)
+        }
+    }
+
+    impl<E, F> Executor<F> for ::alloc::sync::Arc<E>
+        where E: ?Sized + Executor<F>,
+              F: Future<Item = (), Error = ()>,
+    {
+        fn execute(&self, future: F) -> Result<(), ExecuteError<F>> {
+            (**self).execute(future)
+        }
+    }
 }
 
 use {Poll, stream};
+#[cfg(feature = "use_std")]
+use std::time::Duration;
+#[cfg(feature = "use_std")]
+use timer::Timer;
 
 /// Trait for types which are a placeholder of a value that may become
 /// available at some later point in time.
@@ -292,6 +325,11 @@ pub trait Future {
     ///
     /// This function does not attempt to catch panics. If the `poll` function
     /// of this future panics, panics will be propagated to the caller.
+    ///
+    /// This function panics if it's called from within a task, such as from
+    /// the `poll` function of another future, or on a thread which is running
+    /// an executor. Blocking there would stall the executor and deadlock if
+    /// this future depends on it to make progress.
     #[cfg(feature = "use_std")]
     fn wait(self) -> result::Result<Self::Item, Self::Error>
         where Self: Sized
@@ -308,8 +346,8 @@ pub trait Future {
     /// also encodes this. If you'd like to create a `Box<Future>` without the
     /// `Send` bound, then the `Box::new` function can be used instead.
     ///
-    /// This method is only available when the `use_std` feature of this
-    /// library is activated, and it is activated by default.
+    /// This method is only available when the `alloc` feature of this
+    /// library is activated, which the default `use_std` feature implies.
     ///
     /// # Examples
     ///
@@ -319,7 +357,7 @@ pub trait Future {
     ///
     /// let a: BoxFuture<i32, i32> = result(Ok(1)).boxed();
     /// ```
-    #[cfg(feature = "use_std")]
+    #[cfg(feature = "alloc")]
     #[doc(hidden)]
     #[deprecated(note = "removed without replacement, recommended to use a \
                          local extension trait or function if needed, more \
@@ -328,7 +366,7 @@ pub trait Future {
     fn boxed(self) -> BoxFuture<Self::Item, Self::Error>
         where Self: Sized + Send + 'static
     {
-        ::std::boxed::Box::new(self)
+        ::alloc::boxed::Box::new(self)
     }
 
     /// Map this future's result to a different type, returning a new future of
@@ -1012,6 +1050,137 @@ pub trait Future {
     {
         shared::new(self)
     }
+
+    /// Makes `value` available to this future and all futures nested within
+    /// it through `task::get_local`.
+    ///
+    /// Values are keyed by their type, so request-scoped context such as a
+    /// trace ID can be read anywhere down the chain of combinators without
+    /// being threaded through every function. The value is only visible
+    /// while the returned future is being polled, and a nested `with_local`
+    /// with a value of the same type shadows this one.
+    ///
+    /// This method is only available when the `use_std` feature of this
+    /// library is activated, and it is activated by default.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use futures::prelude::*;
+    /// use futures::future;
+    /// use futures::task;
+    ///
+    /// #[derive(Clone)]
+    /// struct User(&'static str);
+    ///
+    /// fn greet() -> Box<Future<Item = String, Error = ()>> {
+    ///     Box::new(future::lazy(|| {
+    ///         let user = task::get_local::<User>().unwrap();
+    ///         Ok(format!("hello, {}", user.0))
+    ///     }))
+    /// }
+    ///
+    /// let f = greet().with_local(User("alice"));
+    /// assert_eq!(f.wait(), Ok("hello, alice".to_string()));
+    /// ```
+    #[cfg(feature = "use_std")]
+    fn with_local<T>(self, value: T) -> WithLocal<Self, T>
+        where T: ::std::any::Any,
+              Self: Sized
+    {
+        with_local::new(self, value)
+    }
+
+    /// Fails this future with `TimeoutError::Elapsed` unless it resolves
+    /// within `duration`.
+    ///
+    /// The timeout is tracked with a delay obtained from `timer` right away.
+    /// If this future is ready by the time the timeout elapses its result
+    /// wins. Errors of this future are passed through as
+    /// `TimeoutError::Inner`.
+    ///
+    /// This method is only available when the `use_std` feature of this
+    /// library is activated, and it is activated by default.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::Duration;
+    ///
+    /// use futures::prelude::*;
+    /// use futures::future::{self, TimeoutError};
+    /// use futures::timer::ThreadTimer;
+    ///
+    /// let timer = ThreadTimer::new();
+    /// let never = future::empty::<(), ()>();
+    /// let res = never.timeout(Duration::from_millis(10), &timer).wait();
+    /// assert_eq!(res, Err(TimeoutError::Elapsed));
+    /// ```
+    #[cfg(feature = "use_std")]
+    fn timeout<T>(self, duration: Duration, timer: &T) -> Timeout<Self, T::Delay>
+        where T: Timer,
+              Self: Sized
+    {
+        timeout::new(self, timer.delay(duration))
+    }
+
+    /// Wraps this future in an `Either` future, making it the left-hand
+    /// variant of that `Either`.
+    ///
+    /// This can be used in combination with the `right_future` method to
+    /// write `if` statements that evaluate to different futures in different
+    /// branches.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use futures::prelude::*;
+    /// use futures::future;
+    ///
+    /// let x = 6;
+    /// let future = if x < 10 {
+    ///     future::ok::<_, bool>(x).left_future()
+    /// } else {
+    ///     future::empty().right_future()
+    /// };
+    ///
+    /// assert_eq!(x, future.wait().unwrap());
+    /// ```
+    fn left_future<B>(self) -> Either<Self, B>
+        where B: Future<Item = Self::Item, Error = Self::Error>,
+              Self: Sized
+    {
+        Either::A(self)
+    }
+
+    /// Wraps this future in an `Either` future, making it the right-hand
+    /// variant of that `Either`.
+    ///
+    /// This can be used in combination with the `left_future` method to write
+    /// `if` statements that evaluate to different futures in different
+    /// branches.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use futures::prelude::*;
+    /// use futures::future;
+    ///
+    /// let x = 6;
+    /// let future = if x < 10 {
+    ///     future::ok::<_, bool>(x).left_future()
+    /// } else {
+    ///     future::empty().right_future()
+    /// };
+    ///
+    /// assert_eq!(x, future.wait().unwrap());
+    /// ```
+    fn right_future<A>(self) -> Either<A, Self>
+        where A: Future<Item = Self::Item, Error = Self::Error>,
+              Self: Sized
+    {
+        Either::B(self)
+    }
 }
 
 impl<'a, F: ?Sized + Future> Future for &'a mut F {
@@ -1115,6 +1284,15 @@ pub trait Executor<F: Future<Item = (), Error = ()>> {
     fn execute(&self, future: F) -> Result<(), ExecuteError<F>>;
 }
 
+impl<'a, E, F> Executor<F> for &'a E
+    where E: ?Sized + Executor<F>,
+          F: Future<Item = (), Error = ()>,
+{
+    fn execute(&self, future: F) -> Result<(), ExecuteError<F>> {
+        (**self).execute(future)
+    }
+}
+
 /// Errors returned from the `Spawn::spawn` function.
 pub struct ExecuteError<F> {
     future: F,
@@ -26,6 +26,10 @@ pub type SelectAllNext<A> = A;
 /// index of the future that was ready and the list of all the remaining
 /// futures.
 ///
+/// All of the futures are polled each time, so selecting over the remaining
+/// futures over and over again gets expensive for long lists. `select_set`
+/// only polls the futures which made progress instead.
+///
 /// # Panics
 ///
 /// This function will panic if the iterator specified contains no items.
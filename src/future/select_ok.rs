@@ -1,8 +1,9 @@
 //! Definition of the `SelectOk` combinator, finding the first successful future
 //! in a list.
 
-use std::mem;
-use std::prelude::v1::*;
+use core::mem;
+
+use alloc::vec::Vec;
 
 use {Future, IntoFuture, Poll, Async};
 
@@ -0,0 +1,144 @@
+//! Definition of the `SelectSet`, repeatedly finding the next future in a set
+//! that finishes.
+
+use std::fmt;
+use std::mem;
+
+use {Future, IntoFuture, Poll, Async};
+use stream::{FuturesUnordered, Stream};
+
+/// Future for the `select_set` combinator, waiting for one of any of a set of
+/// futures to complete.
+///
+/// This is created by the `select_set` function. Once one of the futures
+/// completes, the remaining ones are handed back as another `SelectSet`,
+/// which can be polled again to wait for the next one.
+#[must_use = "futures do nothing unless polled"]
+pub struct SelectSet<A> where A: Future {
+    inner: FuturesUnordered<Indexed<A>>,
+    next_index: usize,
+}
+
+// A future in the set along with the index it was added under.
+struct Indexed<F> {
+    future: F,
+    index: usize,
+}
+
+impl<F: Future> Future for Indexed<F> {
+    type Item = (usize, F::Item);
+    type Error = (usize, F::Error);
+
+    fn poll(&mut self) -> Poll<Self::Item, Self::Error> {
+        match self.future.poll() {
+            Ok(Async::Ready(item)) => Ok(Async::Ready((self.index, item))),
+            Ok(Async::NotReady) => Ok(Async::NotReady),
+            Err(e) => Err((self.index, e)),
+        }
+    }
+}
+
+/// Creates a new future which will select over a set of futures.
+///
+/// This works like `select_all`, except that the remaining futures are handed
+/// back as another `SelectSet` rather than a `Vec`. Each of the futures gets
+/// notifications of its own, so that only the futures which made progress are
+/// polled again, and the remaining set keeps track of that as well. Selecting
+/// over the remaining futures over and over again thus stays cheap however
+/// many of them there are.
+///
+/// The index resolved along with the item is the position of the future
+/// within `iter`, and it stays the same across the `SelectSet`s returned this
+/// way.
+///
+/// # Panics
+///
+/// This function will panic if the iterator specified contains no items.
+///
+/// # Examples
+///
+/// ```
+/// use futures::prelude::*;
+/// use futures::future::{self, select_set};
+///
+/// let futures: Vec<Box<Future<Item = u32, Error = ()>>> = vec![
+///     Box::new(future::empty()),
+///     Box::new(future::ok(2)),
+///     Box::new(future::ok(3)),
+/// ];
+/// let (item, idx, rest) = select_set(futures).wait().ok().unwrap();
+/// assert_eq!((item, idx, rest.len()), (2, 1, 2));
+///
+/// let (item, idx, rest) = rest.wait().ok().unwrap();
+/// assert_eq!((item, idx, rest.len()), (3, 2, 1));
+/// ```
+pub fn select_set<I>(iter: I) -> SelectSet<<I::Item as IntoFuture>::Future>
+    where I: IntoIterator,
+          I::Item: IntoFuture,
+{
+    let mut ret = SelectSet {
+        inner: FuturesUnordered::new(),
+        next_index: 0,
+    };
+    for f in iter {
+        ret.push(f.into_future());
+    }
+    assert!(!ret.is_empty());
+    ret
+}
+
+impl<A> SelectSet<A> where A: Future {
+    /// Adds another future to select over, returning its index.
+    pub fn push(&mut self, future: A) -> usize {
+        let index = self.next_index;
+        self.next_index += 1;
+        self.inner.push(Indexed {
+            future: future,
+            index: index,
+        });
+        index
+    }
+
+    /// Returns the number of futures to select over.
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    /// Returns `true` if there are no futures left to select over.
+    ///
+    /// Polling a `SelectSet` in this state panics.
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    fn take(&mut self) -> SelectSet<A> {
+        mem::replace(self, SelectSet {
+            inner: FuturesUnordered::new(),
+            next_index: self.next_index,
+        })
+    }
+}
+
+impl<A> fmt::Debug for SelectSet<A> where A: Future {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("SelectSet")
+            .field("len", &self.inner.len())
+            .finish()
+    }
+}
+
+impl<A> Future for SelectSet<A>
+    where A: Future,
+{
+    type Item = (A::Item, usize, SelectSet<A>);
+    type Error = (A::Error, usize, SelectSet<A>);
+
+    fn poll(&mut self) -> Poll<Self::Item, Self::Error> {
+        match self.inner.poll() {
+            Ok(Async::Ready(Some((idx, e)))) => Ok(Async::Ready((e, idx, self.take()))),
+            Ok(Async::Ready(None)) => panic!("cannot poll SelectSet without any futures"),
+            Ok(Async::NotReady) => Ok(Async::NotReady),
+            Err((idx, e)) => Err((e, idx, self.take())),
+        }
+    }
+}
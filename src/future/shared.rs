@@ -14,22 +14,21 @@
 //! ```
 
 use {Future, Poll, Async};
-use task::{self, Task};
+use task::AtomicTask;
 use executor::{self, Notify, Spawn};
 
-use std::{fmt, mem, ops};
+use std::{fmt, ops, ptr};
 use std::cell::UnsafeCell;
-use std::sync::{Arc, Mutex};
-use std::sync::atomic::AtomicUsize;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicPtr, AtomicUsize};
 use std::sync::atomic::Ordering::SeqCst;
-use std::collections::HashMap;
 
 /// A future that is cloneable and can be polled in multiple threads.
 /// Use `Future::shared()` method to convert any future into a `Shared` future.
 #[must_use = "futures do nothing unless polled"]
 pub struct Shared<F: Future> {
     inner: Arc<Inner<F>>,
-    waiter: usize,
+    waiter: Arc<Waiter>,
 }
 
 impl<F> fmt::Debug for Shared<F>
@@ -40,13 +39,11 @@ impl<F> fmt::Debug for Shared<F>
     fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
         fmt.debug_struct("Shared")
             .field("inner", &self.inner)
-            .field("waiter", &self.waiter)
             .finish()
     }
 }
 
 struct Inner<F: Future> {
-    next_clone_id: AtomicUsize,
     future: UnsafeCell<Option<Spawn<F>>>,
     result: UnsafeCell<Option<Result<SharedItem<F::Item>, SharedError<F::Error>>>>,
     notifier: Arc<Notifier>,
@@ -54,9 +51,29 @@ struct Inner<F: Future> {
 
 struct Notifier {
     state: AtomicUsize,
-    waiters: Mutex<HashMap<usize, Task>>,
+    // A lock-free stack of the waiters to notify, which is only ever pushed
+    // onto or taken as a whole. Each entry owns a reference to its waiter.
+    waiters: AtomicPtr<Waiter>,
+    // Roughly the number of clones dropped while being in `waiters`.
+    dead: AtomicUsize,
 }
 
+// The registration of a single clone. A clone only pushes its waiter onto
+// the stack if it isn't on it already, which the notifier resets when it
+// takes the stack.
+struct Waiter {
+    task: AtomicTask,
+    next: AtomicPtr<Waiter>,
+    queued: AtomicBool,
+    dropped: AtomicBool,
+}
+
+// Once this many clones have been dropped while waiting, the live waiters are
+// notified so the stack is rebuilt without the dead ones. Otherwise clones
+// which are repeatedly created and dropped while the future doesn't make
+// progress would pile up.
+const MAX_DEAD_WAITERS: usize = 64;
+
 const IDLE: usize = 0;
 const POLLING: usize = 1;
 const REPOLL: usize = 2;
@@ -66,15 +83,15 @@ const POISONED: usize = 4;
 pub fn new<F: Future>(future: F) -> Shared<F> {
     Shared {
         inner: Arc::new(Inner {
-            next_clone_id: AtomicUsize::new(1),
             notifier: Arc::new(Notifier {
                 state: AtomicUsize::new(IDLE),
-                waiters: Mutex::new(HashMap::new()),
+                waiters: AtomicPtr::new(ptr::null_mut()),
+                dead: AtomicUsize::new(0),
             }),
             future: UnsafeCell::new(Some(executor::spawn(future))),
             result: UnsafeCell::new(None),
         }),
-        waiter: 0,
+        waiter: Waiter::new(),
     }
 }
 
@@ -101,8 +118,10 @@ impl<F> Shared<F> where F: Future {
     }
 
     fn set_waiter(&mut self) {
-        let mut waiters = self.inner.notifier.waiters.lock().unwrap();
-        waiters.insert(self.waiter, task::current());
+        self.waiter.task.register();
+        if !self.waiter.queued.swap(true, SeqCst) {
+            self.inner.notifier.push(&self.waiter);
+        }
     }
 
     unsafe fn clone_result(&self) -> Result<SharedItem<F::Item>, SharedError<F::Error>> {
@@ -207,30 +226,83 @@ impl<F> Future for Shared<F>
 
 impl<F> Clone for Shared<F> where F: Future {
     fn clone(&self) -> Self {
-        let next_clone_id = self.inner.next_clone_id.fetch_add(1, SeqCst);
-
         Shared {
             inner: self.inner.clone(),
-            waiter: next_clone_id,
+            waiter: Waiter::new(),
         }
     }
 }
 
 impl<F> Drop for Shared<F> where F: Future {
     fn drop(&mut self) {
-        let mut waiters = self.inner.notifier.waiters.lock().unwrap();
-        waiters.remove(&self.waiter);
+        // The waiter stays on the stack until it's taken, but won't be
+        // notified anymore.
+        self.waiter.dropped.store(true, SeqCst);
+        if self.waiter.queued.load(SeqCst) {
+            let notifier = &self.inner.notifier;
+            if notifier.dead.fetch_add(1, SeqCst) + 1 >= MAX_DEAD_WAITERS {
+                notifier.notify_waiters();
+            }
+        }
+    }
+}
+
+impl Waiter {
+    fn new() -> Arc<Waiter> {
+        Arc::new(Waiter {
+            task: AtomicTask::new(),
+            next: AtomicPtr::new(ptr::null_mut()),
+            queued: AtomicBool::new(false),
+            dropped: AtomicBool::new(false),
+        })
+    }
+}
+
+impl Notifier {
+    fn push(&self, waiter: &Arc<Waiter>) {
+        let node = Arc::into_raw(waiter.clone()) as *mut Waiter;
+        let mut head = self.waiters.load(SeqCst);
+        loop {
+            // Nobody else touches `next` while the waiter is being pushed,
+            // as it's only ever on the stack once.
+            waiter.next.store(head, SeqCst);
+            match self.waiters.compare_exchange(head, node, SeqCst, SeqCst) {
+                Ok(_) => return,
+                Err(actual) => head = actual,
+            }
+        }
+    }
+
+    // Takes the whole stack and notifies all of the live waiters on it.
+    fn notify_waiters(&self) {
+        self.dead.store(0, SeqCst);
+        let mut node = self.waiters.swap(ptr::null_mut(), SeqCst);
+        while !node.is_null() {
+            let waiter = unsafe { Arc::from_raw(node as *const Waiter) };
+            node = waiter.next.load(SeqCst);
+            // Resetting `queued` before notifying makes sure that a clone
+            // registering concurrently either gets pushed again or notified.
+            waiter.queued.store(false, SeqCst);
+            if !waiter.dropped.load(SeqCst) {
+                waiter.task.notify();
+            }
+        }
     }
 }
 
 impl Notify for Notifier {
     fn notify(&self, _id: usize) {
         self.state.compare_and_swap(POLLING, REPOLL, SeqCst);
+        self.notify_waiters();
+    }
+}
 
-        let waiters = mem::replace(&mut *self.waiters.lock().unwrap(), HashMap::new());
-
-        for (_, waiter) in waiters {
-            waiter.notify();
+impl Drop for Notifier {
+    fn drop(&mut self) {
+        let mut node = *self.waiters.get_mut();
+        while !node.is_null() {
+            let waiter = unsafe { Arc::from_raw(node as *const Waiter) };
+            node = waiter.next.load(SeqCst);
         }
     }
 }
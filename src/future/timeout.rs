@@ -0,0 +1,103 @@
+use std::error::Error;
+use std::fmt;
+
+use {Async, Future, Poll};
+use timer::{Delay, TimerError};
+
+/// Future for the `timeout` combinator, failing a future which doesn't
+/// resolve in time.
+///
+/// This is created by the `Future::timeout` method.
+#[derive(Debug)]
+#[must_use = "futures do nothing unless polled"]
+pub struct Timeout<F, D> {
+    future: F,
+    delay: D,
+}
+
+/// Error yielded by a `Timeout` future.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TimeoutError<E> {
+    /// The future didn't resolve before the timeout elapsed.
+    Elapsed,
+
+    /// The timer failed, so the timeout can't be enforced.
+    Timer(TimerError),
+
+    /// The underlying future failed.
+    Inner(E),
+}
+
+impl<E: fmt::Display> fmt::Display for TimeoutError<E> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            TimeoutError::Elapsed => write!(fmt, "timeout has elapsed"),
+            TimeoutError::Timer(ref e) => e.fmt(fmt),
+            TimeoutError::Inner(ref e) => e.fmt(fmt),
+        }
+    }
+}
+
+impl<E: Error> Error for TimeoutError<E> {
+    fn description(&self) -> &str {
+        match *self {
+            TimeoutError::Elapsed => "timeout has elapsed",
+            TimeoutError::Timer(_) => "timer is no longer running",
+            TimeoutError::Inner(_) => "future returned an error",
+        }
+    }
+}
+
+pub fn new<F, D>(future: F, delay: D) -> Timeout<F, D>
+    where F: Future,
+          D: Delay,
+{
+    Timeout {
+        future: future,
+        delay: delay,
+    }
+}
+
+impl<F, D> Timeout<F, D> {
+    /// Acquires a reference to the underlying future.
+    pub fn get_ref(&self) -> &F {
+        &self.future
+    }
+
+    /// Acquires a mutable reference to the underlying future.
+    ///
+    /// Note that care must be taken to avoid tampering with the state of the
+    /// future which may otherwise confuse this combinator.
+    pub fn get_mut(&mut self) -> &mut F {
+        &mut self.future
+    }
+
+    /// Consumes this combinator, returning the underlying future.
+    pub fn into_inner(self) -> F {
+        self.future
+    }
+}
+
+impl<F, D> Future for Timeout<F, D>
+    where F: Future,
+          D: Delay,
+{
+    type Item = F::Item;
+    type Error = TimeoutError<F::Error>;
+
+    fn poll(&mut self) -> Poll<F::Item, TimeoutError<F::Error>> {
+        // The future gets the first chance, so one which is ready by the
+        // time the timeout elapses still wins.
+        match self.future.poll() {
+            Ok(Async::NotReady) => {}
+            Ok(Async::Ready(item)) => return Ok(Async::Ready(item)),
+            Err(e) => return Err(TimeoutError::Inner(e)),
+        }
+
+        match self.delay.poll() {
+            Ok(Async::NotReady) => Ok(Async::NotReady),
+            Ok(Async::Ready(())) => Err(TimeoutError::Elapsed),
+            Err(e) => Err(TimeoutError::Timer(e)),
+        }
+    }
+}
@@ -0,0 +1,47 @@
+use std::any::Any;
+
+use {Future, Poll};
+use task_impl;
+
+/// Future for the `with_local` combinator, making a value available through
+/// `task::get_local` while the future is being polled.
+///
+/// This is created by the `Future::with_local` method.
+#[derive(Debug)]
+#[must_use = "futures do nothing unless polled"]
+pub struct WithLocal<F, T> {
+    future: F,
+    value: T,
+}
+
+pub fn new<F, T>(future: F, value: T) -> WithLocal<F, T> {
+    WithLocal {
+        future: future,
+        value: value,
+    }
+}
+
+impl<F, T> WithLocal<F, T> {
+    /// Returns a reference to the value made available by this future.
+    pub fn local(&self) -> &T {
+        &self.value
+    }
+
+    /// Consumes this combinator, returning the underlying future.
+    pub fn into_inner(self) -> F {
+        self.future
+    }
+}
+
+impl<F, T> Future for WithLocal<F, T>
+    where F: Future,
+          T: Any,
+{
+    type Item = F::Item;
+    type Error = F::Error;
+
+    fn poll(&mut self) -> Poll<F::Item, F::Error> {
+        let WithLocal { ref mut future, ref value } = *self;
+        task_impl::set_local(value, || future.poll())
+    }
+}
@@ -0,0 +1,104 @@
+use std::fmt;
+use std::io;
+use std::prelude::v1::*;
+
+use {Async, Future, Poll};
+use io::{AsyncRead, AsyncWrite};
+
+/// A future copying all of the bytes of a reader into a writer.
+///
+/// This is created by the `copy` function.
+#[must_use = "futures do nothing unless polled"]
+pub struct Copy<R, W> {
+    reader: Option<R>,
+    read_done: bool,
+    writer: Option<W>,
+    pos: usize,
+    cap: usize,
+    amt: u64,
+    buf: Box<[u8]>,
+}
+
+/// Creates a future copying all of the bytes of `reader` into `writer`.
+///
+/// Bytes are read into a buffer of a few kilobytes, which is written out
+/// before more bytes are read. Once the end of `reader` is reached and all
+/// of the bytes have been written, `writer` is flushed and the future
+/// resolves to the number of bytes copied along with `reader` and `writer`.
+///
+/// # Examples
+///
+/// ```
+/// use futures::prelude::*;
+/// use futures::io;
+///
+/// let (n, _, out) = io::copy(&b"hello"[..], Vec::new()).wait().unwrap();
+/// assert_eq!(n, 5);
+/// assert_eq!(out, b"hello");
+/// ```
+pub fn copy<R, W>(reader: R, writer: W) -> Copy<R, W>
+    where R: AsyncRead,
+          W: AsyncWrite,
+{
+    Copy {
+        reader: Some(reader),
+        read_done: false,
+        writer: Some(writer),
+        pos: 0,
+        cap: 0,
+        amt: 0,
+        buf: Box::new([0; 8 * 1024]),
+    }
+}
+
+impl<R, W> Future for Copy<R, W>
+    where R: AsyncRead,
+          W: AsyncWrite,
+{
+    type Item = (u64, R, W);
+    type Error = io::Error;
+
+    fn poll(&mut self) -> Poll<(u64, R, W), io::Error> {
+        loop {
+            // Refill the buffer once everything in it has been written.
+            if self.pos == self.cap && !self.read_done {
+                let reader = self.reader.as_mut().expect("poll a Copy after it's done");
+                let n = try_ready!(reader.poll_read(&mut self.buf));
+                if n == 0 {
+                    self.read_done = true;
+                } else {
+                    self.pos = 0;
+                    self.cap = n;
+                }
+            }
+
+            while self.pos < self.cap {
+                let writer = self.writer.as_mut().unwrap();
+                let n = try_ready!(writer.poll_write(&self.buf[self.pos..self.cap]));
+                if n == 0 {
+                    return Err(io::Error::new(io::ErrorKind::WriteZero,
+                                              "write zero byte into writer"))
+                }
+                self.pos += n;
+                self.amt += n as u64;
+            }
+
+            if self.pos == self.cap && self.read_done {
+                try_ready!(self.writer.as_mut().unwrap().poll_flush());
+                let reader = self.reader.take().unwrap();
+                let writer = self.writer.take().unwrap();
+                return Ok(Async::Ready((self.amt, reader, writer)))
+            }
+        }
+    }
+}
+
+impl<R: fmt::Debug, W: fmt::Debug> fmt::Debug for Copy<R, W> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("Copy")
+            .field("reader", &self.reader)
+            .field("writer", &self.writer)
+            .field("amt", &self.amt)
+            .finish()
+    }
+}
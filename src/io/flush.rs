@@ -0,0 +1,29 @@
+use std::io;
+
+use {Async, Future, Poll};
+use io::AsyncWrite;
+
+/// A future flushing an I/O object.
+///
+/// This is created by the `flush` function.
+#[derive(Debug)]
+#[must_use = "futures do nothing unless polled"]
+pub struct Flush<A> {
+    a: Option<A>,
+}
+
+/// Creates a future flushing the bytes written to `a`, resolving to `a`
+/// once that's done.
+pub fn flush<A: AsyncWrite>(a: A) -> Flush<A> {
+    Flush { a: Some(a) }
+}
+
+impl<A: AsyncWrite> Future for Flush<A> {
+    type Item = A;
+    type Error = io::Error;
+
+    fn poll(&mut self) -> Poll<A, io::Error> {
+        try_ready!(self.a.as_mut().expect("poll a Flush after it's done").poll_flush());
+        Ok(Async::Ready(self.a.take().unwrap()))
+    }
+}
@@ -0,0 +1,185 @@
+//! Asynchronous byte I/O.
+//!
+//! This module defines the `AsyncRead` and `AsyncWrite` traits, the
+//! equivalents of `std::io::Read` and `std::io::Write` for objects which
+//! aren't always ready to transfer bytes. Rather than blocking the thread,
+//! their methods return `Async::NotReady` and arrange for the current task
+//! to be notified once they can make progress, just like `Future::poll`.
+//!
+//! Building on top of those, the functions in this module, such as
+//! `read_exact`, `write_all` or `copy`, return futures performing an entire
+//! operation. They take ownership of the I/O objects and buffers they work
+//! with, and hand them back once they're done.
+//!
+//! This module is only available when the `use_std` feature of this library
+//! is activated, and it is activated by default.
+
+use std::io;
+use std::prelude::v1::*;
+
+use {Async, Poll};
+
+mod copy;
+mod flush;
+mod read_exact;
+mod shutdown;
+mod write_all;
+
+pub use self::copy::{copy, Copy};
+pub use self::flush::{flush, Flush};
+pub use self::read_exact::{read_exact, ReadExact};
+pub use self::shutdown::{shutdown, Shutdown};
+pub use self::write_all::{write_all, WriteAll};
+
+/// Reads bytes from a source which isn't always ready to provide them.
+pub trait AsyncRead {
+    /// Attempts to read some bytes into `buf`, returning how many bytes
+    /// were read.
+    ///
+    /// As with `std::io::Read::read`, if `buf` isn't empty then reading 0
+    /// bytes means the end of the source was reached. If no bytes are
+    /// available yet then this returns `Async::NotReady`, and the current
+    /// task is notified once there are.
+    ///
+    /// # Panics
+    ///
+    /// Implementations may panic if this isn't called from within a task.
+    fn poll_read(&mut self, buf: &mut [u8]) -> Poll<usize, io::Error>;
+}
+
+/// Writes bytes to a sink which isn't always ready to take them.
+pub trait AsyncWrite {
+    /// Attempts to write some bytes from `buf`, returning how many bytes
+    /// were written.
+    ///
+    /// As with `std::io::Write::write`, writing 0 bytes of a buffer which
+    /// isn't empty means the sink can't take any more bytes. If it can't
+    /// take bytes at the moment then this returns `Async::NotReady`, and the
+    /// current task is notified once it can.
+    ///
+    /// # Panics
+    ///
+    /// Implementations may panic if this isn't called from within a task.
+    fn poll_write(&mut self, buf: &[u8]) -> Poll<usize, io::Error>;
+
+    /// Attempts to flush the bytes written so far to their destination.
+    ///
+    /// This returns `Async::NotReady` and notifies the current task later
+    /// if that can't happen right away.
+    fn poll_flush(&mut self) -> Poll<(), io::Error>;
+
+    /// Attempts to shut down the sink, once all of the bytes written so far
+    /// have been flushed.
+    ///
+    /// This is where for example a socket would send its end of stream.
+    /// Nothing should be written anymore afterwards. The default
+    /// implementation only flushes.
+    fn poll_shutdown(&mut self) -> Poll<(), io::Error> {
+        self.poll_flush()
+    }
+}
+
+impl<'a> AsyncRead for &'a [u8] {
+    fn poll_read(&mut self, buf: &mut [u8]) -> Poll<usize, io::Error> {
+        Ok(Async::Ready(io::Read::read(self, buf)?))
+    }
+}
+
+impl<T: AsRef<[u8]>> AsyncRead for io::Cursor<T> {
+    fn poll_read(&mut self, buf: &mut [u8]) -> Poll<usize, io::Error> {
+        Ok(Async::Ready(io::Read::read(self, buf)?))
+    }
+}
+
+impl AsyncRead for io::Empty {
+    fn poll_read(&mut self, _buf: &mut [u8]) -> Poll<usize, io::Error> {
+        Ok(Async::Ready(0))
+    }
+}
+
+impl AsyncRead for io::Repeat {
+    fn poll_read(&mut self, buf: &mut [u8]) -> Poll<usize, io::Error> {
+        Ok(Async::Ready(io::Read::read(self, buf)?))
+    }
+}
+
+impl<T: AsyncRead + ?Sized> AsyncRead for Box<T> {
+    fn poll_read(&mut self, buf: &mut [u8]) -> Poll<usize, io::Error> {
+        (**self).poll_read(buf)
+    }
+}
+
+impl<'a, T: AsyncRead + ?Sized> AsyncRead for &'a mut T {
+    fn poll_read(&mut self, buf: &mut [u8]) -> Poll<usize, io::Error> {
+        (**self).poll_read(buf)
+    }
+}
+
+impl AsyncWrite for Vec<u8> {
+    fn poll_write(&mut self, buf: &[u8]) -> Poll<usize, io::Error> {
+        self.extend_from_slice(buf);
+        Ok(Async::Ready(buf.len()))
+    }
+
+    fn poll_flush(&mut self) -> Poll<(), io::Error> {
+        Ok(Async::Ready(()))
+    }
+}
+
+impl AsyncWrite for io::Cursor<Vec<u8>> {
+    fn poll_write(&mut self, buf: &[u8]) -> Poll<usize, io::Error> {
+        Ok(Async::Ready(io::Write::write(self, buf)?))
+    }
+
+    fn poll_flush(&mut self) -> Poll<(), io::Error> {
+        Ok(Async::Ready(()))
+    }
+}
+
+impl<'a> AsyncWrite for io::Cursor<&'a mut [u8]> {
+    fn poll_write(&mut self, buf: &[u8]) -> Poll<usize, io::Error> {
+        Ok(Async::Ready(io::Write::write(self, buf)?))
+    }
+
+    fn poll_flush(&mut self) -> Poll<(), io::Error> {
+        Ok(Async::Ready(()))
+    }
+}
+
+impl AsyncWrite for io::Sink {
+    fn poll_write(&mut self, buf: &[u8]) -> Poll<usize, io::Error> {
+        Ok(Async::Ready(buf.len()))
+    }
+
+    fn poll_flush(&mut self) -> Poll<(), io::Error> {
+        Ok(Async::Ready(()))
+    }
+}
+
+impl<T: AsyncWrite + ?Sized> AsyncWrite for Box<T> {
+    fn poll_write(&mut self, buf: &[u8]) -> Poll<usize, io::Error> {
+        (**self).poll_write(buf)
+    }
+
+    fn poll_flush(&mut self) -> Poll<(), io::Error> {
+        (**self).poll_flush()
+    }
+
+    fn poll_shutdown(&mut self) -> Poll<(), io::Error> {
+        (**self).poll_shutdown()
+    }
+}
+
+impl<'a, T: AsyncWrite + ?Sized> AsyncWrite for &'a mut T {
+    fn poll_write(&mut self, buf: &[u8]) -> Poll<usize, io::Error> {
+        (**self).poll_write(buf)
+    }
+
+    fn poll_flush(&mut self) -> Poll<(), io::Error> {
+        (**self).poll_flush()
+    }
+
+    fn poll_shutdown(&mut self) -> Poll<(), io::Error> {
+        (**self).poll_shutdown()
+    }
+}
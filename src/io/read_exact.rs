@@ -0,0 +1,83 @@
+use std::io;
+use std::mem;
+
+use {Async, Future, Poll};
+use io::AsyncRead;
+
+/// A future reading exactly enough bytes to fill a buffer.
+///
+/// This is created by the `read_exact` function.
+#[derive(Debug)]
+#[must_use = "futures do nothing unless polled"]
+pub struct ReadExact<A, T> {
+    state: State<A, T>,
+}
+
+#[derive(Debug)]
+enum State<A, T> {
+    Reading {
+        a: A,
+        buf: T,
+        pos: usize,
+    },
+    Empty,
+}
+
+/// Creates a future reading exactly `buf.len()` bytes from `a` into `buf`.
+///
+/// The future resolves to `a` and the filled `buf`. If the end of `a` is
+/// reached before `buf` is full then it fails with an error of the kind
+/// `UnexpectedEof`.
+///
+/// # Examples
+///
+/// ```
+/// use futures::prelude::*;
+/// use futures::io;
+///
+/// let (rest, buf) = io::read_exact(&b"hello world"[..], [0; 5]).wait().unwrap();
+/// assert_eq!(&buf, b"hello");
+/// assert_eq!(rest, b" world");
+/// ```
+pub fn read_exact<A, T>(a: A, buf: T) -> ReadExact<A, T>
+    where A: AsyncRead,
+          T: AsMut<[u8]>,
+{
+    ReadExact {
+        state: State::Reading {
+            a: a,
+            buf: buf,
+            pos: 0,
+        },
+    }
+}
+
+impl<A, T> Future for ReadExact<A, T>
+    where A: AsyncRead,
+          T: AsMut<[u8]>,
+{
+    type Item = (A, T);
+    type Error = io::Error;
+
+    fn poll(&mut self) -> Poll<(A, T), io::Error> {
+        match self.state {
+            State::Reading { ref mut a, ref mut buf, ref mut pos } => {
+                let buf = buf.as_mut();
+                while *pos < buf.len() {
+                    let n = try_ready!(a.poll_read(&mut buf[*pos..]));
+                    if n == 0 {
+                        return Err(io::Error::new(io::ErrorKind::UnexpectedEof,
+                                                  "early eof"))
+                    }
+                    *pos += n;
+                }
+            }
+            State::Empty => panic!("poll a ReadExact after it's done"),
+        }
+
+        match mem::replace(&mut self.state, State::Empty) {
+            State::Reading { a, buf, .. } => Ok(Async::Ready((a, buf))),
+            State::Empty => panic!(),
+        }
+    }
+}
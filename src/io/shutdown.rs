@@ -0,0 +1,29 @@
+use std::io;
+
+use {Async, Future, Poll};
+use io::AsyncWrite;
+
+/// A future shutting down an I/O object.
+///
+/// This is created by the `shutdown` function.
+#[derive(Debug)]
+#[must_use = "futures do nothing unless polled"]
+pub struct Shutdown<A> {
+    a: Option<A>,
+}
+
+/// Creates a future shutting down `a` with `AsyncWrite::poll_shutdown`,
+/// resolving to `a` once that's done.
+pub fn shutdown<A: AsyncWrite>(a: A) -> Shutdown<A> {
+    Shutdown { a: Some(a) }
+}
+
+impl<A: AsyncWrite> Future for Shutdown<A> {
+    type Item = A;
+    type Error = io::Error;
+
+    fn poll(&mut self) -> Poll<A, io::Error> {
+        try_ready!(self.a.as_mut().expect("poll a Shutdown after it's done").poll_shutdown());
+        Ok(Async::Ready(self.a.take().unwrap()))
+    }
+}
@@ -0,0 +1,83 @@
+use std::io;
+use std::mem;
+
+use {Async, Future, Poll};
+use io::AsyncWrite;
+
+/// A future writing an entire buffer.
+///
+/// This is created by the `write_all` function.
+#[derive(Debug)]
+#[must_use = "futures do nothing unless polled"]
+pub struct WriteAll<A, T> {
+    state: State<A, T>,
+}
+
+#[derive(Debug)]
+enum State<A, T> {
+    Writing {
+        a: A,
+        buf: T,
+        pos: usize,
+    },
+    Empty,
+}
+
+/// Creates a future writing all of `buf` to `a`.
+///
+/// The future resolves to `a` and `buf` once every byte has been written.
+/// The bytes aren't flushed, use `flush` for that. If `a` stops taking bytes
+/// before the end of `buf` then it fails with an error of the kind
+/// `WriteZero`.
+///
+/// # Examples
+///
+/// ```
+/// use futures::prelude::*;
+/// use futures::io;
+///
+/// let (out, _) = io::write_all(Vec::new(), b"hello").wait().unwrap();
+/// assert_eq!(out, b"hello");
+/// ```
+pub fn write_all<A, T>(a: A, buf: T) -> WriteAll<A, T>
+    where A: AsyncWrite,
+          T: AsRef<[u8]>,
+{
+    WriteAll {
+        state: State::Writing {
+            a: a,
+            buf: buf,
+            pos: 0,
+        },
+    }
+}
+
+impl<A, T> Future for WriteAll<A, T>
+    where A: AsyncWrite,
+          T: AsRef<[u8]>,
+{
+    type Item = (A, T);
+    type Error = io::Error;
+
+    fn poll(&mut self) -> Poll<(A, T), io::Error> {
+        match self.state {
+            State::Writing { ref mut a, ref buf, ref mut pos } => {
+                let buf = buf.as_ref();
+                while *pos < buf.len() {
+                    let n = try_ready!(a.poll_write(&buf[*pos..]));
+                    if n == 0 {
+                        return Err(io::Error::new(io::ErrorKind::WriteZero,
+                                                  "zero-length write"))
+                    }
+                    *pos += n;
+                }
+            }
+            State::Empty => panic!("poll a WriteAll after it's done"),
+        }
+
+        match mem::replace(&mut self.state, State::Empty) {
+            State::Writing { a, buf, .. } => Ok(Async::Ready((a, buf))),
+            State::Empty => panic!(),
+        }
+    }
+}
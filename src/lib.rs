@@ -163,6 +163,24 @@
 #[cfg(feature = "use_std")]
 extern crate std;
 
+#[cfg(all(feature = "alloc", not(feature = "use_std")))]
+extern crate alloc;
+
+// With `use_std` the allocating types come from `std`, so that the `alloc`
+// crate, which older compilers don't have, is only needed without it. Code
+// working with either refers to them through `alloc` all the same.
+#[cfg(feature = "use_std")]
+mod alloc {
+    pub use std::{boxed, string, sync, vec};
+
+    pub mod collections {
+        pub use std::collections::VecDeque;
+    }
+}
+
+#[cfg(feature = "crossbeam")]
+extern crate crossbeam_channel;
+
 macro_rules! if_std {
     ($($i:item)*) => ($(
         #[cfg(feature = "use_std")]
@@ -170,6 +188,13 @@ macro_rules! if_std {
     )*)
 }
 
+macro_rules! if_alloc {
+    ($($i:item)*) => ($(
+        #[cfg(feature = "alloc")]
+        $i
+    )*)
+}
+
 #[macro_use]
 mod poll;
 pub use poll::{Poll, Async, AsyncSink, StartSend};
@@ -209,6 +234,22 @@ pub mod executor;
 pub mod sync;
 #[cfg(feature = "use_std")]
 pub mod unsync;
+#[cfg(feature = "use_std")]
+pub mod observe;
+#[cfg(feature = "use_std")]
+pub mod timer;
+#[cfg(feature = "use_std")]
+pub mod io;
+#[cfg(feature = "use_std")]
+pub mod codec;
+#[cfg(feature = "use_std")]
+pub mod ffi;
+#[cfg(feature = "use_std")]
+pub mod thread;
+#[cfg(feature = "use_std")]
+pub mod test;
+#[cfg(feature = "compat")]
+pub mod compat;
 
 
 if_std! {
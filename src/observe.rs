@@ -0,0 +1,70 @@
+//! A hook for observing the combinators of this crate at runtime.
+//!
+//! Combinators such as the ones returned by `Stream::instrumented` and
+//! `Sink::instrumented` report what they're doing to the `Observer`
+//! registered with `set_observer`, which can forward it to a metrics or
+//! tracing system of choice. Nothing is reported if no observer is
+//! registered.
+//!
+//! This module is only available when the `use_std` feature of this library
+//! is activated, and it is activated by default.
+
+use std::prelude::v1::*;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering::SeqCst;
+
+use sink::SinkStats;
+use stream::StreamStats;
+
+/// Receives the events reported by instrumented combinators.
+///
+/// All methods have empty default implementations, so implementors only need
+/// to override the events they're interested in.
+pub trait Observer: Send + Sync {
+    /// Called after each poll of a stream created with
+    /// `Stream::instrumented`, with the statistics gathered so far.
+    fn stream_polled(&self, _name: &str, _stats: &StreamStats) {}
+
+    /// Called once an instrumented stream terminates, or when it's dropped
+    /// before terminating.
+    fn stream_finished(&self, _name: &str, _stats: &StreamStats) {}
+
+    /// Called after each attempt to send an item to a sink created with
+    /// `Sink::instrumented`, with the statistics gathered so far.
+    fn sink_started(&self, _name: &str, _stats: &SinkStats) {}
+
+    /// Called after each completed flush of an instrumented sink.
+    fn sink_flushed(&self, _name: &str, _stats: &SinkStats) {}
+
+    /// Called once an instrumented sink is closed, or when it's dropped
+    /// before being closed.
+    fn sink_finished(&self, _name: &str, _stats: &SinkStats) {}
+}
+
+// A leaked `Box<Box<Observer>>`, or zero if no observer is registered.
+static OBSERVER: AtomicUsize = AtomicUsize::new(0);
+
+/// Registers the observer which instrumented combinators report to.
+///
+/// Only one observer can be registered for the lifetime of the program, so
+/// this returns whether `observer` was registered. If another observer was
+/// registered before then `observer` is dropped and `false` is returned.
+pub fn set_observer(observer: Box<Observer>) -> bool {
+    let ptr = Box::into_raw(Box::new(observer));
+    match OBSERVER.compare_exchange(0, ptr as usize, SeqCst, SeqCst) {
+        Ok(_) => true,
+        Err(_) => {
+            unsafe { drop(Box::from_raw(ptr)); }
+            false
+        }
+    }
+}
+
+/// Returns the observer registered with `set_observer`, if any.
+pub fn observer() -> Option<&'static Observer> {
+    match OBSERVER.load(SeqCst) {
+        0 => None,
+        // Registered observers are never freed.
+        ptr => unsafe { Some(&**(ptr as *const Box<Observer>)) },
+    }
+}
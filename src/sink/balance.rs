@@ -0,0 +1,185 @@
+use std::prelude::v1::*;
+
+use {Async, AsyncSink, Poll, StartSend};
+use sink::Sink;
+
+/// The way a `Balance` sink picks among its sinks which are ready to accept
+/// an item.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BalanceStrategy {
+    /// Cycle through the sinks, starting after the one which accepted the
+    /// last item.
+    RoundRobin,
+
+    /// Pick the sink with the fewest items sent to it since it was last
+    /// flushed, cycling through sinks which are equally loaded.
+    LeastLoaded,
+}
+
+/// A sink which sends each item to one of a number of sinks, spreading the
+/// items across them.
+///
+/// This structure is produced by the `sink::balance` and `sink::balance_with`
+/// functions.
+#[derive(Debug)]
+#[must_use = "sinks do nothing unless polled"]
+pub struct Balance<S> {
+    sinks: Vec<Slot<S>>,
+    strategy: BalanceStrategy,
+    next: usize,
+}
+
+#[derive(Debug)]
+struct Slot<S> {
+    sink: S,
+    // Items sent since the sink was last flushed.
+    pending: usize,
+}
+
+/// Creates a sink which sends each item to the next of `sinks` ready to
+/// accept it, in round robin order.
+///
+/// This is `balance_with` using `BalanceStrategy::RoundRobin`.
+///
+/// This function is only available when the `use_std` feature of this
+/// library is activated, and it is activated by default.
+///
+/// # Examples
+///
+/// ```
+/// use futures::prelude::*;
+/// use futures::sink;
+///
+/// let mut balance = sink::balance(vec![Vec::new(), Vec::new()]);
+/// for i in 0..5 {
+///     assert_eq!(balance.start_send(i), Ok(AsyncSink::Ready));
+/// }
+/// assert_eq!(balance.into_inner(), vec![vec![0, 2, 4], vec![1, 3]]);
+/// ```
+pub fn balance<I>(sinks: I) -> Balance<I::Item>
+    where I: IntoIterator,
+          I::Item: Sink,
+{
+    balance_with(sinks, BalanceStrategy::RoundRobin)
+}
+
+/// Creates a sink which sends each item to one of `sinks`, picking among the
+/// sinks ready to accept it according to `strategy`.
+///
+/// Sinks which hand an item back with `AsyncSink::NotReady` are skipped, and
+/// an item is only handed back by the returned sink if none of the sinks
+/// accepted it. A flush or close only completes once it has completed for all
+/// of the sinks. Errors of any sink are returned as they happen.
+///
+/// This function is only available when the `use_std` feature of this
+/// library is activated, and it is activated by default.
+pub fn balance_with<I>(sinks: I, strategy: BalanceStrategy) -> Balance<I::Item>
+    where I: IntoIterator,
+          I::Item: Sink,
+{
+    Balance {
+        sinks: sinks.into_iter().map(|sink| Slot { sink: sink, pending: 0 }).collect(),
+        strategy: strategy,
+        next: 0,
+    }
+}
+
+impl<S> Balance<S> {
+    /// Returns the number of sinks.
+    pub fn len(&self) -> usize {
+        self.sinks.len()
+    }
+
+    /// Returns whether there are no sinks.
+    pub fn is_empty(&self) -> bool {
+        self.sinks.is_empty()
+    }
+
+    /// Returns a reference to the sink at `idx`, in the order they were
+    /// passed in.
+    pub fn get_ref(&self, idx: usize) -> Option<&S> {
+        self.sinks.get(idx).map(|slot| &slot.sink)
+    }
+
+    /// Returns a mutable reference to the sink at `idx`, in the order they
+    /// were passed in.
+    pub fn get_mut(&mut self, idx: usize) -> Option<&mut S> {
+        self.sinks.get_mut(idx).map(|slot| &mut slot.sink)
+    }
+
+    /// Returns the number of items sent to the sink at `idx` since it was
+    /// last flushed.
+    pub fn pending(&self, idx: usize) -> Option<usize> {
+        self.sinks.get(idx).map(|slot| slot.pending)
+    }
+
+    /// Consumes this combinator, returning the underlying sinks.
+    pub fn into_inner(self) -> Vec<S> {
+        self.sinks.into_iter().map(|slot| slot.sink).collect()
+    }
+
+    // The order in which the sinks are offered the next item.
+    fn order(&self) -> Vec<usize> {
+        let n = self.sinks.len();
+        let next = self.next;
+        let mut order = (0..n).map(|i| (next + i) % n).collect::<Vec<_>>();
+        if self.strategy == BalanceStrategy::LeastLoaded {
+            // The sort is stable, so equally loaded sinks keep their round
+            // robin order.
+            order.sort_by_key(|&i| self.sinks[i].pending);
+        }
+        order
+    }
+}
+
+impl<S: Sink> Sink for Balance<S> {
+    type SinkItem = S::SinkItem;
+    type SinkError = S::SinkError;
+
+    fn start_send(&mut self, mut item: S::SinkItem) -> StartSend<S::SinkItem, S::SinkError> {
+        for i in self.order() {
+            let slot = &mut self.sinks[i];
+            match slot.sink.start_send(item)? {
+                AsyncSink::Ready => {
+                    slot.pending += 1;
+                    self.next = i + 1;
+                    return Ok(AsyncSink::Ready)
+                }
+                AsyncSink::NotReady(back) => item = back,
+            }
+        }
+        Ok(AsyncSink::NotReady(item))
+    }
+
+    fn poll_complete(&mut self) -> Poll<(), S::SinkError> {
+        let mut ready = true;
+        for slot in &mut self.sinks {
+            if slot.sink.poll_complete()?.is_ready() {
+                slot.pending = 0;
+            } else {
+                ready = false;
+            }
+        }
+        if ready {
+            Ok(Async::Ready(()))
+        } else {
+            Ok(Async::NotReady)
+        }
+    }
+
+    fn close(&mut self) -> Poll<(), S::SinkError> {
+        let mut ready = true;
+        for slot in &mut self.sinks {
+            if slot.sink.close()?.is_ready() {
+                slot.pending = 0;
+            } else {
+                ready = false;
+            }
+        }
+        if ready {
+            Ok(Async::Ready(()))
+        } else {
+            Ok(Async::NotReady)
+        }
+    }
+}
@@ -0,0 +1,180 @@
+use std::fmt;
+use std::mem;
+use std::time::Duration;
+use std::vec::Vec;
+
+use {Async, AsyncSink, Future, Poll, StartSend, Stream};
+use sink::Sink;
+use timer::{Timer, TimerError};
+
+/// Sink for the `Sink::batched` combinator, which collects items into batches
+/// before sending them to the underlying sink.
+#[must_use = "sinks do nothing unless polled"]
+pub struct Batched<S, I, T> where S: Sink, T: Timer {
+    sink: S,
+    timer: T,
+    max: usize,
+    duration: Duration,
+    batch: Vec<I>,
+    // A batch which the underlying sink didn't accept yet.
+    pending: Option<S::SinkItem>,
+    // Fires once the current batch reached its maximum age.
+    delay: Option<T::Delay>,
+}
+
+impl<S, I, T> fmt::Debug for Batched<S, I, T>
+    where S: Sink + fmt::Debug,
+          S::SinkItem: fmt::Debug,
+          I: fmt::Debug,
+          T: Timer,
+{
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("Batched")
+            .field("sink", &self.sink)
+            .field("max", &self.max)
+            .field("duration", &self.duration)
+            .field("batch", &self.batch)
+            .field("pending", &self.pending)
+            .finish()
+    }
+}
+
+pub fn new<S, I, T>(sink: S, max: usize, duration: Duration, timer: T) -> Batched<S, I, T>
+    where S: Sink<SinkItem = Vec<I>>,
+          T: Timer,
+{
+    assert!(max > 0, "batches must hold at least one item");
+    Batched {
+        sink: sink,
+        timer: timer,
+        max: max,
+        duration: duration,
+        batch: Vec::with_capacity(max),
+        pending: None,
+        delay: None,
+    }
+}
+
+impl<S, I, T> Batched<S, I, T>
+    where S: Sink<SinkItem = Vec<I>>,
+          S::SinkError: From<TimerError>,
+          T: Timer,
+{
+    /// Get a shared reference to the inner sink.
+    pub fn get_ref(&self) -> &S {
+        &self.sink
+    }
+
+    /// Get a mutable reference to the inner sink.
+    pub fn get_mut(&mut self) -> &mut S {
+        &mut self.sink
+    }
+
+    /// Consumes this combinator, returning the underlying sink.
+    ///
+    /// Note that this may discard intermediate state of this combinator, so
+    /// care should be taken to avoid losing resources when this is called.
+    pub fn into_inner(self) -> S {
+        self.sink
+    }
+
+    // Moves the current batch out to be sent to the underlying sink. There
+    // mustn't be another batch waiting to be sent.
+    fn hand_off(&mut self) {
+        let batch = mem::replace(&mut self.batch, Vec::with_capacity(self.max));
+        self.pending = Some(batch);
+        self.delay = None;
+    }
+
+    fn try_empty_pending(&mut self) -> Poll<(), S::SinkError> {
+        if let Some(batch) = self.pending.take() {
+            if let AsyncSink::NotReady(batch) = self.sink.start_send(batch)? {
+                self.pending = Some(batch);
+                return Ok(Async::NotReady)
+            }
+        }
+        Ok(Async::Ready(()))
+    }
+
+    fn poll_delay(&mut self) -> Poll<(), S::SinkError> {
+        if self.delay.is_none() {
+            self.delay = Some(self.timer.delay(self.duration));
+        }
+        let res = self.delay.as_mut().unwrap().poll();
+        if let Ok(Async::NotReady) = res {
+            return Ok(Async::NotReady)
+        }
+        self.delay = None;
+        res.map_err(From::from)
+    }
+}
+
+impl<S, I, T> Sink for Batched<S, I, T>
+    where S: Sink<SinkItem = Vec<I>>,
+          S::SinkError: From<TimerError>,
+          T: Timer,
+{
+    type SinkItem = I;
+    type SinkError = S::SinkError;
+
+    fn start_send(&mut self, item: I) -> StartSend<I, S::SinkError> {
+        // A full batch is left behind only while another one is pending.
+        if self.batch.len() >= self.max {
+            if self.try_empty_pending()?.is_not_ready() {
+                return Ok(AsyncSink::NotReady(item))
+            }
+            self.hand_off();
+            self.try_empty_pending()?;
+        }
+
+        if self.batch.is_empty() {
+            self.delay = Some(self.timer.delay(self.duration));
+        }
+        self.batch.push(item);
+        if self.batch.len() >= self.max && self.pending.is_none() {
+            self.hand_off();
+            self.try_empty_pending()?;
+        }
+        Ok(AsyncSink::Ready)
+    }
+
+    fn poll_complete(&mut self) -> Poll<(), S::SinkError> {
+        if self.try_empty_pending()?.is_ready() && !self.batch.is_empty() &&
+           (self.batch.len() >= self.max || self.poll_delay()?.is_ready()) {
+            self.hand_off();
+            self.try_empty_pending()?;
+        }
+
+        // always propagate `poll_complete` downward to attempt to make progress
+        try_ready!(self.sink.poll_complete());
+        if self.pending.is_none() && self.batch.is_empty() {
+            Ok(Async::Ready(()))
+        } else {
+            Ok(Async::NotReady)
+        }
+    }
+
+    fn close(&mut self) -> Poll<(), S::SinkError> {
+        loop {
+            try_ready!(self.try_empty_pending());
+            if self.batch.is_empty() {
+                break
+            }
+            self.hand_off();
+        }
+        self.sink.close()
+    }
+}
+
+// Forwarding impl of Stream from the underlying sink
+impl<S, I, T> Stream for Batched<S, I, T>
+    where S: Sink + Stream,
+          T: Timer,
+{
+    type Item = S::Item;
+    type Error = S::Error;
+
+    fn poll(&mut self) -> Poll<Option<S::Item>, S::Error> {
+        self.sink.poll()
+    }
+}
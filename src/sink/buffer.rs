@@ -3,29 +3,93 @@ use std::collections::VecDeque;
 use {Poll, Async};
 use {StartSend, AsyncSink};
 use sink::Sink;
-use stream::Stream;
+use stream::{Stream, OverflowPolicy, DropOldest, DropNewest, Evict, Coalesce};
+
+/// Decides what a `Buffer` sink does with an item sent while its buffer is
+/// full.
+///
+/// This is implemented for `Backpressure`, which is the default, and for the
+/// overflow policies of the stream module: `DropOldest`, `DropNewest`, `Evict`
+/// and `Coalesce`.
+pub trait BufferOverflow<T> {
+    /// Handles `item` being sent while `buffer` is full.
+    ///
+    /// Returns the item back if the send should be rejected, in which case
+    /// the buffer must be left as is.
+    fn overflow(&mut self, buffer: &mut VecDeque<T>, item: T) -> Option<T>;
+}
+
+/// The default behavior of a `Buffer` sink, which rejects items with
+/// `AsyncSink::NotReady` while its buffer is full.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Backpressure;
+
+impl<T> BufferOverflow<T> for Backpressure {
+    fn overflow(&mut self, _buffer: &mut VecDeque<T>, item: T) -> Option<T> {
+        Some(item)
+    }
+}
+
+impl<T> BufferOverflow<T> for DropOldest {
+    fn overflow(&mut self, buffer: &mut VecDeque<T>, item: T) -> Option<T> {
+        OverflowPolicy::overflow(self, buffer, item);
+        None
+    }
+}
+
+impl<T> BufferOverflow<T> for DropNewest {
+    fn overflow(&mut self, buffer: &mut VecDeque<T>, item: T) -> Option<T> {
+        OverflowPolicy::overflow(self, buffer, item);
+        None
+    }
+}
+
+impl<T, F> BufferOverflow<T> for Evict<F>
+    where F: FnMut(T),
+{
+    fn overflow(&mut self, buffer: &mut VecDeque<T>, item: T) -> Option<T> {
+        OverflowPolicy::overflow(self, buffer, item);
+        None
+    }
+}
+
+impl<T, F> BufferOverflow<T> for Coalesce<F>
+    where F: FnMut(T, T) -> T,
+{
+    fn overflow(&mut self, buffer: &mut VecDeque<T>, item: T) -> Option<T> {
+        OverflowPolicy::overflow(self, buffer, item);
+        None
+    }
+}
 
 /// Sink for the `Sink::buffer` combinator, which buffers up to some fixed
 /// number of values when the underlying sink is unable to accept them.
+///
+/// What happens once the buffer is full is decided by a `BufferOverflow`
+/// policy, see `Sink::buffer_with_policy`.
 #[derive(Debug)]
 #[must_use = "sinks do nothing unless polled"]
-pub struct Buffer<S: Sink> {
+pub struct Buffer<S: Sink, P = Backpressure> {
     sink: S,
     buf: VecDeque<S::SinkItem>,
 
     // Track capacity separately from the `VecDeque`, which may be rounded up
     cap: usize,
+    policy: P,
 }
 
-pub fn new<S: Sink>(sink: S, amt: usize) -> Buffer<S> {
+pub fn new<S: Sink, P>(sink: S, amt: usize, policy: P) -> Buffer<S, P>
+    where P: BufferOverflow<S::SinkItem>,
+{
     Buffer {
         sink: sink,
         buf: VecDeque::with_capacity(amt),
         cap: amt,
+        policy: policy,
     }
 }
 
-impl<S: Sink> Buffer<S> {
+impl<S: Sink, P> Buffer<S, P> {
     /// Get a shared reference to the inner sink.
     pub fn get_ref(&self) -> &S {
         &self.sink
@@ -61,7 +125,7 @@ impl<S: Sink> Buffer<S> {
 }
 
 // Forwarding impl of Stream from the underlying sink
-impl<S> Stream for Buffer<S> where S: Sink + Stream {
+impl<S, P> Stream for Buffer<S, P> where S: Sink + Stream {
     type Item = S::Item;
     type Error = S::Error;
 
@@ -70,14 +134,20 @@ impl<S> Stream for Buffer<S> where S: Sink + Stream {
     }
 }
 
-impl<S: Sink> Sink for Buffer<S> {
+impl<S, P> Sink for Buffer<S, P>
+    where S: Sink,
+          P: BufferOverflow<S::SinkItem>,
+{
     type SinkItem = S::SinkItem;
     type SinkError = S::SinkError;
 
     fn start_send(&mut self, item: Self::SinkItem) -> StartSend<Self::SinkItem, Self::SinkError> {
         self.try_empty_buffer()?;
         if self.buf.len() > self.cap {
-            return Ok(AsyncSink::NotReady(item));
+            return match self.policy.overflow(&mut self.buf, item) {
+                Some(item) => Ok(AsyncSink::NotReady(item)),
+                None => Ok(AsyncSink::Ready),
+            }
         }
         self.buf.push_back(item);
         Ok(AsyncSink::Ready)
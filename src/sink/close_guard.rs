@@ -0,0 +1,153 @@
+use core::fmt;
+
+use {Async, Future, Poll, StartSend, Stream};
+use future::Executor;
+use sink::Sink;
+
+/// Sink for the `Sink::close_guard` combinator, which closes the underlying
+/// sink on an executor if it is dropped before being flushed.
+#[must_use = "sinks do nothing unless polled"]
+pub struct CloseGuard<S, E>
+    where S: Sink,
+          E: Executor<Closing<S>>,
+{
+    sink: Option<S>,
+    executor: E,
+    // Whether items were accepted since the last completed flush or close.
+    dirty: bool,
+}
+
+impl<S, E> fmt::Debug for CloseGuard<S, E>
+    where S: Sink + fmt::Debug,
+          E: Executor<Closing<S>>,
+{
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("CloseGuard")
+            .field("sink", &self.sink)
+            .field("dirty", &self.dirty)
+            .finish()
+    }
+}
+
+pub fn new<S, E>(sink: S, executor: E) -> CloseGuard<S, E>
+    where S: Sink,
+          E: Executor<Closing<S>>,
+{
+    CloseGuard {
+        sink: Some(sink),
+        executor: executor,
+        dirty: false,
+    }
+}
+
+impl<S, E> CloseGuard<S, E>
+    where S: Sink,
+          E: Executor<Closing<S>>,
+{
+    /// Get a shared reference to the inner sink.
+    pub fn get_ref(&self) -> &S {
+        self.sink.as_ref().unwrap()
+    }
+
+    /// Get a mutable reference to the inner sink.
+    pub fn get_mut(&mut self) -> &mut S {
+        self.sink.as_mut().unwrap()
+    }
+
+    /// Consumes this combinator, returning the underlying sink.
+    ///
+    /// The underlying sink is no longer guarded afterwards, so it won't be
+    /// closed when it is dropped.
+    pub fn into_inner(mut self) -> S {
+        self.sink.take().unwrap()
+    }
+
+    fn track(&mut self, res: Poll<(), S::SinkError>) -> Poll<(), S::SinkError> {
+        if let Ok(Async::Ready(())) = res {
+            self.dirty = false;
+        }
+        res
+    }
+}
+
+impl<S, E> Sink for CloseGuard<S, E>
+    where S: Sink,
+          E: Executor<Closing<S>>,
+{
+    type SinkItem = S::SinkItem;
+    type SinkError = S::SinkError;
+
+    fn start_send(&mut self, item: S::SinkItem) -> StartSend<S::SinkItem, S::SinkError> {
+        let res = self.get_mut().start_send(item)?;
+        if res.is_ready() {
+            self.dirty = true;
+        }
+        Ok(res)
+    }
+
+    fn poll_complete(&mut self) -> Poll<(), S::SinkError> {
+        let res = self.get_mut().poll_complete();
+        self.track(res)
+    }
+
+    fn close(&mut self) -> Poll<(), S::SinkError> {
+        let res = self.get_mut().close();
+        self.track(res)
+    }
+}
+
+// Forwarding impl of Stream from the underlying sink
+impl<S, E> Stream for CloseGuard<S, E>
+    where S: Sink + Stream,
+          E: Executor<Closing<S>>,
+{
+    type Item = S::Item;
+    type Error = S::Error;
+
+    fn poll(&mut self) -> Poll<Option<S::Item>, S::Error> {
+        self.get_mut().poll()
+    }
+}
+
+impl<S, E> Drop for CloseGuard<S, E>
+    where S: Sink,
+          E: Executor<Closing<S>>,
+{
+    fn drop(&mut self) {
+        if !self.dirty {
+            return
+        }
+        if let Some(sink) = self.sink.take() {
+            // If the executor refuses the future there's nothing left to do
+            // but to drop the sink along with its data.
+            drop(self.executor.execute(Closing { sink: sink }));
+        }
+    }
+}
+
+/// Future spawned by a `CloseGuard` which is dropped with unflushed data,
+/// closing the sink it guarded.
+///
+/// Errors of the sink are discarded, as there's nobody left to report them
+/// to.
+#[must_use = "futures do nothing unless polled"]
+#[derive(Debug)]
+pub struct Closing<S> {
+    sink: S,
+}
+
+impl<S> Closing<S> {
+    /// Get a shared reference to the sink being closed.
+    pub fn get_ref(&self) -> &S {
+        &self.sink
+    }
+}
+
+impl<S: Sink> Future for Closing<S> {
+    type Item = ();
+    type Error = ();
+
+    fn poll(&mut self) -> Poll<(), ()> {
+        self.sink.close().map_err(|_| ())
+    }
+}
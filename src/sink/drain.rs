@@ -0,0 +1,55 @@
+use core::marker;
+
+use {Async, AsyncSink, Poll, StartSend};
+use sink::Sink;
+
+/// A sink which discards all of its items.
+///
+/// This sink can be created with the `sink::drain` function.
+#[derive(Debug)]
+#[must_use = "sinks do nothing unless polled"]
+pub struct Drain<T> {
+    _data: marker::PhantomData<fn(T)>,
+}
+
+/// Creates a sink which accepts and discards all items sent to it.
+///
+/// The returned sink is always ready and never fails, which makes it handy
+/// for testing or benchmarking the stages in front of a sink.
+///
+/// # Example
+///
+/// ```rust
+/// use futures::prelude::*;
+/// use futures::{sink, stream};
+///
+/// let sink = sink::drain();
+/// let res = sink.send_all(stream::iter_ok::<_, ()>(1..5)).wait();
+/// assert!(res.is_ok());
+/// ```
+pub fn drain<T>() -> Drain<T> {
+    Drain { _data: marker::PhantomData }
+}
+
+impl<T> Clone for Drain<T> {
+    fn clone(&self) -> Drain<T> {
+        drain()
+    }
+}
+
+impl<T> Sink for Drain<T> {
+    type SinkItem = T;
+    type SinkError = ();
+
+    fn start_send(&mut self, _item: T) -> StartSend<T, ()> {
+        Ok(AsyncSink::Ready)
+    }
+
+    fn poll_complete(&mut self) -> Poll<(), ()> {
+        Ok(Async::Ready(()))
+    }
+
+    fn close(&mut self) -> Poll<(), ()> {
+        Ok(Async::Ready(()))
+    }
+}
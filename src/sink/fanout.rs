@@ -0,0 +1,176 @@
+use std::fmt;
+use std::prelude::v1::*;
+
+use {Async, AsyncSink, Poll, StartSend};
+use sink::Sink;
+
+/// A sink which sends a clone of every item to each of a dynamic set of
+/// sinks.
+///
+/// Sinks can be added and removed at any time. An item is accepted once it
+/// could be started on every sink, where each sink which wasn't ready holds
+/// on to its copy until it is. A flush or close only completes once it has
+/// completed for all of the sinks.
+///
+/// Errors of any sink are returned as they happen. The failing sink stays in
+/// the set, so it should usually be removed before using the fanout again.
+#[must_use = "sinks do nothing unless polled"]
+pub struct Fanout<S: Sink> {
+    sinks: Vec<Slot<S>>,
+    next_key: usize,
+}
+
+struct Slot<S: Sink> {
+    key: usize,
+    sink: S,
+    pending: Option<S::SinkItem>,
+}
+
+impl<S> fmt::Debug for Fanout<S>
+    where S: Sink + fmt::Debug,
+{
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_list()
+            .entries(self.sinks.iter().map(|slot| (slot.key, &slot.sink)))
+            .finish()
+    }
+}
+
+impl<S: Sink> Fanout<S> {
+    /// Creates a new fanout without any sinks, which accepts and discards all
+    /// items until a sink is added.
+    pub fn new() -> Fanout<S> {
+        Fanout {
+            sinks: Vec::new(),
+            next_key: 0,
+        }
+    }
+
+    /// Adds a sink to the set, returning a key to remove it with.
+    ///
+    /// The sink receives all items sent after it was added.
+    pub fn add(&mut self, sink: S) -> usize {
+        let key = self.next_key;
+        self.next_key += 1;
+        self.sinks.push(Slot {
+            key: key,
+            sink: sink,
+            pending: None,
+        });
+        key
+    }
+
+    /// Removes the sink added under `key` from the set, returning it.
+    ///
+    /// Any item which the sink didn't accept yet is dropped, and the sink
+    /// isn't flushed or closed.
+    pub fn remove(&mut self, key: usize) -> Option<S> {
+        self.sinks.iter()
+            .position(|slot| slot.key == key)
+            .map(|i| self.sinks.remove(i).sink)
+    }
+
+    /// Returns a reference to the sink added under `key`.
+    pub fn get_ref(&self, key: usize) -> Option<&S> {
+        self.sinks.iter()
+            .find(|slot| slot.key == key)
+            .map(|slot| &slot.sink)
+    }
+
+    /// Returns a mutable reference to the sink added under `key`.
+    pub fn get_mut(&mut self, key: usize) -> Option<&mut S> {
+        self.sinks.iter_mut()
+            .find(|slot| slot.key == key)
+            .map(|slot| &mut slot.sink)
+    }
+
+    /// Returns the number of sinks in the set.
+    pub fn len(&self) -> usize {
+        self.sinks.len()
+    }
+
+    /// Returns whether the set of sinks is empty.
+    pub fn is_empty(&self) -> bool {
+        self.sinks.is_empty()
+    }
+
+    // Retries the items which some sinks didn't accept yet, returning whether
+    // all of them have been started now.
+    fn start_pending(&mut self) -> Poll<(), S::SinkError> {
+        let mut ready = true;
+        for slot in self.sinks.iter_mut() {
+            if let Some(item) = slot.pending.take() {
+                if let AsyncSink::NotReady(item) = slot.sink.start_send(item)? {
+                    slot.pending = Some(item);
+                    ready = false;
+                }
+            }
+        }
+        if ready {
+            Ok(Async::Ready(()))
+        } else {
+            Ok(Async::NotReady)
+        }
+    }
+}
+
+impl<S: Sink> Default for Fanout<S> {
+    fn default() -> Fanout<S> {
+        Fanout::new()
+    }
+}
+
+impl<S> Sink for Fanout<S>
+    where S: Sink,
+          S::SinkItem: Clone,
+{
+    type SinkItem = S::SinkItem;
+    type SinkError = S::SinkError;
+
+    fn start_send(&mut self, item: S::SinkItem) -> StartSend<S::SinkItem, S::SinkError> {
+        if self.start_pending()?.is_not_ready() {
+            return Ok(AsyncSink::NotReady(item))
+        }
+
+        let mut item = Some(item);
+        let last = self.sinks.len().saturating_sub(1);
+        for (i, slot) in self.sinks.iter_mut().enumerate() {
+            // The last sink gets the original item, saving a clone.
+            let copy = if i == last {
+                item.take().unwrap()
+            } else {
+                item.clone().unwrap()
+            };
+            if let AsyncSink::NotReady(copy) = slot.sink.start_send(copy)? {
+                slot.pending = Some(copy);
+            }
+        }
+        Ok(AsyncSink::Ready)
+    }
+
+    fn poll_complete(&mut self) -> Poll<(), S::SinkError> {
+        let started = self.start_pending()?;
+        let mut ready = started.is_ready();
+        for slot in self.sinks.iter_mut() {
+            ready = slot.sink.poll_complete()?.is_ready() && ready;
+        }
+        if ready {
+            Ok(Async::Ready(()))
+        } else {
+            Ok(Async::NotReady)
+        }
+    }
+
+    fn close(&mut self) -> Poll<(), S::SinkError> {
+        try_ready!(self.start_pending());
+        let mut ready = true;
+        for slot in self.sinks.iter_mut() {
+            ready = slot.sink.close()?.is_ready() && ready;
+        }
+        if ready {
+            Ok(Async::Ready(()))
+        } else {
+            Ok(Async::NotReady)
+        }
+    }
+}
@@ -0,0 +1,44 @@
+use {Poll, Async, Future, AsyncSink};
+use sink::Sink;
+
+/// Future for the `Sink::feed` combinator, which hands a value to a borrowed
+/// sink without flushing it.
+#[derive(Debug)]
+#[must_use = "futures do nothing unless polled"]
+pub struct Feed<'a, S: Sink + ?Sized + 'a> {
+    sink: &'a mut S,
+    item: Option<S::SinkItem>,
+}
+
+pub fn new<'a, S: Sink + ?Sized>(sink: &'a mut S, item: S::SinkItem) -> Feed<'a, S> {
+    Feed {
+        sink: sink,
+        item: Some(item),
+    }
+}
+
+impl<'a, S: Sink + ?Sized> Feed<'a, S> {
+    /// Get a shared reference to the inner sink.
+    pub fn get_ref(&self) -> &S {
+        self.sink
+    }
+
+    /// Get a mutable reference to the inner sink.
+    pub fn get_mut(&mut self) -> &mut S {
+        self.sink
+    }
+}
+
+impl<'a, S: Sink + ?Sized> Future for Feed<'a, S> {
+    type Item = ();
+    type Error = S::SinkError;
+
+    fn poll(&mut self) -> Poll<(), S::SinkError> {
+        let item = self.item.take().expect("Attempted to poll Feed after completion");
+        if let AsyncSink::NotReady(item) = self.sink.start_send(item)? {
+            self.item = Some(item);
+            return Ok(Async::NotReady);
+        }
+        Ok(Async::Ready(()))
+    }
+}
@@ -0,0 +1,164 @@
+use std::error::Error;
+use std::fmt;
+use std::time::Duration;
+
+use {Async, Future, Poll, StartSend, Stream};
+use sink::Sink;
+use timer::{Timer, TimerError};
+
+/// Sink for the `Sink::flush_timeout` combinator, which fails flushes of a
+/// sink that take too long.
+#[must_use = "sinks do nothing unless polled"]
+pub struct FlushTimeout<S, T> where T: Timer {
+    sink: S,
+    timer: T,
+    duration: Duration,
+    delay: Option<T::Delay>,
+}
+
+impl<S, T> fmt::Debug for FlushTimeout<S, T>
+    where S: fmt::Debug,
+          T: Timer,
+{
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("FlushTimeout")
+            .field("sink", &self.sink)
+            .field("duration", &self.duration)
+            .field("flushing", &self.delay.is_some())
+            .finish()
+    }
+}
+
+/// Error returned by a `FlushTimeout` sink.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FlushTimeoutError<E> {
+    /// The underlying sink didn't finish flushing or closing in time.
+    Elapsed,
+
+    /// The timer failed, so the timeout can't be enforced.
+    Timer(TimerError),
+
+    /// The underlying sink returned an error.
+    Inner(E),
+}
+
+impl<E: fmt::Display> fmt::Display for FlushTimeoutError<E> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            FlushTimeoutError::Elapsed => write!(fmt, "sink flush timed out"),
+            FlushTimeoutError::Timer(ref e) => e.fmt(fmt),
+            FlushTimeoutError::Inner(ref e) => e.fmt(fmt),
+        }
+    }
+}
+
+impl<E: Error> Error for FlushTimeoutError<E> {
+    fn description(&self) -> &str {
+        match *self {
+            FlushTimeoutError::Elapsed => "sink flush timed out",
+            FlushTimeoutError::Timer(_) => "timer is no longer running",
+            FlushTimeoutError::Inner(_) => "sink returned an error",
+        }
+    }
+}
+
+pub fn new<S, T>(sink: S, duration: Duration, timer: T) -> FlushTimeout<S, T>
+    where S: Sink,
+          T: Timer,
+{
+    FlushTimeout {
+        sink: sink,
+        timer: timer,
+        duration: duration,
+        delay: None,
+    }
+}
+
+impl<S, T> FlushTimeout<S, T> where T: Timer {
+    /// Get a shared reference to the inner sink.
+    pub fn get_ref(&self) -> &S {
+        &self.sink
+    }
+
+    /// Get a mutable reference to the inner sink.
+    pub fn get_mut(&mut self) -> &mut S {
+        &mut self.sink
+    }
+
+    /// Consumes this combinator, returning the underlying sink.
+    ///
+    /// Note that this may discard intermediate state of this combinator, so
+    /// care should be taken to avoid losing resources when this is called.
+    pub fn into_inner(self) -> S {
+        self.sink
+    }
+
+    // Handles the result of flushing or closing the underlying sink, starting
+    // the timeout when it isn't done right away.
+    fn check(&mut self, res: Poll<(), S::SinkError>)
+             -> Poll<(), FlushTimeoutError<S::SinkError>>
+        where S: Sink,
+    {
+        match res {
+            Ok(Async::Ready(())) => {
+                self.delay = None;
+                return Ok(Async::Ready(()))
+            }
+            Ok(Async::NotReady) => {}
+            Err(e) => {
+                self.delay = None;
+                return Err(FlushTimeoutError::Inner(e))
+            }
+        }
+
+        if self.delay.is_none() {
+            self.delay = Some(self.timer.delay(self.duration));
+        }
+        match self.delay.as_mut().unwrap().poll() {
+            Ok(Async::NotReady) => Ok(Async::NotReady),
+            Ok(Async::Ready(())) => {
+                self.delay = None;
+                Err(FlushTimeoutError::Elapsed)
+            }
+            Err(e) => {
+                self.delay = None;
+                Err(FlushTimeoutError::Timer(e))
+            }
+        }
+    }
+}
+
+impl<S, T> Sink for FlushTimeout<S, T>
+    where S: Sink,
+          T: Timer,
+{
+    type SinkItem = S::SinkItem;
+    type SinkError = FlushTimeoutError<S::SinkError>;
+
+    fn start_send(&mut self, item: S::SinkItem) -> StartSend<S::SinkItem, Self::SinkError> {
+        self.sink.start_send(item).map_err(FlushTimeoutError::Inner)
+    }
+
+    fn poll_complete(&mut self) -> Poll<(), Self::SinkError> {
+        let res = self.sink.poll_complete();
+        self.check(res)
+    }
+
+    fn close(&mut self) -> Poll<(), Self::SinkError> {
+        let res = self.sink.close();
+        self.check(res)
+    }
+}
+
+// Forwarding impl of Stream from the underlying sink
+impl<S, T> Stream for FlushTimeout<S, T>
+    where S: Stream,
+          T: Timer,
+{
+    type Item = S::Item;
+    type Error = S::Error;
+
+    fn poll(&mut self) -> Poll<Option<S::Item>, S::Error> {
+        self.sink.poll()
+    }
+}
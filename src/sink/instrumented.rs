@@ -0,0 +1,227 @@
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use {Async, AsyncSink, Poll, StartSend};
+use observe;
+use sink::Sink;
+use stream::Stream;
+
+/// Sink for the `Sink::instrumented` combinator, which gathers statistics
+/// about the items sent to a sink and reports them to the registered
+/// `observe::Observer`.
+#[must_use = "sinks do nothing unless polled"]
+pub struct Instrumented<S> {
+    sink: S,
+    report: Report,
+}
+
+impl<S: fmt::Debug> fmt::Debug for Instrumented<S> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("Instrumented")
+            .field("sink", &self.sink)
+            .field("name", &self.report.name)
+            .field("stats", &self.report.stats)
+            .finish()
+    }
+}
+
+// Kept apart from the sink so `into_inner` can move the sink out while the
+// finish event is still reported on drop.
+struct Report {
+    name: &'static str,
+    stats: SinkStats,
+    // Items started since the last completed flush.
+    unflushed: u64,
+    // When the current flush was first attempted.
+    flush_start: Option<Instant>,
+    finished: bool,
+}
+
+impl Report {
+    fn flushed(&mut self) {
+        let latency = self.flush_start.take().map_or(Duration::from_secs(0), |t| t.elapsed());
+        let stats = &mut self.stats;
+        stats.flushes += 1;
+        stats.flushed += self.unflushed;
+        self.unflushed = 0;
+        stats.last_flush_latency = Some(latency);
+        stats.max_flush_latency = Some(stats.max_flush_latency.map_or(latency, |max| {
+            if latency > max { latency } else { max }
+        }));
+    }
+
+    fn finish(&mut self) {
+        if !self.finished {
+            self.finished = true;
+            if let Some(observer) = observe::observer() {
+                observer.sink_finished(self.name, &self.stats);
+            }
+        }
+    }
+}
+
+impl Drop for Report {
+    fn drop(&mut self) {
+        self.finish();
+    }
+}
+
+/// Statistics gathered about a sink created with `Sink::instrumented`.
+#[derive(Debug, Clone, Default)]
+pub struct SinkStats {
+    started: u64,
+    not_ready: u64,
+    flushed: u64,
+    flushes: u64,
+    errors: u64,
+    last_flush_latency: Option<Duration>,
+    max_flush_latency: Option<Duration>,
+}
+
+impl SinkStats {
+    /// Returns how many items the sink accepted.
+    pub fn started(&self) -> u64 {
+        self.started
+    }
+
+    /// Returns how many items the sink handed back with
+    /// `AsyncSink::NotReady`.
+    pub fn not_ready(&self) -> u64 {
+        self.not_ready
+    }
+
+    /// Returns how many of the accepted items have been flushed.
+    pub fn flushed(&self) -> u64 {
+        self.flushed
+    }
+
+    /// Returns how many flushes or closes of the sink completed.
+    pub fn flushes(&self) -> u64 {
+        self.flushes
+    }
+
+    /// Returns how many errors the sink returned.
+    pub fn errors(&self) -> u64 {
+        self.errors
+    }
+
+    /// Returns the time the last completed flush took, from its first
+    /// attempt until it completed.
+    pub fn last_flush_latency(&self) -> Option<Duration> {
+        self.last_flush_latency
+    }
+
+    /// Returns the time the slowest completed flush took.
+    pub fn max_flush_latency(&self) -> Option<Duration> {
+        self.max_flush_latency
+    }
+}
+
+pub fn new<S>(sink: S, name: &'static str) -> Instrumented<S>
+    where S: Sink,
+{
+    Instrumented {
+        sink: sink,
+        report: Report {
+            name: name,
+            stats: SinkStats::default(),
+            unflushed: 0,
+            flush_start: None,
+            finished: false,
+        },
+    }
+}
+
+impl<S> Instrumented<S> {
+    /// Returns the name this sink reports its statistics under.
+    pub fn name(&self) -> &'static str {
+        self.report.name
+    }
+
+    /// Returns the statistics gathered so far.
+    pub fn stats(&self) -> &SinkStats {
+        &self.report.stats
+    }
+
+    /// Get a shared reference to the inner sink.
+    pub fn get_ref(&self) -> &S {
+        &self.sink
+    }
+
+    /// Get a mutable reference to the inner sink.
+    pub fn get_mut(&mut self) -> &mut S {
+        &mut self.sink
+    }
+
+    /// Consumes this combinator, returning the underlying sink.
+    ///
+    /// The statistics gathered so far are reported as finished.
+    pub fn into_inner(self) -> S {
+        self.sink
+    }
+
+    // Records the outcome of flushing or closing the sink.
+    fn flush_result<E>(&mut self, res: &Poll<(), E>) {
+        match *res {
+            Ok(Async::Ready(())) => self.report.flushed(),
+            Ok(Async::NotReady) => {}
+            Err(_) => {
+                self.report.flush_start = None;
+                self.report.stats.errors += 1;
+            }
+        }
+    }
+}
+
+impl<S: Sink> Sink for Instrumented<S> {
+    type SinkItem = S::SinkItem;
+    type SinkError = S::SinkError;
+
+    fn start_send(&mut self, item: S::SinkItem) -> StartSend<S::SinkItem, S::SinkError> {
+        let res = self.sink.start_send(item);
+        match res {
+            Ok(AsyncSink::Ready) => {
+                self.report.stats.started += 1;
+                self.report.unflushed += 1;
+            }
+            Ok(AsyncSink::NotReady(_)) => self.report.stats.not_ready += 1,
+            Err(_) => self.report.stats.errors += 1,
+        }
+        if let Some(observer) = observe::observer() {
+            observer.sink_started(self.report.name, &self.report.stats);
+        }
+        res
+    }
+
+    fn poll_complete(&mut self) -> Poll<(), S::SinkError> {
+        self.report.flush_start.get_or_insert_with(Instant::now);
+        let res = self.sink.poll_complete();
+        self.flush_result(&res);
+        if let Ok(Async::Ready(())) = res {
+            if let Some(observer) = observe::observer() {
+                observer.sink_flushed(self.report.name, &self.report.stats);
+            }
+        }
+        res
+    }
+
+    fn close(&mut self) -> Poll<(), S::SinkError> {
+        self.report.flush_start.get_or_insert_with(Instant::now);
+        let res = self.sink.close();
+        self.flush_result(&res);
+        if let Ok(Async::Ready(())) = res {
+            self.report.finish();
+        }
+        res
+    }
+}
+
+// Forwarding impl of Stream from the underlying sink
+impl<S: Stream> Stream for Instrumented<S> {
+    type Item = S::Item;
+    type Error = S::Error;
+
+    fn poll(&mut self) -> Poll<Option<S::Item>, S::Error> {
+        self.sink.poll()
+    }
+}
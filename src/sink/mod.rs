@@ -9,28 +9,60 @@
 //! [online]: https://tokio.rs/docs/getting-started/streams-and-sinks/
 
 use {IntoFuture, Poll, StartSend};
+use future::{Either, Executor};
 use stream::Stream;
+#[cfg(feature = "use_std")]
+use std::time::Duration;
+#[cfg(feature = "use_std")]
+use timer::{Timer, TimerError};
 
 mod with;
 mod with_flat_map;
 // mod with_map;
 // mod with_filter;
-// mod with_filter_map;
+mod with_filter_map;
+mod close_guard;
+mod drain;
+mod err_into;
+mod feed;
 mod flush;
 mod from_err;
 mod send;
 mod send_all;
+mod send_ref;
 mod map_err;
+mod sink2;
+mod unfold;
 
 if_std! {
+    mod balance;
+    mod batched;
     mod buffer;
+    mod fanout;
+    mod flush_timeout;
+    mod instrumented;
+    mod rate_limit;
+    mod retry;
+    mod route;
+    mod spill_buffer;
     mod wait;
 
-    pub use self::buffer::Buffer;
+    pub use self::balance::{balance, balance_with, Balance, BalanceStrategy};
+    pub use self::batched::Batched;
+    pub use self::buffer::{Buffer, BufferOverflow, Backpressure};
+    pub use self::fanout::Fanout;
+    pub use self::flush_timeout::{FlushTimeout, FlushTimeoutError};
+    pub use self::instrumented::{Instrumented, SinkStats};
+    pub use self::rate_limit::{RateLimit, PerItem};
+    pub use self::retry::{Retry, RetryPolicy, ExponentialBackoff};
+    pub use self::route::{route, Route, RouteHandle, RouteError};
+    pub use self::spill_buffer::{SpillBuffer, Spill};
     pub use self::wait::Wait;
+}
 
+if_alloc! {
     // TODO: consider expanding this via e.g. FromIterator
-    impl<T> Sink for ::std::vec::Vec<T> {
+    impl<T> Sink for ::alloc::vec::Vec<T> {
         type SinkItem = T;
         type SinkError = (); // Change this to ! once it stabilizes
 
@@ -50,11 +82,53 @@ if_std! {
         }
     }
 
+    impl<T> Sink for ::alloc::collections::VecDeque<T> {
+        type SinkItem = T;
+        type SinkError = (); // Change this to ! once it stabilizes
+
+        fn start_send(&mut self, item: Self::SinkItem)
+                      -> StartSend<Self::SinkItem, Self::SinkError>
+        {
+            self.push_back(item);
+            Ok(::AsyncSink::Ready)
+        }
+
+        fn poll_complete(&mut self) -> Poll<(), Self::SinkError> {
+            Ok(::Async::Ready(()))
+        }
+
+        fn close(&mut self) -> Poll<(), Self::SinkError> {
+            Ok(::Async::Ready(()))
+        }
+    }
+
+    // Only one item type can be accepted, and `&str` items would need a
+    // lifetime which the impl can't name, so strings are built from `char`s.
+    impl Sink for ::alloc::string::String {
+        type SinkItem = char;
+        type SinkError = (); // Change this to ! once it stabilizes
+
+        fn start_send(&mut self, item: Self::SinkItem)
+                      -> StartSend<Self::SinkItem, Self::SinkError>
+        {
+            self.push(item);
+            Ok(::AsyncSink::Ready)
+        }
+
+        fn poll_complete(&mut self) -> Poll<(), Self::SinkError> {
+            Ok(::Async::Ready(()))
+        }
+
+        fn close(&mut self) -> Poll<(), Self::SinkError> {
+            Ok(::Async::Ready(()))
+        }
+    }
+
     /// A type alias for `Box<Sink + Send>`
-    pub type BoxSink<T, E> = ::std::boxed::Box<Sink<SinkItem = T, SinkError = E> +
-                                               ::core::marker::Send>;
+    pub type BoxSink<T, E> = ::alloc::boxed::Box<Sink<SinkItem = T, SinkError = E> +
+                                                 ::core::marker::Send>;
 
-    impl<S: ?Sized + Sink> Sink for ::std::boxed::Box<S> {
+    impl<S: ?Sized + Sink> Sink for ::alloc::boxed::Box<S> {
         type SinkItem = S::SinkItem;
         type SinkError = S::SinkError;
 
@@ -75,11 +149,19 @@ if_std! {
 
 pub use self::with::With;
 pub use self::with_flat_map::WithFlatMap;
+pub use self::with_filter_map::WithFilterMap;
 pub use self::flush::Flush;
+pub use self::close_guard::{CloseGuard, Closing};
 pub use self::send::Send;
 pub use self::send_all::SendAll;
+pub use self::send_ref::SendRef;
+pub use self::feed::Feed;
 pub use self::map_err::SinkMapErr;
 pub use self::from_err::SinkFromErr;
+pub use self::err_into::SinkErrInto;
+pub use self::unfold::{Unfold, unfold};
+pub use self::sink2::{Sink2, Sink2Compat, Staged};
+pub use self::drain::{Drain, drain};
 
 /// A `Sink` is a value into which other values can be sent, asynchronously.
 ///
@@ -363,11 +445,35 @@ pub trait Sink {
     fn with_filter<F>(self, f: F) -> WithFilter<Self, F>
         where F: FnMut(Self::SinkItem) -> bool,
               Self: Sized;
+     */
 
+    /// Composes a function *in front of* the sink which may drop values.
+    ///
+    /// This adapter produces a new sink that passes each value through the
+    /// given function `f`. If `f` returns `Some` the contained value is sent
+    /// to `self`, and if it returns `None` the value is discarded.
+    ///
+    /// Unlike `with` no future is involved, so this is a cheap way to
+    /// transform values or to suppress some of them, such as duplicates.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use futures::prelude::*;
+    /// use futures::stream;
+    ///
+    /// let sink = Vec::new().with_filter_map(|x: i32| {
+    ///     if x % 2 == 0 { Some(x * 10) } else { None }
+    /// });
+    /// let (sink, _) = sink.send_all(stream::iter_ok(1..6)).wait().unwrap();
+    /// assert_eq!(sink.into_inner(), vec![20, 40]);
+    /// ```
     fn with_filter_map<U, F>(self, f: F) -> WithFilterMap<Self, U, F>
         where F: FnMut(U) -> Option<Self::SinkItem>,
-              Self: Sized;
-     */
+              Self: Sized
+    {
+        with_filter_map::new(self, f)
+    }
 
     /// Transforms the error returned by the sink.
     fn sink_map_err<F, E>(self, f: F) -> SinkMapErr<Self, F>
@@ -387,6 +493,38 @@ pub trait Sink {
         from_err::new(self)
     }
 
+    /// Converts this sink's error to any error type it can be converted
+    /// `Into`, returning a new sink.
+    ///
+    /// This is like `sink_from_err`, but only requires the error to be
+    /// convertible with `Into`, which also covers conversions implemented
+    /// only in that direction.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use futures::prelude::*;
+    /// use futures::stream;
+    ///
+    /// #[derive(Debug, PartialEq)]
+    /// struct MyError;
+    ///
+    /// impl From<()> for MyError {
+    ///     fn from(_: ()) -> MyError { MyError }
+    /// }
+    ///
+    /// let sink = Vec::new().sink_err_into::<MyError>();
+    /// let items = stream::iter_ok::<_, MyError>(vec![1, 2]);
+    /// let (_, sink) = items.forward(sink).wait().unwrap();
+    /// assert_eq!(sink.into_inner(), vec![1, 2]);
+    /// ```
+    fn sink_err_into<E>(self) -> SinkErrInto<Self, E>
+        where Self::SinkError: Into<E>,
+              Self: Sized,
+    {
+        err_into::new(self)
+    }
+
 
     /// Adds a fixed-size buffer to the current sink.
     ///
@@ -404,7 +542,75 @@ pub trait Sink {
     fn buffer(self, amt: usize) -> Buffer<Self>
         where Self: Sized
     {
-        buffer::new(self, amt)
+        buffer::new(self, amt, Backpressure)
+    }
+
+    /// Adds a fixed-size buffer to the current sink, handling items sent
+    /// while the buffer is full with `policy`.
+    ///
+    /// This is like `buffer`, which rejects such items with
+    /// `AsyncSink::NotReady`, except that `policy` may instead make room for
+    /// them. The overflow policies of the stream module can be used, for
+    /// example `stream::DropOldest` to shed the oldest buffered items rather
+    /// than stall the producer, or `stream::Evict` to also hand them to a
+    /// closure.
+    ///
+    /// This method is only available when the `use_std` feature of this
+    /// library is activated, and it is activated by default.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use futures::prelude::*;
+    /// use futures::stream::Evict;
+    /// use futures::sync::mpsc;
+    ///
+    /// let mut evicted = Vec::new();
+    /// {
+    ///     let (tx, _rx) = mpsc::channel::<i32>(0);
+    ///     let tx = tx.buffer_with_policy(1, Evict(|item| evicted.push(item)));
+    ///     let mut tx = tx.wait();
+    ///     for i in 0..5 {
+    ///         tx.send(i).unwrap();
+    ///     }
+    /// }
+    /// assert_eq!(evicted, vec![1, 2]);
+    /// ```
+    #[cfg(feature = "use_std")]
+    fn buffer_with_policy<P>(self, amt: usize, policy: P) -> Buffer<Self, P>
+        where P: BufferOverflow<Self::SinkItem>,
+              Self: Sized
+    {
+        buffer::new(self, amt, policy)
+    }
+
+    /// Adds a buffer to the current sink which spills items to `storage`
+    /// rather than applying backpressure.
+    ///
+    /// Up to `amt` items are buffered in memory while the underlying sink is
+    /// unable to accept them. Items sent while the memory buffer is full,
+    /// or while earlier items are still spilled, are handed to `storage`, so
+    /// the returned sink always accepts items. Spilled items are moved back
+    /// into memory as room frees up, preserving the order in which items were
+    /// sent, and flushing the returned sink only completes once the storage
+    /// is empty again.
+    ///
+    /// Errors of `storage` are returned through the `From` conversion of
+    /// this sink's error type.
+    ///
+    /// This method is only available when the `use_std` feature of this
+    /// library is activated, and it is activated by default.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if `amt` is zero.
+    #[cfg(feature = "use_std")]
+    fn spill_buffer<H>(self, amt: usize, storage: H) -> SpillBuffer<Self, H>
+        where H: Spill<Self::SinkItem>,
+              Self::SinkError: From<H::Error>,
+              Self: Sized
+    {
+        spill_buffer::new(self, amt, storage)
     }
 
     /// A future that completes when the sink has finished processing all
@@ -419,6 +625,154 @@ pub trait Sink {
         flush::new(self)
     }
 
+    /// Guards this sink against being dropped with unflushed data.
+    ///
+    /// If the returned sink is dropped after accepting items without a
+    /// successful flush or close since, a `Closing` future is spawned onto a
+    /// clone of `executor` which closes the underlying sink, delivering the
+    /// buffered items. Errors of that close are discarded, as is the sink if
+    /// the executor refuses to spawn the future.
+    ///
+    /// This helps when the task driving a buffering sink may be cancelled,
+    /// for example because it lost a `select`.
+    fn close_guard<E>(self, executor: &E) -> CloseGuard<Self, E>
+        where E: Executor<Closing<Self>> + Clone,
+              Self: Sized
+    {
+        close_guard::new(self, executor.clone())
+    }
+
+    /// Fails flushes and closes of this sink which don't complete within
+    /// `duration`.
+    ///
+    /// Once `poll_complete` or `close` of this sink returns `NotReady`, a
+    /// delay is obtained from a clone of `timer`. If the flush or close isn't
+    /// done by the time the delay fires, `FlushTimeoutError::Elapsed` is
+    /// returned. The timeout starts anew with the next flush. Errors of this
+    /// sink are returned as `FlushTimeoutError::Inner`.
+    ///
+    /// This method is only available when the `use_std` feature of this
+    /// library is activated, and it is activated by default.
+    #[cfg(feature = "use_std")]
+    fn flush_timeout<T>(self, duration: Duration, timer: &T) -> FlushTimeout<Self, T>
+        where T: Timer + Clone,
+              Self: Sized
+    {
+        flush_timeout::new(self, duration, timer.clone())
+    }
+
+    /// Collects items into batches of up to `max` items before sending them
+    /// to this sink.
+    ///
+    /// A batch is sent as soon as it holds `max` items, or once `duration`
+    /// has passed since its first item was accepted, whichever comes first.
+    /// The delay is obtained from a clone of `timer`, and errors of the timer
+    /// are returned through the `From` conversion of this sink's error type.
+    ///
+    /// Flushing the returned sink doesn't cut a batch short, it only completes
+    /// once the current batch has been sent because of either limit and this
+    /// sink has been flushed. Closing it sends the current batch right away.
+    ///
+    /// This method is only available when the `use_std` feature of this
+    /// library is activated, and it is activated by default.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if `max` is zero.
+    #[cfg(feature = "use_std")]
+    fn batched<I, T>(self, max: usize, duration: Duration, timer: &T) -> Batched<Self, I, T>
+        where Self: Sink<SinkItem = ::std::vec::Vec<I>> + Sized,
+              Self::SinkError: From<TimerError>,
+              T: Timer + Clone,
+    {
+        batched::new(self, max, duration, timer.clone())
+    }
+
+    /// Gathers statistics about the items sent to this sink and reports them
+    /// under `name` to the observer registered with `observe::set_observer`.
+    ///
+    /// The number of items accepted, handed back with `AsyncSink::NotReady`
+    /// and flushed, the number of errors, and how long flushes take from
+    /// their first attempt to their completion are recorded. The observer is
+    /// told about the statistics after every `start_send`, after every
+    /// completed flush, and once more when the sink is closed or dropped.
+    /// They can also be inspected with `Instrumented::stats`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use futures::prelude::*;
+    /// use futures::stream;
+    ///
+    /// let sink = Vec::new().instrumented("numbers");
+    /// let (sink, _) = sink.send_all(stream::iter_ok(vec![1, 2])).wait().unwrap();
+    /// assert_eq!(sink.stats().started(), 2);
+    /// assert_eq!(sink.stats().flushed(), 2);
+    /// ```
+    ///
+    /// This method is only available when the `use_std` feature of this
+    /// library is activated, and it is activated by default.
+    #[cfg(feature = "use_std")]
+    fn instrumented(self, name: &'static str) -> Instrumented<Self>
+        where Self: Sized
+    {
+        instrumented::new(self, name)
+    }
+
+    /// Limits the rate at which items are accepted by this sink to `rate`
+    /// items per second, allowing bursts of up to `burst` items.
+    ///
+    /// This is a token bucket holding up to `burst` tokens, which refills at
+    /// `rate` tokens per second and starts out full. Each item takes up one
+    /// token, or as many as computed with `RateLimit::weighted`. While there
+    /// aren't enough tokens `start_send` returns `NotReady` and arranges for
+    /// the task to be notified once there are.
+    ///
+    /// The time is taken from and waited for with a clone of `timer`. Errors
+    /// of the timer are returned through the `From` conversion of this
+    /// sink's error type.
+    ///
+    /// This method is only available when the `use_std` feature of this
+    /// library is activated, and it is activated by default.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if `rate` or `burst` is zero.
+    #[cfg(feature = "use_std")]
+    fn rate_limit<T>(self, rate: u32, burst: u32, timer: &T) -> RateLimit<Self, T>
+        where T: Timer + Clone,
+              Self::SinkError: From<TimerError>,
+              Self: Sized
+    {
+        rate_limit::new(self, rate, burst, timer.clone())
+    }
+
+    /// Retries sends and flushes of this sink which fail, after a delay
+    /// decided by `policy`.
+    ///
+    /// Each item is cloned before being sent. If sending it fails and
+    /// `policy` decides to retry, the item is accepted anyway and sent again
+    /// once the delay obtained from a clone of `timer` fires. A failed
+    /// `poll_complete` is retried likewise. Errors which `policy` doesn't
+    /// retry, for example after too many attempts, are returned.
+    ///
+    /// Note that a failed flush is retried by flushing again, so any item
+    /// this sink lost in the failure isn't sent again. Errors of the timer
+    /// are returned through the `From` conversion of this sink's error type.
+    ///
+    /// This method is only available when the `use_std` feature of this
+    /// library is activated, and it is activated by default.
+    #[cfg(feature = "use_std")]
+    fn retry<P, T>(self, policy: P, timer: &T) -> Retry<Self, P, T>
+        where P: RetryPolicy<Self::SinkError>,
+              T: Timer + Clone,
+              Self::SinkItem: Clone,
+              Self::SinkError: From<TimerError>,
+              Self: Sized
+    {
+        retry::new(self, policy, timer.clone())
+    }
+
     /// A future that completes after the given item has been fully processed
     /// into the sink, including flushing.
     ///
@@ -433,6 +787,43 @@ pub trait Sink {
         send::new(self, item)
     }
 
+    /// A future that completes after the given item has been fully processed
+    /// into the sink, including flushing, borrowing the sink rather than
+    /// taking ownership of it.
+    ///
+    /// This is like `send`, but makes it easy to keep using a sink which is
+    /// stored elsewhere, for example in a struct.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use futures::prelude::*;
+    /// use futures::sync::mpsc;
+    ///
+    /// let (mut tx, rx) = mpsc::channel::<i32>(2);
+    /// tx.send_ref(1).wait().unwrap();
+    /// tx.send_ref(2).wait().unwrap();
+    /// drop(tx);
+    /// assert_eq!(rx.collect().wait(), Ok(vec![1, 2]));
+    /// ```
+    fn send_ref<'a>(&'a mut self, item: Self::SinkItem) -> SendRef<'a, Self>
+        where Self: Sized
+    {
+        send_ref::new(self, item)
+    }
+
+    /// A future that completes once the given item has been accepted by the
+    /// sink, borrowing the sink.
+    ///
+    /// Unlike `send_ref` the sink isn't flushed, so several items can be fed
+    /// to it before flushing them all at once, for example with
+    /// `(&mut sink).flush()`.
+    fn feed<'a>(&'a mut self, item: Self::SinkItem) -> Feed<'a, Self>
+        where Self: Sized
+    {
+        feed::new(self, item)
+    }
+
     /// A future that completes after the given stream has been fully processed
     /// into the sink, including flushing.
     ///
@@ -454,6 +845,64 @@ pub trait Sink {
     {
         send_all::new(self, stream)
     }
+
+    /// Wraps this sink in an `Either` sink, making it the left-hand variant
+    /// of that `Either`.
+    ///
+    /// This can be used in combination with the `right_sink` method to write
+    /// `if` statements that evaluate to different sinks in different
+    /// branches.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use futures::prelude::*;
+    /// use futures::sink;
+    ///
+    /// let keep = true;
+    /// let mut sink = if keep {
+    ///     Vec::new().left_sink()
+    /// } else {
+    ///     sink::drain().right_sink()
+    /// };
+    ///
+    /// assert_eq!(sink.start_send(1), Ok(AsyncSink::Ready));
+    /// ```
+    fn left_sink<B>(self) -> Either<Self, B>
+        where B: Sink<SinkItem = Self::SinkItem, SinkError = Self::SinkError>,
+              Self: Sized
+    {
+        Either::A(self)
+    }
+
+    /// Wraps this sink in an `Either` sink, making it the right-hand variant
+    /// of that `Either`.
+    ///
+    /// This can be used in combination with the `left_sink` method to write
+    /// `if` statements that evaluate to different sinks in different
+    /// branches.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use futures::prelude::*;
+    /// use futures::sink;
+    ///
+    /// let keep = false;
+    /// let mut sink = if keep {
+    ///     Vec::new().left_sink()
+    /// } else {
+    ///     sink::drain().right_sink()
+    /// };
+    ///
+    /// assert_eq!(sink.start_send(1), Ok(AsyncSink::Ready));
+    /// ```
+    fn right_sink<A>(self) -> Either<A, Self>
+        where A: Sink<SinkItem = Self::SinkItem, SinkError = Self::SinkError>,
+              Self: Sized
+    {
+        Either::B(self)
+    }
 }
 
 impl<'a, S: ?Sized + Sink> Sink for &'a mut S {
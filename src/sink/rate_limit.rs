@@ -0,0 +1,178 @@
+use std::cmp;
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use {Async, AsyncSink, Future, Poll, StartSend, Stream};
+use sink::Sink;
+use stream::Weigh;
+use timer::{Timer, TimerError};
+
+/// A `Weigh` implementation giving every item a weight of one, so that a
+/// `RateLimit` sink limits the number of items.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PerItem;
+
+impl<T> Weigh<T> for PerItem {
+    fn weigh(&mut self, _item: &T) -> usize {
+        1
+    }
+}
+
+/// Sink for the `Sink::rate_limit` combinator, which holds back items to
+/// conform to a token bucket.
+#[must_use = "sinks do nothing unless polled"]
+pub struct RateLimit<S, T, W = PerItem> where T: Timer {
+    sink: S,
+    timer: T,
+    weigh: W,
+    // The time it takes to refill a single token.
+    interval: Duration,
+    burst: u32,
+    // The time at which the bucket will be full again, `None` if it is.
+    full_at: Option<Instant>,
+    delay: Option<T::Delay>,
+}
+
+impl<S, T, W> fmt::Debug for RateLimit<S, T, W>
+    where S: fmt::Debug,
+          T: Timer,
+{
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("RateLimit")
+            .field("sink", &self.sink)
+            .field("interval", &self.interval)
+            .field("burst", &self.burst)
+            .finish()
+    }
+}
+
+pub fn new<S, T>(sink: S, rate: u32, burst: u32, timer: T) -> RateLimit<S, T>
+    where S: Sink,
+          T: Timer,
+{
+    assert!(rate > 0, "rate of a rate limit must be at least one");
+    assert!(burst > 0, "burst of a rate limit must be at least one");
+    RateLimit {
+        sink: sink,
+        timer: timer,
+        weigh: PerItem,
+        interval: Duration::from_secs(1) / rate,
+        burst: burst,
+        full_at: None,
+        delay: None,
+    }
+}
+
+impl<S, T, W> RateLimit<S, T, W> where T: Timer {
+    /// Makes each item take up as many tokens as `weigh` returns for it,
+    /// rather than one.
+    ///
+    /// Items weighing more than the burst size are let through once the
+    /// bucket is full, after which the bucket stays empty until their full
+    /// weight has been refilled.
+    pub fn weighted<V>(self, weigh: V) -> RateLimit<S, T, V> {
+        RateLimit {
+            sink: self.sink,
+            timer: self.timer,
+            weigh: weigh,
+            interval: self.interval,
+            burst: self.burst,
+            full_at: self.full_at,
+            delay: self.delay,
+        }
+    }
+
+    /// Get a shared reference to the inner sink.
+    pub fn get_ref(&self) -> &S {
+        &self.sink
+    }
+
+    /// Get a mutable reference to the inner sink.
+    pub fn get_mut(&mut self) -> &mut S {
+        &mut self.sink
+    }
+
+    /// Consumes this combinator, returning the underlying sink.
+    ///
+    /// Note that this may discard intermediate state of this combinator, so
+    /// care should be taken to avoid losing resources when this is called.
+    pub fn into_inner(self) -> S {
+        self.sink
+    }
+
+    fn tokens(&self, n: usize) -> Duration {
+        self.interval * cmp::min(n, u32::MAX as usize) as u32
+    }
+}
+
+impl<S, T, W> Sink for RateLimit<S, T, W>
+    where S: Sink,
+          S::SinkError: From<TimerError>,
+          T: Timer,
+          W: Weigh<S::SinkItem>,
+{
+    type SinkItem = S::SinkItem;
+    type SinkError = S::SinkError;
+
+    fn start_send(&mut self, item: S::SinkItem) -> StartSend<S::SinkItem, S::SinkError> {
+        let weight = self.weigh.weigh(&item);
+        let cost = self.tokens(weight);
+        let needed = self.tokens(cmp::min(weight, self.burst as usize));
+        let capacity = self.tokens(self.burst as usize);
+
+        loop {
+            // The bucket is modeled by the time at which it will be full
+            // again: an item fits once that is at most `capacity - needed`
+            // away.
+            let now = self.timer.now();
+            let full_at = match self.full_at {
+                Some(at) if at > now => at,
+                _ => now,
+            };
+            if full_at + needed <= now + capacity {
+                self.delay = None;
+                return match self.sink.start_send(item)? {
+                    AsyncSink::Ready => {
+                        self.full_at = Some(full_at + cost);
+                        Ok(AsyncSink::Ready)
+                    }
+                    AsyncSink::NotReady(item) => Ok(AsyncSink::NotReady(item)),
+                }
+            }
+
+            if self.delay.is_none() {
+                let ready_at = full_at + needed - capacity;
+                self.delay = Some(self.timer.delay_until(ready_at));
+            }
+            match self.delay.as_mut().unwrap().poll() {
+                Ok(Async::NotReady) => return Ok(AsyncSink::NotReady(item)),
+                Ok(Async::Ready(())) => self.delay = None,
+                Err(e) => {
+                    self.delay = None;
+                    return Err(From::from(e))
+                }
+            }
+        }
+    }
+
+    fn poll_complete(&mut self) -> Poll<(), S::SinkError> {
+        self.sink.poll_complete()
+    }
+
+    fn close(&mut self) -> Poll<(), S::SinkError> {
+        self.sink.close()
+    }
+}
+
+// Forwarding impl of Stream from the underlying sink
+impl<S, T, W> Stream for RateLimit<S, T, W>
+    where S: Stream,
+          T: Timer,
+{
+    type Item = S::Item;
+    type Error = S::Error;
+
+    fn poll(&mut self) -> Poll<Option<S::Item>, S::Error> {
+        self.sink.poll()
+    }
+}
@@ -0,0 +1,263 @@
+use std::fmt;
+use std::time::Duration;
+
+use {Async, AsyncSink, Future, Poll, StartSend, Stream};
+use sink::Sink;
+use timer::{Timer, TimerError};
+
+/// Decides whether and when a `Retry` sink retries after an error.
+///
+/// This is implemented for `ExponentialBackoff`, which retries every error,
+/// and for closures taking the error and the attempt, so errors can be
+/// classified as retryable by the closure.
+pub trait RetryPolicy<E> {
+    /// Returns how long to wait before retrying after `error`, or `None` if
+    /// the error should be returned instead.
+    ///
+    /// `attempt` is the number of consecutive failures so far, starting at
+    /// one for the first failure.
+    fn retry(&mut self, error: &E, attempt: u32) -> Option<Duration>;
+}
+
+impl<E, F> RetryPolicy<E> for F
+    where F: FnMut(&E, u32) -> Option<Duration>,
+{
+    fn retry(&mut self, error: &E, attempt: u32) -> Option<Duration> {
+        self(error, attempt)
+    }
+}
+
+/// A `RetryPolicy` retrying every error with exponentially growing delays.
+///
+/// The first retry happens after the initial delay, and each further retry
+/// waits twice as long as the one before, up to a maximum delay.
+#[derive(Debug, Clone, Copy)]
+pub struct ExponentialBackoff {
+    initial: Duration,
+    max_delay: Duration,
+    max_attempts: u32,
+}
+
+impl ExponentialBackoff {
+    /// Creates a policy retrying after `initial` at first, without limiting
+    /// the delay or the number of attempts.
+    pub fn new(initial: Duration) -> ExponentialBackoff {
+        ExponentialBackoff {
+            initial: initial,
+            max_delay: Duration::from_secs(u64::MAX),
+            max_attempts: u32::MAX,
+        }
+    }
+
+    /// Never waits longer than `max` before retrying.
+    pub fn max_delay(mut self, max: Duration) -> ExponentialBackoff {
+        self.max_delay = max;
+        self
+    }
+
+    /// Gives up after `max` consecutive failures, returning the last error.
+    pub fn max_attempts(mut self, max: u32) -> ExponentialBackoff {
+        self.max_attempts = max;
+        self
+    }
+
+    /// Returns the delay before retrying after the `attempt`th consecutive
+    /// failure, or `None` if that's one too many.
+    ///
+    /// This is useful to implement a policy which only retries some errors
+    /// with a closure.
+    pub fn delay(&self, attempt: u32) -> Option<Duration> {
+        if attempt == 0 || attempt >= self.max_attempts {
+            return None
+        }
+        let factor = 1u32.checked_shl(attempt - 1).unwrap_or(u32::MAX);
+        let delay = self.initial.checked_mul(factor).unwrap_or(self.max_delay);
+        Some(if delay > self.max_delay { self.max_delay } else { delay })
+    }
+}
+
+impl<E> RetryPolicy<E> for ExponentialBackoff {
+    fn retry(&mut self, _error: &E, attempt: u32) -> Option<Duration> {
+        self.delay(attempt)
+    }
+}
+
+/// Sink for the `Sink::retry` combinator, which retries failed sends and
+/// flushes of a sink after a delay.
+#[must_use = "sinks do nothing unless polled"]
+pub struct Retry<S, P, T> where S: Sink, T: Timer {
+    sink: S,
+    policy: P,
+    timer: T,
+    // The item whose send failed, to be sent again once `delay` fires.
+    pending: Option<S::SinkItem>,
+    attempt: u32,
+    delay: Option<T::Delay>,
+}
+
+impl<S, P, T> fmt::Debug for Retry<S, P, T>
+    where S: Sink + fmt::Debug,
+          S::SinkItem: fmt::Debug,
+          T: Timer,
+{
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("Retry")
+            .field("sink", &self.sink)
+            .field("pending", &self.pending)
+            .field("attempt", &self.attempt)
+            .finish()
+    }
+}
+
+pub fn new<S, P, T>(sink: S, policy: P, timer: T) -> Retry<S, P, T>
+    where S: Sink,
+          T: Timer,
+{
+    Retry {
+        sink: sink,
+        policy: policy,
+        timer: timer,
+        pending: None,
+        attempt: 0,
+        delay: None,
+    }
+}
+
+impl<S, P, T> Retry<S, P, T> where S: Sink, T: Timer {
+    /// Get a shared reference to the inner sink.
+    pub fn get_ref(&self) -> &S {
+        &self.sink
+    }
+
+    /// Get a mutable reference to the inner sink.
+    pub fn get_mut(&mut self) -> &mut S {
+        &mut self.sink
+    }
+
+    /// Consumes this combinator, returning the underlying sink.
+    ///
+    /// Note that this may discard intermediate state of this combinator, so
+    /// care should be taken to avoid losing resources when this is called.
+    pub fn into_inner(self) -> S {
+        self.sink
+    }
+}
+
+impl<S, P, T> Retry<S, P, T>
+    where S: Sink,
+          S::SinkItem: Clone,
+          S::SinkError: From<TimerError>,
+          P: RetryPolicy<S::SinkError>,
+          T: Timer,
+{
+    // Consults the policy about `err`, either starting the delay before the
+    // next attempt or giving up.
+    fn backoff(&mut self, err: S::SinkError) -> Result<(), S::SinkError> {
+        self.attempt = self.attempt.saturating_add(1);
+        match self.policy.retry(&err, self.attempt) {
+            Some(duration) => {
+                self.delay = Some(self.timer.delay(duration));
+                Ok(())
+            }
+            None => {
+                self.attempt = 0;
+                self.pending = None;
+                Err(err)
+            }
+        }
+    }
+
+    // Waits out the current delay, if any, and sends the pending item again.
+    fn poll_retry(&mut self) -> Poll<(), S::SinkError> {
+        loop {
+            let delayed = match self.delay {
+                Some(ref mut delay) => delay.poll(),
+                None => Ok(Async::Ready(())),
+            };
+            match delayed {
+                Ok(Async::Ready(())) => self.delay = None,
+                Ok(Async::NotReady) => return Ok(Async::NotReady),
+                // The delay is of no use anymore, so don't poll it again.
+                Err(e) => {
+                    self.delay = None;
+                    return Err(From::from(e))
+                }
+            }
+
+            let item = match self.pending.take() {
+                Some(item) => item,
+                None => return Ok(Async::Ready(())),
+            };
+            match self.sink.start_send(item.clone()) {
+                Ok(AsyncSink::Ready) => self.attempt = 0,
+                Ok(AsyncSink::NotReady(_)) => {
+                    self.pending = Some(item);
+                    return Ok(Async::NotReady)
+                }
+                Err(e) => {
+                    self.pending = Some(item);
+                    self.backoff(e)?;
+                }
+            }
+        }
+    }
+}
+
+impl<S, P, T> Sink for Retry<S, P, T>
+    where S: Sink,
+          S::SinkItem: Clone,
+          S::SinkError: From<TimerError>,
+          P: RetryPolicy<S::SinkError>,
+          T: Timer,
+{
+    type SinkItem = S::SinkItem;
+    type SinkError = S::SinkError;
+
+    fn start_send(&mut self, item: S::SinkItem) -> StartSend<S::SinkItem, S::SinkError> {
+        if self.poll_retry()?.is_not_ready() {
+            return Ok(AsyncSink::NotReady(item))
+        }
+        match self.sink.start_send(item.clone()) {
+            Ok(AsyncSink::Ready) => self.attempt = 0,
+            Ok(AsyncSink::NotReady(_)) => return Ok(AsyncSink::NotReady(item)),
+            // The item is accepted and sent again once the delay fires.
+            Err(e) => {
+                self.pending = Some(item);
+                self.backoff(e)?;
+            }
+        }
+        Ok(AsyncSink::Ready)
+    }
+
+    fn poll_complete(&mut self) -> Poll<(), S::SinkError> {
+        loop {
+            try_ready!(self.poll_retry());
+            match self.sink.poll_complete() {
+                Ok(Async::Ready(())) => {
+                    self.attempt = 0;
+                    return Ok(Async::Ready(()))
+                }
+                Ok(Async::NotReady) => return Ok(Async::NotReady),
+                Err(e) => self.backoff(e)?,
+            }
+        }
+    }
+
+    fn close(&mut self) -> Poll<(), S::SinkError> {
+        try_ready!(self.poll_complete());
+        self.sink.close()
+    }
+}
+
+// Forwarding impl of Stream from the underlying sink
+impl<S, P, T> Stream for Retry<S, P, T>
+    where S: Sink + Stream,
+          T: Timer,
+{
+    type Item = S::Item;
+    type Error = S::Error;
+
+    fn poll(&mut self) -> Poll<Option<S::Item>, S::Error> {
+        self.sink.poll()
+    }
+}
@@ -0,0 +1,270 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::hash::Hash;
+use std::mem;
+use std::prelude::v1::*;
+use std::sync::{Arc, Mutex};
+
+use {Async, Poll, StartSend};
+use sink::Sink;
+
+/// Creates a sink which sends each item to the sink registered under the key
+/// computed for it by `key`.
+///
+/// An item is accepted once the sink it is routed to accepts it, and is
+/// handed back with `AsyncSink::NotReady` if that sink isn't ready. A flush or
+/// close only completes once it has completed for all of the sinks. Items for
+/// which no sink is registered are returned in `RouteError::NoRoute`.
+///
+/// Sinks can be registered and removed with a `RouteHandle`, even while the
+/// router is being driven elsewhere, see `Route::handle`.
+///
+/// This function is only available when the `use_std` feature of this
+/// library is activated, and it is activated by default.
+///
+/// # Examples
+///
+/// ```
+/// use std::collections::HashMap;
+///
+/// use futures::prelude::*;
+/// use futures::sink;
+///
+/// let mut sinks = HashMap::new();
+/// sinks.insert(true, Vec::new());
+/// sinks.insert(false, Vec::new());
+///
+/// let mut router = sink::route(|x: &i32| x % 2 == 0, sinks);
+/// for i in 0..5 {
+///     assert_eq!(router.start_send(i), Ok(AsyncSink::Ready));
+/// }
+/// assert_eq!(router.get_ref(&true), Some(&vec![0, 2, 4]));
+/// assert_eq!(router.get_ref(&false), Some(&vec![1, 3]));
+/// ```
+pub fn route<K, S, F>(key: F, sinks: HashMap<K, S>) -> Route<K, S, F>
+    where K: Hash + Eq,
+          S: Sink,
+          F: FnMut(&S::SinkItem) -> K,
+{
+    Route {
+        key: key,
+        sinks: sinks,
+        closing: Vec::new(),
+        changes: Arc::new(Mutex::new(Vec::new())),
+    }
+}
+
+/// Sink returned by `sink::route`, dispatching each item to one of a set of
+/// keyed sinks.
+#[must_use = "sinks do nothing unless polled"]
+pub struct Route<K, S, F> {
+    key: F,
+    sinks: HashMap<K, S>,
+    // Removed sinks, which are closed before being dropped.
+    closing: Vec<S>,
+    changes: Arc<Mutex<Vec<Change<K, S>>>>,
+}
+
+enum Change<K, S> {
+    Insert(K, S),
+    Remove(K),
+}
+
+impl<K, S, F> fmt::Debug for Route<K, S, F>
+    where K: fmt::Debug + Hash + Eq,
+          S: fmt::Debug,
+{
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("Route")
+            .field("sinks", &self.sinks)
+            .field("closing", &self.closing.len())
+            .finish()
+    }
+}
+
+/// A handle to register and remove the sinks of a `Route` sink.
+///
+/// Changes made through the handle take effect the next time the router is
+/// used. The handle can be cloned, and it can be sent to other threads if the
+/// keys and sinks can.
+pub struct RouteHandle<K, S> {
+    changes: Arc<Mutex<Vec<Change<K, S>>>>,
+}
+
+impl<K, S> Clone for RouteHandle<K, S> {
+    fn clone(&self) -> RouteHandle<K, S> {
+        RouteHandle { changes: self.changes.clone() }
+    }
+}
+
+impl<K, S> fmt::Debug for RouteHandle<K, S> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("RouteHandle").finish()
+    }
+}
+
+impl<K, S> RouteHandle<K, S> {
+    /// Registers `sink` under `key`, replacing any sink registered under it
+    /// before.
+    ///
+    /// A replaced sink is closed by the router before it is dropped.
+    pub fn insert(&self, key: K, sink: S) {
+        self.changes.lock().unwrap().push(Change::Insert(key, sink));
+    }
+
+    /// Removes the sink registered under `key`.
+    ///
+    /// The sink is closed by the router before it is dropped, so that items
+    /// it accepted aren't lost.
+    pub fn remove(&self, key: K) {
+        self.changes.lock().unwrap().push(Change::Remove(key));
+    }
+}
+
+/// Error returned by a `Route` sink.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RouteError<T, E> {
+    /// No sink is registered for the key of the item, which is handed back.
+    NoRoute(T),
+
+    /// One of the sinks returned an error.
+    Inner(E),
+}
+
+impl<T, E: fmt::Display> fmt::Display for RouteError<T, E> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            RouteError::NoRoute(_) => write!(fmt, "no sink registered for item"),
+            RouteError::Inner(ref e) => e.fmt(fmt),
+        }
+    }
+}
+
+impl<T: fmt::Debug, E: Error> Error for RouteError<T, E> {
+    fn description(&self) -> &str {
+        match *self {
+            RouteError::NoRoute(_) => "no sink registered for item",
+            RouteError::Inner(_) => "sink returned an error",
+        }
+    }
+}
+
+impl<K, S, F> Route<K, S, F>
+    where K: Hash + Eq,
+          S: Sink,
+{
+    /// Returns a handle which can be used to register and remove sinks.
+    pub fn handle(&self) -> RouteHandle<K, S> {
+        RouteHandle { changes: self.changes.clone() }
+    }
+
+    /// Returns a reference to the sink registered under `key`.
+    ///
+    /// Changes made through a `RouteHandle` which the router hasn't seen yet
+    /// aren't taken into account.
+    pub fn get_ref(&self, key: &K) -> Option<&S> {
+        self.sinks.get(key)
+    }
+
+    /// Returns a mutable reference to the sink registered under `key`.
+    ///
+    /// Changes made through a `RouteHandle` which the router hasn't seen yet
+    /// aren't taken into account.
+    pub fn get_mut(&mut self, key: &K) -> Option<&mut S> {
+        self.sinks.get_mut(key)
+    }
+
+    /// Returns the number of registered sinks.
+    pub fn len(&self) -> usize {
+        self.sinks.len()
+    }
+
+    /// Returns whether no sinks are registered.
+    pub fn is_empty(&self) -> bool {
+        self.sinks.is_empty()
+    }
+
+    /// Consumes this router, returning the registered sinks.
+    pub fn into_inner(self) -> HashMap<K, S> {
+        self.sinks
+    }
+
+    fn apply_changes(&mut self) {
+        let changes = mem::replace(&mut *self.changes.lock().unwrap(), Vec::new());
+        for change in changes {
+            let old = match change {
+                Change::Insert(key, sink) => self.sinks.insert(key, sink),
+                Change::Remove(key) => self.sinks.remove(&key),
+            };
+            self.closing.extend(old);
+        }
+    }
+
+    fn poll_closing(&mut self) -> Poll<(), S::SinkError> {
+        let mut i = 0;
+        while i < self.closing.len() {
+            match self.closing[i].close() {
+                Ok(Async::Ready(())) => {
+                    self.closing.swap_remove(i);
+                }
+                Ok(Async::NotReady) => i += 1,
+                // A sink which failed to close is given up on, so that it
+                // isn't closed again.
+                Err(e) => {
+                    self.closing.swap_remove(i);
+                    return Err(e)
+                }
+            }
+        }
+        if self.closing.is_empty() {
+            Ok(Async::Ready(()))
+        } else {
+            Ok(Async::NotReady)
+        }
+    }
+}
+
+impl<K, S, F> Sink for Route<K, S, F>
+    where K: Hash + Eq,
+          S: Sink,
+          F: FnMut(&S::SinkItem) -> K,
+{
+    type SinkItem = S::SinkItem;
+    type SinkError = RouteError<S::SinkItem, S::SinkError>;
+
+    fn start_send(&mut self, item: S::SinkItem) -> StartSend<S::SinkItem, Self::SinkError> {
+        self.apply_changes();
+        let key = (self.key)(&item);
+        match self.sinks.get_mut(&key) {
+            Some(sink) => sink.start_send(item).map_err(RouteError::Inner),
+            None => Err(RouteError::NoRoute(item)),
+        }
+    }
+
+    fn poll_complete(&mut self) -> Poll<(), Self::SinkError> {
+        self.apply_changes();
+        let mut ready = self.poll_closing().map_err(RouteError::Inner)?.is_ready();
+        for sink in self.sinks.values_mut() {
+            ready = sink.poll_complete().map_err(RouteError::Inner)?.is_ready() && ready;
+        }
+        if ready {
+            Ok(Async::Ready(()))
+        } else {
+            Ok(Async::NotReady)
+        }
+    }
+
+    fn close(&mut self) -> Poll<(), Self::SinkError> {
+        self.apply_changes();
+        let mut ready = self.poll_closing().map_err(RouteError::Inner)?.is_ready();
+        for sink in self.sinks.values_mut() {
+            ready = sink.close().map_err(RouteError::Inner)?.is_ready() && ready;
+        }
+        if ready {
+            Ok(Async::Ready(()))
+        } else {
+            Ok(Async::NotReady)
+        }
+    }
+}
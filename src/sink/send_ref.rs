@@ -0,0 +1,48 @@
+use {Poll, Async, Future, AsyncSink};
+use sink::Sink;
+
+/// Future for the `Sink::send_ref` combinator, which sends a value to a
+/// borrowed sink and then waits until the sink has fully flushed.
+#[derive(Debug)]
+#[must_use = "futures do nothing unless polled"]
+pub struct SendRef<'a, S: Sink + ?Sized + 'a> {
+    sink: &'a mut S,
+    item: Option<S::SinkItem>,
+}
+
+pub fn new<'a, S: Sink + ?Sized>(sink: &'a mut S, item: S::SinkItem) -> SendRef<'a, S> {
+    SendRef {
+        sink: sink,
+        item: Some(item),
+    }
+}
+
+impl<'a, S: Sink + ?Sized> SendRef<'a, S> {
+    /// Get a shared reference to the inner sink.
+    pub fn get_ref(&self) -> &S {
+        self.sink
+    }
+
+    /// Get a mutable reference to the inner sink.
+    pub fn get_mut(&mut self) -> &mut S {
+        self.sink
+    }
+}
+
+impl<'a, S: Sink + ?Sized> Future for SendRef<'a, S> {
+    type Item = ();
+    type Error = S::SinkError;
+
+    fn poll(&mut self) -> Poll<(), S::SinkError> {
+        if let Some(item) = self.item.take() {
+            if let AsyncSink::NotReady(item) = self.sink.start_send(item)? {
+                self.item = Some(item);
+                return Ok(Async::NotReady);
+            }
+        }
+
+        // we're done sending the item, but want to block on flushing the
+        // sink
+        self.sink.poll_complete()
+    }
+}
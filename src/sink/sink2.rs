@@ -0,0 +1,273 @@
+use core::fmt;
+
+use {Async, AsyncSink, Poll, StartSend, Stream};
+use sink::Sink;
+
+/// A variant of `Sink` which is asked for capacity *before* being handed an
+/// item.
+///
+/// With `Sink`, an item has to be constructed before `start_send` can tell
+/// whether there's room for it, and it's handed back in `AsyncSink::NotReady`
+/// if there isn't, leaving the caller to hold on to it. With `Sink2` the
+/// caller first waits for `poll_ready` to return `Ready`, after which the
+/// sink must accept exactly one item through `start_send`. Items are never
+/// handed back, so adaptors don't have to buffer rejected ones.
+///
+/// A `Sink2` can be used where a `Sink` is expected through `Sink2::compat`,
+/// and a `Sink` can be used as a `Sink2` through `Staged`.
+pub trait Sink2 {
+    /// The type of value that the sink accepts.
+    type SinkItem;
+
+    /// The type of value produced by the sink when an error occurs.
+    type SinkError;
+
+    /// Attempts to prepare the sink to receive an item.
+    ///
+    /// This returns `Ready` once the sink can accept an item, after which
+    /// the next call to `start_send` must succeed unless the sink fails.
+    /// Otherwise the current task is notified once the sink may be ready.
+    fn poll_ready(&mut self) -> Poll<(), Self::SinkError>;
+
+    /// Begins the process of sending an item to the sink.
+    ///
+    /// This must only be called after `poll_ready` returned `Ready`, once for
+    /// each such return. Like with `Sink`, the item isn't necessarily sent
+    /// until the sink has been flushed.
+    ///
+    /// # Panics
+    ///
+    /// Implementations may panic if this is called without a preceding
+    /// successful `poll_ready`.
+    fn start_send(&mut self, item: Self::SinkItem) -> Result<(), Self::SinkError>;
+
+    /// Flushes all items sent to the sink, returning `Ready` once they made
+    /// it to their destination.
+    fn poll_flush(&mut self) -> Poll<(), Self::SinkError>;
+
+    /// Flushes the sink and then shuts it down, returning `Ready` once done.
+    fn poll_close(&mut self) -> Poll<(), Self::SinkError>;
+
+    /// Wraps this sink so that it can be used as a `Sink`.
+    fn compat(self) -> Sink2Compat<Self>
+        where Self: Sized
+    {
+        Sink2Compat { sink: self }
+    }
+}
+
+impl<'a, S: ?Sized + Sink2> Sink2 for &'a mut S {
+    type SinkItem = S::SinkItem;
+    type SinkError = S::SinkError;
+
+    fn poll_ready(&mut self) -> Poll<(), Self::SinkError> {
+        (**self).poll_ready()
+    }
+
+    fn start_send(&mut self, item: Self::SinkItem) -> Result<(), Self::SinkError> {
+        (**self).start_send(item)
+    }
+
+    fn poll_flush(&mut self) -> Poll<(), Self::SinkError> {
+        (**self).poll_flush()
+    }
+
+    fn poll_close(&mut self) -> Poll<(), Self::SinkError> {
+        (**self).poll_close()
+    }
+}
+
+if_alloc! {
+    impl<S: ?Sized + Sink2> Sink2 for ::alloc::boxed::Box<S> {
+        type SinkItem = S::SinkItem;
+        type SinkError = S::SinkError;
+
+        fn poll_ready(&mut self) -> Poll<(), Self::SinkError> {
+            (**self).poll_ready()
+        }
+
+        fn start_send(&mut self, item: Self::SinkItem) -> Result<(), Self::SinkError> {
+            (**self).start_send(item)
+        }
+
+        fn poll_flush(&mut self) -> Poll<(), Self::SinkError> {
+            (**self).poll_flush()
+        }
+
+        fn poll_close(&mut self) -> Poll<(), Self::SinkError> {
+            (**self).poll_close()
+        }
+    }
+
+    impl<T> Sink2 for ::alloc::vec::Vec<T> {
+        type SinkItem = T;
+        type SinkError = (); // Change this to ! once it stabilizes
+
+        fn poll_ready(&mut self) -> Poll<(), Self::SinkError> {
+            Ok(Async::Ready(()))
+        }
+
+        fn start_send(&mut self, item: Self::SinkItem) -> Result<(), Self::SinkError> {
+            self.push(item);
+            Ok(())
+        }
+
+        fn poll_flush(&mut self) -> Poll<(), Self::SinkError> {
+            Ok(Async::Ready(()))
+        }
+
+        fn poll_close(&mut self) -> Poll<(), Self::SinkError> {
+            Ok(Async::Ready(()))
+        }
+    }
+}
+
+/// Adaptor for the `Sink2::compat` method, implementing `Sink` for a
+/// `Sink2`.
+#[derive(Debug)]
+#[must_use = "sinks do nothing unless polled"]
+pub struct Sink2Compat<S> {
+    sink: S,
+}
+
+impl<S> Sink2Compat<S> {
+    /// Get a shared reference to the inner sink.
+    pub fn get_ref(&self) -> &S {
+        &self.sink
+    }
+
+    /// Get a mutable reference to the inner sink.
+    pub fn get_mut(&mut self) -> &mut S {
+        &mut self.sink
+    }
+
+    /// Consumes this combinator, returning the underlying sink.
+    pub fn into_inner(self) -> S {
+        self.sink
+    }
+}
+
+impl<S: Sink2> Sink for Sink2Compat<S> {
+    type SinkItem = S::SinkItem;
+    type SinkError = S::SinkError;
+
+    fn start_send(&mut self, item: S::SinkItem) -> StartSend<S::SinkItem, S::SinkError> {
+        if self.sink.poll_ready()?.is_not_ready() {
+            return Ok(AsyncSink::NotReady(item))
+        }
+        self.sink.start_send(item)?;
+        Ok(AsyncSink::Ready)
+    }
+
+    fn poll_complete(&mut self) -> Poll<(), S::SinkError> {
+        self.sink.poll_flush()
+    }
+
+    fn close(&mut self) -> Poll<(), S::SinkError> {
+        self.sink.poll_close()
+    }
+}
+
+// Forwarding impl of Stream from the underlying sink
+impl<S: Stream> Stream for Sink2Compat<S> {
+    type Item = S::Item;
+    type Error = S::Error;
+
+    fn poll(&mut self) -> Poll<Option<S::Item>, S::Error> {
+        self.sink.poll()
+    }
+}
+
+/// Adaptor implementing `Sink2` for a `Sink`.
+///
+/// A `Sink` can't tell whether it has room for an item without being given
+/// one, so an item it rejects is staged here and pushed to it by the next
+/// call to `poll_ready`.
+#[must_use = "sinks do nothing unless polled"]
+pub struct Staged<S: Sink> {
+    sink: S,
+    staged: Option<S::SinkItem>,
+}
+
+impl<S> fmt::Debug for Staged<S>
+    where S: Sink + fmt::Debug,
+          S::SinkItem: fmt::Debug,
+{
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("Staged")
+            .field("sink", &self.sink)
+            .field("staged", &self.staged)
+            .finish()
+    }
+}
+
+impl<S: Sink> Staged<S> {
+    /// Wraps `sink` so that it can be used as a `Sink2`.
+    pub fn new(sink: S) -> Staged<S> {
+        Staged {
+            sink: sink,
+            staged: None,
+        }
+    }
+
+    /// Get a shared reference to the inner sink.
+    pub fn get_ref(&self) -> &S {
+        &self.sink
+    }
+
+    /// Get a mutable reference to the inner sink.
+    pub fn get_mut(&mut self) -> &mut S {
+        &mut self.sink
+    }
+
+    /// Consumes this combinator, returning the underlying sink.
+    ///
+    /// Note that this may discard intermediate state of this combinator, so
+    /// care should be taken to avoid losing resources when this is called.
+    pub fn into_inner(self) -> S {
+        self.sink
+    }
+}
+
+impl<S: Sink> Sink2 for Staged<S> {
+    type SinkItem = S::SinkItem;
+    type SinkError = S::SinkError;
+
+    fn poll_ready(&mut self) -> Poll<(), S::SinkError> {
+        if let Some(item) = self.staged.take() {
+            if let AsyncSink::NotReady(item) = self.sink.start_send(item)? {
+                self.staged = Some(item);
+                return Ok(Async::NotReady)
+            }
+        }
+        Ok(Async::Ready(()))
+    }
+
+    fn start_send(&mut self, item: S::SinkItem) -> Result<(), S::SinkError> {
+        assert!(self.staged.is_none(), "start_send called before poll_ready");
+        if let AsyncSink::NotReady(item) = self.sink.start_send(item)? {
+            self.staged = Some(item);
+        }
+        Ok(())
+    }
+
+    fn poll_flush(&mut self) -> Poll<(), S::SinkError> {
+        try_ready!(self.poll_ready());
+        self.sink.poll_complete()
+    }
+
+    fn poll_close(&mut self) -> Poll<(), S::SinkError> {
+        try_ready!(self.poll_ready());
+        self.sink.close()
+    }
+}
+
+// Forwarding impl of Stream from the underlying sink
+impl<S: Sink + Stream> Stream for Staged<S> {
+    type Item = S::Item;
+    type Error = S::Error;
+
+    fn poll(&mut self) -> Poll<Option<S::Item>, S::Error> {
+        self.sink.poll()
+    }
+}
@@ -0,0 +1,166 @@
+use std::collections::VecDeque;
+
+use {Poll, Async};
+use {StartSend, AsyncSink};
+use sink::Sink;
+use stream::Stream;
+
+/// Storage for the items a `SpillBuffer` sink can't hold in memory, such as
+/// a file on disk.
+///
+/// Items are spilled and unspilled in the same order, the storage has to
+/// behave like a queue. Errors of the storage are returned by the sink
+/// through the `From` conversion of its error type.
+pub trait Spill<T> {
+    /// The error returned when storing or loading an item fails.
+    type Error;
+
+    /// Stores `item` at the back of the spilled items.
+    fn spill(&mut self, item: T) -> Result<(), Self::Error>;
+
+    /// Takes the item at the front of the spilled items, returning `None` if
+    /// there aren't any.
+    fn unspill(&mut self) -> Result<Option<T>, Self::Error>;
+}
+
+/// Sink for the `Sink::spill_buffer` combinator, which buffers values in
+/// memory and spills them to a `Spill` storage once the memory buffer is
+/// full.
+#[derive(Debug)]
+#[must_use = "sinks do nothing unless polled"]
+pub struct SpillBuffer<S: Sink, H> {
+    sink: S,
+    buf: VecDeque<S::SinkItem>,
+
+    // Track capacity separately from the `VecDeque`, which may be rounded up
+    cap: usize,
+    storage: H,
+    spilled: usize,
+}
+
+pub fn new<S, H>(sink: S, amt: usize, storage: H) -> SpillBuffer<S, H>
+    where S: Sink,
+          H: Spill<S::SinkItem>,
+{
+    assert!(amt > 0, "spill buffer must be able to hold at least one item");
+    SpillBuffer {
+        sink: sink,
+        buf: VecDeque::with_capacity(amt),
+        cap: amt,
+        storage: storage,
+        spilled: 0,
+    }
+}
+
+impl<S: Sink, H> SpillBuffer<S, H> {
+    /// Get a shared reference to the inner sink.
+    pub fn get_ref(&self) -> &S {
+        &self.sink
+    }
+
+    /// Get a mutable reference to the inner sink.
+    pub fn get_mut(&mut self) -> &mut S {
+        &mut self.sink
+    }
+
+    /// Get a shared reference to the spill storage.
+    pub fn storage(&self) -> &H {
+        &self.storage
+    }
+
+    /// Returns the number of items which are currently spilled.
+    pub fn spilled(&self) -> usize {
+        self.spilled
+    }
+
+    /// Consumes this combinator, returning the underlying sink.
+    ///
+    /// Note that this may discard intermediate state of this combinator, so
+    /// care should be taken to avoid losing resources when this is called.
+    /// Items which are spilled stay in the storage, which is dropped.
+    pub fn into_inner(self) -> S {
+        self.sink
+    }
+}
+
+impl<S, H> SpillBuffer<S, H>
+    where S: Sink,
+          H: Spill<S::SinkItem>,
+          S::SinkError: From<H::Error>,
+{
+    // Moves spilled items back into memory while there's room for them.
+    fn refill(&mut self) -> Result<(), S::SinkError> {
+        while self.spilled > 0 && self.buf.len() < self.cap {
+            match self.storage.unspill()? {
+                Some(item) => {
+                    self.buf.push_back(item);
+                    self.spilled -= 1;
+                }
+                // The storage lost track of some items, there's nothing more
+                // to wait for.
+                None => self.spilled = 0,
+            }
+        }
+        Ok(())
+    }
+
+    fn try_empty_buffer(&mut self) -> Poll<(), S::SinkError> {
+        loop {
+            self.refill()?;
+            let item = match self.buf.pop_front() {
+                Some(item) => item,
+                None => return Ok(Async::Ready(())),
+            };
+            if let AsyncSink::NotReady(item) = self.sink.start_send(item)? {
+                self.buf.push_front(item);
+
+                // ensure that we attempt to complete any pushes we've started
+                self.sink.poll_complete()?;
+
+                return Ok(Async::NotReady);
+            }
+        }
+    }
+}
+
+// Forwarding impl of Stream from the underlying sink
+impl<S, H> Stream for SpillBuffer<S, H> where S: Sink + Stream {
+    type Item = S::Item;
+    type Error = S::Error;
+
+    fn poll(&mut self) -> Poll<Option<S::Item>, S::Error> {
+        self.sink.poll()
+    }
+}
+
+impl<S, H> Sink for SpillBuffer<S, H>
+    where S: Sink,
+          H: Spill<S::SinkItem>,
+          S::SinkError: From<H::Error>,
+{
+    type SinkItem = S::SinkItem;
+    type SinkError = S::SinkError;
+
+    fn start_send(&mut self, item: Self::SinkItem) -> StartSend<Self::SinkItem, Self::SinkError> {
+        self.try_empty_buffer()?;
+        // Once items are spilled, later ones have to queue up behind them.
+        if self.spilled > 0 || self.buf.len() >= self.cap {
+            self.storage.spill(item)?;
+            self.spilled += 1;
+        } else {
+            self.buf.push_back(item);
+        }
+        Ok(AsyncSink::Ready)
+    }
+
+    fn poll_complete(&mut self) -> Poll<(), Self::SinkError> {
+        try_ready!(self.try_empty_buffer());
+        debug_assert!(self.buf.is_empty() && self.spilled == 0);
+        self.sink.poll_complete()
+    }
+
+    fn close(&mut self) -> Poll<(), Self::SinkError> {
+        try_ready!(self.try_empty_buffer());
+        self.sink.close()
+    }
+}
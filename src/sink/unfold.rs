@@ -0,0 +1,125 @@
+use core::marker::PhantomData;
+use core::mem;
+
+use {Future, IntoFuture, Async, AsyncSink, Poll, StartSend};
+use sink::Sink;
+
+/// Creates a `Sink` from a seed and a closure returning a `Future`.
+///
+/// This function is the sink counterpart of `stream::unfold`: each item sent
+/// to the returned sink is passed to `f` together with the current state, and
+/// the `Future` returned by `f` resolves to the next state. Only one such
+/// future runs at a time, so the sink isn't ready for another item until the
+/// previous future has completed, and flushing or closing it waits for the
+/// last one to complete.
+///
+/// In case of error generated by a returned `Future`, the error will be
+/// returned by the `Sink`, after which the sink must no longer be used.
+///
+/// # Example
+///
+/// ```rust
+/// use futures::prelude::*;
+/// use futures::{future, sink, stream};
+///
+/// let sink = sink::unfold(0, |sum, item: i32| future::ok::<_, ()>(sum + item));
+/// let (sink, _) = sink.send_all(stream::iter_ok(1..5)).wait().unwrap();
+/// assert_eq!(sink.into_inner(), Some(10));
+/// ```
+pub fn unfold<T, U, F, Fut>(init: T, f: F) -> Unfold<T, U, F, Fut>
+    where F: FnMut(T, U) -> Fut,
+          Fut: IntoFuture<Item = T>,
+{
+    Unfold {
+        f: f,
+        state: State::Ready(init),
+        _phantom: PhantomData,
+    }
+}
+
+/// A sink which runs a future for each item it receives, threading a state
+/// through them.
+///
+/// This sink is returned by the `futures::sink::unfold` function.
+#[derive(Debug)]
+#[must_use = "sinks do nothing unless polled"]
+pub struct Unfold<T, U, F, Fut> where Fut: IntoFuture {
+    f: F,
+    state: State<T, Fut::Future>,
+    _phantom: PhantomData<fn(U)>,
+}
+
+#[derive(Debug)]
+enum State<T, F> {
+    /// Placeholder state when doing work, or when the returned Future generated an error
+    Empty,
+
+    /// Ready to accept a new item; current internal state is the `T`
+    Ready(T),
+
+    /// Working on a future generated previously
+    Processing(F),
+}
+
+impl<T, U, F, Fut> Unfold<T, U, F, Fut>
+    where Fut: IntoFuture<Item = T>,
+{
+    /// Consumes this sink, returning its current state.
+    ///
+    /// This returns `None` if the future for the last item hasn't completed
+    /// yet, or if it failed.
+    pub fn into_inner(self) -> Option<T> {
+        match self.state {
+            State::Ready(state) => Some(state),
+            _ => None,
+        }
+    }
+
+    fn poll(&mut self) -> Poll<(), Fut::Error> {
+        let res = match self.state {
+            State::Processing(ref mut fut) => fut.poll(),
+            State::Ready(_) => return Ok(Async::Ready(())),
+            State::Empty => panic!("Unfold sink used after an error"),
+        };
+        match res {
+            Ok(Async::Ready(state)) => {
+                self.state = State::Ready(state);
+                Ok(Async::Ready(()))
+            }
+            Ok(Async::NotReady) => Ok(Async::NotReady),
+            Err(e) => {
+                self.state = State::Empty;
+                Err(e)
+            }
+        }
+    }
+}
+
+impl<T, U, F, Fut> Sink for Unfold<T, U, F, Fut>
+    where F: FnMut(T, U) -> Fut,
+          Fut: IntoFuture<Item = T>,
+{
+    type SinkItem = U;
+    type SinkError = Fut::Error;
+
+    fn start_send(&mut self, item: U) -> StartSend<U, Fut::Error> {
+        if self.poll()?.is_not_ready() {
+            return Ok(AsyncSink::NotReady(item))
+        }
+        match mem::replace(&mut self.state, State::Empty) {
+            State::Ready(state) => {
+                self.state = State::Processing((self.f)(state, item).into_future());
+            }
+            _ => unreachable!(),
+        }
+        Ok(AsyncSink::Ready)
+    }
+
+    fn poll_complete(&mut self) -> Poll<(), Fut::Error> {
+        self.poll()
+    }
+
+    fn close(&mut self) -> Poll<(), Fut::Error> {
+        self.poll()
+    }
+}
@@ -0,0 +1,110 @@
+use core::marker::PhantomData;
+
+use {Poll, Async, StartSend, AsyncSink};
+use sink::Sink;
+use stream::Stream;
+
+/// Sink for the `Sink::with_filter_map` combinator, transforming or dropping
+/// values with a closure *prior* to pushing them into the underlying sink.
+#[derive(Debug)]
+#[must_use = "sinks do nothing unless polled"]
+pub struct WithFilterMap<S, U, F>
+    where S: Sink,
+{
+    sink: S,
+    f: F,
+    buffered: Option<S::SinkItem>,
+    _phantom: PhantomData<fn(U)>,
+}
+
+pub fn new<S, U, F>(sink: S, f: F) -> WithFilterMap<S, U, F>
+    where S: Sink,
+          F: FnMut(U) -> Option<S::SinkItem>,
+{
+    WithFilterMap {
+        sink: sink,
+        f: f,
+        buffered: None,
+        _phantom: PhantomData,
+    }
+}
+
+// Forwarding impl of Stream from the underlying sink
+impl<S, U, F> Stream for WithFilterMap<S, U, F>
+    where S: Stream + Sink,
+{
+    type Item = S::Item;
+    type Error = S::Error;
+
+    fn poll(&mut self) -> Poll<Option<S::Item>, S::Error> {
+        self.sink.poll()
+    }
+}
+
+impl<S, U, F> WithFilterMap<S, U, F>
+    where S: Sink,
+{
+    /// Get a shared reference to the inner sink.
+    pub fn get_ref(&self) -> &S {
+        &self.sink
+    }
+
+    /// Get a mutable reference to the inner sink.
+    pub fn get_mut(&mut self) -> &mut S {
+        &mut self.sink
+    }
+
+    /// Consumes this combinator, returning the underlying sink.
+    ///
+    /// Note that this may discard intermediate state of this combinator, so
+    /// care should be taken to avoid losing resources when this is called.
+    pub fn into_inner(self) -> S {
+        self.sink
+    }
+
+    // Pushes the item which the underlying sink didn't accept yet, if any.
+    fn try_empty_buffer(&mut self) -> Poll<(), S::SinkError> {
+        if let Some(item) = self.buffered.take() {
+            if let AsyncSink::NotReady(item) = self.sink.start_send(item)? {
+                self.buffered = Some(item);
+                return Ok(Async::NotReady)
+            }
+        }
+        Ok(Async::Ready(()))
+    }
+}
+
+impl<S, U, F> Sink for WithFilterMap<S, U, F>
+    where S: Sink,
+          F: FnMut(U) -> Option<S::SinkItem>,
+{
+    type SinkItem = U;
+    type SinkError = S::SinkError;
+
+    fn start_send(&mut self, item: U) -> StartSend<U, S::SinkError> {
+        if self.try_empty_buffer()?.is_not_ready() {
+            return Ok(AsyncSink::NotReady(item))
+        }
+        if let Some(item) = (self.f)(item) {
+            // The closure already consumed the item, so hold on to its result
+            // if the underlying sink isn't ready for it.
+            if let AsyncSink::NotReady(item) = self.sink.start_send(item)? {
+                self.buffered = Some(item);
+            }
+        }
+        Ok(AsyncSink::Ready)
+    }
+
+    fn poll_complete(&mut self) -> Poll<(), S::SinkError> {
+        // poll ourselves first, to push data downward
+        let me_ready = self.try_empty_buffer()?;
+        // always propagate `poll_complete` downward to attempt to make progress
+        try_ready!(self.sink.poll_complete());
+        Ok(me_ready)
+    }
+
+    fn close(&mut self) -> Poll<(), S::SinkError> {
+        try_ready!(self.try_empty_buffer());
+        self.sink.close()
+    }
+}
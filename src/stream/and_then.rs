@@ -103,4 +103,11 @@ impl<S, F, U> Stream for AndThen<S, F, U>
             Ok(Async::NotReady) => Ok(Async::NotReady)
         }
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let pending = if self.future.is_some() { 1 } else { 0 };
+        // The futures may fail, so there's no lower bound.
+        let (_, upper) = self.stream.size_hint();
+        (0, upper.and_then(|n| n.checked_add(pending)))
+    }
 }
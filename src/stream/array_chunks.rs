@@ -0,0 +1,254 @@
+use core::fmt;
+
+use {Async, Poll};
+use stream::{Stream, Fuse};
+
+/// A fixed-size array which a stream can be chunked into by the
+/// `Stream::array_chunks` method.
+///
+/// This is implemented for arrays of up to 32 elements.
+pub trait ChunkArray: Sized {
+    /// The type of the elements of the array.
+    type Item;
+
+    /// Storage for a partially filled array.
+    #[doc(hidden)]
+    type Buffer: AsMut<[Option<Self::Item>]>;
+
+    /// Returns the number of elements of the array.
+    fn len() -> usize;
+
+    #[doc(hidden)]
+    fn empty_buffer() -> Self::Buffer;
+
+    // Takes the elements out of a full buffer.
+    #[doc(hidden)]
+    fn from_buffer(buf: &mut Self::Buffer) -> Self;
+}
+
+macro_rules! none {
+    ($i:tt) => (None)
+}
+
+macro_rules! chunk_arrays {
+    ($($n:tt => ($($i:tt)+))+) => ($(
+        impl<T> ChunkArray for [T; $n] {
+            type Item = T;
+            type Buffer = [Option<T>; $n];
+
+            fn len() -> usize {
+                $n
+            }
+
+            fn empty_buffer() -> [Option<T>; $n] {
+                [$(none!($i)),+]
+            }
+
+            fn from_buffer(buf: &mut [Option<T>; $n]) -> [T; $n] {
+                [$(buf[$i].take().expect("buffer not full")),+]
+            }
+        }
+    )+)
+}
+
+chunk_arrays! {
+    1 => (0)
+    2 => (0 1)
+    3 => (0 1 2)
+    4 => (0 1 2 3)
+    5 => (0 1 2 3 4)
+    6 => (0 1 2 3 4 5)
+    7 => (0 1 2 3 4 5 6)
+    8 => (0 1 2 3 4 5 6 7)
+    9 => (0 1 2 3 4 5 6 7 8)
+    10 => (0 1 2 3 4 5 6 7 8 9)
+    11 => (0 1 2 3 4 5 6 7 8 9 10)
+    12 => (0 1 2 3 4 5 6 7 8 9 10 11)
+    13 => (0 1 2 3 4 5 6 7 8 9 10 11 12)
+    14 => (0 1 2 3 4 5 6 7 8 9 10 11 12 13)
+    15 => (0 1 2 3 4 5 6 7 8 9 10 11 12 13 14)
+    16 => (0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15)
+    17 => (0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16)
+    18 => (0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16 17)
+    19 => (0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16 17 18)
+    20 => (0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16 17 18 19)
+    21 => (0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16 17 18 19 20)
+    22 => (0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16 17 18 19 20 21)
+    23 => (0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16 17 18 19 20 21 22)
+    24 => (0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16 17 18 19 20 21 22 23)
+    25 => (0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16 17 18 19 20 21 22 23 24)
+    26 => (0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16 17 18 19 20 21 22 23 24 25)
+    27 => (0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16 17 18 19 20 21 22 23 24 25 26)
+    28 => (0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16 17 18 19 20 21 22 23 24 25 26 27)
+    29 => (0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16 17 18 19 20 21 22 23 24 25 26 27 28)
+    30 => (0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16 17 18 19 20 21 22 23 24 25 26 27 28 29)
+    31 => (0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16 17 18 19 20 21 22 23 24 25 26 27 28 29 30)
+    32 => (0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16 17 18 19 20 21 22 23 24 25 26 27 28 29 30 31)
+}
+
+/// An adaptor that chunks up elements of a stream into fixed-size arrays.
+///
+/// Unlike `Chunks` no allocation happens per chunk: items are buffered inline
+/// until the array is full. This is created by the `Stream::array_chunks`
+/// method.
+#[must_use = "streams do nothing unless polled"]
+pub struct ArrayChunks<S, A> where A: ChunkArray {
+    stream: Fuse<S>,
+    buf: A::Buffer,
+    len: usize,
+}
+
+impl<S, A> fmt::Debug for ArrayChunks<S, A>
+    where S: fmt::Debug,
+          A: ChunkArray,
+{
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("ArrayChunks")
+            .field("stream", &self.stream)
+            .field("buffered", &self.len)
+            .finish()
+    }
+}
+
+pub fn new<S, A>(s: S) -> ArrayChunks<S, A>
+    where S: Stream,
+          A: ChunkArray<Item = S::Item>,
+{
+    ArrayChunks {
+        stream: super::fuse::new(s),
+        buf: A::empty_buffer(),
+        len: 0,
+    }
+}
+
+impl<S, A> ArrayChunks<S, A> where A: ChunkArray {
+    /// Returns the number of items currently buffered towards the next array.
+    pub fn remainder_len(&self) -> usize {
+        self.len
+    }
+
+    /// Acquires a reference to the underlying stream that this combinator is
+    /// pulling from.
+    pub fn get_ref(&self) -> &S {
+        self.stream.get_ref()
+    }
+
+    /// Acquires a mutable reference to the underlying stream that this
+    /// combinator is pulling from.
+    ///
+    /// Note that care must be taken to avoid tampering with the state of the
+    /// stream which may otherwise confuse this combinator.
+    pub fn get_mut(&mut self) -> &mut S {
+        self.stream.get_mut()
+    }
+
+    /// Consumes this combinator, returning the underlying stream.
+    ///
+    /// Items which are buffered towards the next array are dropped, use
+    /// `into_remainder` to get at them.
+    pub fn into_inner(self) -> S {
+        self.stream.into_inner()
+    }
+
+    /// Consumes this combinator, returning an iterator over the items which
+    /// didn't fill up a whole array.
+    ///
+    /// This is typically called after the stream has ended, to handle the
+    /// final partial chunk.
+    pub fn into_remainder(self) -> Remainder<A> {
+        Remainder {
+            buf: self.buf,
+            pos: 0,
+            len: self.len,
+        }
+    }
+}
+
+// Forwarding impl of Sink from the underlying stream
+impl<S, A> ::sink::Sink for ArrayChunks<S, A>
+    where S: ::sink::Sink + Stream,
+          A: ChunkArray,
+{
+    type SinkItem = S::SinkItem;
+    type SinkError = S::SinkError;
+
+    fn start_send(&mut self, item: S::SinkItem) -> ::StartSend<S::SinkItem, S::SinkError> {
+        self.stream.start_send(item)
+    }
+
+    fn poll_complete(&mut self) -> Poll<(), S::SinkError> {
+        self.stream.poll_complete()
+    }
+
+    fn close(&mut self) -> Poll<(), S::SinkError> {
+        self.stream.close()
+    }
+}
+
+impl<S, A> Stream for ArrayChunks<S, A>
+    where S: Stream,
+          A: ChunkArray<Item = S::Item>,
+{
+    type Item = A;
+    type Error = S::Error;
+
+    fn poll(&mut self) -> Poll<Option<A>, S::Error> {
+        loop {
+            match try_ready!(self.stream.poll()) {
+                Some(item) => {
+                    self.buf.as_mut()[self.len] = Some(item);
+                    self.len += 1;
+                    if self.len == A::len() {
+                        self.len = 0;
+                        return Ok(Async::Ready(Some(A::from_buffer(&mut self.buf))))
+                    }
+                }
+                None => return Ok(Async::Ready(None)),
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (lower, upper) = self.stream.size_hint();
+        let lower = lower.saturating_add(self.len) / A::len();
+        let upper = upper.and_then(|u| u.checked_add(self.len)).map(|u| u / A::len());
+        (lower, upper)
+    }
+}
+
+/// An iterator over the items of an `ArrayChunks` stream which didn't fill up
+/// a whole array.
+///
+/// This is created by the `ArrayChunks::into_remainder` method.
+pub struct Remainder<A> where A: ChunkArray {
+    buf: A::Buffer,
+    pos: usize,
+    len: usize,
+}
+
+impl<A> fmt::Debug for Remainder<A> where A: ChunkArray {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("Remainder")
+            .field("remaining", &(self.len - self.pos))
+            .finish()
+    }
+}
+
+impl<A> Iterator for Remainder<A> where A: ChunkArray {
+    type Item = A::Item;
+
+    fn next(&mut self) -> Option<A::Item> {
+        if self.pos == self.len {
+            return None
+        }
+        self.pos += 1;
+        self.buf.as_mut()[self.pos - 1].take()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let n = self.len - self.pos;
+        (n, Some(n))
+    }
+}
+
+impl<A> ExactSizeIterator for Remainder<A> where A: ChunkArray {}
@@ -0,0 +1,339 @@
+use std::mem;
+use std::prelude::v1::*;
+use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering::SeqCst;
+
+use {Async, Poll};
+use stream::{Stream, Fuse, Ticks, NoTicks};
+use task::{self, AtomicTask};
+use timer::{Interval, TimerError};
+
+// The maximum number of items, and of ticks, pulled per poll, so that an
+// always-ready stream can't keep `poll` from returning.
+const MAX_DRAIN: usize = 32;
+
+/// Computes the weight of items for the weight trigger of a `Batched` stream.
+///
+/// This is implemented for closures taking a reference to an item and for
+/// `Unweighted`.
+pub trait Weigh<T> {
+    /// Returns the weight of `item`.
+    fn weigh(&mut self, item: &T) -> usize;
+}
+
+impl<T, F> Weigh<T> for F
+    where F: FnMut(&T) -> usize,
+{
+    fn weigh(&mut self, item: &T) -> usize {
+        self(item)
+    }
+}
+
+/// A `Weigh` implementation giving every item a weight of zero, used when no
+/// weight limit is configured.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Unweighted;
+
+impl<T> Weigh<T> for Unweighted {
+    fn weigh(&mut self, _item: &T) -> usize {
+        0
+    }
+}
+
+/// A `Ticks` implementation driven by a `timer::Interval`, as used by
+/// `BatchConfig::interval`.
+///
+/// Errors of the interval are converted into errors of the stream the ticks
+/// are used with.
+#[derive(Debug)]
+pub struct IntervalTicks<I> {
+    interval: I,
+}
+
+impl<I: Interval> IntervalTicks<I> {
+    /// Creates a source of ticks which ticks whenever `interval` does.
+    pub fn new(interval: I) -> IntervalTicks<I> {
+        IntervalTicks { interval: interval }
+    }
+}
+
+impl<I, E> Ticks<E> for IntervalTicks<I>
+    where I: Interval,
+          E: From<TimerError>,
+{
+    fn poll_tick(&mut self) -> Poll<Option<()>, E> {
+        match self.interval.poll() {
+            Ok(Async::Ready(Some(_))) => Ok(Async::Ready(Some(()))),
+            Ok(Async::Ready(None)) => Ok(Async::Ready(None)),
+            Ok(Async::NotReady) => Ok(Async::NotReady),
+            Err(e) => Err(From::from(e)),
+        }
+    }
+}
+
+/// The triggers deciding when a `Batched` stream emits a batch.
+///
+/// A batch is emitted as soon as any of the configured triggers fires: once it
+/// holds `max_items` items, once the total weight of its items reaches
+/// `max_weight`, or whenever the `ticks` stream yields a value while the batch
+/// isn't empty. By default only the item count, which is unlimited, is used.
+///
+/// The timer trigger is usually an interval of a `timer::Timer`, configured
+/// with `interval`, but any stream of ticks can be used through `ticks`.
+///
+/// This is passed to the `Stream::batched` method.
+#[derive(Debug, Clone)]
+pub struct BatchConfig<W = Unweighted, T = NoTicks> {
+    max_items: usize,
+    max_weight: usize,
+    weigh: W,
+    ticks: T,
+}
+
+impl BatchConfig {
+    /// Creates a new configuration without any limits.
+    pub fn new() -> BatchConfig {
+        BatchConfig {
+            max_items: usize::MAX,
+            max_weight: usize::MAX,
+            weigh: Unweighted,
+            ticks: NoTicks,
+        }
+    }
+}
+
+impl Default for BatchConfig {
+    fn default() -> BatchConfig {
+        BatchConfig::new()
+    }
+}
+
+impl<W, T> BatchConfig<W, T> {
+    /// Emits a batch once it holds `max` items.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max` is zero.
+    pub fn max_items(mut self, max: usize) -> BatchConfig<W, T> {
+        assert!(max > 0);
+        self.max_items = max;
+        self
+    }
+
+    /// Emits a batch once the total weight of its items, as computed by
+    /// `weigh`, reaches `max`.
+    ///
+    /// This replaces any previously configured weight limit.
+    pub fn max_weight<F>(self, max: usize, weigh: F) -> BatchConfig<F, T> {
+        BatchConfig {
+            max_items: self.max_items,
+            max_weight: max,
+            weigh: weigh,
+            ticks: self.ticks,
+        }
+    }
+
+    /// Emits the current batch, if it isn't empty, each time `interval`
+    /// ticks.
+    ///
+    /// Errors of `interval` are converted into errors of the batched stream.
+    /// This replaces any previously configured source of ticks.
+    pub fn interval<I>(self, interval: I) -> BatchConfig<W, IntervalTicks<I>>
+        where I: Interval,
+    {
+        self.ticks(IntervalTicks::new(interval))
+    }
+
+    /// Emits the current batch, if it isn't empty, each time `ticks` yields a
+    /// value.
+    ///
+    /// Errors of `ticks` are passed through. This replaces any previously
+    /// configured source of ticks.
+    pub fn ticks<U>(self, ticks: U) -> BatchConfig<W, U> {
+        BatchConfig {
+            max_items: self.max_items,
+            max_weight: self.max_weight,
+            weigh: self.weigh,
+            ticks: ticks,
+        }
+    }
+}
+
+/// A handle which makes a `Batched` stream emit its current batch right away.
+///
+/// This is created by the `Batched::flush_handle` method and can be cloned
+/// and sent to other threads.
+#[derive(Debug, Clone)]
+pub struct FlushHandle {
+    inner: Arc<FlushInner>,
+}
+
+#[derive(Debug)]
+struct FlushInner {
+    requested: AtomicBool,
+    task: AtomicTask,
+}
+
+impl FlushHandle {
+    /// Requests the current batch to be emitted, even if no trigger fired.
+    ///
+    /// Nothing is emitted if the batch is empty at the time the request is
+    /// handled.
+    pub fn flush(&self) {
+        self.inner.requested.store(true, SeqCst);
+        self.inner.task.notify();
+    }
+}
+
+/// A stream combinator which groups items of a stream into batches according
+/// to a `BatchConfig`.
+///
+/// This structure is produced by the `Stream::batched` method.
+#[derive(Debug)]
+#[must_use = "streams do nothing unless polled"]
+pub struct Batched<S, W, T> where S: Stream {
+    stream: Fuse<S>,
+    items: Vec<S::Item>,
+    weight: usize,
+    err: Option<S::Error>,
+    max_items: usize,
+    max_weight: usize,
+    weigh: W,
+    ticks: T,
+    ticks_done: bool,
+    flush: Arc<FlushInner>,
+}
+
+pub fn new<S, W, T>(s: S, config: BatchConfig<W, T>) -> Batched<S, W, T>
+    where S: Stream,
+          W: Weigh<S::Item>,
+          T: Ticks<S::Error>,
+{
+    Batched {
+        stream: s.fuse(),
+        items: Vec::new(),
+        weight: 0,
+        err: None,
+        max_items: config.max_items,
+        max_weight: config.max_weight,
+        weigh: config.weigh,
+        ticks: config.ticks,
+        ticks_done: false,
+        flush: Arc::new(FlushInner {
+            requested: AtomicBool::new(false),
+            task: AtomicTask::new(),
+        }),
+    }
+}
+
+impl<S, W, T> Batched<S, W, T> where S: Stream {
+    /// Returns a handle which can be used to emit the current batch
+    /// immediately, for example on shutdown.
+    pub fn flush_handle(&self) -> FlushHandle {
+        FlushHandle { inner: self.flush.clone() }
+    }
+
+    fn take(&mut self) -> Vec<S::Item> {
+        self.weight = 0;
+        mem::replace(&mut self.items, Vec::new())
+    }
+
+    /// Acquires a reference to the underlying stream that this combinator is
+    /// pulling from.
+    pub fn get_ref(&self) -> &S {
+        self.stream.get_ref()
+    }
+
+    /// Acquires a mutable reference to the underlying stream that this
+    /// combinator is pulling from.
+    ///
+    /// Note that care must be taken to avoid tampering with the state of the
+    /// stream which may otherwise confuse this combinator.
+    pub fn get_mut(&mut self) -> &mut S {
+        self.stream.get_mut()
+    }
+
+    /// Consumes this combinator, returning the underlying stream.
+    ///
+    /// Note that this may discard intermediate state of this combinator, so
+    /// care should be taken to avoid losing resources when this is called.
+    pub fn into_inner(self) -> S {
+        self.stream.into_inner()
+    }
+}
+
+impl<S, W, T> Stream for Batched<S, W, T>
+    where S: Stream,
+          W: Weigh<S::Item>,
+          T: Ticks<S::Error>,
+{
+    type Item = Vec<S::Item>;
+    type Error = S::Error;
+
+    fn poll(&mut self) -> Poll<Option<Self::Item>, Self::Error> {
+        if let Some(err) = self.err.take() {
+            return Err(err)
+        }
+
+        let mut drained = false;
+        for _ in 0..MAX_DRAIN {
+            match self.stream.poll() {
+                Ok(Async::Ready(Some(item))) => {
+                    self.weight = self.weight.saturating_add(self.weigh.weigh(&item));
+                    self.items.push(item);
+                    if self.items.len() >= self.max_items ||
+                       self.weight >= self.max_weight {
+                        return Ok(Async::Ready(Some(self.take())))
+                    }
+                }
+                Ok(Async::Ready(None)) | Ok(Async::NotReady) => {
+                    drained = true;
+                    break
+                }
+
+                // Emit what we have buffered first and defer the error.
+                Err(e) => {
+                    if self.items.is_empty() {
+                        return Err(e)
+                    }
+                    self.err = Some(e);
+                    return Ok(Async::Ready(Some(self.take())))
+                }
+            }
+        }
+
+        // Register before checking the flag so a request made concurrently
+        // isn't missed.
+        self.flush.task.register();
+        let mut flush = self.flush.requested.swap(false, SeqCst);
+
+        let mut ticks = 0;
+        while !self.ticks_done {
+            if ticks == MAX_DRAIN {
+                drained = false;
+                break
+            }
+            ticks += 1;
+            match self.ticks.poll_tick()? {
+                Async::Ready(Some(())) => flush = true,
+                Async::Ready(None) => self.ticks_done = true,
+                Async::NotReady => break,
+            }
+        }
+
+        if self.stream.is_done() || (flush && !self.items.is_empty()) {
+            if self.items.is_empty() {
+                Ok(Async::Ready(None))
+            } else {
+                Ok(Async::Ready(Some(self.take())))
+            }
+        } else {
+            // Come back for whatever was left ready after hitting the limit.
+            if !drained {
+                task::current().notify();
+            }
+            Ok(Async::NotReady)
+        }
+    }
+}
@@ -0,0 +1,152 @@
+use std::fmt::{self, Debug};
+
+use {Async, AsyncSink, Future, Poll, Stream};
+use stream::FuturesUnordered;
+use task::{self, Task};
+
+/// A `FuturesUnordered` holding at most a fixed number of futures at a time.
+///
+/// Futures are added with `poll_push`, which hands the future back with
+/// `AsyncSink::NotReady` while the set is full. The rejected pusher's task
+/// is then notified once a future completes and its result is taken out of
+/// the set, so that it can try again. This gives backpressure to whatever
+/// feeds the set, for example a stream of incoming work, without needing a
+/// separate semaphore.
+///
+/// Apart from that this behaves like `FuturesUnordered`: the futures run
+/// concurrently, results are yielded in the order they complete, and polling
+/// an empty set returns `Ok(Async::Ready(None))`.
+///
+/// # Examples
+///
+/// ```
+/// use futures::prelude::*;
+/// use futures::future;
+/// use futures::stream::{self, BoundedFuturesUnordered};
+///
+/// let mut incoming = stream::iter_ok::<_, ()>(0..10).fuse();
+/// let mut set = BoundedFuturesUnordered::new(3);
+/// let mut pending = None;
+/// let mut sum = 0;
+///
+/// stream::poll_fn(move || -> Poll<Option<()>, ()> {
+///     loop {
+///         // Take in new work as long as there's room for it.
+///         loop {
+///             let fut = match pending.take() {
+///                 Some(fut) => fut,
+///                 None => match incoming.poll()? {
+///                     Async::Ready(Some(i)) => future::ok::<u32, ()>(i),
+///                     _ => break,
+///                 },
+///             };
+///             if let AsyncSink::NotReady(fut) = set.poll_push(fut) {
+///                 pending = Some(fut);
+///                 break
+///             }
+///         }
+///         match set.poll()? {
+///             Async::Ready(Some(i)) => sum += i,
+///             Async::Ready(None) if incoming.is_done() => {
+///                 assert_eq!(sum, 45);
+///                 return Ok(Async::Ready(None))
+///             }
+///             _ => return Ok(Async::NotReady),
+///         }
+///     }
+/// }).for_each(|()| Ok(())).wait().unwrap();
+/// ```
+#[must_use = "streams do nothing unless polled"]
+pub struct BoundedFuturesUnordered<F> {
+    inner: FuturesUnordered<F>,
+    max: usize,
+    pusher: Option<Task>,
+}
+
+impl<F> BoundedFuturesUnordered<F>
+    where F: Future,
+{
+    /// Constructs a new, empty set holding at most `max` futures at a time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max` is 0.
+    pub fn new(max: usize) -> BoundedFuturesUnordered<F> {
+        assert!(max > 0, "a BoundedFuturesUnordered needs room for a future");
+        BoundedFuturesUnordered {
+            inner: FuturesUnordered::new(),
+            max: max,
+            pusher: None,
+        }
+    }
+}
+
+impl<F> BoundedFuturesUnordered<F> {
+    /// Attempts to push a future into the set.
+    ///
+    /// If the set is full the future is returned as `AsyncSink::NotReady`,
+    /// and the current task is notified once there's room again. As with
+    /// `FuturesUnordered::push`, the future isn't polled until
+    /// `BoundedFuturesUnordered::poll` is called.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the set is full and this isn't called from within a task.
+    pub fn poll_push(&mut self, future: F) -> AsyncSink<F> {
+        if self.is_full() {
+            self.pusher = Some(task::current());
+            return AsyncSink::NotReady(future)
+        }
+        self.inner.push(future);
+        AsyncSink::Ready
+    }
+
+    /// Returns the number of futures in the set.
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    /// Returns `true` if the set contains no futures.
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    /// Returns `true` if the set can't take any more futures at the moment.
+    pub fn is_full(&self) -> bool {
+        self.inner.len() >= self.max
+    }
+
+    /// Returns the maximum number of futures in the set.
+    pub fn max_len(&self) -> usize {
+        self.max
+    }
+}
+
+impl<F> Stream for BoundedFuturesUnordered<F>
+    where F: Future,
+{
+    type Item = F::Item;
+    type Error = F::Error;
+
+    fn poll(&mut self) -> Poll<Option<F::Item>, F::Error> {
+        let res = self.inner.poll();
+        match res {
+            Ok(Async::Ready(Some(_))) | Err(_) => {
+                if let Some(task) = self.pusher.take() {
+                    task.notify();
+                }
+            }
+            Ok(Async::Ready(None)) | Ok(Async::NotReady) => {}
+        }
+        res
+    }
+}
+
+impl<F: Debug> Debug for BoundedFuturesUnordered<F> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("BoundedFuturesUnordered")
+            .field("len", &self.inner.len())
+            .field("max_len", &self.max)
+            .finish()
+    }
+}
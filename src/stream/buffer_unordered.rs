@@ -9,6 +9,9 @@ use stream::{Stream, Fuse, FuturesUnordered};
 /// This adaptor will buffer up a list of pending futures, and then return their
 /// results in the order that they complete. This is created by the
 /// `Stream::buffer_unordered` method.
+///
+/// Only the futures which were notified are polled again, and the allocations
+/// of the futures which completed are reused for later ones.
 #[must_use = "streams do nothing unless polled"]
 pub struct BufferUnordered<S>
     where S: Stream,
@@ -1,7 +1,8 @@
+use std::collections::VecDeque;
 use std::fmt;
 
-use {Async, IntoFuture, Poll};
-use stream::{Stream, Fuse, FuturesOrdered};
+use {Async, Future, IntoFuture, Poll};
+use stream::{Stream, Fuse, FuturesUnordered};
 
 /// An adaptor for a stream of futures to execute the futures concurrently, if
 /// possible.
@@ -9,16 +10,57 @@ use stream::{Stream, Fuse, FuturesOrdered};
 /// This adaptor will buffer up a list of pending futures, and then return their
 /// results in the order that they were pulled out of the original stream. This
 /// is created by the `Stream::buffered` method.
+///
+/// Only the futures which were notified are polled again. The result of a
+/// future which completes before the ones pulled out earlier is kept in a slot
+/// reserved for it when it was pulled out, and these slots as well as the
+/// allocations of the futures themselves are reused for later futures.
+///
+/// Errors of the futures are yielded in order as well, in place of the item
+/// the failed future would have resolved to.
 #[must_use = "streams do nothing unless polled"]
 pub struct Buffered<S>
     where S: Stream,
           S::Item: IntoFuture,
 {
     stream: Fuse<S>,
-    queue: FuturesOrdered<<S::Item as IntoFuture>::Future>,
+    in_progress: FuturesUnordered<Indexed<<S::Item as IntoFuture>::Future>>,
+    // A slot for every future in flight, in the order they were pulled out,
+    // starting with the one at `next_index`.
+    results: VecDeque<Slot<<S::Item as IntoFuture>::Item, <S::Item as IntoFuture>::Error>>,
+    next_index: usize,
     max: usize,
 }
 
+#[derive(Debug)]
+enum Slot<T, E> {
+    Pending,
+    Done(T),
+    Failed(E),
+}
+
+// A future in flight, which completes with its result along with its position
+// in the stream.
+#[derive(Debug)]
+struct Indexed<F> {
+    future: F,
+    index: usize,
+}
+
+impl<F: Future> Future for Indexed<F> {
+    type Item = (usize, Result<F::Item, F::Error>);
+    type Error = ();
+
+    fn poll(&mut self) -> Poll<Self::Item, ()> {
+        let result = match self.future.poll() {
+            Ok(Async::NotReady) => return Ok(Async::NotReady),
+            Ok(Async::Ready(item)) => Ok(item),
+            Err(e) => Err(e),
+        };
+        Ok(Async::Ready((self.index, result)))
+    }
+}
+
 impl<S> fmt::Debug for Buffered<S>
     where S: Stream + fmt::Debug,
           S::Item: IntoFuture,
@@ -29,7 +71,8 @@ impl<S> fmt::Debug for Buffered<S>
     fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
         fmt.debug_struct("Buffered")
             .field("stream", &self.stream)
-            .field("queue", &self.queue)
+            .field("in_progress", &self.in_progress)
+            .field("results", &self.results)
             .field("max", &self.max)
             .finish()
     }
@@ -41,7 +84,9 @@ pub fn new<S>(s: S, amt: usize) -> Buffered<S>
 {
     Buffered {
         stream: super::fuse::new(s),
-        queue: FuturesOrdered::new(),
+        in_progress: FuturesUnordered::new(),
+        results: VecDeque::with_capacity(amt),
+        next_index: 0,
         max: amt,
     }
 }
@@ -105,25 +150,51 @@ impl<S> Stream for Buffered<S>
     fn poll(&mut self) -> Poll<Option<Self::Item>, Self::Error> {
         // First up, try to spawn off as many futures as possible by filling up
         // our slab of futures.
-        while self.queue.len() < self.max {
+        while self.results.len() < self.max {
             let future = match self.stream.poll()? {
                 Async::Ready(Some(s)) => s.into_future(),
                 Async::Ready(None) |
                 Async::NotReady => break,
             };
 
-            self.queue.push(future);
+            let index = self.next_index.wrapping_add(self.results.len());
+            self.in_progress.push(Indexed { future: future, index: index });
+            self.results.push_back(Slot::Pending);
         }
 
-        // Try polling a new future
-        if let Some(val) = try_ready!(self.queue.poll()) {
-            return Ok(Async::Ready(Some(val)));
+        // Poll the futures which were notified, storing their results.
+        loop {
+            let (index, result) = match self.in_progress.poll() {
+                Ok(Async::Ready(Some(done))) => done,
+                Ok(Async::Ready(None)) | Ok(Async::NotReady) => break,
+                Err(()) => unreachable!(),
+            };
+
+            let slot = &mut self.results[index.wrapping_sub(self.next_index)];
+            *slot = match result {
+                Ok(item) => Slot::Done(item),
+                Err(e) => Slot::Failed(e),
+            };
+        }
+
+        match self.results.front() {
+            Some(&Slot::Pending) | None => {}
+            Some(_) => {
+                self.next_index = self.next_index.wrapping_add(1);
+                match self.results.pop_front() {
+                    Some(Slot::Done(item)) => return Ok(Async::Ready(Some(item))),
+                    Some(Slot::Failed(e)) => return Err(e),
+                    _ => unreachable!(),
+                }
+            }
         }
 
         // If we've gotten this far, then there are no events for us to process
         // and nothing was ready, so figure out if we're not done yet  or if
         // we've reached the end.
-        if self.stream.is_done() {
+        if !self.results.is_empty() {
+            Ok(Async::NotReady)
+        } else if self.stream.is_done() {
             Ok(Async::Ready(None))
         } else {
             Ok(Async::NotReady)
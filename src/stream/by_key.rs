@@ -0,0 +1,118 @@
+use {Future, Poll, Async};
+use stream::Stream;
+
+/// A future which resolves to the item of a stream with the smallest key.
+///
+/// This future is created by the `Stream::min_by_key` method.
+#[derive(Debug)]
+#[must_use = "futures do nothing unless polled"]
+pub struct MinByKey<S, F, K> where S: Stream {
+    stream: S,
+    f: F,
+    min: Option<(K, S::Item)>,
+}
+
+pub fn min<S, F, K>(s: S, f: F) -> MinByKey<S, F, K>
+    where S: Stream,
+          F: FnMut(&S::Item) -> K,
+          K: Ord,
+{
+    MinByKey {
+        stream: s,
+        f: f,
+        min: None,
+    }
+}
+
+impl<S, F, K> Future for MinByKey<S, F, K>
+    where S: Stream,
+          F: FnMut(&S::Item) -> K,
+          K: Ord,
+{
+    type Item = Option<S::Item>;
+    type Error = S::Error;
+
+    fn poll(&mut self) -> Poll<Option<S::Item>, S::Error> {
+        loop {
+            match self.stream.poll() {
+                Ok(Async::Ready(Some(e))) => {
+                    let key = (self.f)(&e);
+                    // Ties keep the first item, like `Iterator::min_by_key`.
+                    let replace = match self.min {
+                        Some((ref min, _)) => key < *min,
+                        None => true,
+                    };
+                    if replace {
+                        self.min = Some((key, e));
+                    }
+                }
+                Ok(Async::Ready(None)) => {
+                    return Ok(Async::Ready(self.min.take().map(|(_, e)| e)))
+                }
+                Ok(Async::NotReady) => return Ok(Async::NotReady),
+                Err(e) => {
+                    self.min = None;
+                    return Err(e)
+                }
+            }
+        }
+    }
+}
+
+/// A future which resolves to the item of a stream with the largest key.
+///
+/// This future is created by the `Stream::max_by_key` method.
+#[derive(Debug)]
+#[must_use = "futures do nothing unless polled"]
+pub struct MaxByKey<S, F, K> where S: Stream {
+    stream: S,
+    f: F,
+    max: Option<(K, S::Item)>,
+}
+
+pub fn max<S, F, K>(s: S, f: F) -> MaxByKey<S, F, K>
+    where S: Stream,
+          F: FnMut(&S::Item) -> K,
+          K: Ord,
+{
+    MaxByKey {
+        stream: s,
+        f: f,
+        max: None,
+    }
+}
+
+impl<S, F, K> Future for MaxByKey<S, F, K>
+    where S: Stream,
+          F: FnMut(&S::Item) -> K,
+          K: Ord,
+{
+    type Item = Option<S::Item>;
+    type Error = S::Error;
+
+    fn poll(&mut self) -> Poll<Option<S::Item>, S::Error> {
+        loop {
+            match self.stream.poll() {
+                Ok(Async::Ready(Some(e))) => {
+                    let key = (self.f)(&e);
+                    // Ties keep the last item, like `Iterator::max_by_key`.
+                    let replace = match self.max {
+                        Some((ref max, _)) => key >= *max,
+                        None => true,
+                    };
+                    if replace {
+                        self.max = Some((key, e));
+                    }
+                }
+                Ok(Async::Ready(None)) => {
+                    return Ok(Async::Ready(self.max.take().map(|(_, e)| e)))
+                }
+                Ok(Async::NotReady) => return Ok(Async::NotReady),
+                Err(e) => {
+                    self.max = None;
+                    return Err(e)
+                }
+            }
+        }
+    }
+}
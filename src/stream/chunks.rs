@@ -1,5 +1,6 @@
-use std::mem;
-use std::prelude::v1::*;
+use core::mem;
+
+use alloc::vec::Vec;
 
 use {Async, Poll};
 use stream::{Stream, Fuse};
@@ -9,6 +10,10 @@ use stream::{Stream, Fuse};
 /// This adaptor will buffer up a list of items in the stream and pass on the
 /// vector used for buffering when a specified capacity has been reached. This
 /// is created by the `Stream::chunks` method.
+///
+/// The buffer for the next chunk is allocated with room for a whole chunk as
+/// soon as the previous one is passed on, so items are never moved around
+/// while a chunk fills up.
 #[derive(Debug)]
 #[must_use = "streams do nothing unless polled"]
 pub struct Chunks<S>
@@ -16,7 +21,8 @@ pub struct Chunks<S>
 {
     items: Vec<S::Item>,
     err: Option<S::Error>,
-    stream: Fuse<S>
+    stream: Fuse<S>,
+    cap: usize,
 }
 
 pub fn new<S>(s: S, capacity: usize) -> Chunks<S>
@@ -24,10 +30,20 @@ pub fn new<S>(s: S, capacity: usize) -> Chunks<S>
 {
     assert!(capacity > 0);
 
+    let stream = super::fuse::new(s);
     Chunks {
-        items: Vec::with_capacity(capacity),
+        items: Vec::with_capacity(chunk_capacity(&stream, capacity)),
         err: None,
-        stream: super::fuse::new(s),
+        stream: stream,
+        cap: capacity,
+    }
+}
+
+// Don't reserve more than the stream says it could still produce.
+fn chunk_capacity<S: Stream>(stream: &S, capacity: usize) -> usize {
+    match stream.size_hint() {
+        (_, Some(upper)) => upper.min(capacity),
+        (_, None) => capacity,
     }
 }
 
@@ -54,7 +70,7 @@ impl<S> ::sink::Sink for Chunks<S>
 
 impl<S> Chunks<S> where S: Stream {
     fn take(&mut self) -> Vec<S::Item> {
-        let cap = self.items.capacity();
+        let cap = chunk_capacity(&self.stream, self.cap);
         mem::replace(&mut self.items, Vec::with_capacity(cap))
     }
 
@@ -75,11 +91,22 @@ impl<S> Chunks<S> where S: Stream {
 
     /// Consumes this combinator, returning the underlying stream.
     ///
-    /// Note that this may discard intermediate state of this combinator, so
-    /// care should be taken to avoid losing resources when this is called.
+    /// Items which are buffered towards the next chunk are dropped, use
+    /// `into_pending` to get at them.
     pub fn into_inner(self) -> S {
         self.stream.into_inner()
     }
+
+    /// Consumes this combinator, returning the items which are buffered
+    /// towards the next chunk along with the error which is yet to be
+    /// yielded, if any.
+    ///
+    /// This is typically called when giving up on the stream early, for
+    /// example after a timeout, to handle the partial chunk instead of losing
+    /// it.
+    pub fn into_pending(self) -> (Vec<S::Item>, Option<S::Error>) {
+        (self.items, self.err)
+    }
 }
 
 impl<S> Stream for Chunks<S>
@@ -93,7 +120,7 @@ impl<S> Stream for Chunks<S>
             return Err(err)
         }
 
-        let cap = self.items.capacity();
+        let cap = self.cap;
         loop {
             match self.stream.poll() {
                 Ok(Async::NotReady) => return Ok(Async::NotReady),
@@ -132,4 +159,14 @@ impl<S> Stream for Chunks<S>
             }
         }
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let buffered = self.items.len();
+        let (lower, upper) = self.stream.size_hint();
+        let lower = lower.saturating_add(buffered);
+        // Errors may cut chunks short, so all we know for sure is that every
+        // chunk holds at least one item.
+        let upper = upper.and_then(|n| n.checked_add(buffered));
+        ((lower / self.cap) + if lower % self.cap > 0 { 1 } else { 0 }, upper)
+    }
 }
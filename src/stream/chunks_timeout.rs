@@ -0,0 +1,152 @@
+use std::fmt;
+use std::mem;
+use std::prelude::v1::*;
+use std::time::Duration;
+
+use {Async, Future, Poll};
+use stream::{Stream, Fuse};
+use timer::{Delay, Timer, TimerError};
+
+/// An adaptor that chunks up elements in a vector, yielding partial chunks
+/// once they're old enough.
+///
+/// This is created by the `Stream::chunks_timeout` method.
+#[must_use = "streams do nothing unless polled"]
+pub struct ChunksTimeout<S, T> where S: Stream, T: Timer {
+    items: Vec<S::Item>,
+    err: Option<S::Error>,
+    stream: Fuse<S>,
+    cap: usize,
+    timer: T,
+    duration: Duration,
+    // Reused across chunks, and only armed while `items` isn't empty.
+    delay: Option<T::Delay>,
+}
+
+impl<S, T> fmt::Debug for ChunksTimeout<S, T>
+    where S: Stream + fmt::Debug,
+          S::Item: fmt::Debug,
+          S::Error: fmt::Debug,
+          T: Timer,
+{
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("ChunksTimeout")
+            .field("items", &self.items)
+            .field("err", &self.err)
+            .field("stream", &self.stream)
+            .field("cap", &self.cap)
+            .field("duration", &self.duration)
+            .finish()
+    }
+}
+
+pub fn new<S, T>(s: S, capacity: usize, duration: Duration, timer: T) -> ChunksTimeout<S, T>
+    where S: Stream,
+          S::Error: From<TimerError>,
+          T: Timer,
+{
+    assert!(capacity > 0);
+
+    ChunksTimeout {
+        items: Vec::with_capacity(capacity),
+        err: None,
+        stream: super::fuse::new(s),
+        cap: capacity,
+        timer: timer,
+        duration: duration,
+        delay: None,
+    }
+}
+
+impl<S, T> ChunksTimeout<S, T> where S: Stream, T: Timer {
+    fn take(&mut self) -> Vec<S::Item> {
+        mem::replace(&mut self.items, Vec::with_capacity(self.cap))
+    }
+
+    /// Acquires a reference to the underlying stream that this combinator is
+    /// pulling from.
+    pub fn get_ref(&self) -> &S {
+        self.stream.get_ref()
+    }
+
+    /// Acquires a mutable reference to the underlying stream that this
+    /// combinator is pulling from.
+    ///
+    /// Note that care must be taken to avoid tampering with the state of the
+    /// stream which may otherwise confuse this combinator.
+    pub fn get_mut(&mut self) -> &mut S {
+        self.stream.get_mut()
+    }
+
+    /// Consumes this combinator, returning the underlying stream.
+    ///
+    /// Note that this may discard intermediate state of this combinator, so
+    /// care should be taken to avoid losing resources when this is called.
+    pub fn into_inner(self) -> S {
+        self.stream.into_inner()
+    }
+}
+
+impl<S, T> Stream for ChunksTimeout<S, T>
+    where S: Stream,
+          S::Error: From<TimerError>,
+          T: Timer,
+{
+    type Item = Vec<S::Item>;
+    type Error = S::Error;
+
+    fn poll(&mut self) -> Poll<Option<Vec<S::Item>>, S::Error> {
+        if let Some(err) = self.err.take() {
+            return Err(err)
+        }
+
+        loop {
+            match self.stream.poll() {
+                Ok(Async::NotReady) => break,
+
+                Ok(Async::Ready(Some(item))) => {
+                    // The clock of a chunk starts with its first item.
+                    if self.items.is_empty() {
+                        let at = self.timer.now() + self.duration;
+                        match self.delay {
+                            Some(ref mut delay) => delay.reset(at),
+                            None => self.delay = Some(self.timer.delay_until(at)),
+                        }
+                    }
+                    self.items.push(item);
+                    if self.items.len() >= self.cap {
+                        return Ok(Some(self.take()).into())
+                    }
+                }
+
+                Ok(Async::Ready(None)) => {
+                    return if self.items.is_empty() {
+                        Ok(Async::Ready(None))
+                    } else {
+                        Ok(Some(self.take()).into())
+                    }
+                }
+
+                // Buffered items are returned before the error, as with
+                // `Chunks`.
+                Err(e) => {
+                    if self.items.is_empty() {
+                        return Err(e)
+                    } else {
+                        self.err = Some(e);
+                        return Ok(Some(self.take()).into())
+                    }
+                }
+            }
+        }
+
+        if self.items.is_empty() {
+            return Ok(Async::NotReady)
+        }
+        match self.delay.as_mut().unwrap().poll() {
+            Ok(Async::NotReady) => Ok(Async::NotReady),
+            Ok(Async::Ready(())) => Ok(Some(self.take()).into()),
+            Err(e) => Err(From::from(e)),
+        }
+    }
+}
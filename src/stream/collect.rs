@@ -1,6 +1,6 @@
-use std::prelude::v1::*;
+use core::mem;
 
-use std::mem;
+use alloc::vec::Vec;
 
 use {Future, Poll, Async};
 use stream::Stream;
@@ -18,9 +18,10 @@ pub struct Collect<S> where S: Stream {
 pub fn new<S>(s: S) -> Collect<S>
     where S: Stream,
 {
+    let (lower, _) = s.size_hint();
     Collect {
         stream: s,
-        items: Vec::new(),
+        items: Vec::with_capacity(lower),
     }
 }
 
@@ -0,0 +1,123 @@
+use {Async, Poll};
+use stream::{Stream, Fuse};
+
+/// A stream which yields the latest items of two streams whenever either of
+/// them yields.
+///
+/// This structure is produced by the `stream::combine_latest` function.
+#[derive(Debug)]
+#[must_use = "streams do nothing unless polled"]
+pub struct CombineLatest<S1, S2> where S1: Stream, S2: Stream {
+    stream1: Fuse<S1>,
+    stream2: Fuse<S2>,
+    latest1: Option<S1::Item>,
+    latest2: Option<S2::Item>,
+    flag: bool,
+}
+
+/// Combines two streams into a stream of pairs of their latest items.
+///
+/// Once both streams have produced at least one item, the returned stream
+/// yields a pair every time either stream yields, containing the new item and
+/// a clone of the latest item of the other stream. The two streams are polled
+/// in a round-robin fashion.
+///
+/// The returned stream ends once both streams have ended, or as soon as one
+/// of them ends without having produced any item. Errors from either stream
+/// are passed through.
+///
+/// # Examples
+///
+/// ```
+/// use futures::prelude::*;
+/// use futures::stream;
+///
+/// let a = stream::iter_ok::<_, ()>(vec![1, 2]);
+/// let b = stream::iter_ok(vec!['a', 'b']);
+/// let pairs = stream::combine_latest(a, b).collect().wait();
+/// assert_eq!(pairs, Ok(vec![(1, 'a'), (1, 'b'), (2, 'b')]));
+/// ```
+pub fn combine_latest<S1, S2>(stream1: S1, stream2: S2) -> CombineLatest<S1, S2>
+    where S1: Stream,
+          S2: Stream<Error = S1::Error>,
+          S1::Item: Clone,
+          S2::Item: Clone,
+{
+    CombineLatest {
+        stream1: stream1.fuse(),
+        stream2: stream2.fuse(),
+        latest1: None,
+        latest2: None,
+        flag: false,
+    }
+}
+
+impl<S1, S2> CombineLatest<S1, S2>
+    where S1: Stream,
+          S2: Stream<Error = S1::Error>,
+          S1::Item: Clone,
+          S2::Item: Clone,
+{
+    // Polls one of the two streams, returning `None` if it had nothing new,
+    // and otherwise whether a full pair is available after recording the
+    // item.
+    fn poll_one(&mut self, second: bool)
+                -> Result<Option<Option<<Self as Stream>::Item>>, S1::Error>
+    {
+        if second {
+            match self.stream2.poll()? {
+                Async::Ready(Some(item)) => self.latest2 = Some(item),
+                Async::Ready(None) | Async::NotReady => return Ok(None),
+            }
+        } else {
+            match self.stream1.poll()? {
+                Async::Ready(Some(item)) => self.latest1 = Some(item),
+                Async::Ready(None) | Async::NotReady => return Ok(None),
+            }
+        }
+        Ok(Some(self.pair()))
+    }
+
+    fn pair(&self) -> Option<(S1::Item, S2::Item)> {
+        match (self.latest1.as_ref(), self.latest2.as_ref()) {
+            (Some(a), Some(b)) => Some((a.clone(), b.clone())),
+            _ => None,
+        }
+    }
+}
+
+impl<S1, S2> Stream for CombineLatest<S1, S2>
+    where S1: Stream,
+          S2: Stream<Error = S1::Error>,
+          S1::Item: Clone,
+          S2::Item: Clone,
+{
+    type Item = (S1::Item, S2::Item);
+    type Error = S1::Error;
+
+    fn poll(&mut self) -> Poll<Option<Self::Item>, S1::Error> {
+        loop {
+            let flag = self.flag;
+            self.flag = !flag;
+
+            let mut progress = false;
+            for &second in [flag, !flag].iter() {
+                match self.poll_one(second)? {
+                    Some(Some(pair)) => return Ok(Async::Ready(Some(pair))),
+                    Some(None) => progress = true,
+                    None => {}
+                }
+            }
+
+            let done1 = self.stream1.is_done();
+            let done2 = self.stream2.is_done();
+            if (done1 && (done2 || self.latest1.is_none())) ||
+               (done2 && self.latest2.is_none()) {
+                return Ok(Async::Ready(None))
+            }
+            if !progress {
+                return Ok(Async::NotReady)
+            }
+        }
+    }
+}
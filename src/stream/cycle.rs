@@ -0,0 +1,149 @@
+use {Async, Poll};
+use stream::Stream;
+
+/// A stream which restarts a cloneable stream each time it terminates.
+///
+/// This structure is produced by the `Stream::cycle` method.
+#[derive(Debug)]
+#[must_use = "streams do nothing unless polled"]
+pub struct Cycle<S> {
+    orig: S,
+    stream: S,
+    empty: bool,
+}
+
+pub fn new<S>(s: S) -> Cycle<S>
+    where S: Stream + Clone,
+{
+    Cycle {
+        stream: s.clone(),
+        orig: s,
+        empty: true,
+    }
+}
+
+impl<S> Cycle<S> {
+    /// Acquires a reference to the stream that is currently being pulled from.
+    pub fn get_ref(&self) -> &S {
+        &self.stream
+    }
+
+    /// Acquires a mutable reference to the stream that is currently being
+    /// pulled from.
+    ///
+    /// Note that care must be taken to avoid tampering with the state of the
+    /// stream which may otherwise confuse this combinator.
+    pub fn get_mut(&mut self) -> &mut S {
+        &mut self.stream
+    }
+
+    /// Consumes this combinator, returning the stream that is currently being
+    /// pulled from.
+    ///
+    /// Note that this may discard intermediate state of this combinator, so
+    /// care should be taken to avoid losing resources when this is called.
+    pub fn into_inner(self) -> S {
+        self.stream
+    }
+}
+
+impl<S> Stream for Cycle<S>
+    where S: Stream + Clone,
+{
+    type Item = S::Item;
+    type Error = S::Error;
+
+    fn poll(&mut self) -> Poll<Option<S::Item>, S::Error> {
+        loop {
+            match try_ready!(self.stream.poll()) {
+                Some(item) => {
+                    self.empty = false;
+                    return Ok(Async::Ready(Some(item)))
+                }
+                // If a full pass didn't produce anything then restarting
+                // won't either, so terminate instead of spinning forever.
+                None if self.empty => return Ok(Async::Ready(None)),
+                None => {
+                    self.stream = self.orig.clone();
+                    self.empty = true;
+                }
+            }
+        }
+    }
+}
+
+/// A stream which rebuilds its underlying stream through a closure each time
+/// it terminates.
+///
+/// This structure is produced by the `stream::cycle_fn` function.
+#[derive(Debug)]
+#[must_use = "streams do nothing unless polled"]
+pub struct CycleFn<F, S> {
+    f: F,
+    stream: Option<S>,
+    empty: bool,
+}
+
+/// Creates a stream which repeatedly yields all items of the streams created
+/// by the provided closure.
+///
+/// The closure is called to create the first stream, and then called again
+/// every time the current stream terminates. Errors are passed through without
+/// restarting the stream.
+///
+/// If a freshly created stream terminates without yielding any items then the
+/// returned stream terminates as well.
+///
+/// # Examples
+///
+/// ```
+/// use futures::prelude::*;
+/// use futures::stream;
+///
+/// let mut round = 0;
+/// let stream = stream::cycle_fn(move || {
+///     round += 1;
+///     stream::iter_ok::<_, ()>(vec![round; 2])
+/// });
+///
+/// let items = stream.take(5).collect().wait();
+/// assert_eq!(items, Ok(vec![1, 1, 2, 2, 3]));
+/// ```
+pub fn cycle_fn<F, S>(f: F) -> CycleFn<F, S>
+    where F: FnMut() -> S,
+          S: Stream,
+{
+    CycleFn {
+        f: f,
+        stream: None,
+        empty: true,
+    }
+}
+
+impl<F, S> Stream for CycleFn<F, S>
+    where F: FnMut() -> S,
+          S: Stream,
+{
+    type Item = S::Item;
+    type Error = S::Error;
+
+    fn poll(&mut self) -> Poll<Option<S::Item>, S::Error> {
+        loop {
+            if self.stream.is_none() {
+                self.stream = Some((self.f)());
+            }
+            let next = try_ready!(self.stream.as_mut().unwrap().poll());
+            match next {
+                Some(item) => {
+                    self.empty = false;
+                    return Ok(Async::Ready(Some(item)))
+                }
+                None if self.empty => return Ok(Async::Ready(None)),
+                None => {
+                    self.stream = None;
+                    self.empty = true;
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,125 @@
+use std::error::Error;
+use std::fmt;
+
+use {Async, Future, Poll};
+use stream::Stream;
+use timer::TimerError;
+
+/// A stream combinator which ends a stream once an absolute deadline passes.
+///
+/// This structure is produced by the `Stream::deadline` method.
+#[derive(Debug)]
+#[must_use = "streams do nothing unless polled"]
+pub struct Deadline<S, D> {
+    stream: S,
+    delay: D,
+    done: bool,
+}
+
+/// Error yielded by a `Deadline` stream.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DeadlineError<E> {
+    /// The deadline passed before the stream terminated. This is the last
+    /// result of the stream.
+    Elapsed,
+
+    /// The timer failed, so the deadline can't be enforced. This is the last
+    /// result of the stream.
+    Timer(TimerError),
+
+    /// The underlying stream yielded an error.
+    Inner(E),
+}
+
+impl<E: fmt::Display> fmt::Display for DeadlineError<E> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            DeadlineError::Elapsed => write!(fmt, "deadline has elapsed"),
+            DeadlineError::Timer(ref e) => e.fmt(fmt),
+            DeadlineError::Inner(ref e) => e.fmt(fmt),
+        }
+    }
+}
+
+impl<E: Error> Error for DeadlineError<E> {
+    fn description(&self) -> &str {
+        match *self {
+            DeadlineError::Elapsed => "deadline has elapsed",
+            DeadlineError::Timer(_) => "timer is no longer running",
+            DeadlineError::Inner(_) => "stream returned an error",
+        }
+    }
+}
+
+pub fn new<S, D>(s: S, delay: D) -> Deadline<S, D>
+    where S: Stream,
+          D: Future<Item = (), Error = TimerError>,
+{
+    Deadline {
+        stream: s,
+        delay: delay,
+        done: false,
+    }
+}
+
+impl<S, D> Deadline<S, D> {
+    /// Acquires a reference to the underlying stream that this combinator is
+    /// pulling from.
+    pub fn get_ref(&self) -> &S {
+        &self.stream
+    }
+
+    /// Acquires a mutable reference to the underlying stream that this
+    /// combinator is pulling from.
+    ///
+    /// Note that care must be taken to avoid tampering with the state of the
+    /// stream which may otherwise confuse this combinator.
+    pub fn get_mut(&mut self) -> &mut S {
+        &mut self.stream
+    }
+
+    /// Consumes this combinator, returning the underlying stream.
+    ///
+    /// Note that this may discard intermediate state of this combinator, so
+    /// care should be taken to avoid losing resources when this is called.
+    pub fn into_inner(self) -> S {
+        self.stream
+    }
+}
+
+impl<S, D> Stream for Deadline<S, D>
+    where S: Stream,
+          D: Future<Item = (), Error = TimerError>,
+{
+    type Item = S::Item;
+    type Error = DeadlineError<S::Error>;
+
+    fn poll(&mut self) -> Poll<Option<S::Item>, DeadlineError<S::Error>> {
+        if self.done {
+            return Ok(Async::Ready(None))
+        }
+
+        // Check the deadline first so that a stream which always has an item
+        // ready can't outrun it.
+        match self.delay.poll() {
+            Ok(Async::NotReady) => {}
+            Ok(Async::Ready(())) => {
+                self.done = true;
+                return Err(DeadlineError::Elapsed)
+            }
+            Err(e) => {
+                self.done = true;
+                return Err(DeadlineError::Timer(e))
+            }
+        }
+
+        match self.stream.poll() {
+            Ok(Async::Ready(None)) => {
+                self.done = true;
+                Ok(Async::Ready(None))
+            }
+            Ok(other) => Ok(other),
+            Err(e) => Err(DeadlineError::Inner(e)),
+        }
+    }
+}
@@ -0,0 +1,123 @@
+use std::fmt;
+use std::time::Duration;
+
+use {Async, Future, Poll};
+use stream::{Stream, Fuse};
+use task;
+use timer::{Delay, Timer, TimerError};
+
+// The maximum number of items pulled from the stream per poll, so that an
+// always-ready stream can't keep `poll` from returning.
+const MAX_DRAIN: usize = 32;
+
+/// A stream combinator which only yields an item once the stream has been
+/// quiet for a while, dropping the items superseded before that.
+///
+/// This structure is produced by the `Stream::debounce` method.
+#[must_use = "streams do nothing unless polled"]
+pub struct Debounce<S, T> where S: Stream, T: Timer {
+    stream: Fuse<S>,
+    timer: T,
+    duration: Duration,
+    pending: Option<S::Item>,
+    // Reset to a new deadline for every item instead of being replaced, and
+    // only armed while `pending` is set.
+    delay: Option<T::Delay>,
+}
+
+impl<S, T> fmt::Debug for Debounce<S, T>
+    where S: Stream + fmt::Debug,
+          S::Item: fmt::Debug,
+          T: Timer,
+{
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("Debounce")
+            .field("stream", &self.stream)
+            .field("duration", &self.duration)
+            .field("pending", &self.pending)
+            .finish()
+    }
+}
+
+pub fn new<S, T>(s: S, duration: Duration, timer: T) -> Debounce<S, T>
+    where S: Stream,
+          S::Error: From<TimerError>,
+          T: Timer,
+{
+    Debounce {
+        stream: super::fuse::new(s),
+        timer: timer,
+        duration: duration,
+        pending: None,
+        delay: None,
+    }
+}
+
+impl<S, T> Debounce<S, T> where S: Stream, T: Timer {
+    /// Acquires a reference to the underlying stream that this combinator is
+    /// pulling from.
+    pub fn get_ref(&self) -> &S {
+        self.stream.get_ref()
+    }
+
+    /// Acquires a mutable reference to the underlying stream that this
+    /// combinator is pulling from.
+    ///
+    /// Note that care must be taken to avoid tampering with the state of the
+    /// stream which may otherwise confuse this combinator.
+    pub fn get_mut(&mut self) -> &mut S {
+        self.stream.get_mut()
+    }
+
+    /// Consumes this combinator, returning the underlying stream.
+    ///
+    /// Note that this may discard intermediate state of this combinator, so
+    /// care should be taken to avoid losing resources when this is called.
+    pub fn into_inner(self) -> S {
+        self.stream.into_inner()
+    }
+}
+
+impl<S, T> Stream for Debounce<S, T>
+    where S: Stream,
+          S::Error: From<TimerError>,
+          T: Timer,
+{
+    type Item = S::Item;
+    type Error = S::Error;
+
+    fn poll(&mut self) -> Poll<Option<S::Item>, S::Error> {
+        for i in 0.. {
+            if i == MAX_DRAIN {
+                // The stream is still busy, so the quiet period can't have
+                // ended yet. Come back for the rest of its items later.
+                task::current().notify();
+                return Ok(Async::NotReady)
+            }
+            match self.stream.poll()? {
+                Async::Ready(Some(item)) => {
+                    // Every item restarts the quiet period.
+                    let at = self.timer.now() + self.duration;
+                    match self.delay {
+                        Some(ref mut delay) => delay.reset(at),
+                        None => self.delay = Some(self.timer.delay_until(at)),
+                    }
+                    self.pending = Some(item);
+                }
+                // The last item doesn't have to wait, as nothing can
+                // supersede it anymore.
+                Async::Ready(None) => return Ok(Async::Ready(self.pending.take())),
+                Async::NotReady => break,
+            }
+        }
+
+        if self.pending.is_none() {
+            return Ok(Async::NotReady)
+        }
+        match self.delay.as_mut().unwrap().poll() {
+            Ok(Async::NotReady) => Ok(Async::NotReady),
+            Ok(Async::Ready(())) => Ok(Async::Ready(self.pending.take())),
+            Err(e) => Err(From::from(e)),
+        }
+    }
+}
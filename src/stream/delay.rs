@@ -0,0 +1,149 @@
+use std::collections::VecDeque;
+use std::fmt;
+use std::time::Duration;
+
+use {Async, Future, Poll};
+use stream::Stream;
+use timer::{Timer, TimerError};
+
+// The number of items buffered unless `DelayItems::with_capacity` is used.
+const DEFAULT_CAPACITY: usize = 128;
+
+/// A stream combinator which yields each item of a stream a fixed duration
+/// after it was received.
+///
+/// This structure is produced by the `Stream::delay` method.
+#[must_use = "streams do nothing unless polled"]
+pub struct DelayItems<S, T> where S: Stream, T: Timer {
+    stream: S,
+    done: bool,
+    timer: T,
+    duration: Duration,
+    queue: VecDeque<Queued<S, T>>,
+    cap: usize,
+}
+
+type Queued<S, T> = (<T as Timer>::Delay, Result<<S as Stream>::Item, <S as Stream>::Error>);
+
+impl<S, T> fmt::Debug for DelayItems<S, T>
+    where S: Stream + fmt::Debug,
+          T: Timer,
+{
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("DelayItems")
+            .field("stream", &self.stream)
+            .field("duration", &self.duration)
+            .field("queued", &self.queue.len())
+            .field("cap", &self.cap)
+            .finish()
+    }
+}
+
+pub fn new<S, T>(s: S, duration: Duration, timer: T) -> DelayItems<S, T>
+    where S: Stream,
+          S::Error: From<TimerError>,
+          T: Timer,
+{
+    DelayItems {
+        stream: s,
+        done: false,
+        timer: timer,
+        duration: duration,
+        queue: VecDeque::new(),
+        cap: DEFAULT_CAPACITY,
+    }
+}
+
+impl<S, T> DelayItems<S, T> where S: Stream, T: Timer {
+    /// Sets the number of items which may be waiting for their delay to pass
+    /// at the same time, which is 128 by default.
+    ///
+    /// Once that many items are waiting the underlying stream isn't polled
+    /// until the first of them has been yielded.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is zero.
+    pub fn with_capacity(mut self, capacity: usize) -> DelayItems<S, T> {
+        assert!(capacity > 0);
+        self.cap = capacity;
+        self
+    }
+
+    /// Acquires a reference to the underlying stream that this combinator is
+    /// pulling from.
+    pub fn get_ref(&self) -> &S {
+        &self.stream
+    }
+
+    /// Acquires a mutable reference to the underlying stream that this
+    /// combinator is pulling from.
+    ///
+    /// Note that care must be taken to avoid tampering with the state of the
+    /// stream which may otherwise confuse this combinator.
+    pub fn get_mut(&mut self) -> &mut S {
+        &mut self.stream
+    }
+
+    /// Consumes this combinator, returning the underlying stream.
+    ///
+    /// Note that this may discard intermediate state of this combinator, so
+    /// care should be taken to avoid losing resources when this is called.
+    pub fn into_inner(self) -> S {
+        self.stream
+    }
+}
+
+impl<S, T> Stream for DelayItems<S, T>
+    where S: Stream,
+          S::Error: From<TimerError>,
+          T: Timer,
+{
+    type Item = S::Item;
+    type Error = S::Error;
+
+    fn poll(&mut self) -> Poll<Option<S::Item>, S::Error> {
+        // Errors are queued up as well so they stay in order with the items.
+        while !self.done && self.queue.len() < self.cap {
+            let res = match self.stream.poll() {
+                Ok(Async::Ready(Some(item))) => Ok(item),
+                Ok(Async::Ready(None)) => {
+                    self.done = true;
+                    break
+                }
+                Ok(Async::NotReady) => break,
+                Err(e) => Err(e),
+            };
+            let delay = self.timer.delay(self.duration);
+            self.queue.push_back((delay, res));
+        }
+
+        // All delays have the same duration, so they pass in queue order.
+        let res = match self.queue.front_mut() {
+            Some(&mut (ref mut delay, _)) => delay.poll(),
+            None if self.done => return Ok(Async::Ready(None)),
+            None => return Ok(Async::NotReady),
+        };
+        match res {
+            Ok(Async::NotReady) => Ok(Async::NotReady),
+            Ok(Async::Ready(())) => {
+                let (_, res) = self.queue.pop_front().unwrap();
+                res.map(|item| Async::Ready(Some(item)))
+            }
+            Err(e) => {
+                self.queue.pop_front();
+                Err(From::from(e))
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let queued = self.queue.len();
+        let (lower, upper) = if self.done {
+            (0, Some(0))
+        } else {
+            self.stream.size_hint()
+        };
+        (lower.saturating_add(queued), upper.and_then(|u| u.checked_add(queued)))
+    }
+}
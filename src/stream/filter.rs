@@ -86,4 +86,9 @@ impl<S, F> Stream for Filter<S, F>
             }
         }
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (_, upper) = self.stream.size_hint();
+        (0, upper)
+    }
 }
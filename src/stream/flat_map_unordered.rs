@@ -0,0 +1,118 @@
+use std::fmt;
+
+use {Async, Poll};
+use stream::{Stream, Fuse, FuturesUnordered, StreamFuture};
+
+/// A stream combinator which maps each item of a stream to a stream and
+/// drives several of those streams at once, yielding their items as they
+/// become available.
+///
+/// This combinator is created by the `Stream::flat_map_unordered` method.
+#[must_use = "streams do nothing unless polled"]
+pub struct FlatMapUnordered<S, F, U> {
+    stream: Fuse<S>,
+    f: F,
+    active: FuturesUnordered<StreamFuture<U>>,
+    limit: usize,
+}
+
+impl<S, F, U> fmt::Debug for FlatMapUnordered<S, F, U>
+    where S: fmt::Debug,
+          U: fmt::Debug,
+{
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("FlatMapUnordered")
+            .field("stream", &self.stream)
+            .field("active", &self.active)
+            .field("limit", &self.limit)
+            .finish()
+    }
+}
+
+pub fn new<S, F, U>(s: S, limit: usize, f: F) -> FlatMapUnordered<S, F, U>
+    where S: Stream,
+          F: FnMut(S::Item) -> U,
+          U: Stream,
+          U::Error: From<S::Error>,
+{
+    assert!(limit > 0, "limit of flat_map_unordered must be at least one");
+    FlatMapUnordered {
+        stream: super::fuse::new(s),
+        f: f,
+        active: FuturesUnordered::new(),
+        limit: limit,
+    }
+}
+
+impl<S, F, U> FlatMapUnordered<S, F, U> {
+    /// Acquires a reference to the underlying stream that this combinator is
+    /// pulling from.
+    pub fn get_ref(&self) -> &S {
+        self.stream.get_ref()
+    }
+
+    /// Acquires a mutable reference to the underlying stream that this
+    /// combinator is pulling from.
+    ///
+    /// Note that care must be taken to avoid tampering with the state of the
+    /// stream which may otherwise confuse this combinator.
+    pub fn get_mut(&mut self) -> &mut S {
+        self.stream.get_mut()
+    }
+
+    /// Consumes this combinator, returning the underlying stream.
+    ///
+    /// Note that this may discard intermediate state of this combinator, so
+    /// care should be taken to avoid losing resources when this is called.
+    pub fn into_inner(self) -> S {
+        self.stream.into_inner()
+    }
+}
+
+impl<S, F, U> Stream for FlatMapUnordered<S, F, U>
+    where S: Stream,
+          F: FnMut(S::Item) -> U,
+          U: Stream,
+          U::Error: From<S::Error>,
+{
+    type Item = U::Item;
+    type Error = U::Error;
+
+    fn poll(&mut self) -> Poll<Option<U::Item>, U::Error> {
+        loop {
+            // First up, try to spawn off as many streams as we're allowed.
+            while self.active.len() < self.limit {
+                match self.stream.poll() {
+                    Ok(Async::Ready(Some(e))) => {
+                        self.active.push((self.f)(e).into_future());
+                    }
+                    Ok(Async::Ready(None)) | Ok(Async::NotReady) => break,
+                    Err(e) => return Err(From::from(e)),
+                }
+            }
+
+            // Each stream is polled as a future resolving to its next item,
+            // so it's queued up again whenever it yields something.
+            match self.active.poll() {
+                Ok(Async::Ready(Some((Some(item), rest)))) => {
+                    self.active.push(rest.into_future());
+                    return Ok(Async::Ready(Some(item)))
+                }
+                // A stream finished, which frees up a slot for the next one.
+                Ok(Async::Ready(Some((None, _)))) => {}
+                Ok(Async::Ready(None)) => {
+                    return if self.stream.is_done() {
+                        Ok(Async::Ready(None))
+                    } else {
+                        Ok(Async::NotReady)
+                    }
+                }
+                Ok(Async::NotReady) => return Ok(Async::NotReady),
+                Err((e, rest)) => {
+                    self.active.push(rest.into_future());
+                    return Err(e)
+                }
+            }
+        }
+    }
+}
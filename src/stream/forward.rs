@@ -1,34 +1,71 @@
 use {Poll, Async, Future, AsyncSink};
-use stream::{Stream, Fuse};
+use stream::{Stream, Fuse, Ticks, NoTicks};
 use sink::Sink;
 
 /// Future for the `Stream::forward` combinator, which sends a stream of values
 /// to a sink and then waits until the sink has fully flushed those values.
 #[derive(Debug)]
 #[must_use = "futures do nothing unless polled"]
-pub struct Forward<T: Stream, U> {
+pub struct Forward<T: Stream, U, I = NoTicks> {
     sink: Option<U>,
     stream: Option<Fuse<T>>,
     buffered: Option<T::Item>,
+    policy: FlushPolicy,
+    ticks: I,
+    ticks_done: bool,
+    unflushed: usize,
+    flushing: bool,
 }
 
+/// When `Forward` flushes its sink while the stream is still producing items.
+///
+/// Regardless of the policy the sink is always flushed whenever the stream
+/// isn't ready to produce another item, and it's closed once the stream has
+/// finished. The policy decides whether the sink is additionally flushed
+/// while the stream has items ready. To flush on a timer instead, use
+/// `Stream::forward_with_ticks`, which can be combined with any policy.
+///
+/// This is used with the `Stream::forward_with` method.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum FlushPolicy {
+    /// Only flush once the stream isn't ready. This is the policy used by
+    /// `Stream::forward`.
+    Idle,
 
-pub fn new<T, U>(stream: T, sink: U) -> Forward<T, U>
+    /// Flush after every item sent to the sink.
+    EveryItem,
+
+    /// Flush after every given number of items sent to the sink.
+    Every(usize),
+}
+
+pub fn new<T, U, I>(stream: T, sink: U, policy: FlushPolicy, ticks: I) -> Forward<T, U, I>
     where U: Sink<SinkItem=T::Item>,
           T: Stream,
           T::Error: From<U::SinkError>,
+          I: Ticks<T::Error>,
 {
+    if let FlushPolicy::Every(n) = policy {
+        assert!(n > 0, "cannot flush every zero items");
+    }
+
     Forward {
         sink: Some(sink),
         stream: Some(stream.fuse()),
         buffered: None,
+        policy: policy,
+        ticks: ticks,
+        ticks_done: false,
+        unflushed: 0,
+        flushing: false,
     }
 }
 
-impl<T, U> Forward<T, U>
+impl<T, U, I> Forward<T, U, I>
     where U: Sink<SinkItem=T::Item>,
           T: Stream,
           T::Error: From<U::SinkError>,
+          I: Ticks<T::Error>,
 {
     fn sink_mut(&mut self) -> &mut U {
         self.sink.as_mut().take()
@@ -54,14 +91,47 @@ impl<T, U> Forward<T, U>
             self.buffered = Some(item);
             return Ok(Async::NotReady)
         }
+        self.unflushed += 1;
+        self.flushing = match self.policy {
+            FlushPolicy::Idle => false,
+            FlushPolicy::EveryItem => true,
+            FlushPolicy::Every(n) => self.unflushed >= n,
+        };
+        self.poll_flush()
+    }
+
+    // Requests a flush if a tick fired while there were unflushed items.
+    fn poll_ticks(&mut self) -> Result<(), T::Error> {
+        if self.ticks_done {
+            return Ok(())
+        }
+        match self.ticks.poll_tick()? {
+            Async::Ready(Some(())) => {
+                if self.unflushed > 0 {
+                    self.flushing = true;
+                }
+            }
+            Async::Ready(None) => self.ticks_done = true,
+            Async::NotReady => {}
+        }
+        Ok(())
+    }
+
+    fn poll_flush(&mut self) -> Poll<(), U::SinkError> {
+        if self.flushing {
+            try_ready!(self.sink_mut().poll_complete());
+            self.flushing = false;
+            self.unflushed = 0;
+        }
         Ok(Async::Ready(()))
     }
 }
 
-impl<T, U> Future for Forward<T, U>
+impl<T, U, I> Future for Forward<T, U, I>
     where U: Sink<SinkItem=T::Item>,
           T: Stream,
           T::Error: From<U::SinkError>,
+          I: Ticks<T::Error>,
 {
     type Item = (T, U);
     type Error = T::Error;
@@ -72,8 +142,12 @@ impl<T, U> Future for Forward<T, U>
         if let Some(item) = self.buffered.take() {
             try_ready!(self.try_start_send(item))
         }
-
         loop {
+            // Finish a flush requested by the policy or by a tick before
+            // pulling more items
+            self.poll_ticks()?;
+            try_ready!(self.poll_flush());
+
             match self.stream_mut().poll()? {
                 Async::Ready(Some(item)) => try_ready!(self.try_start_send(item)),
                 Async::Ready(None) => {
@@ -82,6 +156,7 @@ impl<T, U> Future for Forward<T, U>
                 }
                 Async::NotReady => {
                     try_ready!(self.sink_mut().poll_complete());
+                    self.unflushed = 0;
                     return Ok(Async::NotReady)
                 }
             }
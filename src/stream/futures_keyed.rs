@@ -0,0 +1,211 @@
+use std::borrow::Borrow;
+use std::collections::HashMap;
+use std::fmt::{self, Debug};
+use std::hash::Hash;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use {Async, Future, Poll, Stream};
+use stream::FuturesUnordered;
+use task::AtomicTask;
+
+/// A set of futures, each of them stored under a key.
+///
+/// Like `FuturesUnordered`, this runs all of its futures concurrently and
+/// yields their results as they complete, only polling the futures which
+/// generated notifications. Each future is inserted under a key though, and
+/// its result is yielded along with that key, as `(key, item)` on success
+/// and `(key, error)` on failure. An error only concerns the future it came
+/// from, polling the set again keeps going with the other futures.
+///
+/// There's at most one future per key: inserting a future under a key which
+/// is already in the set cancels the previous future and replaces it, and a
+/// future can be canceled with `remove`. The result of a canceled future is
+/// never yielded, even if the future had completed already. This makes it
+/// easy to keep a single piece of work in flight per entity, for example one
+/// refresh per user where a new request supersedes the pending one.
+///
+/// Canceled futures are dropped the next time the set is polled.
+///
+/// # Examples
+///
+/// ```
+/// use futures::prelude::*;
+/// use futures::future;
+/// use futures::stream::FuturesKeyed;
+///
+/// let mut set = FuturesKeyed::new();
+/// set.insert("a", future::ok::<u32, ()>(1));
+/// set.insert("b", future::ok(2));
+/// set.insert("a", future::ok(3));
+///
+/// let mut results = set.collect().wait().unwrap();
+/// results.sort();
+/// assert_eq!(results, [("a", 3), ("b", 2)]);
+/// ```
+#[must_use = "streams do nothing unless polled"]
+pub struct FuturesKeyed<K, F> {
+    in_progress: FuturesUnordered<Keyed<K, F>>,
+    entries: HashMap<K, Arc<Cancel>>,
+}
+
+struct Keyed<K, F> {
+    key: Option<K>,
+    future: F,
+    cancel: Arc<Cancel>,
+}
+
+struct Cancel {
+    canceled: AtomicBool,
+    task: AtomicTask,
+}
+
+impl<K, F> FuturesKeyed<K, F>
+    where K: Hash + Eq + Clone,
+          F: Future,
+{
+    /// Constructs a new, empty `FuturesKeyed`.
+    pub fn new() -> FuturesKeyed<K, F> {
+        FuturesKeyed {
+            in_progress: FuturesUnordered::new(),
+            entries: HashMap::new(),
+        }
+    }
+
+    /// Inserts a future under `key`, returning whether a previous future
+    /// stored under the same key was canceled and replaced.
+    ///
+    /// As with `FuturesUnordered::push`, the future isn't polled until
+    /// `FuturesKeyed::poll` is called.
+    pub fn insert(&mut self, key: K, future: F) -> bool {
+        let cancel = Arc::new(Cancel {
+            canceled: AtomicBool::new(false),
+            task: AtomicTask::new(),
+        });
+        self.in_progress.push(Keyed {
+            key: Some(key.clone()),
+            future: future,
+            cancel: cancel.clone(),
+        });
+        match self.entries.insert(key, cancel) {
+            Some(old) => {
+                old.cancel();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Cancels the future stored under `key`, returning whether there was
+    /// one.
+    pub fn remove<Q>(&mut self, key: &Q) -> bool
+        where K: Borrow<Q>,
+              Q: ?Sized + Hash + Eq,
+    {
+        match self.entries.remove(key) {
+            Some(cancel) => {
+                cancel.cancel();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Returns whether a future is stored under `key`.
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+        where K: Borrow<Q>,
+              Q: ?Sized + Hash + Eq,
+    {
+        self.entries.contains_key(key)
+    }
+
+    /// Returns the number of futures in the set, not counting the canceled
+    /// ones.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if the set contains no futures.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+impl<K, F> Stream for FuturesKeyed<K, F>
+    where K: Hash + Eq + Clone,
+          F: Future,
+{
+    type Item = (K, F::Item);
+    type Error = (K, F::Error);
+
+    fn poll(&mut self) -> Poll<Option<(K, F::Item)>, (K, F::Error)> {
+        loop {
+            // Canceled futures resolve to `None` as soon as they're polled,
+            // so any other result belongs to the future currently stored
+            // under its key.
+            match self.in_progress.poll() {
+                Ok(Async::Ready(Some(Some((key, item))))) => {
+                    self.entries.remove(&key);
+                    return Ok(Async::Ready(Some((key, item))))
+                }
+                Ok(Async::Ready(Some(None))) => {}
+                Ok(Async::Ready(None)) => return Ok(Async::Ready(None)),
+                Ok(Async::NotReady) => return Ok(Async::NotReady),
+                Err((key, err)) => {
+                    self.entries.remove(&key);
+                    return Err((key, err))
+                }
+            }
+        }
+    }
+}
+
+impl<K, F> Debug for FuturesKeyed<K, F>
+    where K: Debug,
+{
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("FuturesKeyed")
+            .field("keys", &KeysDebug(&self.entries))
+            .finish()
+    }
+}
+
+struct KeysDebug<'a, K: 'a>(&'a HashMap<K, Arc<Cancel>>);
+
+impl<'a, K: Debug> Debug for KeysDebug<'a, K> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_list().entries(self.0.keys()).finish()
+    }
+}
+
+impl Cancel {
+    fn cancel(&self) {
+        self.canceled.store(true, Ordering::SeqCst);
+        self.task.notify();
+    }
+}
+
+impl<K, F: Future> Future for Keyed<K, F> {
+    type Item = Option<(K, F::Item)>;
+    type Error = (K, F::Error);
+
+    fn poll(&mut self) -> Poll<Self::Item, Self::Error> {
+        // Register before checking the flag so that a cancellation in
+        // between isn't missed.
+        self.cancel.task.register();
+        if self.cancel.canceled.load(Ordering::SeqCst) {
+            return Ok(Async::Ready(None))
+        }
+        match self.future.poll() {
+            Ok(Async::Ready(item)) => {
+                let key = self.key.take().expect("cannot poll Keyed twice");
+                Ok(Async::Ready(Some((key, item))))
+            }
+            Ok(Async::NotReady) => Ok(Async::NotReady),
+            Err(err) => {
+                let key = self.key.take().expect("cannot poll Keyed twice");
+                Err((key, err))
+            }
+        }
+    }
+}
@@ -8,7 +8,7 @@ use stream::FuturesUnordered;
 #[derive(Debug)]
 struct OrderWrapper<T> {
     item: T,
-    index: usize,
+    index: isize,
 }
 
 impl<T> PartialEq for OrderWrapper<T> {
@@ -67,6 +67,11 @@ impl<T> Future for OrderWrapper<T>
 /// poll managed futures when `FuturesOrdered::poll` is called. As such, it
 /// is important to call `poll` after pushing new futures.
 ///
+/// Futures can be pushed at either end of the queue with `push_back` and
+/// `push_front`, also while results are being consumed from it: a future
+/// pushed at the front is yielded before any future already in the queue,
+/// and one pushed at the back after all of them.
+///
 /// If `FuturesOrdered::poll` returns `Ok(Async::Ready(None))` this means that
 /// the queue is currently not managing any futures. A future may be submitted
 /// to the queue at a later time. At that point, a call to
@@ -85,8 +90,8 @@ pub struct FuturesOrdered<T>
 {
     in_progress: FuturesUnordered<OrderWrapper<T>>,
     queued_results: BinaryHeap<OrderWrapper<T::Item>>,
-    next_incoming_index: usize,
-    next_outgoing_index: usize,
+    next_incoming_index: isize,
+    next_outgoing_index: isize,
 }
 
 /// Converts a list of futures into a `Stream` of results from the futures.
@@ -145,11 +150,19 @@ impl<T> FuturesOrdered<T>
 
     /// Push a future into the queue.
     ///
+    /// This is the same as `push_back`.
+    pub fn push(&mut self, future: T) {
+        self.push_back(future)
+    }
+
+    /// Push a future at the back of the queue, so that its result is yielded
+    /// after the results of all of the futures already in the queue.
+    ///
     /// This function submits the given future to the internal set for managing.
     /// This function will not call `poll` on the submitted future. The caller
     /// must ensure that `FuturesOrdered::poll` is called in order to receive
     /// task notifications.
-    pub fn push(&mut self, future: T) {
+    pub fn push_back(&mut self, future: T) {
         let wrapped = OrderWrapper {
             item: future,
             index: self.next_incoming_index,
@@ -157,6 +170,20 @@ impl<T> FuturesOrdered<T>
         self.next_incoming_index += 1;
         self.in_progress.push(wrapped);
     }
+
+    /// Push a future at the front of the queue, so that its result is yielded
+    /// before the results of all of the futures already in the queue.
+    ///
+    /// As with `push_back`, the future isn't polled until
+    /// `FuturesOrdered::poll` is called.
+    pub fn push_front(&mut self, future: T) {
+        let wrapped = OrderWrapper {
+            item: future,
+            index: self.next_outgoing_index - 1,
+        };
+        self.next_outgoing_index -= 1;
+        self.in_progress.push(wrapped);
+    }
 }
 
 impl<T> Stream for FuturesOrdered<T>
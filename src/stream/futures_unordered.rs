@@ -7,6 +7,7 @@ use std::sync::atomic::Ordering::{Relaxed, SeqCst, Acquire, Release, AcqRel};
 use std::sync::atomic::{AtomicPtr, AtomicBool};
 use std::sync::{Arc, Weak};
 use std::usize;
+use std::vec::Vec;
 
 use {task, Stream, Future, Poll, Async, IntoFuture};
 use executor::{Notify, UnsafeNotify, NotifyHandle};
@@ -37,6 +38,11 @@ use task_impl::{self, AtomicTask};
 /// `FuturesUnordered::poll` will either return the future's resolved value
 /// **or** `Ok(Async::NotReady)` if the future has not yet completed.
 ///
+/// The allocations backing completed futures are kept around and reused for
+/// futures pushed later on, as long as nothing else holds on to them, so that
+/// a set which keeps churning through short-lived futures doesn't hit the
+/// allocator for each of them. `shrink_to_fit` releases those allocations.
+///
 /// Note that you can create a ready-made `FuturesUnordered` via the
 /// `futures_unordered` function in the `stream` module, or you can start with an
 /// empty set with the `FuturesUnordered::new` constructor.
@@ -45,6 +51,7 @@ pub struct FuturesUnordered<F> {
     inner: Arc<Inner<F>>,
     len: usize,
     head_all: *const Node<F>,
+    free: Vec<Arc<Node<F>>>,
 }
 
 unsafe impl<T: Send> Send for FuturesUnordered<T> {}
@@ -141,27 +148,34 @@ impl<T> FuturesUnordered<T>
     /// The returned `FuturesUnordered` does not contain any futures and, in this
     /// state, `FuturesUnordered::poll` will return `Ok(Async::Ready(None))`.
     pub fn new() -> FuturesUnordered<T> {
-        let stub = Arc::new(Node {
-            future: UnsafeCell::new(None),
-            next_all: UnsafeCell::new(ptr::null()),
-            prev_all: UnsafeCell::new(ptr::null()),
-            next_readiness: AtomicPtr::new(ptr::null_mut()),
-            queued: AtomicBool::new(true),
-            queue: Weak::new(),
-        });
-        let stub_ptr = &*stub as *const Node<T>;
-        let inner = Arc::new(Inner {
-            parent: AtomicTask::new(),
-            head_readiness: AtomicPtr::new(stub_ptr as *mut _),
-            tail_readiness: UnsafeCell::new(stub_ptr),
-            stub: stub,
-        });
+        new_set()
+    }
+}
 
-        FuturesUnordered {
-            len: 0,
-            head_all: ptr::null_mut(),
-            inner: inner,
-        }
+/// Creates an empty set of things other than futures, driven through
+/// `poll_entries`.
+pub fn new_set<T>() -> FuturesUnordered<T> {
+    let stub = Arc::new(Node {
+        future: UnsafeCell::new(None),
+        next_all: UnsafeCell::new(ptr::null()),
+        prev_all: UnsafeCell::new(ptr::null()),
+        next_readiness: AtomicPtr::new(ptr::null_mut()),
+        queued: AtomicBool::new(true),
+        queue: Weak::new(),
+    });
+    let stub_ptr = &*stub as *const Node<T>;
+    let inner = Arc::new(Inner {
+        parent: AtomicTask::new(),
+        head_readiness: AtomicPtr::new(stub_ptr as *mut _),
+        tail_readiness: UnsafeCell::new(stub_ptr),
+        stub: stub,
+    });
+
+    FuturesUnordered {
+        len: 0,
+        head_all: ptr::null_mut(),
+        inner: inner,
+        free: Vec::new(),
     }
 }
 
@@ -178,6 +192,39 @@ impl<T> FuturesUnordered<T> {
         self.len == 0
     }
 
+    /// Returns an iterator over the futures in the set which haven't
+    /// completed yet, in no particular order.
+    pub fn iter<'a>(&'a self) -> FuturesUnorderedIter<'a, T> {
+        FuturesUnorderedIter {
+            node: self.head_all,
+            len: self.len,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns an iterator giving mutable access to the futures in the set
+    /// which haven't completed yet, in no particular order.
+    pub fn iter_mut<'a>(&'a mut self) -> FuturesUnorderedIterMut<'a, T> {
+        FuturesUnorderedIterMut {
+            node: self.head_all,
+            len: self.len,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Drops all of the futures in the set, leaving it empty.
+    pub fn clear(&mut self) {
+        // Nodes which may still be notified are left for the readiness queue
+        // to free, see `release_node`.
+        unsafe {
+            while !self.head_all.is_null() {
+                let head = self.head_all;
+                let node = self.unlink(head);
+                self.release_node(node);
+            }
+        }
+    }
+
     /// Push a future into the set.
     ///
     /// This function submits the given future to the set for managing. This
@@ -185,14 +232,25 @@ impl<T> FuturesUnordered<T> {
     /// ensure that `FuturesUnordered::poll` is called in order to receive task
     /// notifications.
     pub fn push(&mut self, future: T) {
-        let node = Arc::new(Node {
-            future: UnsafeCell::new(Some(future)),
-            next_all: UnsafeCell::new(ptr::null_mut()),
-            prev_all: UnsafeCell::new(ptr::null_mut()),
-            next_readiness: AtomicPtr::new(ptr::null_mut()),
-            queued: AtomicBool::new(true),
-            queue: Arc::downgrade(&self.inner),
-        });
+        let node = match self.free.pop() {
+            // Free nodes are unlinked and still flagged as queued, see
+            // `recycle`, so only the future needs to be filled in.
+            Some(node) => {
+                debug_assert!(node.queued.load(Relaxed));
+                unsafe {
+                    *node.future.get() = Some(future);
+                }
+                node
+            }
+            None => Arc::new(Node {
+                future: UnsafeCell::new(Some(future)),
+                next_all: UnsafeCell::new(ptr::null_mut()),
+                prev_all: UnsafeCell::new(ptr::null_mut()),
+                next_readiness: AtomicPtr::new(ptr::null_mut()),
+                queued: AtomicBool::new(true),
+                queue: Arc::downgrade(&self.inner),
+            }),
+        };
 
         // Right now our node has a strong reference count of 1. We transfer
         // ownership of this reference count to our internal linked list
@@ -231,9 +289,27 @@ impl<T> FuturesUnordered<T> {
         // been dropped by the various owning tasks elsewhere.
         if prev {
             mem::forget(node);
+        } else {
+            self.recycle(node);
+        }
+    }
+
+    /// Keeps a node whose future is gone around for reuse by `push`.
+    ///
+    /// This is only possible if ours is the last reference to the node, as
+    /// otherwise a `Task` handle may still notify it.
+    fn recycle(&mut self, mut node: Arc<Node<T>>) {
+        if Arc::get_mut(&mut node).is_some() {
+            self.free.push(node);
         }
     }
 
+    /// Releases the allocations kept around for reuse by futures which have
+    /// completed.
+    pub fn shrink_to_fit(&mut self) {
+        self.free = Vec::new();
+    }
+
     /// Insert a new node into the internal linked list.
     fn link(&mut self, node: Arc<Node<T>>) -> *const Node<T> {
         let ptr = arc2ptr(node);
@@ -279,16 +355,70 @@ impl<T> Stream for FuturesUnordered<T>
     type Error = T::Error;
 
     fn poll(&mut self) -> Poll<Option<T::Item>, T::Error> {
+        let res = self.poll_entries(|future| {
+            match future.poll() {
+                Ok(Async::NotReady) => Polled::NotReady,
+                Ok(Async::Ready(e)) => Polled::Done(Ok(e)),
+                Err(e) => Polled::Done(Err(e)),
+            }
+        });
+        match res {
+            Async::Ready(Some(Ok(e))) => Ok(Async::Ready(Some(e))),
+            Async::Ready(Some(Err(e))) => Err(e),
+            Async::Ready(None) => Ok(Async::Ready(None)),
+            Async::NotReady => Ok(Async::NotReady),
+        }
+    }
+}
+
+/// Polls the entries of `set` which were notified with `f`, until one of
+/// them yields something.
+pub fn poll_entries<T, F, R>(set: &mut FuturesUnordered<T>, f: F) -> Async<Option<R>>
+    where F: FnMut(&mut T) -> Polled<R>,
+{
+    set.poll_entries(f)
+}
+
+/// What to do with an entry of the set after polling it.
+#[allow(missing_debug_implementations)]
+pub enum Polled<R> {
+    /// The entry isn't ready, keep it until it's notified.
+    NotReady,
+
+    /// Yield `R` and keep the entry, polling it again after the other ready
+    /// entries even if it isn't notified.
+    Again(R),
+
+    /// Yield `R` and drop the entry.
+    Done(R),
+
+    /// Drop the entry without yielding anything.
+    Finished,
+}
+
+impl<T> FuturesUnordered<T> {
+    // Polls the entries of the set which were notified with `f`, until one
+    // of them yields something.
+    //
+    // This is the guts of `Stream::poll`, generic over what the entries are
+    // and how they're polled so that sets of streams can share it.
+    fn poll_entries<F, R>(&mut self, mut f: F) -> Async<Option<R>>
+        where F: FnMut(&mut T) -> Polled<R>,
+    {
         // Ensure `parent` is correctly set.
         self.inner.parent.register();
 
+        if task::poll_budget().is_not_ready() {
+            return Async::NotReady
+        }
+
         loop {
             let node = match unsafe { self.inner.dequeue() } {
                 Dequeue::Empty => {
                     if self.is_empty() {
-                        return Ok(Async::Ready(None));
+                        return Async::Ready(None);
                     } else {
-                        return Ok(Async::NotReady)
+                        return Async::NotReady
                     }
                 }
                 Dequeue::Inconsistent => {
@@ -296,7 +426,7 @@ impl<T> Stream for FuturesUnordered<T>
                     // spinning a few times... but for now, just yield using the
                     // task system.
                     task::current().notify();
-                    return Ok(Async::NotReady);
+                    return Async::NotReady;
                 }
                 Dequeue::Data(node) => node,
             };
@@ -304,8 +434,8 @@ impl<T> Stream for FuturesUnordered<T>
             debug_assert!(node != self.inner.stub());
 
             unsafe {
-                let mut future = match (*(*node).future.get()).take() {
-                    Some(future) => future,
+                let mut entry = match (*(*node).future.get()).take() {
+                    Some(entry) => entry,
 
                     // If the future has already gone away then we're just
                     // cleaning out this node. See the comment in
@@ -315,6 +445,7 @@ impl<T> Stream for FuturesUnordered<T>
                         let node = ptr2arc(node);
                         assert!((*node.next_all.get()).is_null());
                         assert!((*node.prev_all.get()).is_null());
+                        self.recycle(node);
                         continue
                     }
                 };
@@ -370,22 +501,32 @@ impl<T> Stream for FuturesUnordered<T>
                 // deallocating the node if need be.
                 let res = {
                     let notify = NodeToHandle(bomb.node.as_ref().unwrap());
-                    task_impl::with_notify(&notify, 0, || {
-                        future.poll()
-                    })
+                    task_impl::with_notify(&notify, 0, || f(&mut entry))
                 };
 
                 let ret = match res {
-                    Ok(Async::NotReady) => {
+                    Polled::NotReady => {
                         let node = bomb.node.take().unwrap();
-                        *node.future.get() = Some(future);
+                        *node.future.get() = Some(entry);
                         bomb.queue.link(node);
                         continue
                     }
-                    Ok(Async::Ready(e)) => Ok(Async::Ready(Some(e))),
-                    Err(e) => Err(e),
+                    Polled::Again(r) => {
+                        // Queue the entry up again, unless it was notified
+                        // while being polled and thus already is.
+                        let node = bomb.node.take().unwrap();
+                        *node.future.get() = Some(entry);
+                        let ptr = bomb.queue.link(node);
+                        if !(*ptr).queued.swap(true, SeqCst) {
+                            bomb.queue.inner.enqueue(ptr);
+                        }
+                        r
+                    }
+                    Polled::Done(r) => r,
+                    Polled::Finished => continue,
                 };
-                return ret
+                task::consume_budget();
+                return Async::Ready(Some(ret))
             }
         }
     }
@@ -397,6 +538,93 @@ impl<T: Debug> Debug for FuturesUnordered<T> {
     }
 }
 
+/// An iterator over the futures of a `FuturesUnordered` which haven't
+/// completed yet.
+///
+/// This is created by the `FuturesUnordered::iter` method.
+pub struct FuturesUnorderedIter<'a, T: 'a> {
+    node: *const Node<T>,
+    len: usize,
+    _marker: PhantomData<&'a FuturesUnordered<T>>,
+}
+
+/// An iterator giving mutable access to the futures of a `FuturesUnordered`
+/// which haven't completed yet.
+///
+/// This is created by the `FuturesUnordered::iter_mut` method.
+pub struct FuturesUnorderedIterMut<'a, T: 'a> {
+    node: *const Node<T>,
+    len: usize,
+    _marker: PhantomData<&'a mut FuturesUnordered<T>>,
+}
+
+unsafe impl<'a, T: Sync> Send for FuturesUnorderedIter<'a, T> {}
+unsafe impl<'a, T: Sync> Sync for FuturesUnorderedIter<'a, T> {}
+unsafe impl<'a, T: Send> Send for FuturesUnorderedIterMut<'a, T> {}
+unsafe impl<'a, T: Sync> Sync for FuturesUnorderedIterMut<'a, T> {}
+
+impl<'a, T> Iterator for FuturesUnorderedIter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        if self.node.is_null() {
+            return None
+        }
+        // Every node in the list of all nodes holds a future, futures are
+        // only taken out of their node while it's unlinked.
+        unsafe {
+            let future = (*(*self.node).future.get()).as_ref().unwrap();
+            self.node = *(*self.node).next_all.get();
+            self.len -= 1;
+            Some(future)
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<'a, T> ExactSizeIterator for FuturesUnorderedIter<'a, T> {}
+
+impl<'a, T> Iterator for FuturesUnorderedIterMut<'a, T> {
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> Option<&'a mut T> {
+        if self.node.is_null() {
+            return None
+        }
+        unsafe {
+            let future = (*(*self.node).future.get()).as_mut().unwrap();
+            self.node = *(*self.node).next_all.get();
+            self.len -= 1;
+            Some(future)
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<'a, T> ExactSizeIterator for FuturesUnorderedIterMut<'a, T> {}
+
+impl<'a, T: Debug> Debug for FuturesUnorderedIter<'a, T> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("FuturesUnorderedIter")
+            .field("len", &self.len)
+            .finish()
+    }
+}
+
+impl<'a, T: Debug> Debug for FuturesUnorderedIterMut<'a, T> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("FuturesUnorderedIterMut")
+            .field("len", &self.len)
+            .finish()
+    }
+}
+
 impl<T> Drop for FuturesUnordered<T> {
     fn drop(&mut self) {
         // When a `FuturesUnordered` is dropped we want to drop all futures associated
@@ -404,13 +632,7 @@ impl<T> Drop for FuturesUnordered<T> {
         // flying around which contain `Node<T>` references inside them. We'll
         // let those naturally get deallocated when the `Task` itself goes out
         // of scope or gets notified.
-        unsafe {
-            while !self.head_all.is_null() {
-                let head = self.head_all;
-                let node = self.unlink(head);
-                self.release_node(node);
-            }
-        }
+        self.clear();
 
         // Note that at this point we could still have a bunch of nodes in the
         // mpsc queue. None of those nodes, however, have futures associated
@@ -61,4 +61,8 @@ impl<S, F> Stream for Inspect<S, F>
             None => Ok(Async::Ready(None)),
         }
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.stream.size_hint()
+    }
 }
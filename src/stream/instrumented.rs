@@ -0,0 +1,207 @@
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use {Async, Poll};
+use observe;
+use stream::Stream;
+
+/// A stream combinator which gathers statistics about how a stream is polled
+/// and reports them to the registered `observe::Observer`.
+///
+/// This structure is produced by the `Stream::instrumented` method.
+#[must_use = "streams do nothing unless polled"]
+pub struct Instrumented<S> {
+    stream: S,
+    report: Report,
+}
+
+impl<S: fmt::Debug> fmt::Debug for Instrumented<S> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("Instrumented")
+            .field("stream", &self.stream)
+            .field("name", &self.report.name)
+            .field("stats", &self.report.stats)
+            .finish()
+    }
+}
+
+// Kept apart from the stream so `into_inner` can move the stream out while
+// the finish event is still reported on drop.
+struct Report {
+    name: &'static str,
+    stats: StreamStats,
+    first_poll: Option<Instant>,
+    finished: bool,
+}
+
+impl Report {
+    fn finish(&mut self) {
+        if !self.finished {
+            self.finished = true;
+            if let Some(observer) = observe::observer() {
+                observer.stream_finished(self.name, &self.stats);
+            }
+        }
+    }
+}
+
+impl Drop for Report {
+    fn drop(&mut self) {
+        self.finish();
+    }
+}
+
+/// Statistics gathered about a stream created with `Stream::instrumented`.
+#[derive(Debug, Clone, Default)]
+pub struct StreamStats {
+    polls: u64,
+    not_ready: u64,
+    items: u64,
+    errors: u64,
+    first_item: Option<Duration>,
+}
+
+impl StreamStats {
+    /// Returns how many times the stream was polled.
+    pub fn polls(&self) -> u64 {
+        self.polls
+    }
+
+    /// Returns how many polls of the stream returned `NotReady`.
+    pub fn not_ready(&self) -> u64 {
+        self.not_ready
+    }
+
+    /// Returns the fraction of polls which returned `NotReady`, or zero if the
+    /// stream wasn't polled yet.
+    pub fn not_ready_ratio(&self) -> f64 {
+        if self.polls == 0 {
+            0.0
+        } else {
+            self.not_ready as f64 / self.polls as f64
+        }
+    }
+
+    /// Returns how many items the stream yielded.
+    pub fn items(&self) -> u64 {
+        self.items
+    }
+
+    /// Returns how many errors the stream yielded.
+    pub fn errors(&self) -> u64 {
+        self.errors
+    }
+
+    /// Returns the time between the first poll of the stream and the first
+    /// item it yielded, if it yielded an item.
+    pub fn time_to_first_item(&self) -> Option<Duration> {
+        self.first_item
+    }
+}
+
+pub fn new<S>(s: S, name: &'static str) -> Instrumented<S>
+    where S: Stream,
+{
+    Instrumented {
+        stream: s,
+        report: Report {
+            name: name,
+            stats: StreamStats::default(),
+            first_poll: None,
+            finished: false,
+        },
+    }
+}
+
+impl<S> Instrumented<S> {
+    /// Returns the name this stream reports its statistics under.
+    pub fn name(&self) -> &'static str {
+        self.report.name
+    }
+
+    /// Returns the statistics gathered so far.
+    pub fn stats(&self) -> &StreamStats {
+        &self.report.stats
+    }
+
+    /// Acquires a reference to the underlying stream that this combinator is
+    /// pulling from.
+    pub fn get_ref(&self) -> &S {
+        &self.stream
+    }
+
+    /// Acquires a mutable reference to the underlying stream that this
+    /// combinator is pulling from.
+    ///
+    /// Note that care must be taken to avoid tampering with the state of the
+    /// stream which may otherwise confuse this combinator.
+    pub fn get_mut(&mut self) -> &mut S {
+        &mut self.stream
+    }
+
+    /// Consumes this combinator, returning the underlying stream.
+    ///
+    /// The statistics gathered so far are reported as finished.
+    pub fn into_inner(self) -> S {
+        self.stream
+    }
+}
+
+// Forwarding impl of Sink from the underlying stream
+impl<S> ::sink::Sink for Instrumented<S>
+    where S: ::sink::Sink
+{
+    type SinkItem = S::SinkItem;
+    type SinkError = S::SinkError;
+
+    fn start_send(&mut self, item: S::SinkItem) -> ::StartSend<S::SinkItem, S::SinkError> {
+        self.stream.start_send(item)
+    }
+
+    fn poll_complete(&mut self) -> Poll<(), S::SinkError> {
+        self.stream.poll_complete()
+    }
+
+    fn close(&mut self) -> Poll<(), S::SinkError> {
+        self.stream.close()
+    }
+}
+
+impl<S: Stream> Stream for Instrumented<S> {
+    type Item = S::Item;
+    type Error = S::Error;
+
+    fn poll(&mut self) -> Poll<Option<S::Item>, S::Error> {
+        let first_poll = *self.report.first_poll.get_or_insert_with(Instant::now);
+        let res = self.stream.poll();
+
+        let mut done = false;
+        {
+            let stats = &mut self.report.stats;
+            stats.polls += 1;
+            match res {
+                Ok(Async::NotReady) => stats.not_ready += 1,
+                Ok(Async::Ready(Some(_))) => {
+                    stats.items += 1;
+                    if stats.first_item.is_none() {
+                        stats.first_item = Some(first_poll.elapsed());
+                    }
+                }
+                Ok(Async::Ready(None)) => done = true,
+                Err(_) => stats.errors += 1,
+            }
+        }
+
+        if let Some(observer) = observe::observer() {
+            observer.stream_polled(self.report.name, &self.report.stats);
+        }
+        if done {
+            self.report.finish();
+        }
+        res
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.stream.size_hint()
+    }
+}
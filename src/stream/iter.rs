@@ -43,4 +43,9 @@ impl<I, T, E> Stream for Iter<I>
     fn poll(&mut self) -> Poll<Option<T>, E> {
         self.0.poll()
     }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
 }
@@ -0,0 +1,59 @@
+use std::fmt;
+
+use Poll;
+use future::Executor;
+use stream::{Stream, IterOk, iter_ok};
+use sync::mpsc::{self, SpawnHandle, Execute};
+
+/// A stream of the items of a blocking iterator which is driven on an
+/// executor.
+///
+/// This stream is created by the `stream::iter_blocking` function.
+#[must_use = "streams do nothing unless polled"]
+pub struct IterBlocking<T, E> {
+    handle: SpawnHandle<T, E>,
+}
+
+/// Converts an `Iterator` whose `next` may block into a `Stream` by pulling
+/// from it on the provided executor.
+///
+/// Unlike `iter_ok`, which calls `next` inline when polled, this function
+/// moves the iterator onto `executor` and only transfers the items it
+/// produces back to the returned stream. This keeps slow or blocking
+/// iterators, such as those reading directories or walking database cursors,
+/// from stalling the task consuming the stream. The executor is expected to
+/// tolerate blocking work, for example a thread pool dedicated to it.
+///
+/// At most `buffer + 1` items are prefetched ahead of the returned stream, once
+/// the buffer is full the iterator isn't advanced until more space is
+/// available. The iterator is dropped once the returned stream is dropped.
+///
+/// # Panics
+///
+/// This function will panic if `executor` is unable to spawn the future
+/// driving the iterator.
+pub fn iter_blocking<I, E, X>(i: I, executor: &X, buffer: usize)
+                              -> IterBlocking<I::Item, E>
+    where I: IntoIterator,
+          X: Executor<Execute<IterOk<I::IntoIter, E>>>,
+{
+    IterBlocking {
+        handle: mpsc::spawn(iter_ok(i), executor, buffer),
+    }
+}
+
+impl<T, E> Stream for IterBlocking<T, E> {
+    type Item = T;
+    type Error = E;
+
+    fn poll(&mut self) -> Poll<Option<T>, E> {
+        self.handle.poll()
+    }
+}
+
+impl<T, E> fmt::Debug for IterBlocking<T, E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("IterBlocking")
+         .finish()
+    }
+}
@@ -36,6 +36,17 @@ pub fn iter_ok<I, E>(i: I) -> IterOk<I::IntoIter, E>
     }
 }
 
+impl<I, E> Clone for IterOk<I, E>
+    where I: Clone,
+{
+    fn clone(&self) -> IterOk<I, E> {
+        IterOk {
+            iter: self.iter.clone(),
+            _marker: marker::PhantomData,
+        }
+    }
+}
+
 impl<I, E> Stream for IterOk<I, E>
     where I: Iterator,
 {
@@ -45,4 +56,8 @@ impl<I, E> Stream for IterOk<I, E>
     fn poll(&mut self) -> Poll<Option<I::Item>, E> {
         Ok(Async::Ready(self.iter.next()))
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
 }
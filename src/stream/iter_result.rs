@@ -4,7 +4,7 @@ use stream::Stream;
 /// A stream which is just a shim over an underlying instance of `Iterator`.
 ///
 /// This stream will never block and is always ready.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 #[must_use = "streams do nothing unless polled"]
 pub struct IterResult<I> {
     iter: I,
@@ -48,4 +48,10 @@ where
             None => Ok(Async::Ready(None)),
         }
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        // Any of the remaining results may turn out to be an error.
+        let (_, upper) = self.iter.size_hint();
+        (0, upper)
+    }
 }
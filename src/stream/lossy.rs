@@ -0,0 +1,171 @@
+use std::collections::VecDeque;
+
+use {Async, Poll};
+use stream::{Stream, Fuse};
+use task;
+
+// The maximum number of items pulled from the underlying stream per poll, so
+// that an always-ready stream can't keep `poll` from returning.
+const MAX_DRAIN: usize = 32;
+
+/// A policy deciding what happens to items when the buffer of a `Lossy`
+/// stream is full.
+///
+/// This crate provides the `DropOldest`, `DropNewest`, `Evict` and `Coalesce`
+/// policies, but any other policy may be implemented as well. The policies
+/// can also be used with the `Sink::buffer_with_policy` adaptor.
+pub trait OverflowPolicy<T> {
+    /// Handles `item` arriving while `buffer` is full.
+    ///
+    /// The buffer is never empty when this is called. Implementations may
+    /// modify the buffer in any way, but should not grow it beyond its
+    /// current length.
+    fn overflow(&mut self, buffer: &mut VecDeque<T>, item: T);
+}
+
+/// An overflow policy which discards the oldest buffered item to make room
+/// for a new one.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DropOldest;
+
+impl<T> OverflowPolicy<T> for DropOldest {
+    fn overflow(&mut self, buffer: &mut VecDeque<T>, item: T) {
+        buffer.pop_front();
+        buffer.push_back(item);
+    }
+}
+
+/// An overflow policy which discards new items until there's room in the
+/// buffer again.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DropNewest;
+
+impl<T> OverflowPolicy<T> for DropNewest {
+    fn overflow(&mut self, _buffer: &mut VecDeque<T>, _item: T) {
+    }
+}
+
+/// An overflow policy which discards the oldest buffered item to make room
+/// for a new one, passing the discarded item to a closure.
+#[derive(Debug, Clone, Copy)]
+pub struct Evict<F>(pub F);
+
+impl<T, F> OverflowPolicy<T> for Evict<F>
+    where F: FnMut(T),
+{
+    fn overflow(&mut self, buffer: &mut VecDeque<T>, item: T) {
+        let oldest = buffer.pop_front().unwrap();
+        buffer.push_back(item);
+        (self.0)(oldest);
+    }
+}
+
+/// An overflow policy which merges a new item into the newest buffered item
+/// with a closure.
+///
+/// The closure is called with the newest buffered item and the new item, and
+/// its result replaces the newest buffered item.
+#[derive(Debug, Clone, Copy)]
+pub struct Coalesce<F>(pub F);
+
+impl<T, F> OverflowPolicy<T> for Coalesce<F>
+    where F: FnMut(T, T) -> T,
+{
+    fn overflow(&mut self, buffer: &mut VecDeque<T>, item: T) {
+        let last = buffer.pop_back().unwrap();
+        buffer.push_back((self.0)(last, item));
+    }
+}
+
+/// A stream combinator which buffers items of a stream, applying an overflow
+/// policy when its consumer doesn't keep up.
+///
+/// This structure is produced by the `Stream::lossy` method.
+#[derive(Debug)]
+#[must_use = "streams do nothing unless polled"]
+pub struct Lossy<S, P> where S: Stream {
+    stream: Fuse<S>,
+    buffer: VecDeque<S::Item>,
+    capacity: usize,
+    policy: P,
+}
+
+pub fn new<S, P>(s: S, capacity: usize, policy: P) -> Lossy<S, P>
+    where S: Stream,
+          P: OverflowPolicy<S::Item>,
+{
+    assert!(capacity > 0);
+
+    Lossy {
+        stream: s.fuse(),
+        buffer: VecDeque::with_capacity(capacity),
+        capacity: capacity,
+        policy: policy,
+    }
+}
+
+impl<S, P> Lossy<S, P> where S: Stream {
+    /// Acquires a reference to the underlying stream that this combinator is
+    /// pulling from.
+    pub fn get_ref(&self) -> &S {
+        self.stream.get_ref()
+    }
+
+    /// Acquires a mutable reference to the underlying stream that this
+    /// combinator is pulling from.
+    ///
+    /// Note that care must be taken to avoid tampering with the state of the
+    /// stream which may otherwise confuse this combinator.
+    pub fn get_mut(&mut self) -> &mut S {
+        self.stream.get_mut()
+    }
+
+    /// Consumes this combinator, returning the underlying stream.
+    ///
+    /// Note that this may discard intermediate state of this combinator, so
+    /// care should be taken to avoid losing resources when this is called.
+    pub fn into_inner(self) -> S {
+        self.stream.into_inner()
+    }
+}
+
+impl<S, P> Stream for Lossy<S, P>
+    where S: Stream,
+          P: OverflowPolicy<S::Item>,
+{
+    type Item = S::Item;
+    type Error = S::Error;
+
+    fn poll(&mut self) -> Poll<Option<S::Item>, S::Error> {
+        // Pull in what the underlying stream has ready, so that items which
+        // piled up while we weren't polled are subject to the policy.
+        let mut drained = false;
+        for _ in 0..MAX_DRAIN {
+            match self.stream.poll()? {
+                Async::Ready(Some(item)) => {
+                    if self.buffer.len() < self.capacity {
+                        self.buffer.push_back(item);
+                    } else {
+                        self.policy.overflow(&mut self.buffer, item);
+                    }
+                }
+                _ => {
+                    drained = true;
+                    break
+                }
+            }
+        }
+
+        match self.buffer.pop_front() {
+            Some(item) => Ok(Async::Ready(Some(item))),
+            None if self.stream.is_done() => Ok(Async::Ready(None)),
+            None if !drained => {
+                // The policy left the buffer empty while the stream still has
+                // items ready, so come back for them.
+                task::current().notify();
+                Ok(Async::NotReady)
+            }
+            None => Ok(Async::NotReady),
+        }
+    }
+}
@@ -0,0 +1,300 @@
+use std::collections::BTreeSet;
+use std::collections::hash_map::RandomState;
+use std::fmt;
+use std::hash::{BuildHasher, Hasher};
+use std::prelude::v1::*;
+use std::sync::{Arc, Mutex};
+
+use {Async, Poll};
+use executor::Notify;
+use stream::Stream;
+use task_impl::{self, AtomicTask};
+
+/// The order in which a `MergeAll` stream picks among its streams which are
+/// ready to make progress.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MergeStrategy {
+    /// Cycle through the streams, starting after the one which last yielded
+    /// a value.
+    RoundRobin,
+
+    /// Pick a stream at random.
+    Random,
+
+    /// Always favor the stream which came first in the original collection.
+    Priority,
+}
+
+/// A stream which merges the values of many streams of the same type as they
+/// become available.
+///
+/// Only the streams which got notified since they were last polled are
+/// polled again. They are kept in a set of ready streams from which the next
+/// one is picked without looking at the other streams, so a wakeup costs at
+/// most logarithmic time in the number of streams being merged.
+///
+/// This structure is produced by the `stream::merge` and `stream::merge_with`
+/// functions.
+#[must_use = "streams do nothing unless polled"]
+pub struct MergeAll<S> {
+    streams: Vec<Option<S>>,
+    remaining: usize,
+    strategy: MergeStrategy,
+    next: usize,
+    rng: u64,
+    wakeups: Arc<Wakeups>,
+}
+
+impl<S: fmt::Debug> fmt::Debug for MergeAll<S> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("MergeAll")
+            .field("streams", &self.streams)
+            .field("strategy", &self.strategy)
+            .finish()
+    }
+}
+
+// Tracks which streams have been notified, routing their wakeups to the task
+// polling the `MergeAll`.
+struct Wakeups {
+    ready: Mutex<Ready>,
+    parent: AtomicTask,
+}
+
+impl Notify for Wakeups {
+    fn notify(&self, id: usize) {
+        self.ready.lock().unwrap().insert(id);
+        self.parent.notify();
+    }
+}
+
+// The indices of the streams which are ready to be polled.
+enum Ready {
+    // Kept in order for the strategies which pick by position.
+    Ordered(BTreeSet<usize>),
+
+    // Kept in any order for picking at random, along with the position of
+    // each index in `list`, or `NOT_READY` if it isn't in there.
+    Unordered { list: Vec<usize>, pos: Vec<usize> },
+}
+
+const NOT_READY: usize = !0;
+
+impl Ready {
+    // Creates a set in which all `n` streams are ready.
+    fn new(n: usize, strategy: MergeStrategy) -> Ready {
+        match strategy {
+            MergeStrategy::Random => {
+                Ready::Unordered { list: (0..n).collect(), pos: (0..n).collect() }
+            }
+            _ => Ready::Ordered((0..n).collect()),
+        }
+    }
+
+    fn insert(&mut self, idx: usize) {
+        match *self {
+            Ready::Ordered(ref mut set) => {
+                set.insert(idx);
+            }
+            Ready::Unordered { ref mut list, ref mut pos } => {
+                if pos[idx] == NOT_READY {
+                    pos[idx] = list.len();
+                    list.push(idx);
+                }
+            }
+        }
+    }
+
+    // Removes the first index which is at least `start`, wrapping around to
+    // the first index overall if there is none.
+    fn take_from(&mut self, start: usize) -> Option<usize> {
+        let set = match *self {
+            Ready::Ordered(ref mut set) => set,
+            Ready::Unordered { .. } => unreachable!(),
+        };
+        let idx = set.range(start..).next().or_else(|| set.iter().next()).cloned();
+        if let Some(i) = idx {
+            set.remove(&i);
+        }
+        idx
+    }
+
+    // Removes an index picked with the help of `random`.
+    fn take_random(&mut self, random: u64) -> Option<usize> {
+        let (list, pos) = match *self {
+            Ready::Unordered { ref mut list, ref mut pos } => (list, pos),
+            Ready::Ordered(..) => unreachable!(),
+        };
+        if list.is_empty() {
+            return None
+        }
+        let k = (random % list.len() as u64) as usize;
+        let idx = list.swap_remove(k);
+        pos[idx] = NOT_READY;
+        if let Some(&moved) = list.get(k) {
+            pos[moved] = k;
+        }
+        Some(idx)
+    }
+}
+
+/// Merges all of the provided streams into one, polling them round robin.
+///
+/// This is `merge_with` using `MergeStrategy::RoundRobin`.
+///
+/// # Examples
+///
+/// ```
+/// use futures::prelude::*;
+/// use futures::stream;
+///
+/// let streams = vec![
+///     stream::iter_ok::<_, ()>(vec![1, 4]),
+///     stream::iter_ok(vec![2, 5]),
+///     stream::iter_ok(vec![3]),
+/// ];
+/// let merged = stream::merge(streams).collect().wait();
+/// assert_eq!(merged, Ok(vec![1, 2, 3, 4, 5]));
+/// ```
+pub fn merge<I>(streams: I) -> MergeAll<I::Item>
+    where I: IntoIterator,
+          I::Item: Stream,
+{
+    merge_with(streams, MergeStrategy::RoundRobin)
+}
+
+/// Merges all of the provided streams into one, picking among the streams
+/// which are ready according to `strategy`.
+///
+/// The returned stream yields values and errors of all streams as they become
+/// available, and terminates once all of the streams have terminated.
+///
+/// # Examples
+///
+/// ```
+/// use futures::prelude::*;
+/// use futures::stream::{self, MergeStrategy};
+///
+/// let streams = vec![
+///     stream::iter_ok::<_, ()>(vec![1, 2]),
+///     stream::iter_ok(vec![3, 4]),
+/// ];
+/// let merged = stream::merge_with(streams, MergeStrategy::Priority);
+/// assert_eq!(merged.collect().wait(), Ok(vec![1, 2, 3, 4]));
+/// ```
+pub fn merge_with<I>(streams: I, strategy: MergeStrategy) -> MergeAll<I::Item>
+    where I: IntoIterator,
+          I::Item: Stream,
+{
+    let streams = streams.into_iter().map(Some).collect::<Vec<_>>();
+    let n = streams.len();
+    // Only used to seed `MergeStrategy::Random`, there's nothing to gain from
+    // pulling in a proper random number generator here.
+    let rng = RandomState::new().build_hasher().finish() | 1;
+    MergeAll {
+        streams: streams,
+        remaining: n,
+        strategy: strategy,
+        next: 0,
+        rng: rng,
+        wakeups: Arc::new(Wakeups {
+            ready: Mutex::new(Ready::new(n, strategy)),
+            parent: AtomicTask::new(),
+        }),
+    }
+}
+
+impl<S> MergeAll<S> {
+    /// Returns the number of streams which haven't terminated yet.
+    pub fn len(&self) -> usize {
+        self.remaining
+    }
+
+    /// Returns whether all of the streams have terminated.
+    pub fn is_empty(&self) -> bool {
+        self.remaining == 0
+    }
+
+    fn random(&mut self) -> u64 {
+        // xorshift64
+        self.rng ^= self.rng << 13;
+        self.rng ^= self.rng >> 7;
+        self.rng ^= self.rng << 17;
+        self.rng
+    }
+
+    // Picks the next ready stream, removing it from the ready set.
+    fn pick(&mut self) -> Option<usize> {
+        let random = match self.strategy {
+            MergeStrategy::Random => self.random(),
+            _ => 0,
+        };
+        let mut ready = self.wakeups.ready.lock().unwrap();
+        match self.strategy {
+            MergeStrategy::Priority => ready.take_from(0),
+            MergeStrategy::RoundRobin => ready.take_from(self.next),
+            MergeStrategy::Random => ready.take_random(random),
+        }
+    }
+
+    fn set_ready(&self, idx: usize) {
+        self.wakeups.ready.lock().unwrap().insert(idx);
+    }
+}
+
+impl<S: Stream> Stream for MergeAll<S> {
+    type Item = S::Item;
+    type Error = S::Error;
+
+    fn poll(&mut self) -> Poll<Option<S::Item>, S::Error> {
+        self.wakeups.parent.register();
+
+        while let Some(idx) = self.pick() {
+            let res = match self.streams[idx] {
+                Some(ref mut stream) => {
+                    task_impl::with_notify(&self.wakeups, idx, || stream.poll())
+                }
+                // Late notifications of finished streams are ignored.
+                None => continue,
+            };
+            match res {
+                Ok(Async::NotReady) => {}
+                Ok(Async::Ready(None)) => {
+                    self.streams[idx] = None;
+                    self.remaining -= 1;
+                }
+                // The stream may have more values ready without notifying us,
+                // so make sure it's polled again.
+                Ok(Async::Ready(Some(item))) => {
+                    self.set_ready(idx);
+                    self.next = idx + 1;
+                    return Ok(Async::Ready(Some(item)))
+                }
+                Err(e) => {
+                    self.set_ready(idx);
+                    self.next = idx + 1;
+                    return Err(e)
+                }
+            }
+        }
+
+        if self.remaining == 0 {
+            Ok(Async::Ready(None))
+        } else {
+            Ok(Async::NotReady)
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.streams.iter()
+            .filter_map(|s| s.as_ref())
+            .map(|s| s.size_hint())
+            .fold((0, Some(0)), |(lower, upper), (l, u)| {
+                let upper = match (upper, u) {
+                    (Some(a), Some(b)) => a.checked_add(b),
+                    _ => None,
+                };
+                (lower.saturating_add(l), upper)
+            })
+    }
+}
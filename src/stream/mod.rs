@@ -15,7 +15,14 @@
 //!
 //! [online]: https://tokio.rs/docs/getting-started/streams-and-sinks/
 
+use core::ops::Add;
+
 use {IntoFuture, Poll};
+use future::Either;
+#[cfg(feature = "use_std")]
+use std::time::{Duration, Instant};
+#[cfg(feature = "use_std")]
+use timer::{Timer, TimerError};
 
 mod iter;
 #[allow(deprecated)]
@@ -30,13 +37,22 @@ pub use self::iter_result::{iter_result, IterResult};
 
 mod repeat;
 pub use self::repeat::{repeat, Repeat};
+mod repeat_with;
+pub use self::repeat_with::{repeat_with, RepeatWith, try_repeat_with, TryRepeatWith};
 
 mod and_then;
+mod array_chunks;
+mod by_key;
 mod chain;
+mod combine_latest;
 mod concat;
+mod count;
+mod cycle;
 mod empty;
 mod filter;
+mod filter_async;
 mod filter_map;
+mod flat_map;
 mod flatten;
 mod fold;
 mod for_each;
@@ -44,6 +60,7 @@ mod from_err;
 mod fuse;
 mod future;
 mod inspect;
+mod last;
 mod map;
 mod map_err;
 mod merge;
@@ -51,21 +68,35 @@ mod once;
 mod or_else;
 mod peek;
 mod poll_fn;
+mod sample;
+mod scan_async;
 mod select;
 mod skip;
 mod skip_while;
+mod sum;
 mod take;
 mod take_while;
 mod then;
+mod try_scan;
+mod try_unfold;
 mod unfold;
+mod with_latest_from;
 mod zip;
 mod forward;
+mod ticks;
 pub use self::and_then::AndThen;
+pub use self::array_chunks::{ArrayChunks, ChunkArray, Remainder};
+pub use self::by_key::{MinByKey, MaxByKey};
 pub use self::chain::Chain;
+pub use self::combine_latest::{CombineLatest, combine_latest};
 pub use self::concat::{Concat, Concat2};
+pub use self::count::Count;
+pub use self::cycle::{Cycle, CycleFn, cycle_fn};
 pub use self::empty::{Empty, empty};
 pub use self::filter::Filter;
+pub use self::filter_async::FilterAsync;
 pub use self::filter_map::FilterMap;
+pub use self::flat_map::FlatMap;
 pub use self::flatten::Flatten;
 pub use self::fold::Fold;
 pub use self::for_each::ForEach;
@@ -73,6 +104,7 @@ pub use self::from_err::FromErr;
 pub use self::fuse::Fuse;
 pub use self::future::StreamFuture;
 pub use self::inspect::Inspect;
+pub use self::last::Last;
 pub use self::map::Map;
 pub use self::map_err::MapErr;
 #[allow(deprecated)]
@@ -81,59 +113,113 @@ pub use self::once::{Once, once};
 pub use self::or_else::OrElse;
 pub use self::peek::Peekable;
 pub use self::poll_fn::{poll_fn, PollFn};
+pub use self::sample::Sample;
+pub use self::scan_async::ScanAsync;
 pub use self::select::Select;
 pub use self::skip::Skip;
 pub use self::skip_while::SkipWhile;
+pub use self::sum::Sum;
 pub use self::take::Take;
-pub use self::take_while::TakeWhile;
+pub use self::take_while::{TakeWhile, Transition};
 pub use self::then::Then;
+pub use self::try_scan::TryScan;
+pub use self::try_unfold::{TryUnfold, try_unfold};
 pub use self::unfold::{Unfold, unfold};
+pub use self::with_latest_from::WithLatestFrom;
 pub use self::zip::Zip;
-pub use self::forward::Forward;
+pub use self::forward::{Forward, FlushPolicy};
+pub use self::ticks::{Ticks, NoTicks};
 use sink::{Sink};
 
 if_std! {
     use std;
 
+    mod batched;
     mod buffered;
     mod buffer_unordered;
     mod catch_unwind;
-    mod chunks;
-    mod collect;
+    mod chunks_timeout;
+    mod deadline;
+    mod debounce;
+    mod delay;
+    mod flat_map_unordered;
+    mod instrumented;
     mod wait;
+    mod wait_buffered;
     mod channel;
     mod split;
     mod futures_unordered;
     mod futures_ordered;
+    mod futures_keyed;
+    mod bounded_futures_unordered;
+    mod stream_map;
+    mod stream_unordered;
+    mod iter_blocking;
+    mod lossy;
+    mod merge_all;
+    mod share_replay;
+    mod throttle;
+    mod unzip;
+    pub use self::batched::{Batched, BatchConfig, FlushHandle, Weigh, Unweighted};
+    pub use self::batched::IntervalTicks;
     pub use self::buffered::Buffered;
     pub use self::buffer_unordered::BufferUnordered;
     pub use self::catch_unwind::CatchUnwind;
-    pub use self::chunks::Chunks;
-    pub use self::collect::Collect;
+    pub use self::chunks_timeout::ChunksTimeout;
+    pub use self::deadline::{Deadline, DeadlineError};
+    pub use self::debounce::Debounce;
+    pub use self::delay::DelayItems;
+    pub use self::flat_map_unordered::FlatMapUnordered;
+    pub use self::instrumented::{Instrumented, StreamStats};
     pub use self::wait::Wait;
+    pub use self::wait_buffered::{WaitBuffered, WaitBufferedTask};
     pub use self::split::{SplitStream, SplitSink};
     pub use self::futures_unordered::{futures_unordered, FuturesUnordered};
+    pub use self::futures_unordered::{FuturesUnorderedIter, FuturesUnorderedIterMut};
     pub use self::futures_ordered::{futures_ordered, FuturesOrdered};
+    pub use self::futures_keyed::FuturesKeyed;
+    pub use self::bounded_futures_unordered::BoundedFuturesUnordered;
+    pub use self::stream_map::StreamMap;
+    pub use self::stream_unordered::StreamUnordered;
+    pub use self::iter_blocking::{iter_blocking, IterBlocking};
+    pub use self::merge_all::{merge, merge_with, MergeAll, MergeStrategy};
+    pub use self::lossy::{Lossy, OverflowPolicy, DropOldest, DropNewest, Evict, Coalesce};
+    pub use self::share_replay::ShareReplay;
+    pub use self::throttle::Throttle;
+    pub use self::unzip::{UnzipLeft, UnzipRight};
 
     #[doc(hidden)]
     #[cfg(feature = "with-deprecated")]
     #[allow(deprecated)]
     pub use self::channel::{channel, Sender, Receiver, FutureSender, SendError};
+}
+
+if_alloc! {
+    mod chunks;
+    mod collect;
+    mod take_last;
+    pub use self::chunks::Chunks;
+    pub use self::collect::Collect;
+    pub use self::take_last::TakeLast;
 
     /// A type alias for `Box<Stream + Send>`
     #[doc(hidden)]
     #[deprecated(note = "removed without replacement, recommended to use a \
                          local extension trait or function if needed, more \
                          details in #228")]
-    pub type BoxStream<T, E> = ::std::boxed::Box<Stream<Item = T, Error = E> + Send>;
+    pub type BoxStream<T, E> = ::alloc::boxed::Box<Stream<Item = T, Error = E> + Send>;
 
-    impl<S: ?Sized + Stream> Stream for ::std::boxed::Box<S> {
+    impl<S: ?Sized + Stream> Stream for ::alloc::boxed::Box<S> {
         type Item = S::Item;
         type Error = S::Error;
 
         fn poll(&mut self) -> Poll<Option<Self::Item>, Self::Error> {
             (**self).poll()
         }
+
+        fn size_hint(&self) -> (usize, Option<usize>) {
+            (**self).size_hint()
+        }
     }
 }
 
@@ -210,6 +296,36 @@ pub trait Stream {
     // TODO: more here
     fn poll(&mut self) -> Poll<Option<Self::Item>, Self::Error>;
 
+    /// Returns the bounds on the remaining number of items of this stream.
+    ///
+    /// This works like `Iterator::size_hint`: the first element of the
+    /// returned tuple is a lower bound and the second element is an upper
+    /// bound, where `None` means that there's either no known upper bound or
+    /// that it doesn't fit in a `usize`. Errors are not counted as items.
+    ///
+    /// The hint is only meant for optimizations such as reserving space for
+    /// items, a buggy stream may return incorrect bounds and must not be able
+    /// to cause memory unsafety through that.
+    ///
+    /// The default implementation returns `(0, None)`, which is correct for
+    /// any stream.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use futures::prelude::*;
+    /// use futures::stream;
+    ///
+    /// let stream = stream::iter_ok::<_, ()>(vec![1, 2, 3, 4, 5]);
+    /// assert_eq!(stream.size_hint(), (5, Some(5)));
+    ///
+    /// let stream = stream.skip(1).take(2);
+    /// assert_eq!(stream.size_hint(), (2, Some(2)));
+    /// ```
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, None)
+    }
+
     // TODO: should there also be a method like `poll` but doesn't return an
     //       item? basically just says "please make more progress internally"
     //       seems crucial for buffering to actually make any sense.
@@ -236,6 +352,9 @@ pub trait Stream {
     ///
     /// The returned iterator does not attempt to catch panics. If the `poll`
     /// function panics, panics will be propagated to the caller of `next`.
+    ///
+    /// Like `Future::wait`, calling `next` panics if it's done from within a
+    /// task or on a thread which is running an executor.
     #[cfg(feature = "use_std")]
     fn wait(self) -> Wait<Self>
         where Self: Sized
@@ -243,6 +362,64 @@ pub trait Stream {
         wait::new(self)
     }
 
+    /// Creates an iterator which blocks the current thread until each item of
+    /// this stream is resolved, while the stream runs ahead on `executor`.
+    ///
+    /// Unlike with `wait`, the stream doesn't sit idle while the consumer of
+    /// the iterator handles an item. It's spawned as a task of its own onto
+    /// `executor`, which keeps producing items into a buffer of about `n`
+    /// items, and calling `next` only blocks if that buffer is empty. Errors
+    /// are yielded by the iterator like items, without ending it.
+    ///
+    /// Dropping the iterator drops the stream the next time it produces an
+    /// item.
+    ///
+    /// This method is only available when the `use_std` feature of this
+    /// library is activated, and it is activated by default.
+    ///
+    /// # Errors
+    ///
+    /// If `executor` rejects the task then it's handed back through the
+    /// returned `ExecuteError`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is 0. Like `wait`, calling `next` panics if it's done
+    /// from within a task or on a thread which is running an executor.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::thread;
+    ///
+    /// use futures::prelude::*;
+    /// use futures::future::{Executor, ExecuteError};
+    /// use futures::stream;
+    ///
+    /// // An executor running every future on a thread of its own.
+    /// struct Threads;
+    ///
+    /// impl<F> Executor<F> for Threads
+    ///     where F: Future<Item = (), Error = ()> + Send + 'static,
+    /// {
+    ///     fn execute(&self, f: F) -> Result<(), ExecuteError<F>> {
+    ///         thread::spawn(move || f.wait());
+    ///         Ok(())
+    ///     }
+    /// }
+    ///
+    /// let items = stream::iter_ok::<_, ()>(0..5).wait_buffered(2, &Threads).unwrap();
+    /// assert_eq!(items.collect::<Result<Vec<_>, _>>(), Ok(vec![0, 1, 2, 3, 4]));
+    /// ```
+    #[cfg(feature = "use_std")]
+    fn wait_buffered<E>(self, n: usize, executor: &E)
+        -> Result<WaitBuffered<Self>, ::future::ExecuteError<WaitBufferedTask<Self>>>
+        where E: ::future::Executor<WaitBufferedTask<Self>>,
+              Self: Sized,
+    {
+        wait_buffered::new(self, n, executor)
+    }
+
     /// Convenience function for turning this stream into a trait object.
     ///
     /// This simply avoids the need to write `Box::new` and can often help with
@@ -251,8 +428,8 @@ pub trait Stream {
     /// also encodes this. If you'd like to create a `Box<Stream>` without the
     /// `Send` bound, then the `Box::new` function can be used instead.
     ///
-    /// This method is only available when the `use_std` feature of this
-    /// library is activated, and it is activated by default.
+    /// This method is only available when the `alloc` feature of this
+    /// library is activated, which the default `use_std` feature implies.
     ///
     /// # Examples
     ///
@@ -263,7 +440,7 @@ pub trait Stream {
     /// let (_tx, rx) = mpsc::channel(1);
     /// let a: BoxStream<i32, ()> = rx.boxed();
     /// ```
-    #[cfg(feature = "use_std")]
+    #[cfg(feature = "alloc")]
     #[doc(hidden)]
     #[deprecated(note = "removed without replacement, recommended to use a \
                          local extension trait or function if needed, more \
@@ -272,7 +449,7 @@ pub trait Stream {
     fn boxed(self) -> BoxStream<Self::Item, Self::Error>
         where Self: Sized + Send + 'static,
     {
-        ::std::boxed::Box::new(self)
+        ::alloc::boxed::Box::new(self)
     }
 
     /// Converts this stream into a `Future`.
@@ -372,6 +549,37 @@ pub trait Stream {
         filter::new(self, f)
     }
 
+    /// Filters the values produced by this stream according to the provided
+    /// asynchronous predicate.
+    ///
+    /// This is like `filter` except that the predicate returns a future
+    /// resolving to whether the value should be yielded, allowing the check to
+    /// perform asynchronous work such as a lookup. Values are yielded in the
+    /// order of this stream, and the next value is only pulled once the
+    /// predicate of the current one has resolved.
+    ///
+    /// All errors of this stream are passed through without filtering, and
+    /// errors of the predicate's future are yielded in place of the value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use futures::prelude::*;
+    /// use futures::stream;
+    /// use futures::future;
+    ///
+    /// let stream = stream::iter_ok::<_, ()>(1..7);
+    /// let evens = stream.filter_async(|x| future::ok(x % 2 == 0));
+    /// assert_eq!(evens.collect().wait(), Ok(vec![2, 4, 6]));
+    /// ```
+    fn filter_async<P, R>(self, pred: P) -> FilterAsync<Self, P, R>
+        where P: FnMut(&Self::Item) -> R,
+              R: IntoFuture<Item = bool, Error = Self::Error>,
+              Self: Sized
+    {
+        filter_async::new(self, pred)
+    }
+
     /// Filters the values produced by this stream while simultaneously mapping
     /// them to a different type.
     ///
@@ -529,8 +737,8 @@ pub trait Stream {
     /// The returned future will be resolved whenever an error happens or when
     /// the stream returns `Ok(None)`.
     ///
-    /// This method is only available when the `use_std` feature of this
-    /// library is activated, and it is activated by default.
+    /// This method is only available when the `alloc` feature of this
+    /// library is activated, which the default `use_std` feature implies.
     ///
     /// # Examples
     ///
@@ -551,13 +759,130 @@ pub trait Stream {
     /// let mut result = rx.collect();
     /// assert_eq!(result.wait(), Ok(vec![5, 4, 3, 2, 1]));
     /// ```
-    #[cfg(feature = "use_std")]
+    #[cfg(feature = "alloc")]
     fn collect(self) -> Collect<Self>
         where Self: Sized
     {
         collect::new(self)
     }
 
+    /// Drives this stream to completion, returning a future which resolves to
+    /// its last item.
+    ///
+    /// The returned future resolves to `None` if the stream finishes without
+    /// yielding any items. If an error happens then the item seen so far is
+    /// dropped and the error is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use futures::prelude::*;
+    /// use futures::stream;
+    ///
+    /// let last = stream::iter_ok::<_, ()>(vec![1, 2, 3]).last();
+    /// assert_eq!(last.wait(), Ok(Some(3)));
+    ///
+    /// let last = stream::empty::<i32, ()>().last();
+    /// assert_eq!(last.wait(), Ok(None));
+    /// ```
+    fn last(self) -> Last<Self>
+        where Self: Sized
+    {
+        last::new(self)
+    }
+
+    /// Drives this stream to completion, returning a future which resolves to
+    /// the number of items it yielded.
+    ///
+    /// If an error happens then it is returned and the count is discarded.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use futures::prelude::*;
+    /// use futures::stream;
+    ///
+    /// let count = stream::iter_ok::<_, ()>(vec![1, 2, 3]).count();
+    /// assert_eq!(count.wait(), Ok(3));
+    /// ```
+    fn count(self) -> Count<Self>
+        where Self: Sized
+    {
+        count::new(self)
+    }
+
+    /// Drives this stream to completion, returning a future which resolves to
+    /// the item for which `f` returned the smallest key.
+    ///
+    /// If several items share the smallest key then the first of them is
+    /// returned. The future resolves to `None` if the stream finishes without
+    /// yielding any items. If an error happens then it is returned and the
+    /// item seen so far is dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use futures::prelude::*;
+    /// use futures::stream;
+    ///
+    /// let words = stream::iter_ok::<_, ()>(vec!["stream", "of", "words"]);
+    /// assert_eq!(words.min_by_key(|w| w.len()).wait(), Ok(Some("of")));
+    /// ```
+    fn min_by_key<F, K>(self, f: F) -> MinByKey<Self, F, K>
+        where F: FnMut(&Self::Item) -> K,
+              K: Ord,
+              Self: Sized
+    {
+        by_key::min(self, f)
+    }
+
+    /// Drives this stream to completion, returning a future which resolves to
+    /// the item for which `f` returned the largest key.
+    ///
+    /// If several items share the largest key then the last of them is
+    /// returned. The future resolves to `None` if the stream finishes without
+    /// yielding any items. If an error happens then it is returned and the
+    /// item seen so far is dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use futures::prelude::*;
+    /// use futures::stream;
+    ///
+    /// let words = stream::iter_ok::<_, ()>(vec!["stream", "of", "words"]);
+    /// assert_eq!(words.max_by_key(|w| w.len()).wait(), Ok(Some("stream")));
+    /// ```
+    fn max_by_key<F, K>(self, f: F) -> MaxByKey<Self, F, K>
+        where F: FnMut(&Self::Item) -> K,
+              K: Ord,
+              Self: Sized
+    {
+        by_key::max(self, f)
+    }
+
+    /// Drives this stream to completion, returning a future which resolves to
+    /// the sum of its items.
+    ///
+    /// The sum of an empty stream is the default value of the item type. If
+    /// an error happens then it is returned and the sum is discarded.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use futures::prelude::*;
+    /// use futures::stream;
+    ///
+    /// let sum = stream::iter_ok::<_, ()>(vec![1, 2, 3]).sum();
+    /// assert_eq!(sum.wait(), Ok(6));
+    /// ```
+    fn sum(self) -> Sum<Self>
+        where Self::Item: Add<Output = Self::Item> + Default,
+              Self: Sized
+    {
+        sum::new(self)
+    }
+
     /// Concatenate all results of a stream into a single extendable
     /// destination, returning a future representing the end result.
     ///
@@ -666,6 +991,70 @@ pub trait Stream {
         fold::new(self, f, init)
     }
 
+    /// Threads state through an asynchronous computation run on each result
+    /// of this stream, yielding the items it produces.
+    ///
+    /// This is the stream counterpart of `fold`: the closure is called with
+    /// the current state and the next result of this stream, in the style of
+    /// `then`, and returns a future. That future resolves to `Some` of the new
+    /// state and the item to yield, or to `None` to terminate the returned
+    /// stream. The closure isn't called again until the previous future has
+    /// resolved, so the state is updated strictly in order.
+    ///
+    /// If a future returned by the closure fails then its error is yielded
+    /// and, as the state is lost, the returned stream terminates afterwards.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use futures::prelude::*;
+    /// use futures::stream;
+    /// use futures::future;
+    ///
+    /// let stream = stream::iter_ok::<_, ()>(vec![1, 2, 3, 4]);
+    /// let sums = stream.scan_async(0, |acc, x| {
+    ///     let acc = acc + x.unwrap();
+    ///     future::ok::<_, ()>(Some((acc, acc)))
+    /// });
+    /// assert_eq!(sums.collect().wait(), Ok(vec![1, 3, 6, 10]));
+    /// ```
+    fn scan_async<T, F, U, B>(self, init: T, f: F) -> ScanAsync<Self, T, F, U>
+        where F: FnMut(T, Result<Self::Item, Self::Error>) -> U,
+              U: IntoFuture<Item = Option<(T, B)>>,
+              Self: Sized
+    {
+        scan_async::new(self, init, f)
+    }
+
+    /// Threads state through an asynchronous computation run on each
+    /// successful item of this stream, yielding the items it produces.
+    ///
+    /// This is like `scan_async` except that, like `and_then`, the closure is
+    /// only called with successful items. Errors of this stream are passed
+    /// through and leave the state untouched.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use futures::prelude::*;
+    /// use futures::stream;
+    /// use futures::future;
+    ///
+    /// let stream = stream::iter_ok::<_, ()>(vec![1, 2, 3, 4, 5]);
+    /// let sums = stream.try_scan(0, |acc, x| {
+    ///     let acc = acc + x;
+    ///     future::ok(if acc < 10 { Some((acc, acc)) } else { None })
+    /// });
+    /// assert_eq!(sums.collect().wait(), Ok(vec![1, 3, 6]));
+    /// ```
+    fn try_scan<T, F, U, B>(self, init: T, f: F) -> TryScan<Self, T, F, U>
+        where F: FnMut(T, Self::Item) -> U,
+              U: IntoFuture<Item = Option<(T, B)>, Error = Self::Error>,
+              Self: Sized
+    {
+        try_scan::new(self, init, f)
+    }
+
     /// Flattens a stream of streams into just one continuous stream.
     ///
     /// If this stream's elements are themselves streams then this combinator
@@ -707,6 +1096,70 @@ pub trait Stream {
         flatten::new(self)
     }
 
+    /// Maps each item of this stream to a stream with the provided closure
+    /// and flattens the results into one long stream of elements.
+    ///
+    /// This is equivalent to `map(f).flatten()`: each stream produced by `f`
+    /// is exhausted before the next item of this stream is pulled. Errors are
+    /// passed through without looking at them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use futures::prelude::*;
+    /// use futures::stream;
+    ///
+    /// let stream = stream::iter_ok::<_, ()>(vec![1, 2, 3])
+    ///     .flat_map(|i| stream::iter_ok::<_, ()>(vec![i; i]));
+    /// assert_eq!(stream.collect().wait(), Ok(vec![1, 2, 2, 3, 3, 3]));
+    /// ```
+    fn flat_map<F, U>(self, f: F) -> FlatMap<Self, F, U>
+        where F: FnMut(Self::Item) -> U,
+              U: Stream,
+              U::Error: From<Self::Error>,
+              Self: Sized
+    {
+        flat_map::new(self, f)
+    }
+
+    /// Maps each item of this stream to a stream with the provided closure
+    /// and drives up to `limit` of the produced streams concurrently.
+    ///
+    /// Items are yielded as soon as any of the active streams produces one,
+    /// so they may be interleaved in any order. Once a stream finishes the
+    /// next item of this stream is pulled to take its place. Errors are
+    /// passed through whenever they occur, and a stream which returned an
+    /// error keeps being polled.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `limit` is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use futures::prelude::*;
+    /// use futures::stream;
+    ///
+    /// let stream = stream::iter_ok::<_, ()>(vec![1, 2, 3])
+    ///     .flat_map_unordered(2, |i| stream::iter_ok::<_, ()>(vec![i; i]));
+    /// let mut items = stream.collect().wait().unwrap();
+    /// items.sort();
+    /// assert_eq!(items, vec![1, 2, 2, 3, 3, 3]);
+    /// ```
+    ///
+    /// This method is only available when the `use_std` feature of this
+    /// library is activated, and it is activated by default.
+    #[cfg(feature = "use_std")]
+    fn flat_map_unordered<F, U>(self, limit: usize, f: F) -> FlatMapUnordered<Self, F, U>
+        where F: FnMut(Self::Item) -> U,
+              U: Stream,
+              U::Error: From<Self::Error>,
+              Self: Sized
+    {
+        flat_map_unordered::new(self, limit, f)
+    }
+
     /// Skip elements on this stream while the predicate provided resolves to
     /// `true`.
     ///
@@ -719,7 +1172,7 @@ pub trait Stream {
               R: IntoFuture<Item=bool, Error=Self::Error>,
               Self: Sized
     {
-        skip_while::new(self, pred)
+        skip_while::new(self, pred, Transition::Keep)
     }
 
     /// Take elements from this stream while the predicate provided resolves to
@@ -733,7 +1186,65 @@ pub trait Stream {
               R: IntoFuture<Item=bool, Error=Self::Error>,
               Self: Sized
     {
-        take_while::new(self, pred)
+        take_while::new(self, pred, Transition::Drop)
+    }
+
+    /// Skip elements on this stream while the asynchronous predicate provided
+    /// resolves to `true`, choosing what happens to the first element for
+    /// which it resolves to `false`.
+    ///
+    /// This is `skip_while` with an explicit `Transition`: with
+    /// `Transition::Keep` the first element which isn't skipped is yielded,
+    /// exactly like `skip_while`, while with `Transition::Drop` it's skipped
+    /// as well.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use futures::prelude::*;
+    /// use futures::stream::{self, Transition};
+    /// use futures::future;
+    ///
+    /// let lines = stream::iter_ok::<_, ()>(vec!["#", "#", "---", "a", "b"]);
+    /// let body = lines.skip_while_async(|l| future::ok(*l == "#"), Transition::Drop);
+    /// assert_eq!(body.collect().wait(), Ok(vec!["a", "b"]));
+    /// ```
+    fn skip_while_async<P, R>(self, pred: P, transition: Transition) -> SkipWhile<Self, P, R>
+        where P: FnMut(&Self::Item) -> R,
+              R: IntoFuture<Item=bool, Error=Self::Error>,
+              Self: Sized
+    {
+        skip_while::new(self, pred, transition)
+    }
+
+    /// Take elements from this stream while the asynchronous predicate
+    /// provided resolves to `true`, choosing what happens to the first element
+    /// for which it resolves to `false`.
+    ///
+    /// This is `take_while` with an explicit `Transition`: with
+    /// `Transition::Drop` the first element which isn't taken is discarded,
+    /// exactly like `take_while`, while with `Transition::Keep` it's held on
+    /// to so that `TakeWhile::into_remainder` can return it along with the
+    /// rest of this stream.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use futures::prelude::*;
+    /// use futures::stream::{self, Transition};
+    /// use futures::future;
+    ///
+    /// let stream = stream::iter_ok::<_, ()>(vec![1, 2, 10, 3]);
+    /// let mut small = stream.take_while_async(|x| future::ok(*x < 5), Transition::Keep);
+    /// assert_eq!(small.by_ref().collect().wait(), Ok(vec![1, 2]));
+    /// assert_eq!(small.into_remainder().collect().wait(), Ok(vec![10, 3]));
+    /// ```
+    fn take_while_async<P, R>(self, pred: P, transition: Transition) -> TakeWhile<Self, P, R>
+        where P: FnMut(&Self::Item) -> R,
+              R: IntoFuture<Item=bool, Error=Self::Error>,
+              Self: Sized
+    {
+        take_while::new(self, pred, transition)
     }
 
     /// Runs this stream to completion, executing the provided closure for each
@@ -788,22 +1299,53 @@ pub trait Stream {
         take::new(self, amt)
     }
 
-    /// Creates a new stream which skips `amt` items of the underlying stream.
+    /// Creates a new stream which yields only the last `amt` items of the
+    /// underlying stream.
     ///
-    /// Once `amt` items have been skipped from this stream then it will always
-    /// return the remaining items on this stream.
+    /// Nothing is yielded until the underlying stream has finished. While the
+    /// underlying stream is running at most `amt` of its most recent items are
+    /// kept in an internal ring buffer, and older items are discarded.
+    ///
+    /// This method is only available when the `alloc` feature of this
+    /// library is activated, which the default `use_std` feature implies.
     ///
     /// # Errors
     ///
-    /// All errors yielded from underlying stream are passed through and do not
-    /// affect the total number of items skipped.
-    fn skip(self, amt: u64) -> Skip<Self>
-        where Self: Sized
-    {
-        skip::new(self, amt)
-    }
-
-    /// Fuse a stream such that `poll` will never again be called once it has
+    /// All errors yielded from the underlying stream are passed through
+    /// immediately and do not affect which items are kept.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use futures::prelude::*;
+    /// use futures::stream;
+    ///
+    /// let stream = stream::iter_ok::<_, ()>(1..10).take_last(3);
+    /// assert_eq!(stream.collect().wait(), Ok(vec![7, 8, 9]));
+    /// ```
+    #[cfg(feature = "alloc")]
+    fn take_last(self, amt: usize) -> TakeLast<Self>
+        where Self: Sized
+    {
+        take_last::new(self, amt)
+    }
+
+    /// Creates a new stream which skips `amt` items of the underlying stream.
+    ///
+    /// Once `amt` items have been skipped from this stream then it will always
+    /// return the remaining items on this stream.
+    ///
+    /// # Errors
+    ///
+    /// All errors yielded from underlying stream are passed through and do not
+    /// affect the total number of items skipped.
+    fn skip(self, amt: u64) -> Skip<Self>
+        where Self: Sized
+    {
+        skip::new(self, amt)
+    }
+
+    /// Fuse a stream such that `poll` will never again be called once it has
     /// finished.
     ///
     /// Currently once a stream has returned `None` from `poll` any further
@@ -824,6 +1366,120 @@ pub trait Stream {
         fuse::new(self)
     }
 
+    /// Ends this stream once the absolute deadline `at` passes.
+    ///
+    /// However quickly or slowly this stream produces items, once `at` has
+    /// passed the returned stream yields `DeadlineError::Elapsed` as its final
+    /// result and then terminates. Errors of this stream are passed through as
+    /// `DeadlineError::Inner`.
+    ///
+    /// The deadline is tracked with a future obtained from `timer` right away.
+    ///
+    /// This method is only available when the `use_std` feature of this
+    /// library is activated, and it is activated by default.
+    #[cfg(feature = "use_std")]
+    fn deadline<T>(self, at: Instant, timer: &T) -> Deadline<Self, T::Delay>
+        where T: Timer,
+              Self: Sized
+    {
+        deadline::new(self, timer.delay_until(at))
+    }
+
+    /// Shifts every item of this stream later in time by `duration`.
+    ///
+    /// Each item, and each error, is yielded `duration` after it was received
+    /// from this stream, so the spacing between items is preserved. Up to 128
+    /// items are buffered while they wait, see `DelayItems::with_capacity`.
+    /// Once the buffer is full this stream isn't polled until room frees up.
+    ///
+    /// The delays are obtained from a clone of `timer`, and errors of those
+    /// delays are yielded through the `From` conversion of this stream's
+    /// error type.
+    ///
+    /// This method is only available when the `use_std` feature of this
+    /// library is activated, and it is activated by default.
+    #[cfg(feature = "use_std")]
+    fn delay<T>(self, duration: Duration, timer: &T) -> DelayItems<Self, T>
+        where T: Timer + Clone,
+              Self::Error: From<TimerError>,
+              Self: Sized
+    {
+        delay::new(self, duration, timer.clone())
+    }
+
+    /// Only yields an item once `duration` has passed without this stream
+    /// producing another one.
+    ///
+    /// Items which are followed by another one within `duration` are
+    /// dropped, which makes this useful for bursts of updates where only the
+    /// latest one matters. Once this stream ends its last item, if it's still
+    /// waiting, is yielded right away. Errors are passed through as soon as
+    /// they're received.
+    ///
+    /// The quiet period is tracked with a delay from a clone of `timer`, and
+    /// errors of that delay are yielded through the `From` conversion of this
+    /// stream's error type.
+    ///
+    /// This method is only available when the `use_std` feature of this
+    /// library is activated, and it is activated by default.
+    #[cfg(feature = "use_std")]
+    fn debounce<T>(self, duration: Duration, timer: &T) -> Debounce<Self, T>
+        where T: Timer + Clone,
+              Self::Error: From<TimerError>,
+              Self: Sized
+    {
+        debounce::new(self, duration, timer.clone())
+    }
+
+    /// Yields the items of this stream at least `duration` apart.
+    ///
+    /// After an item is yielded this stream isn't polled again until
+    /// `duration` has passed, so no items are dropped but a fast producer is
+    /// slowed down to the given rate. Errors don't count as items and are
+    /// passed through as soon as they're received.
+    ///
+    /// The pauses are tracked with a delay from a clone of `timer`, and errors
+    /// of that delay are yielded through the `From` conversion of this
+    /// stream's error type.
+    ///
+    /// This method is only available when the `use_std` feature of this
+    /// library is activated, and it is activated by default.
+    #[cfg(feature = "use_std")]
+    fn throttle<T>(self, duration: Duration, timer: &T) -> Throttle<Self, T>
+        where T: Timer + Clone,
+              Self::Error: From<TimerError>,
+              Self: Sized
+    {
+        throttle::new(self, duration, timer.clone())
+    }
+
+    /// An adaptor for chunking up items of the stream inside a vector, like
+    /// `chunks`, which also yields a partial chunk once its first item has
+    /// been waiting for `duration`.
+    ///
+    /// This bounds the latency added by chunking when items arrive slowly.
+    /// Errors are handled like in `chunks`.
+    ///
+    /// The age of a chunk is tracked with a delay from a clone of `timer`, and
+    /// errors of that delay are yielded through the `From` conversion of this
+    /// stream's error type.
+    ///
+    /// This method is only available when the `use_std` feature of this
+    /// library is activated, and it is activated by default.
+    ///
+    /// # Panics
+    ///
+    /// This method will panic if `capacity` is zero.
+    #[cfg(feature = "use_std")]
+    fn chunks_timeout<T>(self, capacity: usize, duration: Duration, timer: &T)
+        -> ChunksTimeout<Self, T>
+        where T: Timer + Clone,
+              Self::Error: From<TimerError>,
+              Self: Sized
+    {
+        chunks_timeout::new(self, capacity, duration, timer.clone())
+    }
+
     /// Borrows a stream, rather than consuming it.
     ///
     /// This is useful to allow applying stream adaptors while still retaining
@@ -957,6 +1613,87 @@ pub trait Stream {
         zip::new(self, other)
     }
 
+    /// An adapter for pairing each item of this stream with the most recent
+    /// item of another stream.
+    ///
+    /// Each time this stream yields an item, it is yielded together with a
+    /// clone of the latest item seen on `other`. Items of this stream which
+    /// arrive before `other` has produced anything are discarded. Once `other`
+    /// ends its final item keeps being used, and the returned stream ends when
+    /// this stream ends.
+    ///
+    /// Errors from either stream are passed through.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use futures::prelude::*;
+    /// use futures::unsync::mpsc;
+    ///
+    /// let (events, rx) = mpsc::unbounded::<i32>();
+    /// let (config, config_rx) = mpsc::unbounded::<&str>();
+    /// let mut paired = rx.with_latest_from(config_rx).wait();
+    ///
+    /// config.unbounded_send("a").unwrap();
+    /// events.unbounded_send(1).unwrap();
+    /// events.unbounded_send(2).unwrap();
+    /// assert_eq!(paired.next(), Some(Ok((1, "a"))));
+    /// assert_eq!(paired.next(), Some(Ok((2, "a"))));
+    ///
+    /// config.unbounded_send("b").unwrap();
+    /// events.unbounded_send(3).unwrap();
+    /// drop(events);
+    /// assert_eq!(paired.next(), Some(Ok((3, "b"))));
+    /// assert_eq!(paired.next(), None);
+    /// ```
+    fn with_latest_from<S>(self, other: S) -> WithLatestFrom<Self, S>
+        where S: Stream<Error = Self::Error>,
+              S::Item: Clone,
+              Self: Sized,
+    {
+        with_latest_from::new(self, other)
+    }
+
+    /// Splits a stream of pairs into two streams, one of the left-hand items
+    /// and one of the right-hand items.
+    ///
+    /// This is the inverse of `zip`. Both returned streams pull from this
+    /// stream, whichever is polled first, and the half of each pair destined
+    /// for the other stream is buffered until that stream is polled. At most
+    /// `capacity` items are buffered for either side; once a side's buffer is
+    /// full the opposite side will stop pulling new pairs until it catches
+    /// up. If one of the returned streams is dropped then its items are
+    /// discarded instead.
+    ///
+    /// Errors are delivered to whichever of the two streams was being polled
+    /// when the error happened.
+    ///
+    /// This method is only available when the `use_std` feature of this
+    /// library is activated, and it is activated by default.
+    ///
+    /// # Panics
+    ///
+    /// This method will panic if `capacity` is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use futures::prelude::*;
+    /// use futures::stream;
+    ///
+    /// let pairs = stream::iter_ok::<_, ()>(vec![(1, 'a'), (2, 'b'), (3, 'c')]);
+    /// let (numbers, letters) = pairs.unzip(2);
+    ///
+    /// let both = numbers.collect().join(letters.collect()).wait();
+    /// assert_eq!(both, Ok((vec![1, 2, 3], vec!['a', 'b', 'c'])));
+    /// ```
+    #[cfg(feature = "use_std")]
+    fn unzip<A, B>(self, capacity: usize) -> (UnzipLeft<Self, A, B>, UnzipRight<Self, A, B>)
+        where Self: Stream<Item = (A, B)> + Sized
+    {
+        unzip::new(self, capacity)
+    }
+
     /// Adapter for chaining two stream.
     ///
     /// The resulting stream emits elements from the first stream, and when
@@ -983,6 +1720,33 @@ pub trait Stream {
         chain::new(self, other)
     }
 
+    /// Creates a stream which repeats this stream endlessly.
+    ///
+    /// Once this stream terminates, the returned stream starts over with a
+    /// clone of this stream as it was originally passed to `cycle`. Errors
+    /// are passed through without restarting. If a whole pass of the stream
+    /// yields no items then the returned stream terminates as well, rather
+    /// than looping forever.
+    ///
+    /// Streams which can't be cloned can be repeated with the
+    /// `stream::cycle_fn` function instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use futures::prelude::*;
+    /// use futures::stream;
+    ///
+    /// let stream = stream::iter_ok::<_, ()>(vec![1, 2, 3]).cycle();
+    /// let items = stream.take(7).collect().wait();
+    /// assert_eq!(items, Ok(vec![1, 2, 3, 1, 2, 3, 1]));
+    /// ```
+    fn cycle(self) -> Cycle<Self>
+        where Self: Sized + Clone
+    {
+        cycle::new(self)
+    }
+
     /// Creates a new stream which exposes a `peek` method.
     ///
     /// Calling `peek` returns a reference to the next item in the stream.
@@ -1006,19 +1770,138 @@ pub trait Stream {
     ///
     /// Errors are passed through the stream unbuffered.
     ///
-    /// This method is only available when the `use_std` feature of this
-    /// library is activated, and it is activated by default.
+    /// This method is only available when the `alloc` feature of this
+    /// library is activated, which the default `use_std` feature implies.
     ///
     /// # Panics
     ///
     /// This method will panic of `capacity` is zero.
-    #[cfg(feature = "use_std")]
+    #[cfg(feature = "alloc")]
     fn chunks(self, capacity: usize) -> Chunks<Self>
         where Self: Sized
     {
         chunks::new(self, capacity)
     }
 
+    /// An adaptor for chunking up items of the stream into fixed-size arrays.
+    ///
+    /// The size of the chunks is picked with the type of the array, for
+    /// example `array_chunks::<[u8; 4]>()`. Items are buffered inline, so
+    /// unlike `chunks` this doesn't allocate per chunk.
+    ///
+    /// Only full arrays are yielded. If the underlying stream ends with a
+    /// partially filled array its items can be retrieved with
+    /// `ArrayChunks::into_remainder`. Errors are passed through the stream
+    /// without affecting the buffered items.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use futures::prelude::*;
+    /// use futures::stream;
+    ///
+    /// let mut chunks = stream::iter_ok::<_, ()>(1..6).array_chunks::<[i32; 2]>().wait();
+    /// assert_eq!(chunks.next(), Some(Ok([1, 2])));
+    /// assert_eq!(chunks.next(), Some(Ok([3, 4])));
+    /// assert_eq!(chunks.next(), None);
+    ///
+    /// let rest = chunks.into_inner().into_remainder();
+    /// assert_eq!(rest.collect::<Vec<_>>(), vec![5]);
+    /// ```
+    fn array_chunks<A>(self) -> ArrayChunks<Self, A>
+        where A: ChunkArray<Item = Self::Item>,
+              Self: Sized
+    {
+        array_chunks::new(self)
+    }
+
+    /// An adaptor for grouping items of the stream into batches according to
+    /// the triggers of a `BatchConfig`.
+    ///
+    /// Unlike `chunks`, a batch may be emitted because its items reached a
+    /// total weight, because an interval ticked, or because a
+    /// `FlushHandle` obtained through `Batched::flush_handle` asked for it.
+    /// The final partial batch is emitted once this stream ends. Errors are
+    /// handled like in `chunks`.
+    ///
+    /// This method is only available when the `use_std` feature of this
+    /// library is activated, and it is activated by default.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use futures::prelude::*;
+    /// use futures::stream::{self, BatchConfig};
+    ///
+    /// let words = stream::iter_ok::<_, ()>(vec!["a", "bcd", "ef", "g", "hijk"]);
+    /// let config = BatchConfig::new()
+    ///     .max_items(3)
+    ///     .max_weight(4, |word: &&str| word.len());
+    /// let batches = words.batched(config).collect().wait().unwrap();
+    /// assert_eq!(batches, vec![vec!["a", "bcd"], vec!["ef", "g", "hijk"]]);
+    /// ```
+    #[cfg(feature = "use_std")]
+    fn batched<W, T>(self, config: BatchConfig<W, T>) -> Batched<Self, W, T>
+        where W: Weigh<Self::Item>,
+              T: Ticks<Self::Error>,
+              Self: Sized
+    {
+        batched::new(self, config)
+    }
+
+    /// An adaptor which buffers up to `capacity` items of this stream and
+    /// applies an overflow policy once the buffer is full.
+    ///
+    /// Every time the returned stream is polled it first pulls the items which
+    /// are ready from this stream into its buffer. Whenever an item arrives
+    /// while the buffer is full it's handed to `policy` instead, which for
+    /// example may discard the oldest buffered item (`DropOldest`), discard
+    /// the new item (`DropNewest`) or merge it into the newest buffered item
+    /// (`Coalesce`). This way a slow consumer sees a reduced stream of items
+    /// rather than an ever growing backlog.
+    ///
+    /// At most a fixed number of items is pulled per poll, so that streams
+    /// which are always ready can be used as well. If items are still ready
+    /// after that and none is left to yield, the current task is notified to
+    /// poll again for the rest.
+    ///
+    /// Errors are passed through immediately, leaving buffered items in
+    /// place.
+    ///
+    /// This method is only available when the `use_std` feature of this
+    /// library is activated, and it is activated by default.
+    ///
+    /// # Panics
+    ///
+    /// This method will panic if `capacity` is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use futures::prelude::*;
+    /// use futures::stream::DropOldest;
+    /// use futures::unsync::mpsc;
+    ///
+    /// let (tx, rx) = mpsc::unbounded::<i32>();
+    /// let mut latest = rx.lossy(2, DropOldest).wait();
+    ///
+    /// for i in 0..5 {
+    ///     tx.unbounded_send(i).unwrap();
+    /// }
+    /// drop(tx);
+    ///
+    /// assert_eq!(latest.next(), Some(Ok(3)));
+    /// assert_eq!(latest.next(), Some(Ok(4)));
+    /// assert_eq!(latest.next(), None);
+    /// ```
+    #[cfg(feature = "use_std")]
+    fn lossy<P>(self, capacity: usize, policy: P) -> Lossy<Self, P>
+        where P: OverflowPolicy<Self::Item>,
+              Self: Sized
+    {
+        lossy::new(self, capacity, policy)
+    }
+
     /// Creates a stream that selects the next element from either this stream
     /// or the provided one, whichever is ready first.
     ///
@@ -1037,6 +1920,46 @@ pub trait Stream {
         select::new(self, other)
     }
 
+    /// Creates a stream which yields the most recent item of this stream each
+    /// time the `trigger` stream yields an item.
+    ///
+    /// Items of this stream which are superseded by a newer item before the
+    /// next tick of `trigger` are discarded. If no new item has arrived since
+    /// the previous tick then the tick is ignored, so an item is never yielded
+    /// twice.
+    ///
+    /// The returned stream ends when `trigger` ends, or once this stream has
+    /// ended and its final item (if any) has been sampled. Errors from either
+    /// stream are passed through.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use futures::prelude::*;
+    /// use futures::unsync::mpsc;
+    ///
+    /// let (tx, rx) = mpsc::unbounded::<i32>();
+    /// let (ticks, ticker) = mpsc::unbounded::<()>();
+    /// let mut sampled = rx.sample(ticker).wait();
+    ///
+    /// tx.unbounded_send(1).unwrap();
+    /// tx.unbounded_send(2).unwrap();
+    /// ticks.unbounded_send(()).unwrap();
+    /// assert_eq!(sampled.next(), Some(Ok(2)));
+    ///
+    /// tx.unbounded_send(3).unwrap();
+    /// drop(tx);
+    /// ticks.unbounded_send(()).unwrap();
+    /// assert_eq!(sampled.next(), Some(Ok(3)));
+    /// assert_eq!(sampled.next(), None);
+    /// ```
+    fn sample<T>(self, trigger: T) -> Sample<Self, T>
+        where T: Stream<Error = Self::Error>,
+              Self: Sized,
+    {
+        sample::new(self, trigger)
+    }
+
     /// A future that completes after the given stream has been fully processed
     /// into the sink, including flushing.
     ///
@@ -1056,7 +1979,69 @@ pub trait Stream {
               Self::Error: From<S::SinkError>,
               Self: Sized
     {
-        forward::new(self, sink)
+        forward::new(self, sink, FlushPolicy::Idle, NoTicks)
+    }
+
+    /// A future that completes after the given stream has been fully processed
+    /// into the sink, flushing the sink according to the provided policy.
+    ///
+    /// This is the same as `forward`, except that `policy` may request that
+    /// the sink is also flushed while the stream still has items ready, for
+    /// example after every item or after every `n` items. `forward` itself
+    /// only flushes the sink when the stream isn't ready, which is usually
+    /// what buffered sinks want.
+    ///
+    /// On completion, the pair `(stream, sink)` is returned.
+    ///
+    /// # Panics
+    ///
+    /// This method will panic if `policy` is `FlushPolicy::Every(0)`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use futures::prelude::*;
+    /// use futures::stream::{self, FlushPolicy};
+    ///
+    /// let items = stream::iter_ok::<_, ()>(vec![1, 2, 3]);
+    /// let (_, sink) = items.forward_with(Vec::new(), FlushPolicy::Every(2))
+    ///                      .wait()
+    ///                      .unwrap();
+    /// assert_eq!(sink, vec![1, 2, 3]);
+    /// ```
+    fn forward_with<S>(self, sink: S, policy: FlushPolicy) -> Forward<Self, S>
+        where S: Sink<SinkItem = Self::Item>,
+              Self::Error: From<S::SinkError>,
+              Self: Sized
+    {
+        forward::new(self, sink, policy, NoTicks)
+    }
+
+    /// A future that completes after the given stream has been fully processed
+    /// into the sink, flushing the sink according to the provided policy and
+    /// whenever `ticks` ticks.
+    ///
+    /// This is the same as `forward_with`, except that the sink is also
+    /// flushed each time `ticks` yields a tick while items sent to the sink
+    /// haven't been flushed yet. This bounds how long items may sit in a
+    /// buffered sink while the stream keeps producing them. The ticks are
+    /// usually those of an interval of a `timer::Timer`, wrapped in an
+    /// `IntervalTicks`, but any stream of `()` can be used as well. Errors of
+    /// `ticks` are passed through.
+    ///
+    /// On completion, the pair `(stream, sink)` is returned.
+    ///
+    /// # Panics
+    ///
+    /// This method will panic if `policy` is `FlushPolicy::Every(0)`.
+    fn forward_with_ticks<S, I>(self, sink: S, policy: FlushPolicy, ticks: I)
+        -> Forward<Self, S, I>
+        where S: Sink<SinkItem = Self::Item>,
+              Self::Error: From<S::SinkError>,
+              I: Ticks<Self::Error>,
+              Self: Sized
+    {
+        forward::new(self, sink, policy, ticks)
     }
 
     /// Splits this `Stream + Sink` object into separate `Stream` and `Sink`
@@ -1066,6 +2051,10 @@ pub trait Stream {
     /// allow direct interaction between the two objects (e.g. via
     /// `Sink::send_all`).
     ///
+    /// A half which finds the object in use by the other one returns
+    /// `NotReady`, and is notified once the other half is done with it. The
+    /// halves can be put back together with `SplitSink::reunite`.
+    ///
     /// This method is only available when the `use_std` feature of this
     /// library is activated, and it is activated by default.
     #[cfg(feature = "use_std")]
@@ -1075,6 +2064,105 @@ pub trait Stream {
         split::split(self)
     }
 
+    /// Creates a stream which can be cloned to subscribe multiple consumers to
+    /// this stream, replaying up to `history` recent results to each new
+    /// subscriber.
+    ///
+    /// This stream is only polled once per item no matter how many
+    /// subscribers there are, whichever subscriber needs the next item first
+    /// polls it. Every subscriber sees every result produced after it was
+    /// created, and cloning a subscriber creates a new one which first sees
+    /// the last `history` results produced so far. Results are buffered until
+    /// the slowest subscriber has seen them.
+    ///
+    /// Errors are shared between subscribers and replayed just like items,
+    /// which is why both the item and error type must be `Clone`.
+    ///
+    /// This method is only available when the `use_std` feature of this
+    /// library is activated, and it is activated by default.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use futures::prelude::*;
+    /// use futures::stream;
+    ///
+    /// let mut first = stream::iter_ok::<_, ()>(vec![1, 2, 3, 4]).share_replay(2);
+    /// let mut rest = first.by_ref().take(3).collect().wait();
+    /// assert_eq!(rest, Ok(vec![1, 2, 3]));
+    ///
+    /// let second = first.clone();
+    /// assert_eq!(second.collect().wait(), Ok(vec![2, 3, 4]));
+    /// rest = first.collect().wait();
+    /// assert_eq!(rest, Ok(vec![4]));
+    /// ```
+    #[cfg(feature = "use_std")]
+    fn share_replay(self, history: usize) -> ShareReplay<Self>
+        where Self::Item: Clone,
+              Self::Error: Clone,
+              Self: Sized,
+    {
+        share_replay::new(self, history)
+    }
+
+    /// Wraps this stream in an `Either` stream, making it the left-hand
+    /// variant of that `Either`.
+    ///
+    /// This can be used in combination with the `right_stream` method to
+    /// write `if` statements that evaluate to different streams in different
+    /// branches.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use futures::prelude::*;
+    /// use futures::stream;
+    ///
+    /// let x = 6;
+    /// let stream = if x < 10 {
+    ///     stream::iter_ok::<_, ()>(vec![x]).left_stream()
+    /// } else {
+    ///     stream::empty().right_stream()
+    /// };
+    ///
+    /// assert_eq!(stream.collect().wait(), Ok(vec![6]));
+    /// ```
+    fn left_stream<B>(self) -> Either<Self, B>
+        where B: Stream<Item = Self::Item, Error = Self::Error>,
+              Self: Sized
+    {
+        Either::A(self)
+    }
+
+    /// Wraps this stream in an `Either` stream, making it the right-hand
+    /// variant of that `Either`.
+    ///
+    /// This can be used in combination with the `left_stream` method to write
+    /// `if` statements that evaluate to different streams in different
+    /// branches.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use futures::prelude::*;
+    /// use futures::stream;
+    ///
+    /// let x = 6;
+    /// let stream = if x > 10 {
+    ///     stream::empty().left_stream()
+    /// } else {
+    ///     stream::iter_ok::<_, ()>(vec![x]).right_stream()
+    /// };
+    ///
+    /// assert_eq!(stream.collect().wait(), Ok(vec![6]));
+    /// ```
+    fn right_stream<A>(self) -> Either<A, Self>
+        where A: Stream<Item = Self::Item, Error = Self::Error>,
+              Self: Sized
+    {
+        Either::B(self)
+    }
+
     /// Do something with each item of this stream, afterwards passing it on.
     ///
     /// This is similar to the `Iterator::inspect` method in the standard
@@ -1086,6 +2174,36 @@ pub trait Stream {
     {
         inspect::new(self, f)
     }
+
+    /// Gathers statistics about how this stream is polled and reports them
+    /// under `name` to the observer registered with `observe::set_observer`.
+    ///
+    /// The number of polls, how many of them returned `NotReady`, the number
+    /// of items and errors yielded, and the time from the first poll to the
+    /// first item are recorded. The observer is told about the statistics
+    /// after every poll and once more when the stream terminates or is
+    /// dropped. They can also be inspected with `Instrumented::stats`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use futures::prelude::*;
+    /// use futures::stream;
+    ///
+    /// let mut stream = stream::iter_ok::<_, ()>(vec![1, 2]).instrumented("numbers");
+    /// assert_eq!(stream.by_ref().collect().wait(), Ok(vec![1, 2]));
+    /// assert_eq!(stream.stats().polls(), 3);
+    /// assert_eq!(stream.stats().items(), 2);
+    /// ```
+    ///
+    /// This method is only available when the `use_std` feature of this
+    /// library is activated, and it is activated by default.
+    #[cfg(feature = "use_std")]
+    fn instrumented(self, name: &'static str) -> Instrumented<Self>
+        where Self: Sized
+    {
+        instrumented::new(self, name)
+    }
 }
 
 impl<'a, S: ?Sized + Stream> Stream for &'a mut S {
@@ -1095,4 +2213,8 @@ impl<'a, S: ?Sized + Stream> Stream for &'a mut S {
     fn poll(&mut self) -> Poll<Option<Self::Item>, Self::Error> {
         (**self).poll()
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (**self).size_hint()
+    }
 }
@@ -32,4 +32,11 @@ impl<T, E> Stream for Once<T, E> {
             None => Ok(Async::Ready(None)),
         }
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self.0 {
+            Some(Ok(_)) => (1, Some(1)),
+            Some(Err(_)) | None => (0, Some(0)),
+        }
+    }
 }
@@ -51,6 +51,13 @@ impl<S: Stream> Stream for Peekable<S> {
         }
         self.stream.poll()
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let peeked = if self.peeked.is_some() { 1 } else { 0 };
+        let (lower, upper) = self.stream.size_hint();
+        (lower.saturating_add(peeked),
+         upper.and_then(|n| n.checked_add(peeked)))
+    }
 }
 
 
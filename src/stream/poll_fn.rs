@@ -13,13 +13,15 @@ pub struct PollFn<F> {
 
 /// Creates a new stream wrapping around a function returning `Poll`.
 ///
-/// Polling the returned stream delegates to the wrapped function.
+/// Polling the returned stream delegates to the wrapped function. This is the
+/// stream counterpart of `future::poll_fn`, and allows writing ad-hoc streams
+/// without defining a type and implementing `Stream` for it.
 ///
 /// # Examples
 ///
 /// ```
 /// use futures::stream::poll_fn;
-/// use futures::{Async, Poll};
+/// use futures::{Async, Poll, Stream};
 ///
 /// let mut counter = 1usize;
 ///
@@ -28,6 +30,10 @@ pub struct PollFn<F> {
 ///     counter -= 1;
 ///     Ok(Async::Ready(Some("Hello, World!".to_owned())))
 /// });
+///
+/// let mut items = read_stream.wait();
+/// assert_eq!(items.next().unwrap().unwrap(), "Hello, World!");
+/// assert!(items.next().is_none());
 /// ```
 pub fn poll_fn<T, E, F>(f: F) -> PollFn<F>
 where
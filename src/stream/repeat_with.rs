@@ -0,0 +1,106 @@
+use core::marker;
+use stream::Stream;
+
+use {Async, Poll};
+
+/// Stream that produces a freshly generated element each time it's polled.
+///
+/// This structure is created by the `stream::repeat_with` function.
+#[derive(Debug)]
+#[must_use = "streams do nothing unless polled"]
+pub struct RepeatWith<F, E> {
+    f: F,
+    error: marker::PhantomData<fn() -> E>,
+}
+
+/// Create a stream which produces items by calling the provided closure
+/// repeatedly.
+///
+/// Unlike `repeat` the items don't need to be `Clone`, and each item may
+/// differ from the previous ones. Stream never produces an error or EOF, so
+/// just as with `repeat` you likely want to avoid usage of `collect` or such
+/// on the returned stream.
+///
+/// ```rust
+/// use futures::*;
+///
+/// let mut next = 0;
+/// let mut stream = stream::repeat_with::<_, _, bool>(move || {
+///     next += 1;
+///     next
+/// });
+/// assert_eq!(Ok(Async::Ready(Some(1))), stream.poll());
+/// assert_eq!(Ok(Async::Ready(Some(2))), stream.poll());
+/// assert_eq!(Ok(Async::Ready(Some(3))), stream.poll());
+/// ```
+pub fn repeat_with<F, T, E>(f: F) -> RepeatWith<F, E>
+    where F: FnMut() -> T
+{
+    RepeatWith {
+        f: f,
+        error: marker::PhantomData,
+    }
+}
+
+impl<F, T, E> Stream for RepeatWith<F, E>
+    where F: FnMut() -> T
+{
+    type Item = T;
+    type Error = E;
+
+    fn poll(&mut self) -> Poll<Option<T>, E> {
+        Ok(Async::Ready(Some((self.f)())))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (usize::MAX, None)
+    }
+}
+
+/// Stream that produces the result of calling a fallible closure each time
+/// it's polled.
+///
+/// This structure is created by the `stream::try_repeat_with` function.
+#[derive(Debug)]
+#[must_use = "streams do nothing unless polled"]
+pub struct TryRepeatWith<F> {
+    f: F,
+}
+
+/// Create a stream which produces items or errors by calling the provided
+/// closure repeatedly.
+///
+/// Each `Ok` returned by the closure is yielded as an item and each `Err` as
+/// an error of the stream. Stream never produces EOF, errors don't terminate
+/// it.
+///
+/// ```rust
+/// use futures::*;
+///
+/// let mut next = 0;
+/// let mut stream = stream::try_repeat_with(move || {
+///     next += 1;
+///     if next % 2 == 0 { Err(next) } else { Ok(next) }
+/// });
+/// assert_eq!(Ok(Async::Ready(Some(1))), stream.poll());
+/// assert_eq!(Err(2), stream.poll());
+/// assert_eq!(Ok(Async::Ready(Some(3))), stream.poll());
+/// ```
+pub fn try_repeat_with<F, T, E>(f: F) -> TryRepeatWith<F>
+    where F: FnMut() -> Result<T, E>
+{
+    TryRepeatWith {
+        f: f,
+    }
+}
+
+impl<F, T, E> Stream for TryRepeatWith<F>
+    where F: FnMut() -> Result<T, E>
+{
+    type Item = T;
+    type Error = E;
+
+    fn poll(&mut self) -> Poll<Option<T>, E> {
+        (self.f)().map(|item| Async::Ready(Some(item)))
+    }
+}
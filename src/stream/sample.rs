@@ -0,0 +1,101 @@
+use {Async, Poll};
+use task;
+use stream::{Stream, Fuse};
+
+// The maximum number of items taken from the sampled stream per poll, so that
+// an always-ready stream can't keep `poll` from returning. The task notifies
+// itself to take the rest.
+const MAX_DRAIN: usize = 32;
+
+/// A stream combinator which yields the most recent item of a stream each time
+/// another stream ticks.
+///
+/// This structure is produced by the `Stream::sample` method.
+#[derive(Debug)]
+#[must_use = "streams do nothing unless polled"]
+pub struct Sample<S, T> where S: Stream {
+    stream: Fuse<S>,
+    trigger: T,
+    latest: Option<S::Item>,
+}
+
+pub fn new<S, T>(stream: S, trigger: T) -> Sample<S, T>
+    where S: Stream,
+          T: Stream<Error = S::Error>,
+{
+    Sample {
+        stream: stream.fuse(),
+        trigger: trigger,
+        latest: None,
+    }
+}
+
+impl<S, T> Sample<S, T> where S: Stream {
+    /// Acquires a reference to the underlying stream that this combinator is
+    /// sampling.
+    pub fn get_ref(&self) -> &S {
+        self.stream.get_ref()
+    }
+
+    /// Acquires a mutable reference to the underlying stream that this
+    /// combinator is sampling.
+    ///
+    /// Note that care must be taken to avoid tampering with the state of the
+    /// stream which may otherwise confuse this combinator.
+    pub fn get_mut(&mut self) -> &mut S {
+        self.stream.get_mut()
+    }
+
+    /// Consumes this combinator, returning the underlying stream.
+    ///
+    /// Note that this may discard intermediate state of this combinator, so
+    /// care should be taken to avoid losing resources when this is called.
+    pub fn into_inner(self) -> S {
+        self.stream.into_inner()
+    }
+}
+
+impl<S, T> Stream for Sample<S, T>
+    where S: Stream,
+          T: Stream<Error = S::Error>,
+{
+    type Item = S::Item;
+    type Error = S::Error;
+
+    fn poll(&mut self) -> Poll<Option<S::Item>, S::Error> {
+        // Drain what's ready on the sampled stream, keeping only the most
+        // recent item.
+        let mut drained = false;
+        for _ in 0..MAX_DRAIN {
+            match self.stream.poll()? {
+                Async::Ready(Some(item)) => self.latest = Some(item),
+                _ => {
+                    drained = true;
+                    break
+                }
+            }
+        }
+
+        loop {
+            if self.stream.is_done() && self.latest.is_none() {
+                return Ok(Async::Ready(None))
+            }
+            match self.trigger.poll()? {
+                Async::Ready(Some(_)) => {
+                    if let Some(item) = self.latest.take() {
+                        return Ok(Async::Ready(Some(item)))
+                    }
+                }
+                Async::Ready(None) => return Ok(Async::Ready(None)),
+                Async::NotReady => {
+                    if !drained {
+                        // Come back for the rest of the items which are
+                        // ready, so that the next tick samples the latest.
+                        task::current().notify();
+                    }
+                    return Ok(Async::NotReady)
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,162 @@
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+use {Async, Poll};
+use stream::{Stream, Fuse};
+use task::{self, Task};
+
+/// A stream which can be cloned into multiple subscribers of one underlying
+/// stream, replaying recent history to new subscribers.
+///
+/// This structure is produced by the `Stream::share_replay` method.
+#[must_use = "streams do nothing unless polled"]
+pub struct ShareReplay<S> where S: Stream {
+    inner: Arc<Mutex<Inner<S>>>,
+    id: usize,
+}
+
+impl<S> fmt::Debug for ShareReplay<S>
+    where S: Stream + fmt::Debug,
+          S::Item: fmt::Debug,
+          S::Error: fmt::Debug,
+{
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("ShareReplay")
+            .field("inner", &self.inner)
+            .field("id", &self.id)
+            .finish()
+    }
+}
+
+#[derive(Debug)]
+struct Inner<S> where S: Stream {
+    stream: Fuse<S>,
+    // Results which some subscriber has yet to see, or which are recent
+    // enough to be replayed. `base` is the sequence number of the front.
+    log: VecDeque<Result<S::Item, S::Error>>,
+    base: u64,
+    history: usize,
+    next_id: usize,
+    subscribers: HashMap<usize, Subscriber>,
+}
+
+#[derive(Debug)]
+struct Subscriber {
+    cursor: u64,
+    task: Option<Task>,
+}
+
+pub fn new<S>(s: S, history: usize) -> ShareReplay<S>
+    where S: Stream,
+{
+    let mut subscribers = HashMap::new();
+    subscribers.insert(0, Subscriber { cursor: 0, task: None });
+    ShareReplay {
+        inner: Arc::new(Mutex::new(Inner {
+            stream: s.fuse(),
+            log: VecDeque::new(),
+            base: 0,
+            history: history,
+            next_id: 1,
+            subscribers: subscribers,
+        })),
+        id: 0,
+    }
+}
+
+impl<S> Inner<S> where S: Stream {
+    fn end(&self) -> u64 {
+        self.base + self.log.len() as u64
+    }
+
+    // Drop results which every subscriber has seen and which are no longer
+    // part of the replayed history.
+    fn trim(&mut self) {
+        let end = self.end();
+        let min = self.subscribers.values()
+            .map(|s| s.cursor)
+            .min()
+            .unwrap_or(end);
+        while self.log.len() > self.history && self.base < min {
+            self.log.pop_front();
+            self.base += 1;
+        }
+    }
+
+    fn notify_others(&mut self, id: usize) {
+        for (_, subscriber) in self.subscribers.iter_mut().filter(|&(i, _)| *i != id) {
+            if let Some(task) = subscriber.task.take() {
+                task.notify();
+            }
+        }
+    }
+}
+
+impl<S> Stream for ShareReplay<S>
+    where S: Stream,
+          S::Item: Clone,
+          S::Error: Clone,
+{
+    type Item = S::Item;
+    type Error = S::Error;
+
+    fn poll(&mut self) -> Poll<Option<S::Item>, S::Error> {
+        let mut inner = self.inner.lock().unwrap();
+        let end = inner.end();
+        let cursor = inner.subscribers[&self.id].cursor;
+
+        if cursor < end {
+            let result = inner.log[(cursor - inner.base) as usize].clone();
+            inner.subscribers.get_mut(&self.id).unwrap().cursor += 1;
+            inner.trim();
+            return result.map(|item| Async::Ready(Some(item)))
+        }
+
+        let result = match inner.stream.poll() {
+            Ok(Async::Ready(Some(item))) => Ok(item),
+            Ok(Async::Ready(None)) => {
+                inner.notify_others(self.id);
+                return Ok(Async::Ready(None))
+            }
+            Ok(Async::NotReady) => {
+                inner.subscribers.get_mut(&self.id).unwrap().task = Some(task::current());
+                return Ok(Async::NotReady)
+            }
+            Err(e) => Err(e),
+        };
+
+        inner.log.push_back(result.clone());
+        inner.subscribers.get_mut(&self.id).unwrap().cursor = end + 1;
+        inner.notify_others(self.id);
+        inner.trim();
+        result.map(|item| Async::Ready(Some(item)))
+    }
+}
+
+impl<S> Clone for ShareReplay<S> where S: Stream {
+    fn clone(&self) -> ShareReplay<S> {
+        let mut inner = self.inner.lock().unwrap();
+        let id = inner.next_id;
+        inner.next_id += 1;
+        let end = inner.end();
+        let cursor = end - (inner.history as u64).min(inner.log.len() as u64);
+        inner.subscribers.insert(id, Subscriber { cursor: cursor, task: None });
+        ShareReplay {
+            inner: self.inner.clone(),
+            id: id,
+        }
+    }
+}
+
+impl<S> Drop for ShareReplay<S> where S: Stream {
+    fn drop(&mut self) {
+        if let Ok(mut inner) = self.inner.lock() {
+            inner.subscribers.remove(&self.id);
+            inner.trim();
+            // We may have been the subscriber registered with the underlying
+            // stream, so let someone else pick up polling it.
+            inner.notify_others(self.id);
+        }
+    }
+}
@@ -81,4 +81,15 @@ impl<S> Stream for Skip<S>
 
         self.stream.poll()
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = if self.remaining > usize::MAX as u64 {
+            usize::MAX
+        } else {
+            self.remaining as usize
+        };
+        let (lower, upper) = self.stream.size_hint();
+        (lower.saturating_sub(remaining),
+         upper.map(|n| n.saturating_sub(remaining)))
+    }
 }
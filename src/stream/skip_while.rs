@@ -1,10 +1,12 @@
 use {Async, Poll, IntoFuture, Future};
 use stream::Stream;
+use stream::take_while::Transition;
 
 /// A stream combinator which skips elements of a stream while a predicate
 /// holds.
 ///
-/// This structure is produced by the `Stream::skip_while` method.
+/// This structure is produced by the `Stream::skip_while` and
+/// `Stream::skip_while_async` methods.
 #[derive(Debug)]
 #[must_use = "streams do nothing unless polled"]
 pub struct SkipWhile<S, P, R> where S: Stream, R: IntoFuture {
@@ -12,9 +14,10 @@ pub struct SkipWhile<S, P, R> where S: Stream, R: IntoFuture {
     pred: P,
     pending: Option<(R::Future, S::Item)>,
     done_skipping: bool,
+    transition: Transition,
 }
 
-pub fn new<S, P, R>(s: S, p: P) -> SkipWhile<S, P, R>
+pub fn new<S, P, R>(s: S, p: P, transition: Transition) -> SkipWhile<S, P, R>
     where S: Stream,
           P: FnMut(&S::Item) -> R,
           R: IntoFuture<Item=bool, Error=S::Error>,
@@ -24,6 +27,7 @@ pub fn new<S, P, R>(s: S, p: P) -> SkipWhile<S, P, R>
         pred: p,
         pending: None,
         done_skipping: false,
+        transition: transition,
     }
 }
 
@@ -100,6 +104,9 @@ impl<S, P, R> Stream for SkipWhile<S, P, R>
                 Ok(Async::Ready(false)) => {
                     let (_, item) = self.pending.take().unwrap();
                     self.done_skipping = true;
+                    if self.transition == Transition::Drop {
+                        return self.stream.poll()
+                    }
                     return Ok(Async::Ready(Some(item)))
                 }
                 Ok(Async::NotReady) => return Ok(Async::NotReady),
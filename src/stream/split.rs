@@ -5,6 +5,15 @@ use std::fmt;
 use {StartSend, Sink, Stream, Poll, Async, AsyncSink};
 use sync::BiLock;
 
+// A half finding the object in use by the other one returns `NotReady`, and
+// its task is parked in the `BiLock`. Unlocking notifies that parked task
+// only, once, so contention between the halves costs a single wakeup of the
+// half which lost the race, and none at all otherwise.
+//
+// The lock isn't poisoned by a panic, so that a panic of one half, which is
+// propagated to its caller, doesn't make the other half unusable as well.
+// The object may be left in an inconsistent state by such a panic though.
+
 /// A `Stream` part of the split pair
 #[derive(Debug)]
 pub struct SplitStream<S>(BiLock<S>);
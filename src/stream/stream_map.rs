@@ -0,0 +1,124 @@
+use std::borrow::Borrow;
+use std::fmt::{self, Debug};
+use std::hash::Hash;
+
+use {Async, Poll, Stream};
+use stream::{FuturesKeyed, StreamFuture};
+
+/// A map of streams, stored under keys, merged into a single stream.
+///
+/// Every item produced by one of the streams is yielded along with its key,
+/// as `(key, item)`, and errors as `(key, error)`. Streams can be inserted
+/// and removed at any time, also while the map is being polled, and streams
+/// which finish are removed from the map automatically.
+///
+/// Each stream gets notifications of its own, so that polling the map only
+/// polls the streams which are ready. After producing an item a stream goes
+/// to the back of the line, so a busy stream can't starve the other ones.
+///
+/// Inserting a stream under a key which is already in the map replaces the
+/// previous stream. Replaced and removed streams are dropped the next time
+/// the map is polled, and none of their items are yielded anymore.
+///
+/// Once the map is empty, polling it returns `Ok(Async::Ready(None))`. More
+/// streams can still be inserted after that.
+///
+/// # Examples
+///
+/// ```
+/// use futures::prelude::*;
+/// use futures::stream::{self, StreamMap};
+///
+/// let mut map = StreamMap::new();
+/// map.insert("evens", stream::iter_ok::<_, ()>(vec![0, 2]));
+/// map.insert("odds", stream::iter_ok(vec![1, 3]));
+///
+/// let mut items = map.collect().wait().unwrap();
+/// items.sort();
+/// assert_eq!(items, [("evens", 0), ("evens", 2), ("odds", 1), ("odds", 3)]);
+/// ```
+#[must_use = "streams do nothing unless polled"]
+pub struct StreamMap<K, S> {
+    streams: FuturesKeyed<K, StreamFuture<S>>,
+}
+
+impl<K, S> StreamMap<K, S>
+    where K: Hash + Eq + Clone,
+          S: Stream,
+{
+    /// Constructs a new, empty `StreamMap`.
+    pub fn new() -> StreamMap<K, S> {
+        StreamMap { streams: FuturesKeyed::new() }
+    }
+
+    /// Inserts a stream under `key`, returning whether it replaced a stream
+    /// stored under the same key.
+    ///
+    /// The stream isn't polled until `StreamMap::poll` is called.
+    pub fn insert(&mut self, key: K, stream: S) -> bool {
+        self.streams.insert(key, stream.into_future())
+    }
+
+    /// Removes the stream stored under `key`, returning whether there was
+    /// one.
+    pub fn remove<Q>(&mut self, key: &Q) -> bool
+        where K: Borrow<Q>,
+              Q: ?Sized + Hash + Eq,
+    {
+        self.streams.remove(key)
+    }
+
+    /// Returns whether a stream is stored under `key`.
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+        where K: Borrow<Q>,
+              Q: ?Sized + Hash + Eq,
+    {
+        self.streams.contains_key(key)
+    }
+
+    /// Returns the number of streams in the map.
+    pub fn len(&self) -> usize {
+        self.streams.len()
+    }
+
+    /// Returns `true` if the map contains no streams.
+    pub fn is_empty(&self) -> bool {
+        self.streams.is_empty()
+    }
+}
+
+impl<K, S> Stream for StreamMap<K, S>
+    where K: Hash + Eq + Clone,
+          S: Stream,
+{
+    type Item = (K, S::Item);
+    type Error = (K, S::Error);
+
+    fn poll(&mut self) -> Poll<Option<(K, S::Item)>, (K, S::Error)> {
+        loop {
+            // Streams are taken out of the set while they produce an item,
+            // and put back at the end unless they're done.
+            match self.streams.poll() {
+                Ok(Async::Ready(Some((key, (Some(item), stream))))) => {
+                    self.streams.insert(key.clone(), stream.into_future());
+                    return Ok(Async::Ready(Some((key, item))))
+                }
+                Ok(Async::Ready(Some((_, (None, _))))) => {}
+                Ok(Async::Ready(None)) => return Ok(Async::Ready(None)),
+                Ok(Async::NotReady) => return Ok(Async::NotReady),
+                Err((key, (err, stream))) => {
+                    self.streams.insert(key.clone(), stream.into_future());
+                    return Err((key, err))
+                }
+            }
+        }
+    }
+}
+
+impl<K: Debug, S> Debug for StreamMap<K, S> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("StreamMap")
+            .field("streams", &self.streams)
+            .finish()
+    }
+}
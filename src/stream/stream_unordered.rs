@@ -0,0 +1,123 @@
+use std::fmt::{self, Debug};
+
+use {Async, Poll, Stream};
+use stream::FuturesUnordered;
+use stream::futures_unordered::{self, Polled};
+
+/// A set of streams merged into a single stream, built to scale to a very
+/// large number of streams.
+///
+/// This is the equivalent of `FuturesUnordered` for streams. Each stream
+/// lives in a single node for as long as it's in the set, and has
+/// notifications of its own pushing that node onto an intrusive list of
+/// ready streams. Polling the set thus only polls the streams which are
+/// ready, and a wakeup only touches the stream it's meant for, whatever the
+/// number of streams. Unlike with `StreamMap` or `select_all`, producing an
+/// item doesn't allocate anything either.
+///
+/// Every stream gets an id when it's pushed, which is yielded along with its
+/// items, as `(id, item)`, and errors, as `(id, error)`. An error doesn't end
+/// the stream it came from. A stream producing an item goes to the back of
+/// the line of ready streams, so a busy stream can't starve the other ones.
+/// Streams which finish are dropped from the set.
+///
+/// When the set is empty, polling it returns `Ok(Async::Ready(None))`. More
+/// streams can still be pushed after that.
+///
+/// # Examples
+///
+/// ```
+/// use futures::prelude::*;
+/// use futures::stream::{self, StreamUnordered};
+///
+/// let mut set = StreamUnordered::new();
+/// let a = set.push(stream::iter_ok::<_, ()>(vec![1, 2]));
+/// let b = set.push(stream::iter_ok(vec![3]));
+///
+/// let mut items = set.collect().wait().unwrap();
+/// items.sort();
+/// assert_eq!(items, [(a, 1), (a, 2), (b, 3)]);
+/// ```
+#[must_use = "streams do nothing unless polled"]
+pub struct StreamUnordered<S> {
+    streams: FuturesUnordered<Entry<S>>,
+    next_id: usize,
+}
+
+struct Entry<S> {
+    id: usize,
+    stream: S,
+}
+
+impl<S> StreamUnordered<S>
+    where S: Stream,
+{
+    /// Constructs a new, empty `StreamUnordered`.
+    pub fn new() -> StreamUnordered<S> {
+        StreamUnordered {
+            streams: futures_unordered::new_set(),
+            next_id: 0,
+        }
+    }
+
+    /// Pushes a stream into the set, returning the id its items are yielded
+    /// with.
+    ///
+    /// The stream isn't polled until `StreamUnordered::poll` is called.
+    pub fn push(&mut self, stream: S) -> usize {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.streams.push(Entry {
+            id: id,
+            stream: stream,
+        });
+        id
+    }
+
+    /// Returns the number of streams in the set.
+    pub fn len(&self) -> usize {
+        self.streams.len()
+    }
+
+    /// Returns `true` if the set contains no streams.
+    pub fn is_empty(&self) -> bool {
+        self.streams.is_empty()
+    }
+
+    /// Drops all of the streams in the set, leaving it empty.
+    pub fn clear(&mut self) {
+        self.streams.clear()
+    }
+}
+
+impl<S> Stream for StreamUnordered<S>
+    where S: Stream,
+{
+    type Item = (usize, S::Item);
+    type Error = (usize, S::Error);
+
+    fn poll(&mut self) -> Poll<Option<(usize, S::Item)>, (usize, S::Error)> {
+        let res = futures_unordered::poll_entries(&mut self.streams, |entry| {
+            match entry.stream.poll() {
+                Ok(Async::NotReady) => Polled::NotReady,
+                Ok(Async::Ready(Some(e))) => Polled::Again(Ok((entry.id, e))),
+                Ok(Async::Ready(None)) => Polled::Finished,
+                Err(e) => Polled::Again(Err((entry.id, e))),
+            }
+        });
+        match res {
+            Async::Ready(Some(Ok(e))) => Ok(Async::Ready(Some(e))),
+            Async::Ready(Some(Err(e))) => Err(e),
+            Async::Ready(None) => Ok(Async::Ready(None)),
+            Async::NotReady => Ok(Async::NotReady),
+        }
+    }
+}
+
+impl<S> Debug for StreamUnordered<S> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("StreamUnordered")
+            .field("len", &self.streams.len())
+            .finish()
+    }
+}
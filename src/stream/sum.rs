@@ -0,0 +1,52 @@
+use core::ops::Add;
+
+use {Future, Poll, Async};
+use stream::Stream;
+
+/// A future which adds up the items of a stream.
+///
+/// This future is created by the `Stream::sum` method.
+#[derive(Debug)]
+#[must_use = "futures do nothing unless polled"]
+pub struct Sum<S> where S: Stream {
+    stream: S,
+    sum: Option<S::Item>,
+}
+
+pub fn new<S>(s: S) -> Sum<S>
+    where S: Stream,
+          S::Item: Add<Output = S::Item> + Default,
+{
+    Sum {
+        stream: s,
+        sum: Some(S::Item::default()),
+    }
+}
+
+impl<S> Future for Sum<S>
+    where S: Stream,
+          S::Item: Add<Output = S::Item> + Default,
+{
+    type Item = S::Item;
+    type Error = S::Error;
+
+    fn poll(&mut self) -> Poll<S::Item, S::Error> {
+        loop {
+            match self.stream.poll() {
+                Ok(Async::Ready(Some(e))) => {
+                    let sum = self.sum.take().expect("cannot poll Sum twice");
+                    self.sum = Some(sum + e);
+                }
+                Ok(Async::Ready(None)) => {
+                    let sum = self.sum.take().expect("cannot poll Sum twice");
+                    return Ok(Async::Ready(sum))
+                }
+                Ok(Async::NotReady) => return Ok(Async::NotReady),
+                Err(e) => {
+                    self.sum = None;
+                    return Err(e)
+                }
+            }
+        }
+    }
+}
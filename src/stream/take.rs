@@ -83,4 +83,20 @@ impl<S> Stream for Take<S>
             Ok(Async::Ready(next))
         }
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.remaining == 0 {
+            return (0, Some(0))
+        }
+        let (lower, upper) = self.stream.size_hint();
+        if self.remaining > usize::MAX as u64 {
+            return (lower, upper)
+        }
+        let remaining = self.remaining as usize;
+        let upper = match upper {
+            Some(n) if n < remaining => n,
+            _ => remaining,
+        };
+        (lower.min(remaining), Some(upper))
+    }
 }
@@ -0,0 +1,75 @@
+use alloc::collections::VecDeque;
+
+use {Async, Poll};
+use stream::{Stream, Fuse};
+
+/// A stream combinator which yields only the trailing items of a stream.
+///
+/// This structure is produced by the `Stream::take_last` method.
+#[derive(Debug)]
+#[must_use = "streams do nothing unless polled"]
+pub struct TakeLast<S> where S: Stream {
+    stream: Fuse<S>,
+    items: VecDeque<S::Item>,
+    amt: usize,
+}
+
+pub fn new<S>(s: S, amt: usize) -> TakeLast<S>
+    where S: Stream,
+{
+    TakeLast {
+        stream: s.fuse(),
+        items: VecDeque::with_capacity(amt),
+        amt: amt,
+    }
+}
+
+impl<S> TakeLast<S> where S: Stream {
+    /// Acquires a reference to the underlying stream that this combinator is
+    /// pulling from.
+    pub fn get_ref(&self) -> &S {
+        self.stream.get_ref()
+    }
+
+    /// Acquires a mutable reference to the underlying stream that this
+    /// combinator is pulling from.
+    ///
+    /// Note that care must be taken to avoid tampering with the state of the
+    /// stream which may otherwise confuse this combinator.
+    pub fn get_mut(&mut self) -> &mut S {
+        self.stream.get_mut()
+    }
+
+    /// Consumes this combinator, returning the underlying stream.
+    ///
+    /// Note that this may discard intermediate state of this combinator, so
+    /// care should be taken to avoid losing resources when this is called.
+    pub fn into_inner(self) -> S {
+        self.stream.into_inner()
+    }
+}
+
+impl<S> Stream for TakeLast<S>
+    where S: Stream,
+{
+    type Item = S::Item;
+    type Error = S::Error;
+
+    fn poll(&mut self) -> Poll<Option<S::Item>, S::Error> {
+        while !self.stream.is_done() {
+            match try_ready!(self.stream.poll()) {
+                Some(item) => {
+                    if self.amt == 0 {
+                        continue
+                    }
+                    if self.items.len() == self.amt {
+                        self.items.pop_front();
+                    }
+                    self.items.push_back(item);
+                }
+                None => break,
+            }
+        }
+        Ok(Async::Ready(self.items.pop_front()))
+    }
+}
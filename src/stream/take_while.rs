@@ -1,10 +1,28 @@
+use core::option;
+
 use {Async, Poll, IntoFuture, Future};
-use stream::Stream;
+use stream::{self, Chain, IterOk, Stream};
+
+/// What happens to the item which makes `take_while_async` or
+/// `skip_while_async` switch over.
+///
+/// This is the first item for which the predicate resolves to `false`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Transition {
+    /// Keep the item. `skip_while_async` yields it as its first item, while
+    /// `take_while_async` holds on to it so it can be recovered with
+    /// `TakeWhile::into_remainder`.
+    Keep,
+
+    /// Drop the item.
+    Drop,
+}
 
 /// A stream combinator which takes elements from a stream while a predicate
 /// holds.
 ///
-/// This structure is produced by the `Stream::take_while` method.
+/// This structure is produced by the `Stream::take_while` and
+/// `Stream::take_while_async` methods.
 #[derive(Debug)]
 #[must_use = "streams do nothing unless polled"]
 pub struct TakeWhile<S, P, R> where S: Stream, R: IntoFuture {
@@ -12,9 +30,11 @@ pub struct TakeWhile<S, P, R> where S: Stream, R: IntoFuture {
     pred: P,
     pending: Option<(R::Future, S::Item)>,
     done_taking: bool,
+    transition: Transition,
+    kept: Option<S::Item>,
 }
 
-pub fn new<S, P, R>(s: S, p: P) -> TakeWhile<S, P, R>
+pub fn new<S, P, R>(s: S, p: P, transition: Transition) -> TakeWhile<S, P, R>
     where S: Stream,
           P: FnMut(&S::Item) -> R,
           R: IntoFuture<Item=bool, Error=S::Error>,
@@ -24,6 +44,8 @@ pub fn new<S, P, R>(s: S, p: P) -> TakeWhile<S, P, R>
         pred: p,
         pending: None,
         done_taking: false,
+        transition: transition,
+        kept: None,
     }
 }
 
@@ -50,6 +72,18 @@ impl<S, P, R> TakeWhile<S, P, R> where S: Stream, R: IntoFuture {
     pub fn into_inner(self) -> S {
         self.stream
     }
+
+    /// Consumes this combinator, returning a stream of the items which
+    /// haven't been taken.
+    ///
+    /// The returned stream first yields the item which ended this stream if
+    /// it was kept through `Transition::Keep`, or the item whose predicate
+    /// was still being evaluated, followed by the rest of the underlying
+    /// stream.
+    pub fn into_remainder(self) -> Chain<IterOk<option::IntoIter<S::Item>, S::Error>, S> {
+        let item = self.kept.or(self.pending.map(|(_, item)| item));
+        stream::iter_ok(item).chain(self.stream)
+    }
 }
 
 // Forwarding impl of Sink from the underlying stream
@@ -100,6 +134,10 @@ impl<S, P, R> Stream for TakeWhile<S, P, R>
                 Ok(Async::Ready(Some(item)))
             },
             Ok(Async::Ready(false)) => {
+                let (_, item) = self.pending.take().unwrap();
+                if self.transition == Transition::Keep {
+                    self.kept = Some(item);
+                }
                 self.done_taking = true;
                 Ok(Async::Ready(None))
             }
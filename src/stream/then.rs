@@ -78,4 +78,8 @@ impl<S, F, U> Stream for Then<S, F, U>
             Ok(Async::NotReady) => Ok(Async::NotReady)
         }
     }
+
+    // The closure sees errors as well as items and can turn either into the
+    // other, so nothing is known about the number of items and the default
+    // `(0, None)` is kept.
 }
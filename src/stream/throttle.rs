@@ -0,0 +1,108 @@
+use std::fmt;
+use std::time::Duration;
+
+use {Async, Future, Poll};
+use stream::Stream;
+use timer::{Delay, Timer, TimerError};
+
+/// A stream combinator which spaces out the items of a stream by at least a
+/// fixed duration.
+///
+/// This structure is produced by the `Stream::throttle` method.
+#[must_use = "streams do nothing unless polled"]
+pub struct Throttle<S, T> where T: Timer {
+    stream: S,
+    timer: T,
+    duration: Duration,
+    // Reused for every item, and armed while the next item has to wait.
+    delay: Option<T::Delay>,
+    waiting: bool,
+}
+
+impl<S, T> fmt::Debug for Throttle<S, T>
+    where S: fmt::Debug,
+          T: Timer,
+{
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("Throttle")
+            .field("stream", &self.stream)
+            .field("duration", &self.duration)
+            .field("waiting", &self.waiting)
+            .finish()
+    }
+}
+
+pub fn new<S, T>(s: S, duration: Duration, timer: T) -> Throttle<S, T>
+    where S: Stream,
+          S::Error: From<TimerError>,
+          T: Timer,
+{
+    Throttle {
+        stream: s,
+        timer: timer,
+        duration: duration,
+        delay: None,
+        waiting: false,
+    }
+}
+
+impl<S, T> Throttle<S, T> where T: Timer {
+    /// Acquires a reference to the underlying stream that this combinator is
+    /// pulling from.
+    pub fn get_ref(&self) -> &S {
+        &self.stream
+    }
+
+    /// Acquires a mutable reference to the underlying stream that this
+    /// combinator is pulling from.
+    ///
+    /// Note that care must be taken to avoid tampering with the state of the
+    /// stream which may otherwise confuse this combinator.
+    pub fn get_mut(&mut self) -> &mut S {
+        &mut self.stream
+    }
+
+    /// Consumes this combinator, returning the underlying stream.
+    pub fn into_inner(self) -> S {
+        self.stream
+    }
+}
+
+impl<S, T> Stream for Throttle<S, T>
+    where S: Stream,
+          S::Error: From<TimerError>,
+          T: Timer,
+{
+    type Item = S::Item;
+    type Error = S::Error;
+
+    fn poll(&mut self) -> Poll<Option<S::Item>, S::Error> {
+        // The underlying stream isn't polled while waiting, so that it's
+        // pushed back on rather than buffered.
+        if self.waiting {
+            match self.delay.as_mut().unwrap().poll() {
+                Ok(Async::NotReady) => return Ok(Async::NotReady),
+                Ok(Async::Ready(())) => self.waiting = false,
+                Err(e) => {
+                    self.waiting = false;
+                    return Err(From::from(e))
+                }
+            }
+        }
+
+        let item = try_ready!(self.stream.poll());
+        if item.is_some() {
+            let at = self.timer.now() + self.duration;
+            match self.delay {
+                Some(ref mut delay) => delay.reset(at),
+                None => self.delay = Some(self.timer.delay_until(at)),
+            }
+            self.waiting = true;
+        }
+        Ok(Async::Ready(item))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.stream.size_hint()
+    }
+}
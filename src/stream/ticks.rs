@@ -0,0 +1,32 @@
+use {Async, Poll};
+use stream::Stream;
+
+/// A source of ticks for the timer triggers of `Batched` streams and of
+/// `Stream::forward_with_ticks`.
+///
+/// This is implemented for all streams of `()` and for `NoTicks`, and for the
+/// intervals of a `timer::Timer` through `IntervalTicks`.
+pub trait Ticks<E> {
+    /// Attempts to pull out the next tick, returning `None` if there won't be
+    /// any more ticks.
+    fn poll_tick(&mut self) -> Poll<Option<()>, E>;
+}
+
+impl<S> Ticks<S::Error> for S
+    where S: Stream<Item = ()>,
+{
+    fn poll_tick(&mut self) -> Poll<Option<()>, S::Error> {
+        self.poll()
+    }
+}
+
+/// A `Ticks` implementation which never ticks, used when no timer trigger
+/// is configured.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoTicks;
+
+impl<E> Ticks<E> for NoTicks {
+    fn poll_tick(&mut self) -> Poll<Option<()>, E> {
+        Ok(Async::Ready(None))
+    }
+}
@@ -0,0 +1,100 @@
+use {Async, IntoFuture, Future, Poll};
+use stream::Stream;
+
+/// A stream combinator which threads state through an asynchronous
+/// computation run on each successful item of a stream.
+///
+/// This structure is produced by the `Stream::try_scan` method.
+#[derive(Debug)]
+#[must_use = "streams do nothing unless polled"]
+pub struct TryScan<S, T, F, U> where U: IntoFuture {
+    stream: S,
+    state: Option<T>,
+    f: F,
+    future: Option<U::Future>,
+}
+
+pub fn new<S, T, F, U, B>(s: S, init: T, f: F) -> TryScan<S, T, F, U>
+    where S: Stream,
+          F: FnMut(T, S::Item) -> U,
+          U: IntoFuture<Item = Option<(T, B)>, Error = S::Error>,
+{
+    TryScan {
+        stream: s,
+        state: Some(init),
+        f: f,
+        future: None,
+    }
+}
+
+impl<S, T, F, U> TryScan<S, T, F, U> where U: IntoFuture {
+    /// Acquires a reference to the underlying stream that this combinator is
+    /// pulling from.
+    pub fn get_ref(&self) -> &S {
+        &self.stream
+    }
+
+    /// Acquires a mutable reference to the underlying stream that this
+    /// combinator is pulling from.
+    ///
+    /// Note that care must be taken to avoid tampering with the state of the
+    /// stream which may otherwise confuse this combinator.
+    pub fn get_mut(&mut self) -> &mut S {
+        &mut self.stream
+    }
+
+    /// Consumes this combinator, returning the underlying stream.
+    ///
+    /// Note that this may discard intermediate state of this combinator, so
+    /// care should be taken to avoid losing resources when this is called.
+    pub fn into_inner(self) -> S {
+        self.stream
+    }
+}
+
+impl<S, T, F, U, B> Stream for TryScan<S, T, F, U>
+    where S: Stream,
+          F: FnMut(T, S::Item) -> U,
+          U: IntoFuture<Item = Option<(T, B)>, Error = S::Error>,
+{
+    type Item = B;
+    type Error = S::Error;
+
+    fn poll(&mut self) -> Poll<Option<B>, S::Error> {
+        if self.future.is_none() {
+            // The state is only missing once the stream has terminated.
+            let state = match self.state.take() {
+                Some(state) => state,
+                None => return Ok(Async::Ready(None)),
+            };
+            // Errors of the underlying stream are passed through without
+            // touching the state.
+            let item = match self.stream.poll() {
+                Ok(Async::NotReady) => {
+                    self.state = Some(state);
+                    return Ok(Async::NotReady)
+                }
+                Ok(Async::Ready(None)) => return Ok(Async::Ready(None)),
+                Ok(Async::Ready(Some(e))) => e,
+                Err(e) => {
+                    self.state = Some(state);
+                    return Err(e)
+                }
+            };
+            self.future = Some((self.f)(state, item).into_future());
+        }
+        let next = self.future.as_mut().unwrap().poll();
+        if let Ok(Async::NotReady) = next {
+            return Ok(Async::NotReady)
+        }
+        self.future = None;
+        match next? {
+            Async::Ready(Some((state, item))) => {
+                self.state = Some(state);
+                Ok(Async::Ready(Some(item)))
+            }
+            Async::Ready(None) => Ok(Async::Ready(None)),
+            Async::NotReady => unreachable!(),
+        }
+    }
+}
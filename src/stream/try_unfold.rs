@@ -0,0 +1,108 @@
+use core::mem;
+
+use {Future, IntoFuture, Async, Poll};
+use stream::Stream;
+
+/// Creates a `Stream` from a seed and a closure returning a fallible `Future`
+/// which decides whether the stream continues.
+///
+/// This function is similar to `unfold`, except that the end of the stream is
+/// decided by the future returned from the closure rather than by the closure
+/// itself. This makes it possible to write sources where finding out whether
+/// there's another item requires asynchronous work which may fail, such as
+/// fetching pages of results from a remote service.
+///
+/// `try_unfold()` will call the provided closure with the provided seed, then
+/// wait for the returned `Future` to complete. If it completes with
+/// `Some((a, b))` then the stream yields `a` and `b` will be passed to the
+/// closure next. If it completes with `None` the stream is finished.
+///
+/// In case of error generated by the returned `Future`, the error will be
+/// returned by the `Stream`. The `Stream` will then yield
+/// `Ok(Async::Ready(None))` in future calls to `poll()`.
+///
+/// # Example
+///
+/// ```rust
+/// use futures::stream::{self, Stream};
+/// use futures::future::{self, Future};
+///
+/// let stream = stream::try_unfold(0, |page| {
+///     if page > 5 {
+///         future::err("no such page")
+///     } else if page == 3 {
+///         future::ok(None)
+///     } else {
+///         future::ok(Some((page * 10, page + 1)))
+///     }
+/// });
+///
+/// let result = stream.collect().wait();
+/// assert_eq!(result, Ok(vec![0, 10, 20]));
+/// ```
+pub fn try_unfold<T, F, Fut, It>(init: T, f: F) -> TryUnfold<T, F, Fut>
+    where F: FnMut(T) -> Fut,
+          Fut: IntoFuture<Item = Option<(It, T)>>,
+{
+    TryUnfold {
+        f: f,
+        state: State::Ready(init),
+    }
+}
+
+/// A stream which creates futures deciding on the next item, polls them and
+/// returns their result
+///
+/// This stream is returned by the `futures::stream::try_unfold` method
+#[derive(Debug)]
+#[must_use = "streams do nothing unless polled"]
+pub struct TryUnfold<T, F, Fut> where Fut: IntoFuture {
+    f: F,
+    state: State<T, Fut::Future>,
+}
+
+impl <T, F, Fut, It> Stream for TryUnfold<T, F, Fut>
+    where F: FnMut(T) -> Fut,
+          Fut: IntoFuture<Item = Option<(It, T)>>,
+{
+    type Item = It;
+    type Error = Fut::Error;
+
+    fn poll(&mut self) -> Poll<Option<It>, Fut::Error> {
+        loop {
+            match mem::replace(&mut self.state, State::Empty) {
+                // State::Empty may happen if the future returned an error or
+                // decided to end the stream
+                State::Empty => return Ok(Async::Ready(None)),
+                State::Ready(state) => {
+                    self.state = State::Processing((self.f)(state).into_future());
+                }
+                State::Processing(mut fut) => {
+                    match fut.poll()? {
+                        Async::Ready(Some((item, next_state))) => {
+                            self.state = State::Ready(next_state);
+                            return Ok(Async::Ready(Some(item)));
+                        }
+                        Async::Ready(None) => return Ok(Async::Ready(None)),
+                        Async::NotReady => {
+                            self.state = State::Processing(fut);
+                            return Ok(Async::NotReady);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+enum State<T, F> where F: Future {
+    /// Placeholder state when doing work, or once the stream has finished
+    Empty,
+
+    /// Ready to generate new future; current internal state is the `T`
+    Ready(T),
+
+    /// Working on a future generated previously
+    Processing(F),
+}
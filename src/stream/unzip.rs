@@ -0,0 +1,179 @@
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+use {Async, Poll};
+use stream::Stream;
+use task::{self, Task};
+
+/// The stream of left-hand items of an unzipped stream of pairs.
+///
+/// This structure is produced by the `Stream::unzip` method.
+#[derive(Debug)]
+#[must_use = "streams do nothing unless polled"]
+pub struct UnzipLeft<S, A, B> {
+    inner: Arc<Mutex<Inner<S, A, B>>>,
+}
+
+/// The stream of right-hand items of an unzipped stream of pairs.
+///
+/// This structure is produced by the `Stream::unzip` method.
+#[derive(Debug)]
+#[must_use = "streams do nothing unless polled"]
+pub struct UnzipRight<S, A, B> {
+    inner: Arc<Mutex<Inner<S, A, B>>>,
+}
+
+#[derive(Debug)]
+struct Inner<S, A, B> {
+    stream: S,
+    capacity: usize,
+    done: bool,
+    left: Half<A>,
+    right: Half<B>,
+}
+
+#[derive(Debug)]
+struct Half<T> {
+    buffer: VecDeque<T>,
+    task: Option<Task>,
+    dropped: bool,
+}
+
+impl<T> Half<T> {
+    fn new() -> Half<T> {
+        Half {
+            buffer: VecDeque::new(),
+            task: None,
+            dropped: false,
+        }
+    }
+
+    fn notify(&mut self) {
+        if let Some(task) = self.task.take() {
+            task.notify();
+        }
+    }
+}
+
+pub fn new<S, A, B>(s: S, capacity: usize) -> (UnzipLeft<S, A, B>, UnzipRight<S, A, B>)
+    where S: Stream<Item = (A, B)>,
+{
+    assert!(capacity > 0);
+
+    let inner = Arc::new(Mutex::new(Inner {
+        stream: s,
+        capacity: capacity,
+        done: false,
+        left: Half::new(),
+        right: Half::new(),
+    }));
+    (UnzipLeft { inner: inner.clone() }, UnzipRight { inner: inner })
+}
+
+// Shared polling logic of the two halves. `mine` is the half being polled and
+// `theirs` is the opposite half, `split` takes apart a pair into the item for
+// `mine` and the item for `theirs`.
+fn poll_half<S, T, U, F>(stream: &mut S,
+                         done: &mut bool,
+                         capacity: usize,
+                         mine: &mut Half<T>,
+                         theirs: &mut Half<U>,
+                         split: F)
+                         -> Poll<Option<T>, S::Error>
+    where S: Stream,
+          F: Fn(S::Item) -> (T, U),
+{
+    if let Some(item) = mine.buffer.pop_front() {
+        // The other half may be blocked on our buffer being full, so let it
+        // know that there's room again.
+        theirs.notify();
+        return Ok(Async::Ready(Some(item)))
+    }
+    if *done {
+        return Ok(Async::Ready(None))
+    }
+
+    // Pulling another pair would overflow the other half's buffer, so wait
+    // for it to catch up.
+    if !theirs.dropped && theirs.buffer.len() >= capacity {
+        mine.task = Some(task::current());
+        return Ok(Async::NotReady)
+    }
+
+    match stream.poll() {
+        Ok(Async::Ready(Some(pair))) => {
+            let (item, other) = split(pair);
+            if !theirs.dropped {
+                theirs.buffer.push_back(other);
+                theirs.notify();
+            }
+            Ok(Async::Ready(Some(item)))
+        }
+        Ok(Async::Ready(None)) => {
+            *done = true;
+            theirs.notify();
+            Ok(Async::Ready(None))
+        }
+        Ok(Async::NotReady) => {
+            mine.task = Some(task::current());
+            Ok(Async::NotReady)
+        }
+        Err(e) => Err(e),
+    }
+}
+
+impl<S, A, B> Stream for UnzipLeft<S, A, B>
+    where S: Stream<Item = (A, B)>,
+{
+    type Item = A;
+    type Error = S::Error;
+
+    fn poll(&mut self) -> Poll<Option<A>, S::Error> {
+        let mut inner = self.inner.lock().unwrap();
+        let inner = &mut *inner;
+        poll_half(&mut inner.stream,
+                  &mut inner.done,
+                  inner.capacity,
+                  &mut inner.left,
+                  &mut inner.right,
+                  |(a, b)| (a, b))
+    }
+}
+
+impl<S, A, B> Stream for UnzipRight<S, A, B>
+    where S: Stream<Item = (A, B)>,
+{
+    type Item = B;
+    type Error = S::Error;
+
+    fn poll(&mut self) -> Poll<Option<B>, S::Error> {
+        let mut inner = self.inner.lock().unwrap();
+        let inner = &mut *inner;
+        poll_half(&mut inner.stream,
+                  &mut inner.done,
+                  inner.capacity,
+                  &mut inner.right,
+                  &mut inner.left,
+                  |(a, b)| (b, a))
+    }
+}
+
+impl<S, A, B> Drop for UnzipLeft<S, A, B> {
+    fn drop(&mut self) {
+        if let Ok(mut inner) = self.inner.lock() {
+            inner.left.dropped = true;
+            inner.left.buffer.clear();
+            inner.right.notify();
+        }
+    }
+}
+
+impl<S, A, B> Drop for UnzipRight<S, A, B> {
+    fn drop(&mut self) {
+        if let Ok(mut inner) = self.inner.lock() {
+            inner.right.dropped = true;
+            inner.right.buffer.clear();
+            inner.left.notify();
+        }
+    }
+}
@@ -0,0 +1,99 @@
+use std::fmt;
+
+use {Async, AsyncSink, Future, Poll, Sink};
+use executor::{self, Spawn};
+use future::{Executor, ExecuteError};
+use stream::{Fuse, Stream};
+use sync::mpsc;
+
+/// A blocking iterator over the items of a stream running on an executor.
+///
+/// Created by the `Stream::wait_buffered` method. The stream runs on its own
+/// task, which keeps producing items into a bounded buffer while the
+/// iterator's consumer is busy, and `next` only blocks the current thread
+/// when that buffer is empty.
+#[must_use = "iterators do nothing unless advanced"]
+pub struct WaitBuffered<S: Stream> {
+    rx: Spawn<mpsc::Receiver<Result<S::Item, S::Error>>>,
+}
+
+/// The task running the stream of a `WaitBuffered`.
+///
+/// This is spawned onto the executor given to `Stream::wait_buffered`.
+#[must_use = "futures do nothing unless polled"]
+pub struct WaitBufferedTask<S: Stream> {
+    stream: Fuse<S>,
+    tx: mpsc::Sender<Result<S::Item, S::Error>>,
+    pending: Option<Result<S::Item, S::Error>>,
+}
+
+pub fn new<S, E>(s: S, n: usize, executor: &E)
+    -> Result<WaitBuffered<S>, ExecuteError<WaitBufferedTask<S>>>
+    where S: Stream,
+          E: Executor<WaitBufferedTask<S>>,
+{
+    assert!(n > 0, "cannot prefetch zero items");
+    // The sender has a slot of its own in the channel.
+    let (tx, rx) = mpsc::channel(n - 1);
+    executor.execute(WaitBufferedTask {
+        stream: s.fuse(),
+        tx: tx,
+        pending: None,
+    })?;
+    Ok(WaitBuffered { rx: executor::spawn(rx) })
+}
+
+impl<S: Stream> Iterator for WaitBuffered<S> {
+    type Item = Result<S::Item, S::Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.rx.wait_stream() {
+            Some(Ok(item)) => Some(item),
+            Some(Err(())) | None => None,
+        }
+    }
+}
+
+impl<S: Stream> fmt::Debug for WaitBuffered<S> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("WaitBuffered").finish()
+    }
+}
+
+impl<S: Stream> Future for WaitBufferedTask<S> {
+    type Item = ();
+    type Error = ();
+
+    fn poll(&mut self) -> Poll<(), ()> {
+        loop {
+            if let Some(item) = self.pending.take() {
+                match self.tx.start_send(item) {
+                    Ok(AsyncSink::Ready) => {}
+                    Ok(AsyncSink::NotReady(item)) => {
+                        self.pending = Some(item);
+                        return Ok(Async::NotReady)
+                    }
+                    // The iterator is gone, so is the need for more items.
+                    Err(_) => return Ok(Async::Ready(())),
+                }
+            }
+
+            let item = match self.stream.poll() {
+                Ok(Async::Ready(Some(item))) => Ok(item),
+                Ok(Async::Ready(None)) => return Ok(Async::Ready(())),
+                Ok(Async::NotReady) => return Ok(Async::NotReady),
+                Err(e) => Err(e),
+            };
+            self.pending = Some(item);
+        }
+    }
+}
+
+impl<S: Stream + fmt::Debug> fmt::Debug for WaitBufferedTask<S> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("WaitBufferedTask")
+            .field("stream", &self.stream)
+            .field("pending", &self.pending.is_some())
+            .finish()
+    }
+}
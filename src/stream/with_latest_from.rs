@@ -0,0 +1,86 @@
+use {Async, Poll};
+use stream::{Stream, Fuse};
+
+// The maximum number of items taken from the other stream per poll, so that
+// an always-ready other stream can't keep `poll` from returning.
+const MAX_DRAIN: usize = 32;
+
+/// A stream combinator which pairs each item of a stream with the latest item
+/// of another stream.
+///
+/// This structure is produced by the `Stream::with_latest_from` method.
+#[derive(Debug)]
+#[must_use = "streams do nothing unless polled"]
+pub struct WithLatestFrom<S1, S2> where S2: Stream {
+    stream: S1,
+    other: Fuse<S2>,
+    latest: Option<S2::Item>,
+}
+
+pub fn new<S1, S2>(stream: S1, other: S2) -> WithLatestFrom<S1, S2>
+    where S1: Stream,
+          S2: Stream<Error = S1::Error>,
+          S2::Item: Clone,
+{
+    WithLatestFrom {
+        stream: stream,
+        other: other.fuse(),
+        latest: None,
+    }
+}
+
+impl<S1, S2> WithLatestFrom<S1, S2> where S2: Stream {
+    /// Acquires a reference to the underlying stream that this combinator is
+    /// pulling from.
+    pub fn get_ref(&self) -> &S1 {
+        &self.stream
+    }
+
+    /// Acquires a mutable reference to the underlying stream that this
+    /// combinator is pulling from.
+    ///
+    /// Note that care must be taken to avoid tampering with the state of the
+    /// stream which may otherwise confuse this combinator.
+    pub fn get_mut(&mut self) -> &mut S1 {
+        &mut self.stream
+    }
+
+    /// Consumes this combinator, returning the underlying stream.
+    ///
+    /// Note that this may discard intermediate state of this combinator, so
+    /// care should be taken to avoid losing resources when this is called.
+    pub fn into_inner(self) -> S1 {
+        self.stream
+    }
+}
+
+impl<S1, S2> Stream for WithLatestFrom<S1, S2>
+    where S1: Stream,
+          S2: Stream<Error = S1::Error>,
+          S2::Item: Clone,
+{
+    type Item = (S1::Item, S2::Item);
+    type Error = S1::Error;
+
+    fn poll(&mut self) -> Poll<Option<Self::Item>, S1::Error> {
+        loop {
+            // Catch up with the other stream first so items of the primary
+            // stream are paired with the freshest value available.
+            for _ in 0..MAX_DRAIN {
+                match self.other.poll()? {
+                    Async::Ready(Some(item)) => self.latest = Some(item),
+                    _ => break,
+                }
+            }
+
+            match try_ready!(self.stream.poll()) {
+                Some(item) => {
+                    if let Some(ref latest) = self.latest {
+                        return Ok(Async::Ready(Some((item, latest.clone()))))
+                    }
+                }
+                None => return Ok(Async::Ready(None)),
+            }
+        }
+    }
+}
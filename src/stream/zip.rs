@@ -56,4 +56,22 @@ impl<S1, S2> Stream for Zip<S1, S2>
             Ok(Async::NotReady)
         }
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        fn queued<T>(queued: &Option<T>, (lower, upper): (usize, Option<usize>))
+                     -> (usize, Option<usize>)
+        {
+            let queued = if queued.is_some() { 1 } else { 0 };
+            (lower.saturating_add(queued), upper.and_then(|n| n.checked_add(queued)))
+        }
+
+        let (lower1, upper1) = queued(&self.queued1, self.stream1.size_hint());
+        let (lower2, upper2) = queued(&self.queued2, self.stream2.size_hint());
+        let upper = match (upper1, upper2) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (Some(a), None) => Some(a),
+            (None, b) => b,
+        };
+        (lower1.min(lower2), upper)
+    }
 }
@@ -77,7 +77,7 @@ impl<T> BiLock<T> {
     ///
     /// This function will panic if called outside the context of a future's
     /// task.
-    pub fn poll_lock(&self) -> Async<BiLockGuard<T>> {
+    pub fn poll_lock<'a>(&'a self) -> Async<BiLockGuard<'a, T>> {
         loop {
             match self.inner.state.swap(1, SeqCst) {
                 // Woohoo, we grabbed the lock!
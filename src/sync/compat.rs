@@ -0,0 +1,164 @@
+//! Bridges from the synchronization primitives of the standard library.
+
+use std::fmt;
+use std::sync::mpsc as std_mpsc;
+
+use {Async, AsyncSink, Future, Poll, Sink, Stream};
+use future::{Executor, ExecuteError};
+use sync::mpsc;
+
+// The number of messages `receiver_stream` prefetches.
+const DEFAULT_BUFFER: usize = 32;
+
+/// A stream of the messages of a `std::sync::mpsc::Receiver`.
+///
+/// This is created by the `receiver_stream` function. The stream ends once
+/// all of the senders of the standard channel are gone and every message
+/// has been yielded.
+#[must_use = "streams do nothing unless polled"]
+pub struct ReceiverStream<T> {
+    rx: mpsc::Receiver<T>,
+}
+
+/// The task draining a `std::sync::mpsc::Receiver` into a `ReceiverStream`.
+///
+/// This blocks the thread it runs on while it waits for messages.
+#[must_use = "futures do nothing unless polled"]
+pub struct ReceiverDrain<T> {
+    rx: std_mpsc::Receiver<T>,
+    tx: mpsc::Sender<T>,
+    pending: Option<T>,
+}
+
+/// Turns a receiver of the standard library into a `Stream`.
+///
+/// A task is spawned onto `executor` receiving messages out of `rx` and
+/// passing them on to the returned stream, with a buffer of a few dozen
+/// messages. While the buffer is full nothing more is received, so slow
+/// consumers of the stream push back on the senders of a
+/// `std::sync::mpsc::sync_channel`.
+///
+/// Waiting for a message blocks the thread the task runs on, so `executor`
+/// must be able to afford that, for example by being a thread pool or
+/// running each task on a thread of its own. Using an executor running many
+/// tasks on a single thread, such as `LocalPool`, will likely deadlock.
+///
+/// The task ends once all of the senders of `rx` are gone, or once it
+/// receives a message after the stream was dropped.
+///
+/// # Errors
+///
+/// If `executor` rejects the task then it's handed back through the
+/// returned `ExecuteError`.
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::mpsc;
+/// use std::thread;
+///
+/// use futures::prelude::*;
+/// use futures::future::{Executor, ExecuteError};
+/// use futures::sync::compat;
+///
+/// // An executor running every future on a thread of its own.
+/// struct Threads;
+///
+/// impl<F> Executor<F> for Threads
+///     where F: Future<Item = (), Error = ()> + Send + 'static,
+/// {
+///     fn execute(&self, f: F) -> Result<(), ExecuteError<F>> {
+///         thread::spawn(move || f.wait());
+///         Ok(())
+///     }
+/// }
+///
+/// let (tx, rx) = mpsc::channel();
+/// thread::spawn(move || {
+///     for i in 0..3 {
+///         tx.send(i).unwrap();
+///     }
+/// });
+///
+/// let stream = compat::receiver_stream(rx, &Threads).unwrap();
+/// assert_eq!(stream.collect().wait(), Ok(vec![0, 1, 2]));
+/// ```
+pub fn receiver_stream<T, E>(rx: std_mpsc::Receiver<T>, executor: &E)
+    -> Result<ReceiverStream<T>, ExecuteError<ReceiverDrain<T>>>
+    where E: Executor<ReceiverDrain<T>>,
+{
+    receiver_stream_buffered(rx, executor, DEFAULT_BUFFER)
+}
+
+/// Turns a receiver of the standard library into a `Stream`, prefetching
+/// about `buffer` messages.
+///
+/// This is the same as `receiver_stream`, except for the size of the buffer
+/// between the receiver and the stream.
+pub fn receiver_stream_buffered<T, E>(rx: std_mpsc::Receiver<T>, executor: &E, buffer: usize)
+    -> Result<ReceiverStream<T>, ExecuteError<ReceiverDrain<T>>>
+    where E: Executor<ReceiverDrain<T>>,
+{
+    let (tx, stream_rx) = mpsc::channel(buffer);
+    executor.execute(ReceiverDrain {
+        rx: rx,
+        tx: tx,
+        pending: None,
+    })?;
+    Ok(ReceiverStream { rx: stream_rx })
+}
+
+impl<T> Stream for ReceiverStream<T> {
+    type Item = T;
+    type Error = ();
+
+    fn poll(&mut self) -> Poll<Option<T>, ()> {
+        self.rx.poll()
+    }
+}
+
+impl<T> fmt::Debug for ReceiverStream<T> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("ReceiverStream").finish()
+    }
+}
+
+impl<T> Future for ReceiverDrain<T> {
+    type Item = ();
+    type Error = ();
+
+    fn poll(&mut self) -> Poll<(), ()> {
+        loop {
+            if let Some(msg) = self.pending.take() {
+                match self.tx.start_send(msg) {
+                    Ok(AsyncSink::Ready) => {}
+                    Ok(AsyncSink::NotReady(msg)) => {
+                        self.pending = Some(msg);
+                        return Ok(Async::NotReady)
+                    }
+                    Err(_) => return Ok(Async::Ready(())),
+                }
+            }
+
+            // Only block on the next message once there's room for it, so
+            // that a full buffer stops receiving.
+            match self.tx.poll_ready() {
+                Ok(Async::Ready(())) => {}
+                Ok(Async::NotReady) => return Ok(Async::NotReady),
+                Err(_) => return Ok(Async::Ready(())),
+            }
+            match self.rx.recv() {
+                Ok(msg) => self.pending = Some(msg),
+                Err(std_mpsc::RecvError) => return Ok(Async::Ready(())),
+            }
+        }
+    }
+}
+
+impl<T> fmt::Debug for ReceiverDrain<T> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("ReceiverDrain")
+            .field("pending", &self.pending.is_some())
+            .finish()
+    }
+}
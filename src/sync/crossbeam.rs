@@ -0,0 +1,310 @@
+//! Futures-aware wrappers around crossbeam channels.
+//!
+//! The channels of the `crossbeam-channel` crate are fast and well suited to
+//! plain threads, but know nothing about tasks: a task waiting for a message
+//! wouldn't be notified when a thread sends one. The channels created by the
+//! `bounded` and `unbounded` functions of this module wrap a crossbeam
+//! channel along with a side table of the tasks waiting on either end. Every
+//! operation made through the wrappers, whether it's a blocking call from a
+//! thread or a poll from a task, notifies the tasks waiting on the other end.
+//!
+//! The `Receiver` is a `Stream` and the `Sender` is a `Sink`, while both
+//! also have blocking and non-blocking methods like their crossbeam
+//! counterparts for use on threads. The blocking ones are named
+//! `blocking_send` and `blocking_recv`, so as not to clash with
+//! `Sink::send`. As with crossbeam, both ends can be cloned.
+//!
+//! This module is only available when the `crossbeam` feature of this
+//! library is activated.
+
+use std::fmt;
+use std::prelude::v1::*;
+use std::sync::{Arc, Mutex};
+
+use crossbeam_channel as channel;
+pub use crossbeam_channel::{RecvError, SendError, TryRecvError, TrySendError};
+
+use {Async, AsyncSink, Poll, Sink, StartSend, Stream};
+use task::{self, Task};
+
+/// The sending end of a channel created by `bounded` or `unbounded`.
+///
+/// Messages can be sent from threads with `blocking_send` and `try_send`, or
+/// from tasks through the `Sink` implementation.
+pub struct Sender<T> {
+    // Fields are dropped in order, so the channel knows it's disconnected
+    // by the time `shared` notifies the other end.
+    inner: channel::Sender<T>,
+    shared: SenderShared,
+}
+
+/// The receiving end of a channel created by `bounded` or `unbounded`.
+///
+/// Messages can be received on threads with `blocking_recv` and `try_recv`,
+/// or from tasks through the `Stream` implementation. The stream ends once
+/// all of the senders are gone and the channel is empty.
+#[must_use = "streams do nothing unless polled"]
+pub struct Receiver<T> {
+    inner: channel::Receiver<T>,
+    shared: ReceiverShared,
+}
+
+// The tasks waiting on either end of a channel.
+struct Shared {
+    receivers: Waiters,
+    senders: Waiters,
+}
+
+struct SenderShared(Arc<Shared>);
+
+struct ReceiverShared(Arc<Shared>);
+
+struct Waiters {
+    tasks: Mutex<Vec<Task>>,
+}
+
+impl Waiters {
+    fn new() -> Waiters {
+        Waiters { tasks: Mutex::new(Vec::new()) }
+    }
+
+    // Registers the current task, which the caller then has to follow with
+    // another attempt at the operation it's waiting for. Whoever completes
+    // the opposite operation afterwards takes the lock, so either that
+    // attempt succeeds or the task is notified.
+    fn register(&self) {
+        let mut tasks = self.tasks.lock().unwrap();
+        if !tasks.iter().any(|t| t.will_notify_current()) {
+            tasks.push(task::current());
+        }
+    }
+
+    fn notify(&self) {
+        let tasks = {
+            let mut tasks = self.tasks.lock().unwrap();
+            if tasks.is_empty() {
+                return
+            }
+            ::std::mem::replace(&mut *tasks, Vec::new())
+        };
+        for task in tasks {
+            task.notify();
+        }
+    }
+}
+
+/// Creates a channel holding at most `cap` messages at a time.
+///
+/// As with `crossbeam_channel::bounded`, a capacity of 0 makes a channel
+/// where every send waits for a receive.
+///
+/// # Examples
+///
+/// ```
+/// use std::thread;
+///
+/// use futures::prelude::*;
+/// use futures::sync::crossbeam;
+///
+/// let (tx, rx) = crossbeam::bounded(1);
+/// thread::spawn(move || {
+///     for i in 0..3 {
+///         tx.blocking_send(i).unwrap();
+///     }
+/// });
+/// assert_eq!(rx.collect().wait(), Ok(vec![0, 1, 2]));
+/// ```
+pub fn bounded<T>(cap: usize) -> (Sender<T>, Receiver<T>) {
+    wrap(channel::bounded(cap))
+}
+
+/// Creates a channel holding any number of messages.
+pub fn unbounded<T>() -> (Sender<T>, Receiver<T>) {
+    wrap(channel::unbounded())
+}
+
+fn wrap<T>((tx, rx): (channel::Sender<T>, channel::Receiver<T>)) -> (Sender<T>, Receiver<T>) {
+    let shared = Arc::new(Shared {
+        receivers: Waiters::new(),
+        senders: Waiters::new(),
+    });
+    let tx = Sender {
+        inner: tx,
+        shared: SenderShared(shared.clone()),
+    };
+    let rx = Receiver {
+        inner: rx,
+        shared: ReceiverShared(shared),
+    };
+    (tx, rx)
+}
+
+impl<T> Sender<T> {
+    /// Sends a message, blocking the current thread while the channel is
+    /// full.
+    ///
+    /// This fails if all of the receivers are gone, handing the message
+    /// back. Like other blocking calls, this shouldn't be used from within
+    /// a task.
+    pub fn blocking_send(&self, msg: T) -> Result<(), SendError<T>> {
+        self.inner.send(msg)?;
+        self.shared.0.receivers.notify();
+        Ok(())
+    }
+
+    /// Attempts to send a message without blocking.
+    pub fn try_send(&self, msg: T) -> Result<(), TrySendError<T>> {
+        self.inner.try_send(msg)?;
+        self.shared.0.receivers.notify();
+        Ok(())
+    }
+
+    /// Returns the number of messages in the channel.
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    /// Returns `true` if the channel is empty.
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    /// Returns the capacity of the channel, or `None` if it's unbounded.
+    pub fn capacity(&self) -> Option<usize> {
+        self.inner.capacity()
+    }
+}
+
+impl<T> Clone for Sender<T> {
+    fn clone(&self) -> Sender<T> {
+        Sender {
+            inner: self.inner.clone(),
+            shared: SenderShared((self.shared.0).clone()),
+        }
+    }
+}
+
+impl Drop for SenderShared {
+    fn drop(&mut self) {
+        // This may have been the last sender, in which case the receivers
+        // have to find out the channel is disconnected.
+        self.0.receivers.notify();
+    }
+}
+
+impl<T> Sink for Sender<T> {
+    type SinkItem = T;
+    type SinkError = SendError<T>;
+
+    fn start_send(&mut self, msg: T) -> StartSend<T, SendError<T>> {
+        let msg = match self.try_send(msg) {
+            Ok(()) => return Ok(AsyncSink::Ready),
+            Err(TrySendError::Full(msg)) => msg,
+            Err(TrySendError::Disconnected(msg)) => return Err(SendError(msg)),
+        };
+        self.shared.0.senders.register();
+        match self.try_send(msg) {
+            Ok(()) => Ok(AsyncSink::Ready),
+            Err(TrySendError::Full(msg)) => Ok(AsyncSink::NotReady(msg)),
+            Err(TrySendError::Disconnected(msg)) => Err(SendError(msg)),
+        }
+    }
+
+    fn poll_complete(&mut self) -> Poll<(), SendError<T>> {
+        Ok(Async::Ready(()))
+    }
+
+    fn close(&mut self) -> Poll<(), SendError<T>> {
+        Ok(Async::Ready(()))
+    }
+}
+
+impl<T> fmt::Debug for Sender<T> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("Sender")
+            .field("len", &self.inner.len())
+            .field("capacity", &self.inner.capacity())
+            .finish()
+    }
+}
+
+impl<T> Receiver<T> {
+    /// Receives a message, blocking the current thread while the channel is
+    /// empty.
+    ///
+    /// This fails once all of the senders are gone and the channel is
+    /// empty. Like other blocking calls, this shouldn't be used from within
+    /// a task.
+    pub fn blocking_recv(&self) -> Result<T, RecvError> {
+        let msg = self.inner.recv()?;
+        self.shared.0.senders.notify();
+        Ok(msg)
+    }
+
+    /// Attempts to receive a message without blocking.
+    pub fn try_recv(&self) -> Result<T, TryRecvError> {
+        let msg = self.inner.try_recv()?;
+        self.shared.0.senders.notify();
+        Ok(msg)
+    }
+
+    /// Returns the number of messages in the channel.
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    /// Returns `true` if the channel is empty.
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    /// Returns the capacity of the channel, or `None` if it's unbounded.
+    pub fn capacity(&self) -> Option<usize> {
+        self.inner.capacity()
+    }
+}
+
+impl<T> Clone for Receiver<T> {
+    fn clone(&self) -> Receiver<T> {
+        Receiver {
+            inner: self.inner.clone(),
+            shared: ReceiverShared((self.shared.0).clone()),
+        }
+    }
+}
+
+impl Drop for ReceiverShared {
+    fn drop(&mut self) {
+        // Senders waiting for room have to find out if the channel is
+        // disconnected.
+        self.0.senders.notify();
+    }
+}
+
+impl<T> Stream for Receiver<T> {
+    type Item = T;
+    type Error = ();
+
+    fn poll(&mut self) -> Poll<Option<T>, ()> {
+        match self.try_recv() {
+            Ok(msg) => return Ok(Async::Ready(Some(msg))),
+            Err(TryRecvError::Disconnected) => return Ok(Async::Ready(None)),
+            Err(TryRecvError::Empty) => {}
+        }
+        self.shared.0.receivers.register();
+        match self.try_recv() {
+            Ok(msg) => Ok(Async::Ready(Some(msg))),
+            Err(TryRecvError::Disconnected) => Ok(Async::Ready(None)),
+            Err(TryRecvError::Empty) => Ok(Async::NotReady),
+        }
+    }
+}
+
+impl<T> fmt::Debug for Receiver<T> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("Receiver")
+            .field("len", &self.inner.len())
+            .field("capacity", &self.inner.capacity())
+            .finish()
+    }
+}
@@ -0,0 +1,108 @@
+use std::fmt;
+
+use {Async, Poll, Sink, StartSend, Stream};
+use sync::mpsc::{self, Receiver, SendError, Sender};
+
+/// One end of an in-memory, bidirectional transport created by `duplex`.
+///
+/// A `Duplex<S, R>` is a `Sink` of the `S` messages it sends to the other
+/// end and a `Stream` of the `R` messages the other end sends to it. This
+/// makes a pair of them a stand-in for a connection when testing protocol
+/// logic written against `Stream` and `Sink`.
+///
+/// Closing the sink of an endpoint, for example at the end of
+/// `Sink::send_all`, ends the stream of the other endpoint just like dropping
+/// it does. Nothing can be sent through a closed endpoint anymore, and
+/// `start_send` panics if it's tried.
+#[must_use = "streams do nothing unless polled"]
+pub struct Duplex<S, R> {
+    // Taken out once the sink is closed, even if the endpoint lives on as a
+    // stream.
+    tx: Option<Sender<S>>,
+    rx: Receiver<R>,
+}
+
+/// Creates two connected endpoints of an in-memory transport.
+///
+/// Everything sent into one endpoint is received from the other one. Each
+/// direction is a bounded `mpsc` channel with a buffer of `capacity`, with
+/// the same backpressure: once the buffer is full, sending waits until the
+/// other end receives. The stream of an endpoint ends once the other
+/// endpoint is dropped or closed, and everything sent before has been
+/// received.
+///
+/// # Examples
+///
+/// ```
+/// use futures::prelude::*;
+/// use futures::sync;
+///
+/// let (client, server) = sync::duplex::<&str, usize>(1);
+/// let client = client.send("hello").wait().unwrap();
+///
+/// let (msg, server) = server.into_future().wait().ok().unwrap();
+/// assert_eq!(msg, Some("hello"));
+/// server.send(5).wait().unwrap();
+///
+/// let (len, _) = client.into_future().wait().ok().unwrap();
+/// assert_eq!(len, Some(5));
+/// ```
+pub fn duplex<A, B>(capacity: usize) -> (Duplex<A, B>, Duplex<B, A>) {
+    let (a_tx, a_rx) = mpsc::channel(capacity);
+    let (b_tx, b_rx) = mpsc::channel(capacity);
+    let a = Duplex { tx: Some(a_tx), rx: b_rx };
+    let b = Duplex { tx: Some(b_tx), rx: a_rx };
+    (a, b)
+}
+
+impl<S, R> Duplex<S, R> {
+    /// Splits this endpoint into the sender and the receiver it's made of.
+    ///
+    /// The sender is `None` if the sink of this endpoint has been closed.
+    pub fn into_parts(self) -> (Option<Sender<S>>, Receiver<R>) {
+        (self.tx, self.rx)
+    }
+}
+
+impl<S, R> Sink for Duplex<S, R> {
+    type SinkItem = S;
+    type SinkError = SendError<S>;
+
+    fn start_send(&mut self, msg: S) -> StartSend<S, SendError<S>> {
+        match self.tx {
+            Some(ref mut tx) => tx.start_send(msg),
+            None => panic!("start_send called on a closed Duplex"),
+        }
+    }
+
+    fn poll_complete(&mut self) -> Poll<(), SendError<S>> {
+        match self.tx {
+            Some(ref mut tx) => tx.poll_complete(),
+            None => Ok(Async::Ready(())),
+        }
+    }
+
+    fn close(&mut self) -> Poll<(), SendError<S>> {
+        if let Some(ref mut tx) = self.tx {
+            try_ready!(tx.close());
+        }
+        // Dropping the sender is what lets the other end know.
+        self.tx = None;
+        Ok(Async::Ready(()))
+    }
+}
+
+impl<S, R> Stream for Duplex<S, R> {
+    type Item = R;
+    type Error = ();
+
+    fn poll(&mut self) -> Poll<Option<R>, ()> {
+        self.rx.poll()
+    }
+}
+
+impl<S, R> fmt::Debug for Duplex<S, R> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("Duplex").finish()
+    }
+}
@@ -13,6 +13,11 @@
 pub mod oneshot;
 pub mod mpsc;
 pub mod slot;
+pub mod compat;
+#[cfg(feature = "crossbeam")]
+pub mod crossbeam;
 mod bilock;
+mod duplex;
 
 pub use self::bilock::{BiLock, BiLockGuard, BiLockAcquire, BiLockAcquired};
+pub use self::duplex::{duplex, Duplex};
@@ -84,6 +84,7 @@ use resultstream::{self, Results};
 use {Async, AsyncSink, Future, Poll, StartSend, Sink, Stream};
 
 mod queue;
+mod segmented;
 
 /// The transmission end of a channel which is used to send values.
 ///
@@ -244,8 +245,9 @@ struct Inner<T> {
     // channel as well as a flag signalling that the channel is closed.
     state: AtomicUsize,
 
-    // Atomic, FIFO queue used to send messages to the receiver
-    message_queue: Queue<Option<T>>,
+    // Atomic, FIFO queue used to send messages to the receiver. Messages are
+    // stored in blocks of slots, rather than allocating a node for each.
+    message_queue: segmented::Queue<Option<T>>,
 
     // Atomic, FIFO queue used to send parked task handles to the receiver.
     parked_queue: Queue<Arc<Mutex<SenderTask>>>,
@@ -359,7 +361,7 @@ fn channel2<T>(buffer: Option<usize>) -> (Sender<T>, Receiver<T>) {
     let inner = Arc::new(Inner {
         buffer: buffer,
         state: AtomicUsize::new(INIT_STATE),
-        message_queue: Queue::new(),
+        message_queue: segmented::Queue::new(),
         parked_queue: Queue::new(),
         num_senders: AtomicUsize::new(1),
         recv_task: Mutex::new(ReceiverTask {
@@ -901,6 +903,10 @@ impl<T> Stream for Receiver<T> {
     type Error = ();
 
     fn poll(&mut self) -> Poll<Option<T>, ()> {
+        if task::poll_budget().is_not_ready() {
+            return Ok(Async::NotReady)
+        }
+
         loop {
             // Try to read a message off of the message queue.
             let msg = match self.next_message() {
@@ -937,10 +943,23 @@ impl<T> Stream for Receiver<T> {
             // Decrement number of messages
             self.dec_num_messages();
 
+            task::consume_budget();
+
             // Return the message
             return Ok(Async::Ready(msg));
         }
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let state = decode_state(self.inner.state.load(SeqCst));
+        if state.is_open {
+            (state.num_messages, None)
+        } else {
+            // Once closed the count may include the message signalling the
+            // end of the stream, so it's only an upper bound.
+            (0, Some(state.num_messages))
+        }
+    }
 }
 
 impl<T> Drop for Receiver<T> {
@@ -970,6 +989,10 @@ impl<T> Stream for UnboundedReceiver<T> {
     fn poll(&mut self) -> Poll<Option<T>, ()> {
         self.0.poll()
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
 }
 
 /// Handle returned from the `spawn` function.
@@ -1064,6 +1087,12 @@ impl<I, E> Stream for SpawnHandle<I, E> {
             Err(_) => unreachable!("mpsc::Receiver should never return Err"),
         }
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        // Any of the buffered results may be an error.
+        let (_, upper) = self.rx.size_hint();
+        (0, upper)
+    }
 }
 
 impl<I, E> fmt::Debug for SpawnHandle<I, E> {
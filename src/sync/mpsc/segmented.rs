@@ -0,0 +1,244 @@
+//! A multi-producer, single consumer queue storing its values in blocks.
+//!
+//! Unlike `queue::Queue`, which allocates a node for every value, this queue
+//! is a linked list of blocks which have room for `BLOCK_CAP` values each.
+//! Pushers claim a slot by bumping a shared index, and only the pusher which
+//! first needs a new block allocates it. This amortizes the allocation over a
+//! whole block and keeps consecutive values next to each other for the
+//! consumer.
+//!
+//! The tricky part is knowing when the consumer may free a block, as pushers
+//! may still be walking through it on their way to their slot. Pushers start
+//! walking at `tail_block`, which is only ever moved past a block once all of
+//! the slots of the block have been written. The pusher moving it records the
+//! tail index at that point and marks the block as released. Any pusher which
+//! can still see the block has claimed its slot before that, so once the
+//! consumer has popped all values up to the recorded index, nobody can see
+//! the block anymore and it's freed.
+
+use std::prelude::v1::*;
+
+use std::cell::UnsafeCell;
+use std::fmt;
+use std::ptr;
+use std::sync::atomic::{AtomicBool, AtomicPtr, AtomicUsize};
+use std::sync::atomic::Ordering::SeqCst;
+
+use sync::mpsc::queue::PopResult::{self, Data, Empty, Inconsistent};
+
+// The number of values per block. This has to be a power of two, and the
+// `ready` bits of a block have to fit in a `usize`.
+const BLOCK_CAP: usize = 32;
+
+const SLOT_MASK: usize = BLOCK_CAP - 1;
+
+// All of the `ready` bits of a block, written so that it doesn't overflow if
+// `BLOCK_CAP` is the number of bits in a `usize`.
+const ALL_READY: usize = (1 << (BLOCK_CAP - 1)) | ((1 << (BLOCK_CAP - 1)) - 1);
+
+struct Block<T> {
+    // The index of the first slot of this block.
+    start: usize,
+
+    slots: Vec<UnsafeCell<Option<T>>>,
+
+    // A bit per slot, set once its value has been written.
+    ready: AtomicUsize,
+
+    next: AtomicPtr<Block<T>>,
+
+    // Set once `tail_block` has been moved past this block, with
+    // `observed_tail` being the tail index at that point.
+    released: AtomicBool,
+    observed_tail: AtomicUsize,
+}
+
+/// The multi-producer single-consumer structure. This is not cloneable, but it
+/// may be safely shared so long as it is guaranteed that there is only one
+/// popper at a time (many pushers are allowed).
+pub struct Queue<T> {
+    // The index of the next slot to be claimed by a pusher.
+    tail: AtomicUsize,
+
+    // The block pushers start looking for their slot from.
+    tail_block: AtomicPtr<Block<T>>,
+
+    // Only ever accessed by the popper.
+    head: UnsafeCell<Head<T>>,
+}
+
+struct Head<T> {
+    // The index of the next slot to pop.
+    index: usize,
+
+    // The block holding `index`, or the one before it if `index` is the
+    // first slot of a block which hasn't been reached yet.
+    block: *mut Block<T>,
+
+    // The oldest block which hasn't been freed yet.
+    free: *mut Block<T>,
+}
+
+unsafe impl<T: Send> Send for Queue<T> { }
+unsafe impl<T: Send> Sync for Queue<T> { }
+
+impl<T> Block<T> {
+    fn new(start: usize) -> *mut Block<T> {
+        Box::into_raw(Box::new(Block {
+            start: start,
+            slots: (0..BLOCK_CAP).map(|_| UnsafeCell::new(None)).collect(),
+            ready: AtomicUsize::new(0),
+            next: AtomicPtr::new(ptr::null_mut()),
+            released: AtomicBool::new(false),
+            observed_tail: AtomicUsize::new(0),
+        }))
+    }
+
+    // Returns the next block, allocating it if there is none yet.
+    unsafe fn grow(&self) -> *mut Block<T> {
+        let next = self.next.load(SeqCst);
+        if !next.is_null() {
+            return next
+        }
+
+        let new = Block::new(self.start.wrapping_add(BLOCK_CAP));
+        match self.next.compare_exchange(ptr::null_mut(), new, SeqCst, SeqCst) {
+            Ok(_) => new,
+            Err(actual) => {
+                // Another pusher got there first.
+                drop(Box::from_raw(new));
+                actual
+            }
+        }
+    }
+}
+
+impl<T> Queue<T> {
+    /// Creates a new queue that is safe to share among multiple producers and
+    /// one consumer.
+    pub fn new() -> Queue<T> {
+        let block = Block::new(0);
+        Queue {
+            tail: AtomicUsize::new(0),
+            tail_block: AtomicPtr::new(block),
+            head: UnsafeCell::new(Head {
+                index: 0,
+                block: block,
+                free: block,
+            }),
+        }
+    }
+
+    /// Pushes a new value onto this queue.
+    pub fn push(&self, t: T) {
+        let index = self.tail.fetch_add(1, SeqCst);
+        unsafe {
+            let block = &*self.find_block(index);
+            let offset = index & SLOT_MASK;
+            *block.slots[offset].get() = Some(t);
+            block.ready.fetch_or(1 << offset, SeqCst);
+        }
+    }
+
+    // Walks from `tail_block` to the block holding the slot `index`, moving
+    // `tail_block` along past blocks which are full.
+    unsafe fn find_block(&self, index: usize) -> *mut Block<T> {
+        let start = index & !SLOT_MASK;
+        let mut block = self.tail_block.load(SeqCst);
+        let mut at_tail = true;
+
+        // The slot can't be in a block before `tail_block`, as the block
+        // holding it isn't full yet.
+        while (*block).start != start {
+            let next = (*block).grow();
+
+            if at_tail && (*block).ready.load(SeqCst) == ALL_READY {
+                match self.tail_block.compare_exchange(block, next, SeqCst, SeqCst) {
+                    Ok(_) => {
+                        (*block).observed_tail.store(self.tail.load(SeqCst), SeqCst);
+                        (*block).released.store(true, SeqCst);
+                    }
+                    Err(_) => at_tail = false,
+                }
+            } else {
+                at_tail = false;
+            }
+
+            block = next;
+        }
+
+        block
+    }
+
+    /// Pops some data from this queue.
+    ///
+    /// Like `queue::Queue::pop`, this returns `Inconsistent` if a value has
+    /// been pushed after the next one to pop, while that one is still being
+    /// written.
+    ///
+    /// This function is unsafe because only one thread can call it at a time.
+    pub unsafe fn pop(&self) -> PopResult<T> {
+        let head = &mut *self.head.get();
+
+        if (*head.block).start != head.index & !SLOT_MASK {
+            let next = (*head.block).next.load(SeqCst);
+            if next.is_null() {
+                return self.not_ready(head.index)
+            }
+            head.block = next;
+        }
+
+        let block = &*head.block;
+        let offset = head.index & SLOT_MASK;
+        if block.ready.load(SeqCst) & (1 << offset) == 0 {
+            return self.not_ready(head.index)
+        }
+
+        let ret = (*block.slots[offset].get()).take().unwrap();
+        head.index = head.index.wrapping_add(1);
+        self.reclaim(head);
+        Data(ret)
+    }
+
+    fn not_ready(&self, index: usize) -> PopResult<T> {
+        if self.tail.load(SeqCst) == index {Empty} else {Inconsistent}
+    }
+
+    // Frees the blocks before the head which pushers are done with.
+    unsafe fn reclaim(&self, head: &mut Head<T>) {
+        while head.free != head.block {
+            let block = head.free;
+            if !(*block).released.load(SeqCst) {
+                return
+            }
+            // Indices wrap around, so compare them by their distance.
+            let observed_tail = (*block).observed_tail.load(SeqCst);
+            if observed_tail.wrapping_sub(head.index) as isize > 0 {
+                return
+            }
+            head.free = (*block).next.load(SeqCst);
+            drop(Box::from_raw(block));
+        }
+    }
+}
+
+impl<T> Drop for Queue<T> {
+    fn drop(&mut self) {
+        unsafe {
+            let mut cur = (*self.head.get()).free;
+            while !cur.is_null() {
+                let next = (*cur).next.load(SeqCst);
+                drop(Box::from_raw(cur));
+                cur = next;
+            }
+        }
+    }
+}
+
+impl<T> fmt::Debug for Queue<T> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("Queue")
+            .field("tail", &self.tail.load(SeqCst))
+            .finish()
+    }
+}
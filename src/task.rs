@@ -25,7 +25,9 @@
 //! There is an important bare function in this module: `current`. The
 //! `current` function returns a handle to the currently running task, panicking
 //! if one isn't present. This handle is then used to later notify the task that
-//! it's ready to make progress through the `Task::notify` method.
+//! it's ready to make progress through the `Task::notify` method. Futures
+//! which only need to look at the handle can borrow it with `with_current`
+//! instead, which avoids creating a new handle on every call.
 
 #[doc(hidden)]
 #[deprecated(since = "0.1.4", note = "import through the executor module instead")]
@@ -33,7 +35,15 @@
 #[allow(deprecated)]
 pub use task_impl::{Spawn, spawn, Unpark, Executor, Run, park};
 
-pub use task_impl::{Task, AtomicTask, current, init};
+pub use task_impl::{Task, TaskId, AtomicTask, current, current_id, with_current, init};
+pub use task_impl::{YieldNow, yield_now};
+
+#[cfg(feature = "use_std")]
+pub use task_impl::{Budget, budget, poll_budget, consume_budget, get_local};
+#[cfg(feature = "use_std")]
+pub use task_impl::{Named, named, name};
+#[cfg(feature = "use_std")]
+pub use task_impl::{BlockingFuture, blocking};
 
 #[allow(deprecated)]
 #[cfg(feature = "use_std")]
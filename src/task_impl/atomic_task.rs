@@ -86,7 +86,16 @@ impl AtomicTask {
     /// idea. Concurrent calls to `register` will attempt to register different
     /// tasks to be notified. One of the callers will win and have its task set,
     /// but there is no guarantee as to which caller will succeed.
+    ///
+    /// If the registered task is the current one already, as is usually the
+    /// case for a future which registers every time it isn't ready, this
+    /// returns early, without creating a new `Task` handle or replacing the
+    /// old one, see `will_notify_current`.
     pub fn register(&self) {
+        if self.will_notify_current() {
+            return
+        }
+
         // Get a new task handle
         let task = super::current();
 
@@ -123,10 +132,59 @@ impl AtomicTask {
         }
     }
 
+    /// Returns whether notifying the registered task is equivalent to
+    /// notifying the current task, in which case `register` has nothing to
+    /// do.
+    ///
+    /// Like `Task::will_notify_current`, this may return false negatives, in
+    /// particular while another thread is calling `register` or `take`.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if no current future is being polled.
+    pub fn will_notify_current(&self) -> bool {
+        let mut curr = self.state.load(Acquire);
+
+        loop {
+            if curr == LOCKED_WRITE || curr == LOCKED_WRITE_NOTIFIED {
+                // The task is being replaced.
+                return false;
+            }
+
+            // Take a read lock, just like `notify`.
+            let actual = self.state.compare_and_swap(curr, curr + 1, Acquire);
+            if actual == curr {
+                break;
+            }
+            curr = actual;
+        }
+
+        let res = unsafe {
+            match *self.task.get() {
+                Some(ref task) => task.will_notify_current(),
+                None => false,
+            }
+        };
+
+        // Release the lock
+        self.state.fetch_sub(1, Release);
+        res
+    }
+
     /// Notifies the task that last called `register`.
     ///
     /// If `register` has not been called yet, then this does nothing.
     pub fn notify(&self) {
+        self.notify_if_registered();
+    }
+
+    /// Notifies the task that last called `register`, returning whether there
+    /// was one.
+    ///
+    /// This returns `false` if `register` hasn't been called yet, or if the
+    /// task was removed with `take`. A task which is being registered
+    /// concurrently is notified once it's in place, and counts as registered.
+    pub fn notify_if_registered(&self) -> bool {
         let mut curr = WAITING;
 
         loop {
@@ -136,7 +194,7 @@ impl AtomicTask {
 
                 if curr == actual {
                     // Success, return
-                    return;
+                    return true;
                 }
 
                 // update current state variable and try again
@@ -144,7 +202,7 @@ impl AtomicTask {
 
             } else if curr == LOCKED_WRITE_NOTIFIED {
                 // Currently in `LOCKED_WRITE_NOTIFIED` state, nothing else to do.
-                return;
+                return true;
 
             } else {
                 // Currently in a LOCKED_READ state, so attempt to increment the
@@ -154,17 +212,21 @@ impl AtomicTask {
                 // Locked acquired
                 if actual == curr {
                     // Notify the task
-                    unsafe {
-                        if let Some(ref task) = *self.task.get() {
-                            task.notify();
+                    let notified = unsafe {
+                        match *self.task.get() {
+                            Some(ref task) => {
+                                task.notify();
+                                true
+                            }
+                            None => false,
                         }
-                    }
+                    };
 
                     // Release the lock
                     self.state.fetch_sub(1, Release);
 
                     // Done
-                    return;
+                    return notified;
                 }
 
                 // update current state variable and try again
@@ -173,6 +235,37 @@ impl AtomicTask {
             }
         }
     }
+
+    /// Removes the registered task, returning it.
+    ///
+    /// Calls to `notify` afterwards do nothing until `register` is called
+    /// again. If `notify` is called while the task is being removed, the
+    /// returned task is notified before it's returned, so that the
+    /// notification isn't lost.
+    ///
+    /// This waits for concurrent calls to `register` and `notify` to finish,
+    /// so it must not be called from within the notification of the
+    /// registered task.
+    pub fn take(&self) -> Option<Task> {
+        loop {
+            // Lock the task cell for writing, like `register` does.
+            if self.state.compare_and_swap(WAITING, LOCKED_WRITE, Acquire) == WAITING {
+                break;
+            }
+        }
+
+        unsafe {
+            let task = (*self.task.get()).take();
+
+            if LOCKED_WRITE_NOTIFIED == self.state.swap(WAITING, Release) {
+                if let Some(ref task) = task {
+                    task.notify();
+                }
+            }
+
+            task
+        }
+    }
 }
 
 impl fmt::Debug for AtomicTask {
@@ -1,13 +1,17 @@
 use core::fmt;
+use core::cell::UnsafeCell;
 use core::marker::PhantomData;
 
-use {Poll, Future, Stream, Sink, StartSend};
+use {Poll, Async, AsyncSink, Future, Stream, Sink, StartSend};
 
 mod atomic_task;
 pub use self::atomic_task::AtomicTask;
 
 mod core;
 
+mod yield_now;
+pub use self::yield_now::{YieldNow, yield_now};
+
 #[cfg(feature = "use_std")]
 mod std;
 #[cfg(feature = "use_std")]
@@ -21,6 +25,8 @@ pub struct BorrowedTask<'a> {
     events: BorrowedEvents<'a>,
     // Task-local storage
     map: &'a LocalMap,
+    // The handle lent out by `with_current`, created on first use.
+    current: UnsafeCell<Option<Task>>,
 }
 
 fn fresh_task_id() -> usize {
@@ -87,16 +93,97 @@ impl AssertSend for Task {}
 /// is, this method can be dangerous to call outside of an implementation of
 /// `poll`.
 pub fn current() -> Task {
+    with(|borrowed| borrowed.to_task())
+}
+
+/// Runs `f` with a handle to the current task, without creating a new one.
+///
+/// Creating a `Task` with `current` involves bumping a reference count,
+/// which shows up in the profile of futures which do so every time they
+/// aren't ready. Instead, this lends out a handle which is created the first
+/// time it's asked for while the task is being polled, and is then shared by
+/// all of the futures asking for it throughout the poll. The handle can
+/// still be cloned if it has to be stored, although checking
+/// `Task::will_notify_current` on a stored handle first often avoids that.
+///
+/// # Panics
+///
+/// This function will panic if a task is not currently being executed, just
+/// like `current`.
+///
+/// # Examples
+///
+/// ```
+/// use futures::task::{self, Task};
+///
+/// fn register(waiter: &mut Option<Task>) {
+///     task::with_current(|task| {
+///         match *waiter {
+///             Some(ref waiter) if waiter.will_notify_current() => {}
+///             _ => *waiter = Some(task.clone()),
+///         }
+///     })
+/// }
+/// # let _ = register;
+/// ```
+pub fn with_current<F, R>(f: F) -> R
+    where F: FnOnce(&Task) -> R,
+{
     with(|borrowed| {
-        let unpark = borrowed.unpark.to_owned();
-        let events = borrowed.events.to_owned();
+        // The handle is only ever written here, before any reference to it is
+        // handed out, and creating it doesn't call back into this function.
+        unsafe {
+            let current = borrowed.current.get();
+            if (*current).is_none() {
+                *current = Some(borrowed.to_task());
+            }
+            f((*current).as_ref().unwrap())
+        }
+    })
+}
 
+impl<'a> BorrowedTask<'a> {
+    fn to_task(&self) -> Task {
         Task {
-            id: borrowed.id,
-            unpark: unpark,
-            events: events,
+            id: self.id,
+            unpark: self.unpark.to_owned(),
+            events: self.events.to_owned(),
         }
-    })
+    }
+}
+
+/// A unique identifier of a task.
+///
+/// Every `Spawn` gets a fresh id when it's created, which doesn't change for
+/// as long as it lives and is shared by all of the `Task` handles pointing to
+/// it. Ids are never reused, so they can be used to correlate log lines or
+/// channel operations with the task responsible for them.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct TaskId(usize);
+
+impl TaskId {
+    /// Returns the raw value of this id.
+    pub fn as_usize(&self) -> usize {
+        self.0
+    }
+}
+
+impl fmt::Display for TaskId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "task {}", self.0)
+    }
+}
+
+/// Returns the id of the current task.
+///
+/// This is cheap, it doesn't create a `Task` handle like `current` does.
+///
+/// # Panics
+///
+/// This function will panic if a task is not currently being executed, just
+/// like `current`.
+pub fn current_id() -> TaskId {
+    with(|borrowed| TaskId(borrowed.id))
 }
 
 #[doc(hidden)]
@@ -125,6 +212,11 @@ impl Task {
         self.notify()
     }
 
+    /// Returns the id of the task this handle notifies.
+    pub fn id(&self) -> TaskId {
+        TaskId(self.id)
+    }
+
     /// Returns `true` when called from within the context of the task.
     ///
     /// In other words, the task is currently running on the thread calling the
@@ -196,6 +288,7 @@ impl Task {
 impl fmt::Debug for Task {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         f.debug_struct("Task")
+         .field("id", &self.id())
          .finish()
     }
 }
@@ -214,6 +307,7 @@ pub struct Spawn<T> {
     id: usize,
     obj: T,
     data: LocalMap,
+    accounting: Accounting,
 }
 
 /// Spawns a new future, returning the fused future and task.
@@ -230,6 +324,7 @@ pub fn spawn<T>(obj: T) -> Spawn<T> {
         id: fresh_task_id(),
         obj: obj,
         data: local_map(),
+        accounting: accounting(),
     }
 }
 
@@ -248,6 +343,12 @@ impl<T> Spawn<T> {
     pub fn into_inner(self) -> T {
         self.obj
     }
+
+    /// Returns the id of this task, the one `task::current_id` returns while
+    /// it's being polled.
+    pub fn id(&self) -> TaskId {
+        TaskId(self.id)
+    }
 }
 
 impl<F: Future> Spawn<F> {
@@ -280,13 +381,25 @@ impl<F: Future> Spawn<F> {
     /// `Arc<T>` or a `Box<T>`. The `clone_id` and `drop_id` functions are then
     /// intended to be sufficient for the memory management related to that
     /// pointer.
+    ///
+    /// With the `use_std` feature each poll is reported to the `PollObserver`
+    /// registered through `executor::set_poll_observer`, if any.
     pub fn poll_future_notify<T>(&mut self,
                                  notify: &T,
                                  id: usize) -> Poll<F::Item, F::Error>
         where T: Clone + Into<NotifyHandle>,
     {
         let mk = || notify.clone().into();
-        self.enter(BorrowedUnpark::new(&mk, id), |f| f.poll())
+        let task = self.id();
+        let res = observe(task, || {
+            self.enter(BorrowedUnpark::new(&mk, id), |f| f.poll())
+        });
+        let not_ready = match res {
+            Ok(Async::NotReady) => true,
+            _ => false,
+        };
+        self.accounting.finish(not_ready);
+        res
     }
 }
 
@@ -299,7 +412,13 @@ impl<S: Stream> Spawn<S> {
         where T: Clone + Into<NotifyHandle>,
     {
         let mk = || notify.clone().into();
-        self.enter(BorrowedUnpark::new(&mk, id), |s| s.poll())
+        let res = self.enter(BorrowedUnpark::new(&mk, id), |s| s.poll());
+        let not_ready = match res {
+            Ok(Async::NotReady) => true,
+            _ => false,
+        };
+        self.accounting.finish(not_ready);
+        res
     }
 }
 
@@ -317,7 +436,13 @@ impl<S: Sink> Spawn<S> {
         where T: Clone + Into<NotifyHandle>,
     {
         let mk = || notify.clone().into();
-        self.enter(BorrowedUnpark::new(&mk, id), |s| s.start_send(value))
+        let res = self.enter(BorrowedUnpark::new(&mk, id), |s| s.start_send(value));
+        let not_ready = match res {
+            Ok(AsyncSink::NotReady(_)) => true,
+            _ => false,
+        };
+        self.accounting.finish(not_ready);
+        res
     }
 
     /// Invokes the underlying `poll_complete` method with this task in place.
@@ -332,7 +457,13 @@ impl<S: Sink> Spawn<S> {
         where T: Clone + Into<NotifyHandle>,
     {
         let mk = || notify.clone().into();
-        self.enter(BorrowedUnpark::new(&mk, id), |s| s.poll_complete())
+        let res = self.enter(BorrowedUnpark::new(&mk, id), |s| s.poll_complete());
+        let not_ready = match res {
+            Ok(Async::NotReady) => true,
+            _ => false,
+        };
+        self.accounting.finish(not_ready);
+        res
     }
 
     /// Invokes the underlying `close` method with this task in place.
@@ -347,7 +478,13 @@ impl<S: Sink> Spawn<S> {
         where T: Clone + Into<NotifyHandle>,
     {
         let mk = || notify.clone().into();
-        self.enter(BorrowedUnpark::new(&mk, id), |s| s.close())
+        let res = self.enter(BorrowedUnpark::new(&mk, id), |s| s.close());
+        let not_ready = match res {
+            Ok(Async::NotReady) => true,
+            _ => false,
+        };
+        self.accounting.finish(not_ready);
+        res
     }
 }
 
@@ -355,11 +492,13 @@ impl<T> Spawn<T> {
     fn enter<F, R>(&mut self, unpark: BorrowedUnpark, f: F) -> R
         where F: FnOnce(&mut T) -> R
     {
+        self.accounting.start();
         let borrowed = BorrowedTask {
             id: self.id,
             unpark: unpark,
-            events: BorrowedEvents::new(),
+            events: self.accounting.events(),
             map: &self.data,
+            current: UnsafeCell::new(None),
         };
         let obj = &mut self.obj;
         set(&borrowed, || f(obj))
@@ -369,6 +508,7 @@ impl<T> Spawn<T> {
 impl<T: fmt::Debug> fmt::Debug for Spawn<T> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         f.debug_struct("Spawn")
+         .field("id", &self.id())
          .field("obj", &self.obj)
          .finish()
     }
@@ -477,6 +617,7 @@ pub fn with_notify<F, T, R>(notify: &T, id: usize, f: F) -> R
             unpark: BorrowedUnpark::new(&mk, id),
             events: task.events,
             map: task.map,
+            current: UnsafeCell::new(None),
         };
 
         set(&new_task, f)
@@ -612,7 +753,7 @@ impl NotifyHandle {
     ///
     /// If you're working with the standard library then it's recommended to
     /// use the `NotifyHandle::from` function instead which works with the safe
-    /// `Arc` type and the safe `Notify` trait.
+    /// `Arc` type and the safe `Notify` trait, or `ArcNotify`.
     #[inline]
     pub unsafe fn new(inner: *mut UnsafeNotify) -> NotifyHandle {
         NotifyHandle { inner: inner }
@@ -0,0 +1,158 @@
+#![allow(deprecated)]
+
+use std::prelude::v1::*;
+
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, AtomicUsize};
+use std::sync::atomic::Ordering::SeqCst;
+use std::time::{Duration, Instant};
+
+use task_impl::Spawn;
+use super::{BorrowedEvents, EventSet, UnparkEvent};
+
+/// Statistics about the notifications received and polls performed by a
+/// `Spawn`.
+///
+/// These are only collected once enabled through
+/// `Spawn::track_notifications`, and are returned by `Spawn::notify_stats`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NotifyStats {
+    /// The number of times the task was notified through a `Task` handle.
+    pub notifies: usize,
+
+    /// The number of times the task was polled.
+    pub polls: usize,
+
+    /// The number of polls following a notification which returned
+    /// `NotReady` anyway, that is wakeups which didn't lead to any progress.
+    pub spurious: usize,
+
+    /// The time at which the task was last polled, if it was polled since
+    /// tracking started.
+    pub last_poll: Option<Instant>,
+}
+
+impl NotifyStats {
+    /// Returns the time elapsed since the task was last polled.
+    pub fn since_last_poll(&self) -> Option<Duration> {
+        self.last_poll.map(|t| t.elapsed())
+    }
+}
+
+type Callback = Arc<Fn(usize) + Send + Sync>;
+
+// Notified through the unpark event attached to all of the `Task` handles of
+// a tracked `Spawn`.
+struct Counters {
+    notifies: AtomicUsize,
+    notified: AtomicBool,
+    callback: Mutex<Option<Callback>>,
+}
+
+impl EventSet for Counters {
+    fn insert(&self, _id: usize) {
+        let notifies = self.notifies.fetch_add(1, SeqCst) + 1;
+        self.notified.store(true, SeqCst);
+        let callback = self.callback.lock().unwrap().clone();
+        if let Some(callback) = callback {
+            callback(notifies);
+        }
+    }
+}
+
+pub struct Accounting(Option<Box<State>>);
+
+struct State {
+    counters: Arc<Counters>,
+    event: UnparkEvent,
+    polls: usize,
+    spurious: usize,
+    last_poll: Option<Instant>,
+    woken: bool,
+}
+
+pub fn accounting() -> Accounting {
+    Accounting(None)
+}
+
+impl Accounting {
+    pub fn start(&mut self) {
+        if let Some(ref mut state) = self.0 {
+            state.polls += 1;
+            state.last_poll = Some(Instant::now());
+            state.woken = state.counters.notified.swap(false, SeqCst);
+        }
+    }
+
+    pub fn events<'a>(&'a self) -> BorrowedEvents<'a> {
+        match self.0 {
+            Some(ref state) => BorrowedEvents::One(&state.event, &BorrowedEvents::None),
+            None => BorrowedEvents::new(),
+        }
+    }
+
+    pub fn finish(&mut self, not_ready: bool) {
+        if let Some(ref mut state) = self.0 {
+            if not_ready && state.woken {
+                state.spurious += 1;
+            }
+        }
+    }
+
+    fn enable(&mut self) -> &mut State {
+        if self.0.is_none() {
+            let counters = Arc::new(Counters {
+                notifies: AtomicUsize::new(0),
+                notified: AtomicBool::new(false),
+                callback: Mutex::new(None),
+            });
+            self.0 = Some(Box::new(State {
+                event: UnparkEvent::new(counters.clone(), 0),
+                counters: counters,
+                polls: 0,
+                spurious: 0,
+                last_poll: None,
+                woken: false,
+            }));
+        }
+        self.0.as_mut().unwrap()
+    }
+}
+
+impl<T> Spawn<T> {
+    /// Starts collecting `NotifyStats` for this task.
+    ///
+    /// Only notifications through `Task` handles obtained while polling after
+    /// this call are counted. Tracking has a small cost on every poll and
+    /// notification, so it is disabled by default. Calling this again has no
+    /// effect.
+    pub fn track_notifications(&mut self) {
+        self.accounting.enable();
+    }
+
+    /// Registers a callback invoked on every notification of this task, with
+    /// the number of notifications received so far.
+    ///
+    /// This enables tracking as with `track_notifications`, replacing any
+    /// previously registered callback. The callback runs on whichever thread
+    /// notifies the task, so it should do as little work as possible.
+    pub fn on_notify<F>(&mut self, f: F)
+        where F: Fn(usize) + Send + Sync + 'static,
+    {
+        let state = self.accounting.enable();
+        *state.counters.callback.lock().unwrap() = Some(Arc::new(f));
+    }
+
+    /// Returns the statistics collected for this task, or `None` if tracking
+    /// wasn't enabled.
+    pub fn notify_stats(&self) -> Option<NotifyStats> {
+        self.accounting.0.as_ref().map(|state| {
+            NotifyStats {
+                notifies: state.counters.notifies.load(SeqCst),
+                polls: state.polls,
+                spurious: state.spurious,
+                last_poll: state.last_poll,
+            }
+        })
+    }
+}
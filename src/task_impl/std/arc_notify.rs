@@ -0,0 +1,91 @@
+use std::prelude::v1::*;
+
+use std::marker::PhantomData;
+use std::mem;
+use std::ptr;
+use std::sync::Arc;
+
+use task_impl::{Notify, NotifyHandle, UnsafeNotify};
+
+/// A `Notify` which is told about notifications through the `Arc` it lives
+/// in.
+///
+/// This is the most convenient way to write the notification side of an
+/// executor: a task stored in an `Arc` implements this trait to put a clone
+/// of itself back onto the run queue when it's notified. The `Arc` is turned
+/// into a `NotifyHandle` with `into_notify_handle`, which takes care of all
+/// of the reference counting without any unsafe code on the implementer's
+/// side.
+///
+/// The `id` is passed through unchanged from the `Spawn::*_notify` call which
+/// created the `Task` being notified.
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::{Arc, Mutex};
+///
+/// use futures::prelude::*;
+/// use futures::executor::{self, ArcNotify};
+///
+/// struct Queue(Mutex<Vec<usize>>);
+///
+/// impl ArcNotify for Queue {
+///     fn notify(me: &Arc<Queue>, id: usize) {
+///         me.0.lock().unwrap().push(id);
+///     }
+/// }
+///
+/// let queue = Arc::new(Queue(Mutex::new(Vec::new())));
+/// let handle = ArcNotify::into_notify_handle(queue.clone());
+/// let mut task = executor::spawn(futures::future::lazy(|| {
+///     futures::task::current().notify();
+///     Ok::<(), ()>(())
+/// }));
+/// task.poll_future_notify(&handle, 7).unwrap();
+/// assert_eq!(*queue.0.lock().unwrap(), [7]);
+/// ```
+pub trait ArcNotify: Send + Sync + 'static {
+    /// Indicates that the future or task associated with `id` is ready to
+    /// make progress, like `Notify::notify`.
+    fn notify(me: &Arc<Self>, id: usize);
+
+    /// Converts `me` into a `NotifyHandle` calling `ArcNotify::notify`.
+    fn into_notify_handle(me: Arc<Self>) -> NotifyHandle
+        where Self: Sized,
+    {
+        unsafe {
+            let ptr = mem::transmute::<Arc<Self>, *mut ArcNotifyWrapped<Self>>(me);
+            NotifyHandle::new(ptr)
+        }
+    }
+}
+
+// Identical to the `ArcWrapped` used to convert an `Arc` of a `Notify`, see
+// there for the details of why `*const ArcNotifyWrapped<T>` may be cast to
+// an `Arc<T>`.
+struct ArcNotifyWrapped<T>(PhantomData<T>);
+
+impl<T: ArcNotify> Notify for ArcNotifyWrapped<T> {
+    fn notify(&self, id: usize) {
+        unsafe {
+            let me: *const ArcNotifyWrapped<T> = self;
+            T::notify(&*(&me as *const *const ArcNotifyWrapped<T> as *const Arc<T>),
+                      id)
+        }
+    }
+}
+
+unsafe impl<T: ArcNotify> UnsafeNotify for ArcNotifyWrapped<T> {
+    unsafe fn clone_raw(&self) -> NotifyHandle {
+        let me: *const ArcNotifyWrapped<T> = self;
+        let arc = (*(&me as *const *const ArcNotifyWrapped<T> as *const Arc<T>)).clone();
+        T::into_notify_handle(arc)
+    }
+
+    unsafe fn drop_raw(&self) {
+        let mut me: *const ArcNotifyWrapped<T> = self;
+        let me = &mut me as *mut *const ArcNotifyWrapped<T> as *mut Arc<T>;
+        ptr::drop_in_place(me);
+    }
+}
@@ -0,0 +1,187 @@
+use std::prelude::v1::*;
+
+use std::collections::VecDeque;
+use std::fmt;
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
+use std::sync::{Condvar, Mutex, Once};
+use std::thread;
+use std::time::Duration;
+
+use {Future, Poll, Async};
+use sync::oneshot::{self, Canceled};
+
+// The maximum number of threads running blocking closures at the same time,
+// further closures queue up until a thread becomes available.
+const MAX_THREADS: usize = 64;
+
+// How long, in seconds, an idle thread waits for more work before exiting.
+const KEEP_ALIVE_SECS: u64 = 10;
+
+/// A future resolving to the result of a closure run on the blocking thread
+/// pool.
+///
+/// This is created by the `task::blocking` function.
+#[must_use = "futures do nothing unless polled"]
+pub struct BlockingFuture<T> {
+    rx: oneshot::Receiver<thread::Result<T>>,
+}
+
+/// Runs the blocking closure `f` on a thread pool owned by this crate,
+/// returning a future which resolves with its result.
+///
+/// This is the way to call blocking APIs, such as filesystem operations or
+/// DNS lookups, from within a future without stalling the executor running
+/// it. The threads of the pool are started lazily, the pool grows on demand
+/// up to a fixed limit and idle threads exit after a while.
+///
+/// The closure starts running right away, whether or not the returned future
+/// is polled, and keeps running if the future is dropped. If the closure
+/// panics then the panic is propagated to whoever polls the returned future.
+/// The future only fails with `Canceled` if the closure couldn't be run at
+/// all.
+///
+/// # Examples
+///
+/// ```
+/// use futures::prelude::*;
+/// use futures::task;
+///
+/// let f = task::blocking(|| {
+///     // Pretend this is reading a file.
+///     std::thread::sleep(std::time::Duration::from_millis(10));
+///     "contents".to_string()
+/// });
+/// assert_eq!(f.wait().unwrap(), "contents");
+/// ```
+pub fn blocking<F, T>(f: F) -> BlockingFuture<T>
+    where F: FnOnce() -> T + Send + 'static,
+          T: Send + 'static,
+{
+    BlockingFuture { rx: execute_blocking(f) }
+}
+
+// Runs `f` on the pool, catching any panic, and passes its result on through
+// the returned receiver. This is shared with `thread::spawn_blocking`.
+pub fn execute_blocking<F, T>(f: F) -> oneshot::Receiver<thread::Result<T>>
+    where F: FnOnce() -> T + Send + 'static,
+          T: Send + 'static,
+{
+    let (tx, rx) = oneshot::channel();
+    pool().execute(Box::new(move || {
+        let res = panic::catch_unwind(AssertUnwindSafe(f));
+        drop(tx.send(res));
+    }));
+    rx
+}
+
+impl<T> Future for BlockingFuture<T> {
+    type Item = T;
+    type Error = Canceled;
+
+    fn poll(&mut self) -> Poll<T, Canceled> {
+        match try_ready!(self.rx.poll()) {
+            Ok(t) => Ok(Async::Ready(t)),
+            Err(payload) => panic::resume_unwind(payload),
+        }
+    }
+}
+
+impl<T> fmt::Debug for BlockingFuture<T> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("BlockingFuture").finish()
+    }
+}
+
+trait Job: Send {
+    fn run(self: Box<Self>);
+}
+
+impl<F: FnOnce() + Send> Job for F {
+    fn run(self: Box<Self>) {
+        (*self)()
+    }
+}
+
+struct Pool {
+    state: Mutex<State>,
+    condvar: Condvar,
+}
+
+struct State {
+    queue: VecDeque<Box<Job>>,
+    threads: usize,
+    idle: usize,
+}
+
+fn pool() -> &'static Pool {
+    static INIT: Once = Once::new();
+    static mut POOL: *const Pool = ptr::null();
+
+    // The pool is never deallocated, its threads exit on their own once
+    // they're idle.
+    unsafe {
+        INIT.call_once(|| {
+            POOL = Box::into_raw(Box::new(Pool {
+                state: Mutex::new(State {
+                    queue: VecDeque::new(),
+                    threads: 0,
+                    idle: 0,
+                }),
+                condvar: Condvar::new(),
+            }));
+        });
+        &*POOL
+    }
+}
+
+impl Pool {
+    fn execute(&'static self, job: Box<Job>) {
+        let mut state = self.state.lock().unwrap();
+        state.queue.push_back(job);
+        if state.queue.len() <= state.idle {
+            self.condvar.notify_one();
+            return
+        }
+        if state.threads >= MAX_THREADS {
+            return
+        }
+        let spawned = thread::Builder::new()
+            .name("futures-blocking".to_string())
+            .spawn(move || self.work());
+        match spawned {
+            Ok(_) => state.threads += 1,
+
+            // Without any thread to run it, dropping the job is the only way
+            // to let its future know that it won't complete.
+            Err(_) if state.threads == 0 => {
+                let job = state.queue.pop_back();
+                drop(state);
+                drop(job);
+            }
+            Err(_) => {}
+        }
+    }
+
+    fn work(&self) {
+        let mut state = self.state.lock().unwrap();
+        loop {
+            if let Some(job) = state.queue.pop_front() {
+                drop(state);
+                job.run();
+                state = self.state.lock().unwrap();
+                continue
+            }
+
+            state.idle += 1;
+            let keep_alive = Duration::from_secs(KEEP_ALIVE_SECS);
+            let (guard, timeout) = self.condvar.wait_timeout(state, keep_alive).unwrap();
+            state = guard;
+            state.idle -= 1;
+            if timeout.timed_out() && state.queue.is_empty() {
+                state.threads -= 1;
+                return
+            }
+        }
+    }
+}
@@ -0,0 +1,111 @@
+use std::cell::Cell;
+
+use {Future, IntoFuture, Poll, Async};
+use task_impl::current;
+
+thread_local!(static BUDGET: Cell<Option<usize>> = Cell::new(None));
+
+/// A future which limits how many ready results cooperating futures produce
+/// each time it is polled.
+///
+/// This future is created by the `task::budget` function.
+#[derive(Debug)]
+#[must_use = "futures do nothing unless polled"]
+pub struct Budget<F> {
+    future: F,
+    budget: usize,
+}
+
+/// Runs `f` with a polling budget of `n` ready results per poll.
+///
+/// Each time the returned future is polled it hands out a fresh budget of
+/// `n` for the duration of polling `f`. Cooperating leaf futures and streams,
+/// such as the channels in this crate and `FuturesUnordered`, consume one
+/// unit of budget for every value they produce. Once the budget is exhausted
+/// they return `NotReady` and notify the current task, forcing the task to
+/// yield back to its executor before it continues.
+///
+/// This prevents a stream which is always ready from starving the other
+/// futures joined into the same task, or the other tasks of its executor.
+/// Futures outside of a `budget` are never limited, and a nested `budget`
+/// replaces the enclosing one while its future is being polled.
+///
+/// # Examples
+///
+/// ```
+/// use futures::prelude::*;
+/// use futures::task;
+///
+/// let (tx, rx) = futures::sync::mpsc::unbounded();
+/// for i in 0..10 {
+///     tx.unbounded_send(i).unwrap();
+/// }
+/// drop(tx);
+///
+/// let f = task::budget(3, rx.collect());
+/// assert_eq!(f.wait(), Ok((0..10).collect::<Vec<_>>()));
+/// ```
+///
+/// # Panics
+///
+/// Panics if `n` is zero.
+pub fn budget<F: IntoFuture>(n: usize, f: F) -> Budget<F::Future> {
+    assert!(n > 0, "budget must be greater than zero");
+    Budget {
+        future: f.into_future(),
+        budget: n,
+    }
+}
+
+impl<F: Future> Future for Budget<F> {
+    type Item = F::Item;
+    type Error = F::Error;
+
+    fn poll(&mut self) -> Poll<F::Item, F::Error> {
+        struct Reset(Option<usize>);
+
+        impl Drop for Reset {
+            fn drop(&mut self) {
+                let prev = self.0;
+                BUDGET.with(|b| b.set(prev));
+            }
+        }
+
+        let _reset = Reset(BUDGET.with(|b| b.replace(Some(self.budget))));
+        self.future.poll()
+    }
+}
+
+/// Checks whether the budget of the current task, if any, allows producing
+/// another value.
+///
+/// This is intended for leaf futures and streams which may be ready many
+/// times in a row. If the budget set by `task::budget` has been exhausted
+/// this notifies the current task and returns `NotReady`, in which case the
+/// caller should return `NotReady` as well. Values produced afterwards should
+/// be accounted for with `consume_budget`.
+///
+/// # Panics
+///
+/// Panics if the budget is exhausted and this isn't called from within a
+/// task.
+pub fn poll_budget() -> Async<()> {
+    match BUDGET.with(|b| b.get()) {
+        Some(0) => {
+            current().notify();
+            Async::NotReady
+        }
+        _ => Async::Ready(()),
+    }
+}
+
+/// Consumes one unit of the budget of the current task, if it has one.
+///
+/// See `poll_budget` for more information.
+pub fn consume_budget() {
+    BUDGET.with(|b| {
+        if let Some(n) = b.get() {
+            b.set(Some(n.saturating_sub(1)));
+        }
+    })
+}
@@ -0,0 +1,108 @@
+use std::cell::Cell;
+use std::error::Error;
+use std::fmt;
+use std::marker::PhantomData;
+
+use super::get_ptr;
+
+thread_local!(static ENTERED: Cell<bool> = Cell::new(false));
+
+/// A guard marking the current thread as running an executor, returned by
+/// `executor::enter`.
+///
+/// The mark is removed when the guard is dropped. The guard can't be sent to
+/// other threads.
+pub struct Enter {
+    // Must be dropped on the thread it was created on.
+    _marker: PhantomData<*mut ()>,
+}
+
+/// The error returned by `executor::enter` if the current thread is already
+/// running an executor.
+pub struct EnterError {
+    _priv: (),
+}
+
+/// Marks the current thread as running an executor until the returned guard
+/// is dropped.
+///
+/// Executors call this while they're running, so that blocking calls such as
+/// `Future::wait` can tell that they would block the thread the executor
+/// needs to make progress, and panic rather than deadlock. The executors of
+/// this crate, as well as `Future::wait` and friends, all do so. Executors
+/// which block the thread while waiting for their tasks should enter as
+/// well, to catch being run from within another executor.
+///
+/// # Errors
+///
+/// This fails if the current thread is already running an executor.
+///
+/// # Examples
+///
+/// ```
+/// use futures::executor;
+///
+/// let enter = executor::enter().unwrap();
+/// assert!(executor::enter().is_err());
+/// drop(enter);
+/// assert!(executor::enter().is_ok());
+/// ```
+pub fn enter() -> Result<Enter, EnterError> {
+    ENTERED.with(|entered| {
+        if entered.get() {
+            Err(EnterError { _priv: () })
+        } else {
+            entered.set(true);
+            Ok(Enter { _marker: PhantomData })
+        }
+    })
+}
+
+impl Drop for Enter {
+    fn drop(&mut self) {
+        ENTERED.with(|entered| entered.set(false));
+    }
+}
+
+impl fmt::Debug for Enter {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("Enter").finish()
+    }
+}
+
+impl fmt::Debug for EnterError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("EnterError").finish()
+    }
+}
+
+impl fmt::Display for EnterError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "an executor is already running on the current thread")
+    }
+}
+
+impl Error for EnterError {
+    fn description(&self) -> &str {
+        "an executor is already running on the current thread"
+    }
+}
+
+// Enters the executor guard for a blocking `wait`-style call, panicking if
+// the call happens from within a task or on a thread which is running an
+// executor, as blocking would then deadlock the thread.
+pub fn enter_blocking(what: &str) -> Enter {
+    let in_task = match get_ptr() {
+        Some(ptr) => !ptr.is_null(),
+        None => false,
+    };
+    match enter() {
+        Ok(ref _enter) if in_task => {}
+        Ok(enter) => return enter,
+        Err(_) => {}
+    }
+    panic!("`{}` was called from within a task or a running executor, this \
+            would block the thread driving the task and deadlock if the \
+            task is needed to make progress; spawn the work or compose it \
+            into the task instead", what)
+}
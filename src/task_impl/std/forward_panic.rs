@@ -0,0 +1,31 @@
+use std::prelude::v1::*;
+
+use std::any::Any;
+use std::cell::RefCell;
+
+// The payload of a panic which an executor is handing over to whichever
+// future takes it while the panicked task is being dropped.
+thread_local!(static PANIC: RefCell<Option<Box<Any + Send>>> = RefCell::new(None));
+
+// Runs `f`, typically dropping a task which panicked, letting the futures
+// dropped by it take `payload` through `take_forwarded_panic`.
+pub fn forward_panic<F, R>(payload: Box<Any + Send>, f: F) -> R
+    where F: FnOnce() -> R,
+{
+    struct Reset(Option<Box<Any + Send>>);
+
+    impl Drop for Reset {
+        fn drop(&mut self) {
+            let prev = self.0.take();
+            PANIC.with(|p| *p.borrow_mut() = prev);
+        }
+    }
+
+    let prev = PANIC.with(|p| p.borrow_mut().replace(payload));
+    let _reset = Reset(prev);
+    f()
+}
+
+pub fn take_forwarded_panic() -> Option<Box<Any + Send>> {
+    PANIC.with(|p| p.borrow_mut().take())
+}
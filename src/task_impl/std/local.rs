@@ -0,0 +1,66 @@
+use std::prelude::v1::*;
+
+use std::any::{Any, TypeId};
+use std::cell::RefCell;
+
+// The values set by the `Future::with_local` futures currently being polled
+// on this thread, innermost last.
+thread_local!(static LOCALS: RefCell<Vec<(TypeId, *const Any)>> = RefCell::new(Vec::new()));
+
+/// Makes `value` available through `get_local` for the duration of `f`.
+pub fn set_local<T, F, R>(value: &T, f: F) -> R
+    where T: Any,
+          F: FnOnce() -> R,
+{
+    struct Reset;
+
+    impl Drop for Reset {
+        fn drop(&mut self) {
+            LOCALS.with(|l| l.borrow_mut().pop());
+        }
+    }
+
+    LOCALS.with(|l| {
+        l.borrow_mut().push((TypeId::of::<T>(), value as &Any as *const Any))
+    });
+    let _reset = Reset;
+    f()
+}
+
+/// Returns a clone of the innermost value of type `T` set with
+/// `Future::with_local` for the future which is currently being polled.
+///
+/// Values set with `with_local` are visible to all futures polled from
+/// within the wrapped future, no matter how deeply nested, but not to other
+/// tasks, including those spawned by the wrapped future. If no value of type
+/// `T` is set then `None` is returned.
+///
+/// # Examples
+///
+/// ```
+/// use futures::prelude::*;
+/// use futures::future;
+/// use futures::task;
+///
+/// #[derive(Clone, Debug, PartialEq)]
+/// struct TraceId(u64);
+///
+/// let f = future::lazy(|| Ok::<_, ()>(task::get_local::<TraceId>()));
+/// assert_eq!(f.with_local(TraceId(7)).wait(), Ok(Some(TraceId(7))));
+/// assert_eq!(task::get_local::<TraceId>(), None);
+/// ```
+pub fn get_local<T: Any + Clone>() -> Option<T> {
+    let id = TypeId::of::<T>();
+    let ptr = LOCALS.with(|l| {
+        l.borrow().iter()
+            .rev()
+            .find(|&&(key, _)| key == id)
+            .map(|&(_, ptr)| ptr)
+    });
+    // The pointer stays valid as the value is kept alive by the `WithLocal`
+    // future being polled further up the stack. The borrow of `LOCALS` is
+    // released before cloning in case `clone` sets values itself.
+    ptr.map(|ptr| unsafe {
+        (*ptr).downcast_ref::<T>().expect("type mismatch in task locals").clone()
+    })
+}
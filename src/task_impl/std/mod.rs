@@ -1,13 +1,11 @@
 use std::prelude::v1::*;
 
-use std::cell::Cell;
+use std::cell::{Cell, UnsafeCell};
 use std::fmt;
 use std::marker::PhantomData;
 use std::mem;
 use std::ptr;
 use std::sync::{Arc, Once, ONCE_INIT};
-use std::sync::atomic::{AtomicBool, Ordering};
-use std::thread;
 
 use {Future, Stream, Sink, Poll, Async, StartSend, AsyncSink};
 use super::core;
@@ -19,6 +17,36 @@ pub use self::unpark_mutex::UnparkMutex;
 mod data;
 pub use self::data::*;
 
+mod accounting;
+pub use self::accounting::{Accounting, accounting, NotifyStats};
+
+mod enter;
+pub use self::enter::{Enter, EnterError, enter, enter_blocking};
+
+mod arc_notify;
+pub use self::arc_notify::ArcNotify;
+
+mod observer;
+pub use self::observer::{PollObserver, set_poll_observer, observe};
+
+mod forward_panic;
+pub use self::forward_panic::{forward_panic, take_forwarded_panic};
+
+mod thread_parker;
+pub use self::thread_parker::ThreadParker;
+
+mod local;
+pub use self::local::{set_local, get_local};
+
+mod named;
+pub use self::named::{Named, named, name};
+
+mod blocking;
+pub use self::blocking::{BlockingFuture, blocking, execute_blocking};
+
+mod budget;
+pub use self::budget::{Budget, budget, poll_budget, consume_budget};
+
 mod task_rc;
 #[allow(deprecated)]
 #[cfg(feature = "with-deprecated")]
@@ -237,8 +265,14 @@ impl<F: Future> Spawn<F> {
     /// This function will call `poll_future` in a loop, waiting for the future
     /// to complete. When a future cannot make progress it will use
     /// `thread::park` to block the current thread.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if it's called from within a task or on a thread
+    /// running an executor, as that'd deadlock the thread.
     pub fn wait_future(&mut self) -> Result<F::Item, F::Error> {
-        let unpark = Arc::new(ThreadNotify::new(thread::current()));
+        let _enter = enter_blocking("wait");
+        let unpark = ThreadParker::new();
 
         loop {
             match self.poll_future_notify(&unpark, 0)? {
@@ -296,7 +330,8 @@ impl<S: Stream> Spawn<S> {
     /// Like `wait_future`, except only waits for the next element to arrive on
     /// the underlying stream.
     pub fn wait_stream(&mut self) -> Option<Result<S::Item, S::Error>> {
-        let unpark = Arc::new(ThreadNotify::new(thread::current()));
+        let _enter = enter_blocking("wait");
+        let unpark = ThreadParker::new();
         loop {
             match self.poll_stream_notify(&unpark, 0) {
                 Ok(Async::NotReady) => unpark.park(),
@@ -340,7 +375,8 @@ impl<S: Sink> Spawn<S> {
     /// be blocked until it's able to send the value.
     pub fn wait_send(&mut self, mut value: S::SinkItem)
                      -> Result<(), S::SinkError> {
-        let notify = Arc::new(ThreadNotify::new(thread::current()));
+        let _enter = enter_blocking("wait");
+        let notify = ThreadParker::new();
         loop {
             value = match self.start_send_notify(value, &notify, 0)? {
                 AsyncSink::NotReady(v) => v,
@@ -359,7 +395,8 @@ impl<S: Sink> Spawn<S> {
     /// The thread will be blocked until `poll_complete` returns that it's
     /// ready.
     pub fn wait_flush(&mut self) -> Result<(), S::SinkError> {
-        let notify = Arc::new(ThreadNotify::new(thread::current()));
+        let _enter = enter_blocking("wait");
+        let notify = ThreadParker::new();
         loop {
             if self.poll_flush_notify(&notify, 0)?.is_ready() {
                 return Ok(())
@@ -374,7 +411,8 @@ impl<S: Sink> Spawn<S> {
     /// is not ready to be close yet, then the current thread will be blocked
     /// until it's closed.
     pub fn wait_close(&mut self) -> Result<(), S::SinkError> {
-        let notify = Arc::new(ThreadNotify::new(thread::current()));
+        let _enter = enter_blocking("wait");
+        let notify = ThreadParker::new();
         loop {
             if self.close_notify(&notify, 0)?.is_ready() {
                 return Ok(())
@@ -474,35 +512,6 @@ impl Unpark for RunInner {
     }
 }
 
-// ===== ThreadNotify =====
-
-struct ThreadNotify {
-    thread: thread::Thread,
-    ready: AtomicBool,
-}
-
-impl ThreadNotify {
-    fn new(thread: thread::Thread) -> ThreadNotify {
-        ThreadNotify {
-            thread: thread,
-            ready: AtomicBool::new(false),
-        }
-    }
-
-    fn park(&self) {
-        if !self.ready.swap(false, Ordering::SeqCst) {
-            thread::park();
-        }
-    }
-}
-
-impl Notify for ThreadNotify {
-    fn notify(&self, _unpark_id: usize) {
-        self.ready.store(true, Ordering::SeqCst);
-        self.thread.unpark()
-    }
-}
-
 // ===== UnparkEvent =====
 
 /// For the duration of the given callback, add an "unpark event" to be
@@ -538,6 +547,7 @@ pub fn with_unpark_event<F, R>(event: UnparkEvent, f: F) -> R
             unpark: task.unpark,
             events: BorrowedEvents::One(&event, &task.events),
             map: task.map,
+            current: UnsafeCell::new(None),
         };
 
         super::set(&new_task, f)
@@ -0,0 +1,79 @@
+use std::prelude::v1::*;
+
+use std::sync::Arc;
+
+use {Future, IntoFuture, Poll};
+use super::{get_local, set_local};
+
+// The name of a task, stored as a task local so it can't be confused with the
+// values set through `with_local`.
+#[derive(Clone, Debug)]
+struct TaskName(Arc<str>);
+
+/// A future which names the task running it.
+///
+/// While this future is being polled `task::name` returns its name. It is
+/// created by the `task::named` function.
+#[derive(Debug)]
+#[must_use = "futures do nothing unless polled"]
+pub struct Named<F> {
+    name: TaskName,
+    future: F,
+}
+
+/// Wraps `f` so that the task running it is named `name`.
+///
+/// This is typically used at spawn time, such as with
+/// `LocalSpawner::spawn_named`, so that any code running as part of the task
+/// can attribute log messages and errors to it through `task::name`. Any
+/// executor can run named tasks as the name travels with the future. If
+/// `Named` futures are nested the innermost name is reported.
+///
+/// # Examples
+///
+/// ```
+/// use futures::prelude::*;
+/// use futures::future;
+/// use futures::task;
+///
+/// let f = future::lazy(|| Ok::<_, ()>(task::name()));
+/// let name = task::named("worker-1", f).wait().unwrap();
+/// assert_eq!(name.as_ref().map(|s| &**s), Some("worker-1"));
+/// ```
+pub fn named<N, F>(name: N, f: F) -> Named<F::Future>
+    where N: Into<String>,
+          F: IntoFuture,
+{
+    Named {
+        name: TaskName(Arc::from(name.into())),
+        future: f.into_future(),
+    }
+}
+
+impl<F> Named<F> {
+    /// Returns the name given to this future.
+    pub fn name(&self) -> &str {
+        &self.name.0
+    }
+
+    /// Consumes this combinator, returning the underlying future.
+    pub fn into_inner(self) -> F {
+        self.future
+    }
+}
+
+impl<F: Future> Future for Named<F> {
+    type Item = F::Item;
+    type Error = F::Error;
+
+    fn poll(&mut self) -> Poll<F::Item, F::Error> {
+        let Named { ref name, ref mut future } = *self;
+        set_local(name, || future.poll())
+    }
+}
+
+/// Returns the name of the task currently being polled, if it was given one
+/// with `task::named`.
+pub fn name() -> Option<Arc<str>> {
+    get_local::<TaskName>().map(|name| name.0)
+}
@@ -0,0 +1,117 @@
+use std::prelude::v1::*;
+
+use std::mem;
+use std::ptr;
+use std::sync::{Arc, Mutex, Once};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+use task_impl::TaskId;
+
+/// Callbacks invoked around every poll of a task.
+///
+/// An observer is registered for the whole process with `set_poll_observer`,
+/// and is then called by `Spawn::poll_future_notify` and thus by every
+/// executor built on top of it. This is meant for tracing and metrics, for
+/// example to measure poll latency or to report polls which take so long
+/// that they stall the executor.
+///
+/// `task` is the id of the task being polled, as returned by
+/// `task::current_id` from within the poll. Both callbacks run on the thread
+/// polling the task, inline with the poll, so they should be cheap.
+pub trait PollObserver: Send + Sync {
+    /// Called right before the task is polled.
+    fn enter(&self, _task: TaskId) {}
+
+    /// Called right after the task was polled, with the time the poll took.
+    fn exit(&self, _task: TaskId, _elapsed: Duration) {}
+}
+
+// Set whenever an observer is registered, so that polling doesn't need to
+// touch the lock otherwise.
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+fn observer() -> &'static Mutex<Option<Arc<PollObserver>>> {
+    static INIT: Once = Once::new();
+    static mut OBSERVER: *const Mutex<Option<Arc<PollObserver>>> = ptr::null();
+
+    unsafe {
+        INIT.call_once(|| {
+            OBSERVER = Box::into_raw(Box::new(Mutex::new(None)));
+        });
+        &*OBSERVER
+    }
+}
+
+/// Registers the `PollObserver` to invoke around every poll, returning the
+/// previously registered one.
+///
+/// Passing `None` removes the current observer. Polls which are already in
+/// progress keep using the observer they started with.
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::Arc;
+/// use std::sync::atomic::{AtomicUsize, Ordering};
+/// use std::time::Duration;
+///
+/// use futures::prelude::*;
+/// use futures::executor::{self, PollObserver};
+/// use futures::task;
+///
+/// struct SlowPolls(AtomicUsize);
+///
+/// impl PollObserver for SlowPolls {
+///     fn exit(&self, _task: task::TaskId, elapsed: Duration) {
+///         if elapsed > Duration::from_millis(100) {
+///             self.0.fetch_add(1, Ordering::SeqCst);
+///         }
+///     }
+/// }
+///
+/// let slow = Arc::new(SlowPolls(AtomicUsize::new(0)));
+/// executor::set_poll_observer(Some(slow.clone()));
+/// futures::future::ok::<(), ()>(()).wait().unwrap();
+/// executor::set_poll_observer(None);
+/// ```
+pub fn set_poll_observer(new: Option<Arc<PollObserver>>)
+                         -> Option<Arc<PollObserver>> {
+    let mut slot = observer().lock().unwrap();
+    ENABLED.store(new.is_some(), Ordering::SeqCst);
+    mem::replace(&mut *slot, new)
+}
+
+pub fn observe<F, R>(task: TaskId, f: F) -> R
+    where F: FnOnce() -> R,
+{
+    if !ENABLED.load(Ordering::Relaxed) {
+        return f()
+    }
+    let observer = match observer().lock().unwrap().clone() {
+        Some(observer) => observer,
+        None => return f(),
+    };
+
+    // Report the exit even if the poll panics, so that observers tracking
+    // the tasks being polled don't get out of sync.
+    struct Exit<'a> {
+        observer: &'a PollObserver,
+        task: TaskId,
+        start: Instant,
+    }
+
+    impl<'a> Drop for Exit<'a> {
+        fn drop(&mut self) {
+            self.observer.exit(self.task, self.start.elapsed());
+        }
+    }
+
+    observer.enter(task);
+    let _exit = Exit {
+        observer: &*observer,
+        task: task,
+        start: Instant::now(),
+    };
+    f()
+}
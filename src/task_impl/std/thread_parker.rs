@@ -0,0 +1,117 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use task_impl::{Notify, NotifyHandle};
+
+/// Blocks the current thread until a task notifies it.
+///
+/// This is what `Future::wait` is built on, and makes it easy to write other
+/// blocking bridges or test harnesses: poll a future through
+/// `Spawn::poll_future_notify`, passing the parker as the notifier, and park
+/// on it whenever the future isn't ready. A notification which arrives before
+/// parking isn't lost, parking then returns right away.
+///
+/// Cloning a parker is cheap and the clones refer to the same thread. Only
+/// the thread which created the parker may park on it, the clones and the
+/// `NotifyHandle`s created from it may be used from any thread to unpark it.
+///
+/// # Examples
+///
+/// ```
+/// use futures::prelude::*;
+/// use futures::executor::{self, ThreadParker};
+/// use futures::sync::oneshot;
+///
+/// let (tx, rx) = oneshot::channel();
+/// std::thread::spawn(move || tx.send(3));
+///
+/// let parker = ThreadParker::new();
+/// let mut task = executor::spawn(rx);
+/// let value = loop {
+///     match task.poll_future_notify(&parker, 0) {
+///         Ok(Async::Ready(value)) => break value,
+///         Ok(Async::NotReady) => parker.park(),
+///         Err(e) => panic!("canceled: {}", e),
+///     }
+/// };
+/// assert_eq!(value, 3);
+/// ```
+#[derive(Clone, Debug)]
+pub struct ThreadParker {
+    inner: Arc<Inner>,
+}
+
+#[derive(Debug)]
+struct Inner {
+    thread: thread::Thread,
+    notified: AtomicBool,
+}
+
+impl ThreadParker {
+    /// Creates a new parker for the current thread.
+    pub fn new() -> ThreadParker {
+        ThreadParker {
+            inner: Arc::new(Inner {
+                thread: thread::current(),
+                notified: AtomicBool::new(false),
+            }),
+        }
+    }
+
+    /// Blocks the current thread until this parker is notified.
+    ///
+    /// Returns right away if it has been notified since the last time this
+    /// returned. Unlike `thread::park` this doesn't return spuriously.
+    pub fn park(&self) {
+        while !self.inner.notified.swap(false, Ordering::SeqCst) {
+            thread::park();
+        }
+    }
+
+    /// Blocks the current thread until this parker is notified or `timeout`
+    /// has elapsed, returning whether it was notified.
+    pub fn park_timeout(&self, timeout: Duration) -> bool {
+        let deadline = Instant::now() + timeout;
+        loop {
+            if self.inner.notified.swap(false, Ordering::SeqCst) {
+                return true
+            }
+            let now = Instant::now();
+            if now >= deadline {
+                return false
+            }
+            thread::park_timeout(deadline - now);
+        }
+    }
+
+    /// Notifies this parker, unblocking the thread parked on it.
+    pub fn unpark(&self) {
+        self.inner.notify(0);
+    }
+
+    /// Returns a handle which unparks the thread when notified.
+    pub fn notify_handle(&self) -> NotifyHandle {
+        self.inner.clone().into()
+    }
+}
+
+impl Default for ThreadParker {
+    fn default() -> ThreadParker {
+        ThreadParker::new()
+    }
+}
+
+impl Notify for Inner {
+    fn notify(&self, _id: usize) {
+        self.notified.store(true, Ordering::SeqCst);
+        self.thread.unpark()
+    }
+}
+
+impl From<ThreadParker> for NotifyHandle {
+    fn from(parker: ThreadParker) -> NotifyHandle {
+        parker.inner.into()
+    }
+}
@@ -0,0 +1,52 @@
+use core::marker;
+
+use {Future, Poll, Async};
+use super::current;
+
+/// A future which yields to the executor once before resolving.
+///
+/// This future is created by the `task::yield_now` function.
+#[derive(Debug)]
+#[must_use = "futures do nothing unless polled"]
+pub struct YieldNow<E> {
+    yielded: bool,
+    _data: marker::PhantomData<E>,
+}
+
+/// Creates a future which returns `NotReady` exactly once, notifying the
+/// current task so that it is polled again right away.
+///
+/// This lets long running computations give other tasks on the same executor
+/// a chance to run, and since the task is notified before the future returns
+/// `NotReady` it never causes the task to hang.
+///
+/// # Panics
+///
+/// Polling the returned future panics if it isn't done from within a task.
+///
+/// # Examples
+///
+/// ```
+/// use futures::prelude::*;
+/// use futures::task;
+///
+/// let f = task::yield_now::<()>().map(|()| 1);
+/// assert_eq!(f.wait(), Ok(1));
+/// ```
+pub fn yield_now<E>() -> YieldNow<E> {
+    YieldNow { yielded: false, _data: marker::PhantomData }
+}
+
+impl<E> Future for YieldNow<E> {
+    type Item = ();
+    type Error = E;
+
+    fn poll(&mut self) -> Poll<(), E> {
+        if self.yielded {
+            return Ok(Async::Ready(()))
+        }
+        self.yielded = true;
+        current().notify();
+        Ok(Async::NotReady)
+    }
+}
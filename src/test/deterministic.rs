@@ -0,0 +1,300 @@
+use std::boxed::Box;
+use std::cell::RefCell;
+use std::fmt;
+use std::mem;
+use std::rc::{Rc, Weak};
+use std::sync::{Arc, Mutex};
+use std::vec::Vec;
+
+use {Async, Future};
+use future::{Executor, ExecuteError, ExecuteErrorKind};
+use task_impl::{self, Notify, Spawn};
+
+type LocalFuture = Box<Future<Item = (), Error = ()>>;
+
+/// A single-threaded executor which only polls its tasks when told to.
+///
+/// Every task gets an id when it's spawned, and ids are handed out in order
+/// starting from 0. The pool keeps track of which tasks are *runnable*, that
+/// is were spawned or notified since they were last polled, in the order in
+/// which that happened. Tasks are never polled on their own: the test picks
+/// which task to poll next with `poll_task`, or lets the pool poll the
+/// first runnable task with `step` or all of them with `run_until_stalled`.
+/// Notifications may come from any thread and merely mark the task as
+/// runnable.
+///
+/// This makes it possible to reproduce bugs which depend on the order in
+/// which tasks are woken up and polled, and to check that some code doesn't
+/// wake tasks it shouldn't with `assert_stalled`.
+///
+/// # Examples
+///
+/// ```
+/// use futures::prelude::*;
+/// use futures::future;
+/// use futures::sync::oneshot;
+/// use futures::test::DeterministicPool;
+///
+/// let mut pool = DeterministicPool::new();
+/// let (tx, rx) = oneshot::channel::<()>();
+/// let receiver = pool.spawn(rx.map_err(|_| ()));
+/// let sender = pool.spawn(future::lazy(move || tx.send(())));
+/// assert_eq!(pool.runnable(), vec![receiver, sender]);
+///
+/// // Run the sender first, which makes the receiver runnable again.
+/// assert!(pool.poll_task(sender).is_ready());
+/// assert!(pool.poll_task(receiver).is_ready());
+/// pool.assert_stalled();
+/// ```
+pub struct DeterministicPool {
+    tasks: Vec<Option<Spawn<LocalFuture>>>,
+    live: usize,
+    shared: Rc<RefCell<Shared>>,
+    notify: Arc<ReadyNotify>,
+}
+
+// State shared with the spawners, so that spawned futures get their id right
+// away.
+struct Shared {
+    next_id: usize,
+    incoming: Vec<(usize, LocalFuture)>,
+}
+
+/// A handle used to spawn futures onto a `DeterministicPool`.
+///
+/// This implements `Executor` for all futures which are `'static`, even if
+/// they aren't `Send`. Spawning fails with `ExecuteErrorKind::Shutdown` once
+/// the pool has been dropped.
+///
+/// This structure is produced by the `DeterministicPool::spawner` method.
+#[derive(Clone)]
+pub struct DeterministicSpawner {
+    shared: Weak<RefCell<Shared>>,
+    notify: Arc<ReadyNotify>,
+}
+
+// The ids of the runnable tasks, in the order they became runnable.
+struct ReadyNotify {
+    ready: Mutex<Vec<usize>>,
+}
+
+impl Notify for ReadyNotify {
+    fn notify(&self, id: usize) {
+        let mut ready = self.ready.lock().unwrap();
+        if !ready.contains(&id) {
+            ready.push(id);
+        }
+    }
+}
+
+impl DeterministicPool {
+    /// Creates a new pool without any tasks.
+    pub fn new() -> DeterministicPool {
+        DeterministicPool {
+            tasks: Vec::new(),
+            live: 0,
+            shared: Rc::new(RefCell::new(Shared {
+                next_id: 0,
+                incoming: Vec::new(),
+            })),
+            notify: Arc::new(ReadyNotify { ready: Mutex::new(Vec::new()) }),
+        }
+    }
+
+    /// Returns a handle which can be used to spawn futures onto this pool,
+    /// also from within its tasks.
+    pub fn spawner(&self) -> DeterministicSpawner {
+        DeterministicSpawner {
+            shared: Rc::downgrade(&self.shared),
+            notify: self.notify.clone(),
+        }
+    }
+
+    /// Spawns `future` onto this pool, returning the id of the new task.
+    ///
+    /// The task starts out runnable.
+    pub fn spawn<F>(&mut self, future: F) -> usize
+        where F: Future<Item = (), Error = ()> + 'static,
+    {
+        spawn(&self.shared, &self.notify, Box::new(future))
+    }
+
+    /// Returns the ids of the runnable tasks, in the order in which they
+    /// became runnable.
+    pub fn runnable(&self) -> Vec<usize> {
+        let mut ready = self.notify.ready.lock().unwrap();
+        // Tasks may still be notified after they've completed.
+        ready.retain(|&id| self.is_pending(id));
+        ready.clone()
+    }
+
+    /// Returns whether the task `id` has been spawned and hasn't completed
+    /// yet.
+    pub fn is_pending(&self, id: usize) -> bool {
+        match self.tasks.get(id) {
+            Some(&Some(_)) => true,
+            Some(&None) => false,
+            None => self.shared.borrow().incoming.iter().any(|&(i, _)| i == id),
+        }
+    }
+
+    /// Returns the number of tasks which haven't completed yet.
+    pub fn pending(&self) -> usize {
+        self.live + self.shared.borrow().incoming.len()
+    }
+
+    /// Polls the task `id` once, whether or not it's runnable, and returns
+    /// whether it has completed.
+    ///
+    /// The task stops being runnable, unless it notifies itself while being
+    /// polled.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is no pending task `id`, or if this is called from
+    /// within a task or while another executor is running on the current
+    /// thread, see `executor::enter`.
+    pub fn poll_task(&mut self, id: usize) -> Async<()> {
+        let _enter = task_impl::enter_blocking("DeterministicPool::poll_task");
+        self.poll(id)
+    }
+
+    /// Polls the task which became runnable first, returning its id, or
+    /// `None` if no task is runnable.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this is called from within a task or while another executor
+    /// is running on the current thread, see `executor::enter`.
+    pub fn step(&mut self) -> Option<usize> {
+        self.step_with(|_| 0)
+    }
+
+    /// Polls the runnable task picked by `choose`, returning its id, or
+    /// `None` if no task is runnable.
+    ///
+    /// The closure is passed the ids of the runnable tasks, as returned by
+    /// `runnable`, and returns the index of the one to poll. This makes it
+    /// possible to explore different orders, for example randomly with a
+    /// fixed seed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the returned index is out of bounds, or if this is called
+    /// from within a task or while another executor is running on the
+    /// current thread, see `executor::enter`.
+    pub fn step_with<F>(&mut self, choose: F) -> Option<usize>
+        where F: FnOnce(&[usize]) -> usize,
+    {
+        let _enter = task_impl::enter_blocking("DeterministicPool::step");
+        let ready = self.runnable();
+        if ready.is_empty() {
+            return None
+        }
+        let id = ready[choose(&ready)];
+        self.poll(id);
+        Some(id)
+    }
+
+    /// Polls runnable tasks in the order in which they became runnable, until
+    /// none is left, and returns the number of polls.
+    ///
+    /// Note that this never returns if the tasks keep notifying each other.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this is called from within a task or while another executor
+    /// is running on the current thread, see `executor::enter`.
+    pub fn run_until_stalled(&mut self) -> usize {
+        let mut polls = 0;
+        while self.step().is_some() {
+            polls += 1;
+        }
+        polls
+    }
+
+    /// Asserts that no task is runnable.
+    ///
+    /// # Panics
+    ///
+    /// Panics, listing the runnable tasks, if there are any.
+    pub fn assert_stalled(&self) {
+        let ready = self.runnable();
+        if !ready.is_empty() {
+            panic!("expected no runnable tasks, but tasks {:?} are runnable", ready);
+        }
+    }
+
+    fn poll(&mut self, id: usize) -> Async<()> {
+        let incoming = mem::replace(&mut self.shared.borrow_mut().incoming, Vec::new());
+        for (i, future) in incoming {
+            // Ids are handed out in order, so this is the next slot.
+            debug_assert_eq!(i, self.tasks.len());
+            self.tasks.push(Some(task_impl::spawn(future)));
+            self.live += 1;
+        }
+
+        self.notify.ready.lock().unwrap().retain(|&i| i != id);
+        let res = match self.tasks.get_mut(id) {
+            Some(&mut Some(ref mut spawn)) => spawn.poll_future_notify(&self.notify, id),
+            _ => panic!("there is no pending task {}", id),
+        };
+        match res {
+            Ok(Async::NotReady) => Async::NotReady,
+            Ok(Async::Ready(())) | Err(()) => {
+                self.tasks[id] = None;
+                self.live -= 1;
+                // The task may have notified itself before completing.
+                self.notify.ready.lock().unwrap().retain(|&i| i != id);
+                Async::Ready(())
+            }
+        }
+    }
+}
+
+fn spawn(shared: &RefCell<Shared>, notify: &ReadyNotify, future: LocalFuture) -> usize {
+    let id = {
+        let mut shared = shared.borrow_mut();
+        let id = shared.next_id;
+        shared.next_id += 1;
+        shared.incoming.push((id, future));
+        id
+    };
+    notify.notify(id);
+    id
+}
+
+impl Default for DeterministicPool {
+    fn default() -> DeterministicPool {
+        DeterministicPool::new()
+    }
+}
+
+impl fmt::Debug for DeterministicPool {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("DeterministicPool")
+            .field("pending", &self.pending())
+            .field("runnable", &self.runnable())
+            .finish()
+    }
+}
+
+impl<F> Executor<F> for DeterministicSpawner
+    where F: Future<Item = (), Error = ()> + 'static,
+{
+    fn execute(&self, future: F) -> Result<(), ExecuteError<F>> {
+        match self.shared.upgrade() {
+            Some(shared) => {
+                spawn(&shared, &self.notify, Box::new(future));
+                Ok(())
+            }
+            None => Err(ExecuteError::new(ExecuteErrorKind::Shutdown, future)),
+        }
+    }
+}
+
+impl fmt::Debug for DeterministicSpawner {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("DeterministicSpawner").finish()
+    }
+}
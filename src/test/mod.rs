@@ -0,0 +1,14 @@
+//! Utilities for testing futures, streams and sinks.
+//!
+//! These make it possible to exercise code written against the traits of
+//! this crate in a controlled way, without relying on the timing of real
+//! executors: `task::MockTask` polls things by hand, while
+//! `DeterministicPool` runs whole sets of tasks in an order the test picks.
+//!
+//! This module is only available when the `use_std` feature of this library
+//! is activated, and it is activated by default.
+
+pub mod task;
+
+mod deterministic;
+pub use self::deterministic::{DeterministicPool, DeterministicSpawner};
@@ -0,0 +1,207 @@
+//! A mock task for polling futures, streams and sinks by hand in tests.
+//!
+//! Polling needs a task, so tests of combinators usually set up an executor
+//! just to poll something once. A `MockTask` instead polls right away on the
+//! current thread and counts how many times the task was notified, which is
+//! what makes it possible to check whether something that wasn't ready will
+//! wake its task up. Together with the `assert_ready!` and
+//! `assert_not_ready!` macros this reads like this:
+//!
+//! ```
+//! #[macro_use]
+//! extern crate futures;
+//!
+//! use futures::sync::oneshot;
+//! use futures::test::task::MockTask;
+//!
+//! # fn main() {
+//! let task = MockTask::new();
+//! let (tx, mut rx) = oneshot::channel();
+//!
+//! assert_not_ready!(task.poll_future(&mut rx));
+//! assert!(!task.is_notified());
+//!
+//! tx.send(5).unwrap();
+//! assert!(task.is_notified());
+//! assert_eq!(assert_ready!(task.poll_future(&mut rx)), 5);
+//! # }
+//! ```
+
+use std::fmt;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use {Async, AsyncSink, Future, Poll, Sink, StartSend, Stream};
+use future;
+use executor::{self, Notify};
+
+/// A task which futures, streams and sinks can be polled under, counting its
+/// notifications.
+///
+/// Clones of a `MockTask` are the same task and share the count.
+#[derive(Clone)]
+pub struct MockTask {
+    notify: Arc<MockNotify>,
+}
+
+struct MockNotify {
+    count: AtomicUsize,
+    // The count when the most recent poll started.
+    polled_at: AtomicUsize,
+}
+
+impl Notify for MockNotify {
+    fn notify(&self, _id: usize) {
+        self.count.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+impl MockTask {
+    /// Creates a new mock task, which hasn't been notified yet.
+    pub fn new() -> MockTask {
+        MockTask {
+            notify: Arc::new(MockNotify {
+                count: AtomicUsize::new(0),
+                polled_at: AtomicUsize::new(0),
+            }),
+        }
+    }
+
+    /// Runs `f` with this task as the current task, so that it can call
+    /// `task::current` or poll things itself.
+    pub fn enter<F, R>(&self, f: F) -> R
+        where F: FnOnce() -> R,
+    {
+        let count = self.notify.count.load(Ordering::SeqCst);
+        self.notify.polled_at.store(count, Ordering::SeqCst);
+        let mut f = Some(f);
+        let poll = future::poll_fn(|| -> Poll<R, ()> {
+            Ok(Async::Ready((f.take().unwrap())()))
+        });
+        match executor::spawn(poll).poll_future_notify(&self.notify, 0) {
+            Ok(Async::Ready(r)) => r,
+            _ => unreachable!(),
+        }
+    }
+
+    /// Polls `future` once under this task.
+    pub fn poll_future<F>(&self, future: &mut F) -> Poll<F::Item, F::Error>
+        where F: Future + ?Sized,
+    {
+        self.enter(|| future.poll())
+    }
+
+    /// Polls `stream` once under this task.
+    pub fn poll_stream<S>(&self, stream: &mut S) -> Poll<Option<S::Item>, S::Error>
+        where S: Stream + ?Sized,
+    {
+        self.enter(|| stream.poll())
+    }
+
+    /// Starts sending `item` into `sink` under this task.
+    pub fn start_send<S>(&self, sink: &mut S, item: S::SinkItem)
+                         -> StartSend<S::SinkItem, S::SinkError>
+        where S: Sink + ?Sized,
+    {
+        self.enter(|| sink.start_send(item))
+    }
+
+    /// Polls `sink` once under this task to flush it.
+    pub fn poll_complete<S>(&self, sink: &mut S) -> Poll<(), S::SinkError>
+        where S: Sink + ?Sized,
+    {
+        self.enter(|| sink.poll_complete())
+    }
+
+    /// Polls `sink` once under this task to close it.
+    pub fn close<S>(&self, sink: &mut S) -> Poll<(), S::SinkError>
+        where S: Sink + ?Sized,
+    {
+        self.enter(|| sink.close())
+    }
+
+    /// Returns the number of times this task has been notified since it was
+    /// created.
+    pub fn notify_count(&self) -> usize {
+        self.notify.count.load(Ordering::SeqCst)
+    }
+
+    /// Returns whether this task has been notified since the start of the
+    /// most recent poll under it.
+    pub fn is_notified(&self) -> bool {
+        self.notify.count.load(Ordering::SeqCst) != self.notify.polled_at.load(Ordering::SeqCst)
+    }
+}
+
+impl Default for MockTask {
+    fn default() -> MockTask {
+        MockTask::new()
+    }
+}
+
+impl fmt::Debug for MockTask {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("MockTask")
+            .field("notify_count", &self.notify_count())
+            .finish()
+    }
+}
+
+// Lets `assert_ready!` treat the results of `start_send` like the results of
+// polls.
+#[doc(hidden)]
+pub trait IntoAsync {
+    type Item;
+
+    fn into_async(self) -> Async<Self::Item>;
+}
+
+impl<T> IntoAsync for Async<T> {
+    type Item = T;
+
+    fn into_async(self) -> Async<T> {
+        self
+    }
+}
+
+impl<T> IntoAsync for AsyncSink<T> {
+    type Item = ();
+
+    fn into_async(self) -> Async<()> {
+        match self {
+            AsyncSink::Ready => Async::Ready(()),
+            AsyncSink::NotReady(_) => Async::NotReady,
+        }
+    }
+}
+
+/// Asserts that the result of a poll is ready, evaluating to the value it's
+/// ready with.
+///
+/// This takes a `Poll`, or the `StartSend` of a sink, and panics if it's not
+/// ready or an error.
+#[macro_export]
+macro_rules! assert_ready {
+    ($e:expr) => (match $e {
+        Ok(a) => match $crate::test::task::IntoAsync::into_async(a) {
+            $crate::Async::Ready(t) => t,
+            $crate::Async::NotReady => panic!("expected ready, got not ready"),
+        },
+        Err(e) => panic!("expected ready, got error: {:?}", e),
+    })
+}
+
+/// Asserts that the result of a poll isn't ready.
+///
+/// This takes a `Poll`, or the `StartSend` of a sink, and panics if it's
+/// ready or an error.
+#[macro_export]
+macro_rules! assert_not_ready {
+    ($e:expr) => (match $e {
+        Ok(a) => match $crate::test::task::IntoAsync::into_async(a) {
+            $crate::Async::Ready(_) => panic!("expected not ready, got ready"),
+            $crate::Async::NotReady => {}
+        },
+        Err(e) => panic!("expected not ready, got error: {:?}", e),
+    })
+}
@@ -0,0 +1,106 @@
+//! Offloading synchronous work onto threads.
+//!
+//! This module is only available when the `use_std` feature of this library
+//! is activated, and it is activated by default.
+
+use std::any::Any;
+use std::error::Error;
+use std::fmt;
+use std::prelude::v1::*;
+use std::thread;
+
+use {Async, Future, Poll};
+use sync::oneshot;
+use task_impl::execute_blocking;
+
+/// A future resolving to the return value of a closure run by
+/// `spawn_blocking`.
+#[must_use = "futures do nothing unless polled"]
+pub struct JoinFuture<T> {
+    rx: oneshot::Receiver<thread::Result<T>>,
+}
+
+/// Error yielded by a `JoinFuture` when its closure didn't return.
+#[derive(Debug)]
+pub enum JoinError {
+    /// The closure panicked, with the given payload.
+    Panicked(Box<Any + Send>),
+
+    /// The closure couldn't be run at all, because no thread could be
+    /// spawned for it.
+    Canceled,
+}
+
+/// Runs the closure `f` on a thread pool shared by this crate, returning a
+/// future which resolves with its return value.
+///
+/// This offloads blocking or CPU-heavy synchronous work without having to
+/// set up an `Executor` first. The pool is created on first use, starts
+/// threads on demand up to a fixed limit and lets idle threads exit after a
+/// while. It's the same pool that `task::blocking` runs on.
+///
+/// The closure starts running right away, whether or not the returned future
+/// is polled, and keeps running if the future is dropped. Unlike with
+/// `task::blocking`, a panic of the closure isn't resumed in whoever polls
+/// the future but reported as `JoinError::Panicked`, like
+/// `std::thread::JoinHandle::join` does.
+///
+/// # Examples
+///
+/// ```
+/// use futures::prelude::*;
+/// use futures::thread::{self, JoinError};
+///
+/// let f = thread::spawn_blocking(|| 1 + 2);
+/// assert_eq!(f.wait().unwrap(), 3);
+///
+/// let f = thread::spawn_blocking(|| -> i32 { panic!("oops") });
+/// match f.wait() {
+///     Err(JoinError::Panicked(_)) => {}
+///     _ => panic!("the panic should have been reported"),
+/// }
+/// ```
+pub fn spawn_blocking<F, T>(f: F) -> JoinFuture<T>
+    where F: FnOnce() -> T + Send + 'static,
+          T: Send + 'static,
+{
+    JoinFuture { rx: execute_blocking(f) }
+}
+
+impl<T> Future for JoinFuture<T> {
+    type Item = T;
+    type Error = JoinError;
+
+    fn poll(&mut self) -> Poll<T, JoinError> {
+        match self.rx.poll() {
+            Ok(Async::Ready(Ok(t))) => Ok(Async::Ready(t)),
+            Ok(Async::Ready(Err(payload))) => Err(JoinError::Panicked(payload)),
+            Ok(Async::NotReady) => Ok(Async::NotReady),
+            Err(oneshot::Canceled) => Err(JoinError::Canceled),
+        }
+    }
+}
+
+impl<T> fmt::Debug for JoinFuture<T> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("JoinFuture").finish()
+    }
+}
+
+impl fmt::Display for JoinError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            JoinError::Panicked(_) => write!(fmt, "blocking closure panicked"),
+            JoinError::Canceled => write!(fmt, "blocking closure couldn't be run"),
+        }
+    }
+}
+
+impl Error for JoinError {
+    fn description(&self) -> &str {
+        match *self {
+            JoinError::Panicked(_) => "blocking closure panicked",
+            JoinError::Canceled => "blocking closure couldn't be run",
+        }
+    }
+}
@@ -0,0 +1,53 @@
+use std::time::{Duration, Instant};
+
+use {Async, Poll, Stream};
+use super::{Delay, Timer, TimerError};
+
+/// An interval built out of a single delay which is reset after every tick.
+///
+/// This is the interval to use for an implementation of `Timer` which has no
+/// dedicated way to fire periodically.
+#[derive(Debug)]
+#[must_use = "streams do nothing unless polled"]
+pub struct DelayInterval<D> {
+    delay: D,
+    next: Instant,
+    period: Duration,
+}
+
+impl<D: Delay> DelayInterval<D> {
+    /// Creates an interval which first ticks at `at` and then every
+    /// `period`, using a delay obtained from `timer`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `period` is zero.
+    pub fn new<T>(timer: &T, at: Instant, period: Duration) -> DelayInterval<D>
+        where T: Timer<Delay = D> + ?Sized,
+    {
+        assert!(period > Duration::new(0, 0), "the period of an interval can't be zero");
+        DelayInterval {
+            delay: timer.delay_until(at),
+            next: at,
+            period: period,
+        }
+    }
+
+    /// Returns the period of this interval.
+    pub fn period(&self) -> Duration {
+        self.period
+    }
+}
+
+impl<D: Delay> Stream for DelayInterval<D> {
+    type Item = Instant;
+    type Error = TimerError;
+
+    fn poll(&mut self) -> Poll<Option<Instant>, TimerError> {
+        try_ready!(self.delay.poll());
+        let tick = self.next;
+        self.next += self.period;
+        self.delay.reset(self.next);
+        Ok(Async::Ready(Some(tick)))
+    }
+}
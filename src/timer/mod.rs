@@ -0,0 +1,155 @@
+//! Integration point for timers.
+//!
+//! This crate doesn't ship an event loop and hence doesn't have a timer of its
+//! own. Instead the time-based combinators, such as `Stream::deadline` or
+//! `Future::timeout`, are generic over the `Timer` trait in this module which
+//! timer implementations elsewhere, such as timer wheels driven by an event
+//! loop, can implement. The futures and streams a timer hands out implement
+//! the `Delay` and `Interval` traits.
+//!
+//! For programs without such a timer, `ThreadTimer` is a simple
+//! implementation keeping track of deadlines on a background thread.
+//!
+//! This module is only available when the `use_std` feature of this library
+//! is activated, and it is activated by default.
+
+use std::error::Error;
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use {Future, Stream};
+
+mod interval;
+mod thread;
+pub use self::interval::DelayInterval;
+pub use self::thread::{ThreadTimer, ThreadDelay};
+
+/// A source of futures which complete at a point in time.
+pub trait Timer {
+    /// The future returned by `delay_until`, resolving once its deadline has
+    /// been reached.
+    type Delay: Delay;
+
+    /// The stream returned by `interval_at`, ticking periodically.
+    ///
+    /// Implementations without a dedicated interval of their own can use
+    /// `DelayInterval<Self::Delay>`.
+    type Interval: Interval;
+
+    /// Returns a future which resolves at `at`.
+    ///
+    /// If `at` is in the past then the returned future should resolve right
+    /// away.
+    fn delay_until(&self, at: Instant) -> Self::Delay;
+
+    /// Returns a future which resolves once `duration` has elapsed.
+    fn delay(&self, duration: Duration) -> Self::Delay {
+        self.delay_until(self.now() + duration)
+    }
+
+    /// Returns a stream which first ticks at `at` and then every `period`.
+    ///
+    /// Each tick yields the instant it was scheduled for. Ticks which were
+    /// missed because the stream wasn't polled in time should be yielded
+    /// right away once it is.
+    ///
+    /// # Panics
+    ///
+    /// Implementations may panic if `period` is zero.
+    fn interval_at(&self, at: Instant, period: Duration) -> Self::Interval;
+
+    /// Returns a stream which ticks every `period`, starting one `period`
+    /// from now.
+    fn interval(&self, period: Duration) -> Self::Interval {
+        self.interval_at(self.now() + period, period)
+    }
+
+    /// Returns the current time according to this timer.
+    ///
+    /// Combinators which need to know the time, rather than just wait for it,
+    /// use this so that timers with their own notion of time are respected.
+    /// This defaults to `Instant::now`.
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+impl<'a, T: ?Sized + Timer> Timer for &'a T {
+    type Delay = T::Delay;
+    type Interval = T::Interval;
+
+    fn delay_until(&self, at: Instant) -> T::Delay {
+        (**self).delay_until(at)
+    }
+
+    fn delay(&self, duration: Duration) -> T::Delay {
+        (**self).delay(duration)
+    }
+
+    fn interval_at(&self, at: Instant, period: Duration) -> T::Interval {
+        (**self).interval_at(at, period)
+    }
+
+    fn interval(&self, period: Duration) -> T::Interval {
+        (**self).interval(period)
+    }
+
+    fn now(&self) -> Instant {
+        (**self).now()
+    }
+}
+
+/// A future handed out by a `Timer`, resolving once a deadline is reached.
+///
+/// Unlike a plain future a delay can be moved to another deadline, which
+/// lets combinators waiting on ever changing deadlines, like
+/// `Stream::debounce`, reuse a single delay instead of asking the timer for a
+/// new one every time.
+pub trait Delay: Future<Item = (), Error = TimerError> {
+    /// Changes the deadline of this delay to `at`.
+    ///
+    /// This may be called whether or not the delay has already resolved, and
+    /// the delay then resolves once `at` is reached, or right away if `at` is
+    /// in the past.
+    fn reset(&mut self, at: Instant);
+}
+
+/// A stream handed out by a `Timer`, yielding the instants of periodic ticks.
+///
+/// This is implemented for all streams of instants which fail with a
+/// `TimerError`.
+pub trait Interval: Stream<Item = Instant, Error = TimerError> {}
+
+impl<S> Interval for S
+    where S: Stream<Item = Instant, Error = TimerError> + ?Sized,
+{}
+
+/// Error returned by the futures of a `Timer` when the timer can no longer
+/// keep track of time, for example because it was shut down.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TimerError(());
+
+impl TimerError {
+    /// Creates a new `TimerError`, for use by implementations of `Timer`.
+    pub fn new() -> TimerError {
+        TimerError(())
+    }
+}
+
+impl Default for TimerError {
+    fn default() -> TimerError {
+        TimerError::new()
+    }
+}
+
+impl fmt::Display for TimerError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "timer is no longer running")
+    }
+}
+
+impl Error for TimerError {
+    fn description(&self) -> &str {
+        "timer is no longer running"
+    }
+}
@@ -0,0 +1,377 @@
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use std::fmt;
+use std::mem;
+use std::prelude::v1::*;
+use std::sync::{Arc, Condvar, Mutex};
+use std::sync::atomic::{AtomicUsize, Ordering as AtomicOrdering};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use {Async, Future, Poll};
+use task::AtomicTask;
+use super::{Delay, DelayInterval, Timer, TimerError};
+
+/// A `Timer` keeping track of deadlines on a background thread.
+///
+/// This is a fallback for programs without an event loop providing a timer.
+/// A thread is spawned by `ThreadTimer::new` which sleeps until the earliest
+/// pending deadline and then notifies the task waiting for it. Clones of a
+/// `ThreadTimer` share the same thread.
+///
+/// Once all clones of a timer are dropped the thread exits, and the delays
+/// which haven't resolved yet fail with a `TimerError`.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::{Duration, Instant};
+///
+/// use futures::prelude::*;
+/// use futures::timer::{Timer, ThreadTimer};
+///
+/// let timer = ThreadTimer::new();
+/// let start = Instant::now();
+/// timer.delay(Duration::from_millis(10)).wait().unwrap();
+/// assert!(start.elapsed() >= Duration::from_millis(10));
+/// ```
+#[derive(Clone)]
+pub struct ThreadTimer {
+    handle: Arc<Handle>,
+}
+
+/// The delay handed out by a `ThreadTimer`.
+#[must_use = "futures do nothing unless polled"]
+pub struct ThreadDelay {
+    inner: Arc<Inner>,
+    slot: Arc<Slot>,
+    at: Instant,
+}
+
+// Shuts the thread down once the last `ThreadTimer` is gone.
+struct Handle {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    state: Mutex<State>,
+    condvar: Condvar,
+}
+
+struct State {
+    entries: BinaryHeap<Entry>,
+    // The number of entries which no longer match the deadline queued for
+    // their slot, as the delay was moved to an earlier deadline or dropped.
+    stale: usize,
+    shutdown: bool,
+}
+
+struct Entry {
+    at: Instant,
+    generation: usize,
+    slot: Arc<Slot>,
+}
+
+// Where the thread reports back to a `ThreadDelay`, which keeps the same slot
+// across all of its deadlines.
+struct Slot {
+    state: AtomicUsize,
+    task: AtomicTask,
+    // Only accessed with the lock of `State` held.
+    times: Mutex<Times>,
+}
+
+struct Times {
+    // The current deadline of the delay.
+    deadline: Instant,
+    // The deadline of the entry of this slot in the heap, if any. When the
+    // delay is reset to a later deadline the entry stays where it is, and
+    // it's queued again for the new deadline once it comes up.
+    queued: Option<Instant>,
+    // Tells the queued entry apart from stale ones of the same slot.
+    generation: usize,
+}
+
+const WAITING: usize = 0;
+const FIRED: usize = 1;
+const SHUTDOWN: usize = 2;
+
+impl ThreadTimer {
+    /// Creates a new timer, spawning the thread which runs it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the thread can't be spawned.
+    pub fn new() -> ThreadTimer {
+        let inner = Arc::new(Inner {
+            state: Mutex::new(State {
+                entries: BinaryHeap::new(),
+                stale: 0,
+                shutdown: false,
+            }),
+            condvar: Condvar::new(),
+        });
+        let thread_inner = inner.clone();
+        thread::Builder::new()
+            .name("futures-timer".to_string())
+            .spawn(move || run(&thread_inner))
+            .expect("failed to spawn timer thread");
+        ThreadTimer {
+            handle: Arc::new(Handle { inner: inner }),
+        }
+    }
+}
+
+impl Default for ThreadTimer {
+    fn default() -> ThreadTimer {
+        ThreadTimer::new()
+    }
+}
+
+impl Timer for ThreadTimer {
+    type Delay = ThreadDelay;
+    type Interval = DelayInterval<ThreadDelay>;
+
+    fn delay_until(&self, at: Instant) -> ThreadDelay {
+        let inner = self.handle.inner.clone();
+        let slot = Arc::new(Slot {
+            state: AtomicUsize::new(WAITING),
+            task: AtomicTask::new(),
+            times: Mutex::new(Times {
+                deadline: at,
+                queued: None,
+                generation: 0,
+            }),
+        });
+        inner.schedule(&slot, at);
+        ThreadDelay {
+            inner: inner,
+            slot: slot,
+            at: at,
+        }
+    }
+
+    fn interval_at(&self, at: Instant, period: Duration) -> DelayInterval<ThreadDelay> {
+        DelayInterval::new(self, at, period)
+    }
+}
+
+impl fmt::Debug for ThreadTimer {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        let state = self.handle.inner.state.lock().unwrap();
+        fmt.debug_struct("ThreadTimer")
+            .field("pending", &state.entries.len())
+            .finish()
+    }
+}
+
+impl Drop for Handle {
+    fn drop(&mut self) {
+        self.inner.state.lock().unwrap().shutdown = true;
+        self.inner.condvar.notify_one();
+    }
+}
+
+impl Inner {
+    fn schedule(&self, slot: &Arc<Slot>, at: Instant) {
+        let mut state = self.state.lock().unwrap();
+        if state.shutdown {
+            slot.state.store(SHUTDOWN, AtomicOrdering::SeqCst);
+            return
+        }
+        slot.state.store(WAITING, AtomicOrdering::SeqCst);
+        let mut times = slot.times.lock().unwrap();
+        times.deadline = at;
+        match times.queued {
+            // The thread queues the entry again once it comes up.
+            Some(queued) if queued <= at => return,
+            Some(_) => state.stale += 1,
+            None => {}
+        }
+        let entry = times.queue(at, slot.clone());
+        drop(times);
+
+        // The thread only has to wake up early if this is the new earliest
+        // deadline.
+        let earliest = state.entries.peek().map_or(true, |e| at < e.at);
+        state.entries.push(entry);
+        state.compact();
+        if earliest {
+            self.condvar.notify_one();
+        }
+    }
+
+    fn cancel(&self, slot: &Slot) {
+        let mut state = self.state.lock().unwrap();
+        if slot.times.lock().unwrap().queued.take().is_some() {
+            state.stale += 1;
+            state.compact();
+        }
+    }
+}
+
+impl State {
+    // Drops the stale entries once they make up most of the heap, so that
+    // it doesn't grow with the number of delays which are moved around or
+    // dropped before their deadline.
+    fn compact(&mut self) {
+        if self.stale <= self.entries.len() / 2 {
+            return
+        }
+        let entries = mem::replace(&mut self.entries, BinaryHeap::new());
+        self.entries = entries.into_iter()
+            .filter(|e| e.is_live())
+            .collect();
+        self.stale = 0;
+    }
+}
+
+impl Times {
+    fn queue(&mut self, at: Instant, slot: Arc<Slot>) -> Entry {
+        self.queued = Some(at);
+        self.generation = self.generation.wrapping_add(1);
+        Entry {
+            at: at,
+            generation: self.generation,
+            slot: slot,
+        }
+    }
+
+    fn is_queued(&self, entry: &Entry) -> bool {
+        self.queued.is_some() && self.generation == entry.generation
+    }
+}
+
+impl Entry {
+    fn is_live(&self) -> bool {
+        self.slot.times.lock().unwrap().is_queued(self)
+    }
+}
+
+fn run(inner: &Inner) {
+    let mut state = inner.state.lock().unwrap();
+    loop {
+        if state.shutdown {
+            let entries = state.entries.drain().collect::<Vec<_>>();
+            drop(state);
+            for entry in entries {
+                entry.slot.finish(SHUTDOWN);
+            }
+            return
+        }
+
+        let now = Instant::now();
+        let mut fired = Vec::new();
+        while state.entries.peek().map_or(false, |e| e.at <= now) {
+            let entry = state.entries.pop().unwrap();
+            let mut times = entry.slot.times.lock().unwrap();
+            if !times.is_queued(&entry) {
+                drop(times);
+                state.stale -= 1;
+                continue
+            }
+            if times.deadline > entry.at {
+                // The delay has been reset to a later deadline since.
+                let deadline = times.deadline;
+                let entry = times.queue(deadline, entry.slot.clone());
+                drop(times);
+                state.entries.push(entry);
+                continue
+            }
+            times.queued = None;
+            drop(times);
+            // Resolved with the lock held, so that a `reset` racing with
+            // this is sure to see it and start over.
+            entry.slot.state.store(FIRED, AtomicOrdering::SeqCst);
+            fired.push(entry);
+        }
+        if !fired.is_empty() {
+            // Notify without holding the lock, as tasks may well schedule
+            // new deadlines right away.
+            drop(state);
+            for entry in fired {
+                entry.slot.task.notify();
+            }
+            state = inner.state.lock().unwrap();
+            continue
+        }
+
+        state = match state.entries.peek().map(|e| e.at) {
+            Some(at) => inner.condvar.wait_timeout(state, at - now).unwrap().0,
+            None => inner.condvar.wait(state).unwrap(),
+        };
+    }
+}
+
+impl Slot {
+    fn finish(&self, state: usize) {
+        self.state.store(state, AtomicOrdering::SeqCst);
+        self.task.notify();
+    }
+
+    fn poll(&self) -> Poll<(), TimerError> {
+        match self.state.load(AtomicOrdering::SeqCst) {
+            FIRED => Ok(Async::Ready(())),
+            SHUTDOWN => Err(TimerError::new()),
+            _ => Ok(Async::NotReady),
+        }
+    }
+}
+
+impl Future for ThreadDelay {
+    type Item = ();
+    type Error = TimerError;
+
+    fn poll(&mut self) -> Poll<(), TimerError> {
+        if self.at <= Instant::now() {
+            return Ok(Async::Ready(()))
+        }
+        if let Async::Ready(()) = self.slot.poll()? {
+            return Ok(Async::Ready(()))
+        }
+        self.slot.task.register();
+        self.slot.poll()
+    }
+}
+
+impl Delay for ThreadDelay {
+    fn reset(&mut self, at: Instant) {
+        self.inner.schedule(&self.slot, at);
+        self.at = at;
+    }
+}
+
+impl Drop for ThreadDelay {
+    fn drop(&mut self) {
+        self.inner.cancel(&self.slot);
+    }
+}
+
+impl fmt::Debug for ThreadDelay {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("ThreadDelay")
+            .field("at", &self.at)
+            .finish()
+    }
+}
+
+impl PartialEq for Entry {
+    fn eq(&self, other: &Entry) -> bool {
+        self.at == other.at
+    }
+}
+
+impl Eq for Entry {}
+
+impl PartialOrd for Entry {
+    fn partial_cmp(&self, other: &Entry) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Entry {
+    // Reversed, so that the heap yields the earliest deadline first.
+    fn cmp(&self, other: &Entry) -> Ordering {
+        other.at.cmp(&self.at)
+    }
+}
@@ -172,6 +172,10 @@ impl<T> Stream for Receiver<T> {
     type Error = ();
 
     fn poll(&mut self) -> Poll<Option<Self::Item>, Self::Error> {
+        if task::poll_budget().is_not_ready() {
+            return Ok(Async::NotReady)
+        }
+
         let me = match self.state {
             State::Open(ref mut me) => me,
             State::Closed(ref mut items) => {
@@ -191,12 +195,27 @@ impl<T> Stream for Receiver<T> {
                 drop(shared);
                 task.notify();
             }
+            task::consume_budget();
             Ok(Async::Ready(Some(msg)))
         } else {
             shared.blocked_recv = Some(task::current());
             Ok(Async::NotReady)
         }
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self.state {
+            State::Open(ref me) => {
+                let len = me.borrow().buffer.len();
+                if Rc::strong_count(me) == 1 && Rc::weak_count(me) == 0 {
+                    (len, Some(len))
+                } else {
+                    (len, None)
+                }
+            }
+            State::Closed(ref items) => (items.len(), Some(items.len())),
+        }
+    }
 }
 
 impl<T> Drop for Receiver<T> {
@@ -304,6 +323,10 @@ impl<T> Stream for UnboundedReceiver<T> {
     fn poll(&mut self) -> Poll<Option<Self::Item>, Self::Error> {
         self.0.poll()
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
 }
 
 /// Creates an unbounded in-memory channel with buffered storage.
@@ -439,6 +462,12 @@ impl<I, E> Stream for SpawnHandle<I, E> {
             Err(_) => unreachable!("mpsc::Receiver should never return Err"),
         }
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        // Any of the buffered results may be an error.
+        let (_, upper) = self.inner.size_hint();
+        (0, upper)
+    }
 }
 
 impl<I, E> fmt::Debug for SpawnHandle<I, E> {
@@ -251,6 +251,42 @@ fn collect_collects() {
     // TODO: needs more tests
 }
 
+#[test]
+fn collect_polls_notified_children() {
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    let polls = Rc::new(Cell::new(0));
+    let mut txs = Vec::new();
+    let mut rxs = Vec::new();
+    for _ in 0..100 {
+        let (tx, mut rx) = oneshot::channel::<u32>();
+        let polls = polls.clone();
+        txs.push(tx);
+        rxs.push(poll_fn(move || {
+            polls.set(polls.get() + 1);
+            rx.poll()
+        }));
+    }
+
+    let mut f = executor::spawn(join_all(rxs).map_err(|_| ()));
+    assert!(f.poll_future_notify(&notify_noop(), 0).unwrap().is_not_ready());
+    assert_eq!(polls.get(), 100);
+
+    // Only the child which was notified is polled again, and results come
+    // back in input order however the children complete.
+    let mut txs = txs.into_iter().enumerate().rev();
+    let (i, tx) = txs.next().unwrap();
+    tx.send(i as u32).unwrap();
+    assert!(f.poll_future_notify(&notify_noop(), 0).unwrap().is_not_ready());
+    assert_eq!(polls.get(), 101);
+    for (i, tx) in txs {
+        tx.send(i as u32).unwrap();
+    }
+    assert_eq!(f.wait_future(), Ok((0..100).collect::<Vec<_>>()));
+    assert_eq!(polls.get(), 200);
+}
+
 #[test]
 fn select2() {
     fn d<T, U, E>(r: Result<(T, U), (E, U)>) -> Result<T, E> {
@@ -359,3 +395,16 @@ fn option() {
     assert_eq!(Ok(Some(())), Some(ok::<(), ()>(())).wait());
     assert_eq!(Ok(None), <Option<FutureResult<(), ()>> as Future>::wait(None));
 }
+
+#[test]
+fn either() {
+    let pick = |left: bool| {
+        if left {
+            f_ok(1).left_future()
+        } else {
+            f_err(2).right_future()
+        }
+    };
+    assert_done(|| pick(true), Ok(1));
+    assert_done(|| pick(false), Err(2));
+}
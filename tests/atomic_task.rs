@@ -0,0 +1,47 @@
+extern crate futures;
+
+use futures::task::AtomicTask;
+use futures::test::task::MockTask;
+
+#[test]
+fn register_same_task() {
+    let mock = MockTask::new();
+    let atomic = AtomicTask::new();
+    assert!(!atomic.notify_if_registered());
+
+    mock.enter(|| {
+        assert!(!atomic.will_notify_current());
+        atomic.register();
+        assert!(atomic.will_notify_current());
+        // The handle in place stays there.
+        atomic.register();
+    });
+    assert!(atomic.notify_if_registered());
+    assert_eq!(mock.notify_count(), 1);
+
+    // Another task replaces the registered one.
+    let other = MockTask::new();
+    other.enter(|| {
+        assert!(!atomic.will_notify_current());
+        atomic.register();
+    });
+    atomic.notify();
+    assert_eq!(mock.notify_count(), 1);
+    assert_eq!(other.notify_count(), 1);
+}
+
+#[test]
+fn take() {
+    let mock = MockTask::new();
+    let atomic = AtomicTask::new();
+    assert!(atomic.take().is_none());
+
+    mock.enter(|| atomic.register());
+    let task = atomic.take().unwrap();
+    assert!(mock.enter(|| task.will_notify_current()));
+
+    // Nothing is registered anymore.
+    assert!(!atomic.notify_if_registered());
+    assert!(atomic.take().is_none());
+    assert_eq!(mock.notify_count(), 0);
+}
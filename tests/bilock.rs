@@ -7,6 +7,7 @@ use futures::executor;
 use futures::stream;
 use futures::future;
 use futures::sync::BiLock;
+use futures::test::task::MockTask;
 
 mod support;
 use support::*;
@@ -107,3 +108,26 @@ fn concurrent() {
         }
     }
 }
+
+#[test]
+fn unlock_notifies_parked_half() {
+    let (a, b) = BiLock::new(1);
+    let task_a = MockTask::new();
+    let task_b = MockTask::new();
+
+    let guard = match task_a.enter(|| a.poll_lock()) {
+        Async::Ready(guard) => guard,
+        Async::NotReady => panic!("should be unlocked"),
+    };
+    assert!(task_b.enter(|| b.poll_lock()).is_not_ready());
+    assert_eq!(task_b.notify_count(), 0);
+
+    // Only the half which has been parked is notified, and only once.
+    drop(guard);
+    assert_eq!(task_a.notify_count(), 0);
+    assert_eq!(task_b.notify_count(), 1);
+    assert!(task_a.enter(|| a.poll_lock()).is_ready());
+    assert_eq!(task_b.notify_count(), 1);
+
+    assert!(task_b.enter(|| b.poll_lock()).is_ready());
+}
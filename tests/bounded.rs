@@ -0,0 +1,78 @@
+#![cfg(feature = "use_std")]
+
+extern crate futures;
+
+use std::cell::Cell;
+use std::rc::Rc;
+
+use futures::prelude::*;
+use futures::executor::{Bounded, LocalPool};
+use futures::future::{self, lazy, Executor, ExecuteError, ExecuteErrorKind};
+use futures::sync::oneshot;
+
+#[test]
+fn limits_in_flight() {
+    let mut pool = LocalPool::new();
+    let bounded = Bounded::new(pool.spawner(), 1);
+    assert_eq!(bounded.max_in_flight(), 1);
+
+    let (tx, rx) = oneshot::channel::<()>();
+    bounded.execute(rx.map_err(|_| ())).unwrap();
+    assert_eq!(bounded.in_flight(), 1);
+    let err = bounded.clone().execute(future::ok(())).unwrap_err();
+    assert_eq!(err.kind(), ExecuteErrorKind::NoCapacity);
+
+    // Still in flight while the task is pending.
+    assert_eq!(pool.run_until(future::ok::<_, ()>(())), Ok(()));
+    assert_eq!(bounded.in_flight(), 1);
+
+    tx.send(()).unwrap();
+    pool.run();
+    assert_eq!(bounded.in_flight(), 0);
+
+    // Tasks which are dropped without completing release their slot too.
+    let (_tx, rx) = oneshot::channel::<()>();
+    bounded.execute(rx.map_err(|_| ())).unwrap();
+    drop(pool);
+    assert_eq!(bounded.in_flight(), 0);
+}
+
+#[test]
+fn rejected_by_inner() {
+    struct Reject;
+
+    impl<F: Future<Item = (), Error = ()>> Executor<F> for Reject {
+        fn execute(&self, f: F) -> Result<(), ExecuteError<F>> {
+            Err(ExecuteError::new(ExecuteErrorKind::Shutdown, f))
+        }
+    }
+
+    let bounded = Bounded::new(Reject, 1);
+    let err = bounded.execute(future::ok(())).unwrap_err();
+    assert_eq!(err.kind(), ExecuteErrorKind::Shutdown);
+    assert_eq!(bounded.in_flight(), 0);
+}
+
+#[test]
+fn spawn_ready() {
+    let mut pool = LocalPool::new();
+    let bounded = Bounded::new(pool.spawner(), 2);
+    let (tx, rx) = oneshot::channel::<()>();
+    bounded.execute(rx.map_err(|_| ())).unwrap();
+    bounded.execute(future::ok(())).unwrap();
+
+    // Spawn a third future once one of the first two has completed.
+    let spawned = Rc::new(Cell::new(false));
+    let spawned2 = spawned.clone();
+    let bounded2 = bounded.clone();
+    let res = pool.run_until(bounded.spawn_ready().and_then(move |()| {
+        bounded2.execute(lazy(move || {
+            spawned2.set(true);
+            tx.send(()).map_err(|_| ())
+        })).map_err(|_| ())
+    }));
+    assert_eq!(res, Ok(()));
+    pool.run();
+    assert!(spawned.get());
+    assert_eq!(bounded.in_flight(), 0);
+}
@@ -0,0 +1,67 @@
+extern crate futures;
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use futures::prelude::*;
+use futures::executor::{self, Notify};
+use futures::future;
+use futures::stream::BoundedFuturesUnordered;
+use futures::sync::oneshot;
+
+mod support;
+
+struct Flag(AtomicBool);
+
+impl Notify for Flag {
+    fn notify(&self, _id: usize) {
+        self.0.store(true, Ordering::SeqCst)
+    }
+}
+
+// Pushes from within a task notifying `flag`.
+fn push<F: Future>(set: &mut BoundedFuturesUnordered<F>, f: F, flag: &Arc<Flag>)
+                   -> AsyncSink<F> {
+    let mut f = Some(f);
+    let mut task = executor::spawn(future::poll_fn(|| {
+        Ok::<_, ()>(Async::Ready(set.poll_push(f.take().unwrap())))
+    }));
+    match task.poll_future_notify(flag, 0) {
+        Ok(Async::Ready(res)) => res,
+        _ => panic!("push didn't complete"),
+    }
+}
+
+#[test]
+fn backpressure() {
+    let (a_tx, a_rx) = oneshot::channel::<u32>();
+    let (b_tx, b_rx) = oneshot::channel::<u32>();
+    let (_c_tx, c_rx) = oneshot::channel::<u32>();
+    let flag = Arc::new(Flag(AtomicBool::new(false)));
+
+    let mut set = BoundedFuturesUnordered::new(2);
+    assert!(push(&mut set, a_rx, &flag).is_ready());
+    assert!(push(&mut set, b_rx, &flag).is_ready());
+    assert!(set.is_full());
+    let c_rx = match push(&mut set, c_rx, &flag) {
+        AsyncSink::NotReady(c_rx) => c_rx,
+        AsyncSink::Ready => panic!("pushed into a full set"),
+    };
+    assert_eq!(set.len(), 2);
+
+    // Completing a future doesn't free its slot until its result is taken.
+    let mut set = executor::spawn(set);
+    a_tx.send(1).unwrap();
+    assert!(!flag.0.load(Ordering::SeqCst));
+    assert_eq!(set.wait_stream(), Some(Ok(1)));
+    assert!(flag.0.load(Ordering::SeqCst));
+
+    let mut set = set.into_inner();
+    assert!(!set.is_full());
+    assert!(push(&mut set, c_rx, &flag).is_ready());
+    drop(b_tx);
+    let mut set = executor::spawn(set);
+    assert_eq!(set.wait_stream(), Some(Err(oneshot::Canceled)));
+    assert!(set.poll_stream_notify(&support::notify_noop(), 0).unwrap().is_not_ready());
+    assert_eq!(set.get_ref().len(), 1);
+}
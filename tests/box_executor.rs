@@ -0,0 +1,74 @@
+#![cfg(feature = "use_std")]
+
+extern crate futures;
+
+use std::sync::{Arc, Mutex};
+
+use futures::prelude::*;
+use futures::executor::{BoxExecutor, BoxedFuture};
+use futures::future::{self, Executor, ExecuteError, ExecuteErrorKind};
+
+// Queues up futures to be run later, rejecting them once `closed` is set.
+#[derive(Default)]
+struct Queue {
+    futures: Mutex<Vec<BoxedFuture>>,
+    closed: Mutex<bool>,
+}
+
+impl Executor<BoxedFuture> for Queue {
+    fn execute(&self, f: BoxedFuture) -> Result<(), ExecuteError<BoxedFuture>> {
+        if *self.closed.lock().unwrap() {
+            return Err(ExecuteError::new(ExecuteErrorKind::Shutdown, f))
+        }
+        self.futures.lock().unwrap().push(f);
+        Ok(())
+    }
+}
+
+fn assert_send_sync<T: Send + Sync + Clone>(_: &T) {}
+
+#[test]
+fn box_executor() {
+    let queue = Arc::new(Queue::default());
+    // Through the blanket impl for `Arc`.
+    let exec = BoxExecutor::new(queue.clone());
+    assert_send_sync(&exec);
+
+    let (tx, rx) = futures::sync::oneshot::channel();
+    exec.clone().execute(future::lazy(move || tx.send(1).map_err(|_| ()))).unwrap();
+    for f in queue.futures.lock().unwrap().drain(..) {
+        f.wait().unwrap();
+    }
+    assert_eq!(rx.wait(), Ok(1));
+
+    // Rejected futures come back with their original type.
+    *queue.closed.lock().unwrap() = true;
+    let err = exec.execute(future::ok::<(), ()>(())).unwrap_err();
+    assert_eq!(err.kind(), ExecuteErrorKind::Shutdown);
+    let f: future::FutureResult<(), ()> = err.into_future();
+    assert_eq!(f.wait(), Ok(()));
+}
+
+struct Inline;
+
+impl<F: Future<Item = (), Error = ()>> Executor<F> for Inline {
+    fn execute(&self, f: F) -> Result<(), ExecuteError<F>> {
+        let _ = f.wait();
+        Ok(())
+    }
+}
+
+fn spawn_one<E: Executor<future::FutureResult<(), ()>>>(exec: E) {
+    exec.execute(future::ok(())).unwrap();
+}
+
+#[test]
+fn blanket_impls() {
+    spawn_one(&Inline);
+    spawn_one(Box::new(Inline));
+    spawn_one(Arc::new(Inline));
+    spawn_one(&*Box::new(Inline));
+    let boxed: Box<Executor<future::FutureResult<(), ()>>> = Box::new(Inline);
+    spawn_one(boxed);
+    spawn_one(BoxExecutor::new(Inline));
+}
@@ -0,0 +1,191 @@
+extern crate futures;
+
+use std::io as std_io;
+
+use futures::prelude::*;
+use futures::codec::{Decoder, Framed, FramedRead, FramedWrite, LengthDelimitedCodec,
+                     LinesCodec};
+use futures::io::{AsyncRead, AsyncWrite};
+use futures::task;
+
+// An I/O object handing out its input a chunk at a time, and only taking a
+// few bytes per write, not being ready in between.
+#[derive(Debug)]
+struct Chunked {
+    input: Vec<Vec<u8>>,
+    output: Vec<u8>,
+    ready: bool,
+    shutdown: bool,
+}
+
+fn chunked(input: Vec<&[u8]>) -> Chunked {
+    Chunked {
+        input: input.into_iter().rev().map(|c| c.to_vec()).collect(),
+        output: Vec::new(),
+        ready: false,
+        shutdown: false,
+    }
+}
+
+impl Chunked {
+    fn ready(&mut self) -> bool {
+        self.ready = !self.ready;
+        if !self.ready {
+            task::current().notify();
+        }
+        self.ready
+    }
+}
+
+impl AsyncRead for Chunked {
+    fn poll_read(&mut self, buf: &mut [u8]) -> Poll<usize, std_io::Error> {
+        if !self.ready() {
+            return Ok(Async::NotReady)
+        }
+        match self.input.pop() {
+            Some(chunk) => {
+                buf[..chunk.len()].copy_from_slice(&chunk);
+                Ok(Async::Ready(chunk.len()))
+            }
+            None => Ok(Async::Ready(0)),
+        }
+    }
+}
+
+impl AsyncWrite for Chunked {
+    fn poll_write(&mut self, buf: &[u8]) -> Poll<usize, std_io::Error> {
+        if !self.ready() {
+            return Ok(Async::NotReady)
+        }
+        let n = buf.len().min(3);
+        self.output.extend_from_slice(&buf[..n]);
+        Ok(Async::Ready(n))
+    }
+
+    fn poll_flush(&mut self) -> Poll<(), std_io::Error> {
+        Ok(Async::Ready(()))
+    }
+
+    fn poll_shutdown(&mut self) -> Poll<(), std_io::Error> {
+        self.shutdown = true;
+        Ok(Async::Ready(()))
+    }
+}
+
+#[test]
+fn lines_across_chunks() {
+    let io = chunked(vec![b"hel", b"lo\r\nwor", b"ld\n\nlast"]);
+    let lines = FramedRead::new(io, LinesCodec::new()).collect().wait().unwrap();
+    assert_eq!(lines, ["hello", "world", "", "last"]);
+}
+
+#[test]
+fn lines_too_long() {
+    let io = chunked(vec![b"abc", b"def\n"]);
+    let mut lines = FramedRead::new(io, LinesCodec::with_max_length(4)).wait();
+    assert_eq!(lines.next().unwrap().unwrap_err().kind(), std_io::ErrorKind::InvalidData);
+
+    let io = chunked(vec![b"ab\nabcde\n"]);
+    let mut lines = FramedRead::new(io, LinesCodec::with_max_length(4)).wait();
+    assert_eq!(lines.next().unwrap().unwrap(), "ab");
+    assert_eq!(lines.next().unwrap().unwrap_err().kind(), std_io::ErrorKind::InvalidData);
+}
+
+#[test]
+fn lines_invalid_utf8() {
+    let io = chunked(vec![b"\xff\n"]);
+    let mut lines = FramedRead::new(io, LinesCodec::new()).wait();
+    assert_eq!(lines.next().unwrap().unwrap_err().kind(), std_io::ErrorKind::InvalidData);
+}
+
+#[test]
+fn length_delimited_across_chunks() {
+    let io = chunked(vec![b"\0\0", b"\0\x03ab", b"c\0\0\0\0\0\0\0\x01d"]);
+    let frames = FramedRead::new(io, LengthDelimitedCodec::new()).collect().wait().unwrap();
+    assert_eq!(frames, [b"abc".to_vec(), Vec::new(), b"d".to_vec()]);
+}
+
+#[test]
+fn length_delimited_limits() {
+    let io = chunked(vec![b"\0\0\0\x05abc"]);
+    let mut frames = FramedRead::new(io, LengthDelimitedCodec::with_max_frame_length(4)).wait();
+    assert_eq!(frames.next().unwrap().unwrap_err().kind(), std_io::ErrorKind::InvalidData);
+
+    // A frame cut off by the end of the transport.
+    let io = chunked(vec![b"\0\0\0\x05abc"]);
+    let mut frames = FramedRead::new(io, LengthDelimitedCodec::new()).wait();
+    assert_eq!(frames.next().unwrap().unwrap_err().kind(), std_io::ErrorKind::UnexpectedEof);
+
+    let sink = FramedWrite::new(Vec::new(), LengthDelimitedCodec::with_max_frame_length(2));
+    let err = sink.send(b"abc".to_vec()).wait().err().unwrap();
+    assert_eq!(err.kind(), std_io::ErrorKind::InvalidData);
+}
+
+#[test]
+fn write_frames() {
+    let sink = FramedWrite::new(chunked(vec![]), LengthDelimitedCodec::new());
+    let frames = vec![b"hello".to_vec(), b"world".to_vec()];
+    let mut sink = sink.send_all(futures::stream::iter_ok::<_, std_io::Error>(frames))
+        .wait()
+        .unwrap()
+        .0;
+    assert_eq!(sink.get_ref().output, b"\0\0\0\x05hello\0\0\0\x05world");
+    assert!(sink.write_buffer().is_empty());
+    // `send_all` closes the sink, which shuts the transport down.
+    assert!(sink.get_mut().shutdown);
+}
+
+#[test]
+fn write_backpressure() {
+    let mut sink = FramedWrite::new(chunked(vec![]), LinesCodec::new());
+    let line = String::from_utf8(vec![b'a'; 10_000]).unwrap();
+    let mut spawn = futures::executor::spawn(futures::future::lazy(move || {
+        assert!(sink.start_send(line.clone()).unwrap().is_ready());
+        // The buffer is full, and the transport stops being ready after
+        // taking a first few bytes of it.
+        assert!(sink.start_send(line).unwrap().is_not_ready());
+        Ok::<_, ()>(sink)
+    }));
+    let sink = spawn.wait_future().unwrap();
+    assert_eq!(sink.get_ref().output.len(), 3);
+    assert_eq!(sink.write_buffer().len(), 10_001 - 3);
+}
+
+#[test]
+fn framed_echo() {
+    let io = chunked(vec![b"ping\npong\n"]);
+    let mut framed = Framed::new(io, LinesCodec::new());
+    let lines = (&mut framed).take(2).collect().wait().unwrap();
+    assert_eq!(lines, ["ping", "pong"]);
+    for line in lines {
+        framed = framed.send(line.to_uppercase()).wait().unwrap();
+    }
+    assert_eq!(framed.get_ref().output, b"PING\nPONG\n");
+}
+
+#[test]
+fn custom_decode_eof() {
+    // A decoder producing whatever is left at the end as a frame.
+    struct Rest;
+
+    impl Decoder for Rest {
+        type Item = Vec<u8>;
+        type Error = std_io::Error;
+
+        fn decode(&mut self, _src: &mut Vec<u8>) -> Result<Option<Vec<u8>>, std_io::Error> {
+            Ok(None)
+        }
+
+        fn decode_eof(&mut self, src: &mut Vec<u8>) -> Result<Option<Vec<u8>>, std_io::Error> {
+            if src.is_empty() {
+                Ok(None)
+            } else {
+                Ok(Some(std::mem::replace(src, Vec::new())))
+            }
+        }
+    }
+
+    let io = chunked(vec![b"ab", b"cd"]);
+    let frames = FramedRead::new(io, Rest).collect().wait().unwrap();
+    assert_eq!(frames, [b"abcd".to_vec()]);
+}
@@ -0,0 +1,140 @@
+#![cfg(feature = "compat")]
+
+extern crate futures;
+
+use std::future::{self as std_future, Future as StdFuture};
+use std::pin::Pin;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::task::{Context, Poll as StdPoll, Wake, Waker};
+
+use futures::prelude::*;
+use futures::compat::{self, CompatExt, StdExecutor};
+use futures::executor::LocalPool;
+use futures::stream;
+use futures::sync::oneshot;
+
+struct Counter(AtomicUsize);
+
+impl Wake for Counter {
+    fn wake(self: Arc<Self>) {
+        self.0.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+fn counter() -> (Arc<Counter>, Waker) {
+    let counter = Arc::new(Counter(AtomicUsize::new(0)));
+    let waker = counter.clone().into();
+    (counter, waker)
+}
+
+#[test]
+fn compat_future() {
+    let (counter, waker) = counter();
+    let mut cx = Context::from_waker(&waker);
+    let (tx, rx) = oneshot::channel::<u32>();
+    let mut f = rx.compat();
+
+    assert_eq!(Pin::new(&mut f).poll(&mut cx), StdPoll::Pending);
+    assert_eq!(counter.0.load(Ordering::SeqCst), 0);
+    tx.send(1).unwrap();
+    assert_eq!(counter.0.load(Ordering::SeqCst), 1);
+    assert_eq!(Pin::new(&mut f).poll(&mut cx), StdPoll::Ready(Ok(1)));
+}
+
+#[test]
+fn compat_future_error() {
+    let (_, waker) = counter();
+    let mut cx = Context::from_waker(&waker);
+    let mut f = futures::future::err::<(), u32>(3).compat();
+    assert_eq!(Pin::new(&mut f).poll(&mut cx), StdPoll::Ready(Err(3)));
+}
+
+#[test]
+fn compat_stream() {
+    let (_, waker) = counter();
+    let mut cx = Context::from_waker(&waker);
+    let mut s = stream::iter_result(vec![Ok(1), Err(2), Ok(3)]).compat();
+    assert_eq!(s.poll_next(&mut cx), StdPoll::Ready(Some(Ok(1))));
+    assert_eq!(s.poll_next(&mut cx), StdPoll::Ready(Some(Err(2))));
+    assert_eq!(s.poll_next(&mut cx), StdPoll::Ready(Some(Ok(3))));
+    assert_eq!(s.poll_next(&mut cx), StdPoll::Ready(None));
+}
+
+#[test]
+fn compat_wakes_latest_waker() {
+    let (first, waker) = counter();
+    let (second, other) = counter();
+    let (tx, rx) = oneshot::channel::<u32>();
+    let mut f = rx.compat();
+
+    assert_eq!(Pin::new(&mut f).poll(&mut Context::from_waker(&waker)), StdPoll::Pending);
+    assert_eq!(Pin::new(&mut f).poll(&mut Context::from_waker(&other)), StdPoll::Pending);
+    tx.send(1).unwrap();
+    assert_eq!(first.0.load(Ordering::SeqCst), 0);
+    assert_eq!(second.0.load(Ordering::SeqCst), 1);
+}
+
+// A std future which is pending until its waker has been woken once.
+struct YieldOnce {
+    yielded: bool,
+}
+
+impl StdFuture for YieldOnce {
+    type Output = Result<u32, ()>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context) -> StdPoll<Result<u32, ()>> {
+        if self.yielded {
+            return StdPoll::Ready(Ok(7))
+        }
+        self.yielded = true;
+        cx.waker().wake_by_ref();
+        StdPoll::Pending
+    }
+}
+
+#[test]
+fn from_std_future() {
+    assert_eq!(compat::from_std(std_future::ready(Ok::<u32, ()>(1))).wait(), Ok(1));
+    assert_eq!(compat::from_std(std_future::ready(Err::<(), u32>(2))).wait(), Err(2));
+    assert_eq!(compat::from_std(YieldOnce { yielded: false }).wait(), Ok(7));
+}
+
+#[test]
+fn round_trip() {
+    let (tx, rx) = oneshot::channel::<u32>();
+    let f = compat::from_std(rx.compat());
+    tx.send(5).unwrap();
+    assert_eq!(f.wait(), Ok(5));
+}
+
+#[test]
+fn std_executor() {
+    let mut pool = LocalPool::new();
+    let executor = StdExecutor::new(pool.spawner());
+    let (tx, rx) = oneshot::channel();
+    let mut tx = Some(tx);
+    let mut inner = YieldOnce { yielded: false };
+    executor.spawn(std_future::poll_fn(move |cx| {
+        match Pin::new(&mut inner).poll(cx) {
+            StdPoll::Ready(r) => {
+                tx.take().unwrap().send(r.unwrap()).unwrap();
+                StdPoll::Ready(())
+            }
+            StdPoll::Pending => StdPoll::Pending,
+        }
+    })).unwrap();
+    assert_eq!(pool.run_until(rx), Ok(7));
+}
+
+#[test]
+fn waker_reexports() {
+    use futures::compat::waker::WakeHandle;
+    use futures::test::task::MockTask;
+
+    let mock = MockTask::new();
+    let handle: futures::ffi::WakeHandle =
+        mock.enter(|| WakeHandle::from_task(futures::task::current()));
+    handle.wake();
+    assert_eq!(mock.notify_count(), 1);
+}
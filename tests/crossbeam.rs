@@ -0,0 +1,69 @@
+#![cfg(feature = "crossbeam")]
+
+extern crate futures;
+
+use std::thread;
+use std::time::Duration;
+
+use futures::prelude::*;
+use futures::sync::crossbeam;
+
+#[test]
+fn thread_to_task() {
+    let (tx, rx) = crossbeam::unbounded();
+    let producer = thread::spawn(move || {
+        for i in 0..1000 {
+            // Give the consumer a chance to wait for messages.
+            if i % 100 == 0 {
+                thread::sleep(Duration::from_millis(1));
+            }
+            tx.blocking_send(i).unwrap();
+        }
+    });
+    assert_eq!(rx.collect().wait(), Ok((0..1000).collect::<Vec<_>>()));
+    producer.join().unwrap();
+}
+
+#[test]
+fn task_to_thread() {
+    let (tx, rx) = crossbeam::bounded(2);
+    let consumer = thread::spawn(move || {
+        let mut items = Vec::new();
+        while let Ok(i) = rx.blocking_recv() {
+            thread::sleep(Duration::from_millis(1));
+            items.push(i);
+        }
+        items
+    });
+    drop(tx.send_all(futures::stream::iter_ok(0..20)).wait().unwrap());
+    assert_eq!(consumer.join().unwrap(), (0..20).collect::<Vec<_>>());
+}
+
+#[test]
+fn wakes_waiting_receiver() {
+    let (tx, rx) = crossbeam::unbounded::<u32>();
+    let consumer = thread::spawn(move || rx.wait().collect::<Vec<_>>());
+
+    // Let the consumer block on the stream before anything is sent.
+    thread::sleep(Duration::from_millis(10));
+    tx.blocking_send(3).unwrap();
+    thread::sleep(Duration::from_millis(10));
+    assert!(tx.try_send(4).is_ok());
+    drop(tx);
+    assert_eq!(consumer.join().unwrap(), [Ok(3), Ok(4)]);
+}
+
+#[test]
+fn full_sink_is_not_ready() {
+    let (mut tx, rx) = crossbeam::bounded(1);
+    let mut spawn = futures::executor::spawn(futures::future::lazy(move || {
+        assert!(tx.start_send(1).unwrap().is_ready());
+        assert_eq!(tx.start_send(2).unwrap(), AsyncSink::NotReady(2));
+        assert_eq!(rx.try_recv(), Ok(1));
+        assert!(tx.start_send(2).unwrap().is_ready());
+        drop(rx);
+        assert!(tx.start_send(3).is_err());
+        Ok::<_, ()>(())
+    }));
+    spawn.wait_future().unwrap();
+}
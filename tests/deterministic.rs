@@ -0,0 +1,77 @@
+extern crate futures;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use futures::prelude::*;
+use futures::future::{self, Executor};
+use futures::sync::{mpsc, oneshot};
+use futures::test::DeterministicPool;
+
+#[test]
+fn controlled_order() {
+    let mut pool = DeterministicPool::new();
+    let log = Rc::new(RefCell::new(Vec::new()));
+    let (tx, rx) = oneshot::channel::<i32>();
+
+    let log2 = log.clone();
+    let receiver = pool.spawn(rx.map(move |v| log2.borrow_mut().push(v)).map_err(|_| ()));
+    let sender = pool.spawn(future::lazy(move || tx.send(1).map_err(|_| ())));
+    assert_eq!(pool.runnable(), vec![receiver, sender]);
+
+    // The receiver registers itself first, and is only woken by the send.
+    assert!(pool.poll_task(receiver).is_not_ready());
+    assert_eq!(pool.runnable(), vec![sender]);
+    assert!(pool.poll_task(sender).is_ready());
+    assert_eq!(pool.runnable(), vec![receiver]);
+    assert_eq!(pool.step(), Some(receiver));
+
+    pool.assert_stalled();
+    assert_eq!(pool.pending(), 0);
+    assert_eq!(*log.borrow(), [1]);
+}
+
+#[test]
+fn step_with_and_spawner() {
+    let mut pool = DeterministicPool::new();
+    let spawner = pool.spawner();
+    let log = Rc::new(RefCell::new(Vec::new()));
+    for i in 0..3 {
+        let log = log.clone();
+        spawner.execute(future::lazy(move || {
+            log.borrow_mut().push(i);
+            Ok(())
+        })).unwrap();
+    }
+
+    // Always pick the task which became runnable last.
+    while pool.step_with(|ready| ready.len() - 1).is_some() {}
+    assert_eq!(*log.borrow(), [2, 1, 0]);
+
+    drop(pool);
+    assert!(spawner.execute(future::ok(())).is_err());
+}
+
+#[test]
+fn run_until_stalled() {
+    let mut pool = DeterministicPool::new();
+    let (tx, rx) = mpsc::unbounded::<i32>();
+    let consumer = pool.spawn(rx.for_each(|_| Ok(())));
+    tx.unbounded_send(1).unwrap();
+    tx.unbounded_send(2).unwrap();
+    assert_eq!(pool.run_until_stalled(), 1);
+    assert!(pool.is_pending(consumer));
+
+    drop(tx);
+    assert_eq!(pool.runnable(), vec![consumer]);
+    assert_eq!(pool.run_until_stalled(), 1);
+    assert!(!pool.is_pending(consumer));
+}
+
+#[test]
+#[should_panic(expected = "tasks [0] are runnable")]
+fn assert_stalled() {
+    let mut pool = DeterministicPool::new();
+    pool.spawn(future::ok(()));
+    pool.assert_stalled();
+}
@@ -0,0 +1,45 @@
+extern crate futures;
+
+use futures::prelude::*;
+use futures::executor;
+use futures::stream;
+use futures::sync;
+
+mod support;
+use support::*;
+
+#[test]
+fn both_directions() {
+    let (a, b) = sync::duplex::<i32, String>(4);
+    let (a_tx, a_rx) = a.split();
+    let (b_tx, b_rx) = b.split();
+
+    drop(a_tx.send_all(stream::iter_ok(vec![1, 2, 3])).wait().ok().unwrap());
+    drop(b_tx.send_all(stream::iter_ok(vec!["x".to_string()])).wait().ok().unwrap());
+
+    // Closed senders end the stream on the other side.
+    assert_eq!(b_rx.collect().wait(), Ok(vec![1, 2, 3]));
+    assert_eq!(a_rx.collect().wait(), Ok(vec!["x".to_string()]));
+}
+
+#[test]
+fn backpressure() {
+    let (mut a, mut b) = sync::duplex::<i32, ()>(1);
+    let mut a_task = executor::spawn(futures::future::lazy(move || {
+        // The buffer plus the slot of the sender.
+        assert!(a.start_send(1).unwrap().is_ready());
+        assert!(a.start_send(2).unwrap().is_ready());
+        assert!(a.start_send(3).unwrap().is_not_ready());
+        Ok::<_, ()>(a)
+    }));
+    let mut a = a_task.wait_future().unwrap();
+
+    sassert_next(&mut b, 1);
+    assert!(executor::spawn(futures::future::lazy(|| a.start_send(3))).wait_future().unwrap().is_ready());
+    sassert_next(&mut b, 2);
+    sassert_next(&mut b, 3);
+    sassert_empty(&mut b);
+
+    drop(a);
+    sassert_done(&mut b);
+}
@@ -0,0 +1,164 @@
+extern crate futures;
+
+use std::os::raw::c_void;
+use std::sync::Arc;
+use std::sync::atomic::{self, AtomicUsize, Ordering};
+
+use futures::prelude::*;
+use futures::ffi::{FfiFuture, FfiPoll, FfiWake};
+use futures::future;
+use futures::sync::oneshot;
+
+// An `FfiWake` counting its wake-ups and whether it's been dropped, as a C
+// event loop would.
+struct Counts {
+    wakes: AtomicUsize,
+    drops: AtomicUsize,
+}
+
+unsafe extern "C" fn count_wake(data: *mut c_void) {
+    (*(data as *const Counts)).wakes.fetch_add(1, Ordering::SeqCst);
+}
+
+unsafe extern "C" fn count_drop(data: *mut c_void) {
+    (*(data as *const Counts)).drops.fetch_add(1, Ordering::SeqCst);
+}
+
+fn counting_wake(counts: &Arc<Counts>) -> FfiWake {
+    unsafe {
+        FfiWake::from_raw(&**counts as *const Counts as *mut c_void,
+                          count_wake,
+                          Some(count_drop))
+    }
+}
+
+#[test]
+fn poll_rust_future_from_c() {
+    let counts = Arc::new(Counts {
+        wakes: AtomicUsize::new(0),
+        drops: AtomicUsize::new(0),
+    });
+    let (tx, rx) = oneshot::channel::<()>();
+    let mut f = FfiFuture::new(rx.map_err(|_| ()));
+    assert_eq!(f.poll_ffi(counting_wake(&counts)), FfiPoll::NotReady);
+    assert_eq!(counts.wakes.load(Ordering::SeqCst), 0);
+
+    tx.send(()).unwrap();
+    assert_eq!(counts.wakes.load(Ordering::SeqCst), 1);
+    assert_eq!(f.poll_ffi(counting_wake(&counts)), FfiPoll::Ready);
+    drop(f);
+    assert_eq!(counts.drops.load(Ordering::SeqCst), 2);
+
+    let mut f = FfiFuture::new(future::err(()));
+    assert_eq!(f.poll_ffi(counting_wake(&counts)), FfiPoll::Error);
+
+    let mut f = FfiFuture::new(future::lazy(|| -> Result<(), ()> { panic!() }));
+    assert_eq!(f.poll_ffi(counting_wake(&counts)), FfiPoll::Panicked);
+}
+
+// A future implemented in "C", resolving on its second poll.
+unsafe extern "C" fn poll_twice(data: *mut c_void, wake: FfiWake) -> FfiPoll {
+    let polls = &mut *(data as *mut usize);
+    *polls += 1;
+    if *polls == 2 {
+        FfiPoll::Ready
+    } else {
+        wake.wake();
+        FfiPoll::NotReady
+    }
+}
+
+unsafe extern "C" fn drop_polls(data: *mut c_void) {
+    drop(Box::from_raw(data as *mut usize));
+}
+
+#[test]
+fn poll_c_future_from_rust() {
+    let f = unsafe {
+        FfiFuture::from_raw(Box::into_raw(Box::new(0usize)) as *mut c_void,
+                            poll_twice,
+                            drop_polls)
+    };
+    assert_eq!(f.wait(), Ok(()));
+
+    // And round trip a Rust future through the C representation.
+    let (tx, rx) = oneshot::channel();
+    let f = FfiFuture::new(rx.map(|v| assert_eq!(v, 1)).map_err(|_| ()));
+    tx.send(1).unwrap();
+    assert_eq!(f.wait(), Ok(()));
+}
+
+#[test]
+fn wake_handle_from_task() {
+    use futures::ffi::WakeHandle;
+    use futures::test::task::MockTask;
+
+    let mock = MockTask::new();
+    let handle = mock.enter(|| WakeHandle::from_task(futures::task::current()));
+    let clone = handle.clone();
+    drop(handle);
+    clone.wake();
+    clone.wake();
+    assert_eq!(mock.notify_count(), 2);
+
+    // The raw parts survive a round trip.
+    let raw = clone.into_raw();
+    let handle = unsafe { WakeHandle::from_raw(raw) };
+    handle.wake();
+    assert_eq!(mock.notify_count(), 3);
+}
+
+#[test]
+fn wake_handle_into_notify_handle() {
+    use futures::ffi::{RawWakeHandle, WakeHandle, WakeVtable};
+    use futures::executor::{self, NotifyHandle};
+
+    // A handle of a "foreign" event source, counting wake-ups in a static.
+    #[allow(deprecated)]
+    static WAKES: AtomicUsize = atomic::ATOMIC_USIZE_INIT;
+    static VTABLE: WakeVtable = WakeVtable {
+        clone: clone_static,
+        wake: wake_static,
+        drop: drop_static,
+    };
+    unsafe extern "C" fn clone_static(data: *const c_void) -> RawWakeHandle {
+        RawWakeHandle { data: data, vtable: &VTABLE }
+    }
+    unsafe extern "C" fn wake_static(_data: *const c_void) {
+        WAKES.fetch_add(1, Ordering::SeqCst);
+    }
+    unsafe extern "C" fn drop_static(_data: *const c_void) {}
+
+    let handle = unsafe {
+        WakeHandle::from_raw(RawWakeHandle { data: std::ptr::null(), vtable: &VTABLE })
+    };
+    let notify = NotifyHandle::from(handle);
+    let (tx, rx) = oneshot::channel::<i32>();
+    let mut rx = executor::spawn(rx);
+    assert!(rx.poll_future_notify(&notify, 0).unwrap().is_not_ready());
+    tx.send(1).unwrap();
+    assert_eq!(WAKES.load(Ordering::SeqCst), 1);
+    assert_eq!(rx.poll_future_notify(&notify, 0), Ok(Async::Ready(1)));
+}
+
+#[test]
+fn wake_handle_ffi_wake_conversions() {
+    use futures::ffi::WakeHandle;
+
+    // An `FfiWake` shared by the clones of a `WakeHandle`, and dropped along
+    // with the last one.
+    let counts = Arc::new(Counts { wakes: AtomicUsize::new(0), drops: AtomicUsize::new(0) });
+    let handle = WakeHandle::from(counting_wake(&counts));
+    let clone = handle.clone();
+    handle.wake();
+    clone.wake();
+    drop(handle);
+    assert_eq!(counts.drops.load(Ordering::SeqCst), 0);
+
+    // And back, where the `FfiWake` owns the handle.
+    let wake = FfiWake::from(clone);
+    wake.wake();
+    assert_eq!(counts.wakes.load(Ordering::SeqCst), 3);
+    drop(wake);
+    assert_eq!(counts.drops.load(Ordering::SeqCst), 1);
+}
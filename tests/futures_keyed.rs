@@ -0,0 +1,57 @@
+extern crate futures;
+
+use futures::stream::FuturesKeyed;
+use futures::sync::oneshot;
+
+mod support;
+
+#[test]
+fn replace_and_remove() {
+    let (a1_tx, a1_rx) = oneshot::channel::<u32>();
+    let (a2_tx, a2_rx) = oneshot::channel::<u32>();
+    let (b_tx, b_rx) = oneshot::channel::<u32>();
+    let (c_tx, c_rx) = oneshot::channel::<u32>();
+
+    let mut set = FuturesKeyed::new();
+    assert!(!set.insert("a", a1_rx));
+    assert!(!set.insert("b", b_rx));
+    assert!(!set.insert("c", c_rx));
+    assert_eq!(set.len(), 3);
+
+    let mut spawn = futures::executor::spawn(set);
+    assert!(spawn.poll_stream_notify(&support::notify_noop(), 0).unwrap().is_not_ready());
+
+    // The replaced future is dropped without yielding its result.
+    a1_tx.send(1).unwrap();
+    assert!(spawn.get_mut().insert("a", a2_rx));
+    assert!(spawn.get_mut().remove("c"));
+    assert!(!spawn.get_mut().remove("c"));
+    assert_eq!(spawn.get_ref().len(), 2);
+    assert!(spawn.poll_stream_notify(&support::notify_noop(), 0).unwrap().is_not_ready());
+    assert!(c_tx.is_canceled());
+
+    b_tx.send(2).unwrap();
+    assert_eq!(spawn.wait_stream(), Some(Ok(("b", 2))));
+    assert!(!spawn.get_ref().contains_key("b"));
+    a2_tx.send(3).unwrap();
+    assert_eq!(spawn.wait_stream(), Some(Ok(("a", 3))));
+    assert!(spawn.get_ref().is_empty());
+    assert_eq!(spawn.wait_stream(), None);
+}
+
+#[test]
+fn errors_carry_key() {
+    let (a_tx, a_rx) = oneshot::channel::<u32>();
+    let (b_tx, b_rx) = oneshot::channel::<u32>();
+
+    let mut set = FuturesKeyed::new();
+    set.insert(1, a_rx);
+    set.insert(2, b_rx);
+    drop(a_tx);
+
+    let mut spawn = futures::executor::spawn(set);
+    assert_eq!(spawn.wait_stream(), Some(Err((1, oneshot::Canceled))));
+    b_tx.send(5).unwrap();
+    assert_eq!(spawn.wait_stream(), Some(Ok((2, 5))));
+    assert_eq!(spawn.wait_stream(), None);
+}
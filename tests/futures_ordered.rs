@@ -70,3 +70,36 @@ fn queue_never_unblocked() {
     assert!(spawn.poll_stream_notify(&support::notify_noop(), 0).unwrap().is_not_ready());
     assert!(spawn.poll_stream_notify(&support::notify_noop(), 0).unwrap().is_not_ready());
 }
+
+#[test]
+fn push_front_and_back() {
+    let (a_tx, a_rx) = oneshot::channel::<u32>();
+    let (b_tx, b_rx) = oneshot::channel::<u32>();
+    let (c_tx, c_rx) = oneshot::channel::<u32>();
+    let (d_tx, d_rx) = oneshot::channel::<u32>();
+
+    let mut queue = futures_ordered(vec![a_rx]);
+    queue.push_back(b_rx);
+    queue.push_front(c_rx);
+    assert_eq!(queue.len(), 3);
+
+    let mut spawn = futures::executor::spawn(queue);
+    a_tx.send(1).unwrap();
+    b_tx.send(2).unwrap();
+    assert!(spawn.poll_stream_notify(&support::notify_noop(), 0).unwrap().is_not_ready());
+    c_tx.send(3).unwrap();
+    assert_eq!(Some(Ok(3)), spawn.wait_stream());
+
+    // Pushing while consuming goes before, or after, what's left.
+    spawn.get_mut().push_front(d_rx);
+    d_tx.send(4).unwrap();
+    assert_eq!(spawn.get_ref().len(), 3);
+    assert_eq!(Some(Ok(4)), spawn.wait_stream());
+    assert_eq!(Some(Ok(1)), spawn.wait_stream());
+    let (e_tx, e_rx) = oneshot::channel::<u32>();
+    spawn.get_mut().push_back(e_rx);
+    e_tx.send(5).unwrap();
+    assert_eq!(Some(Ok(2)), spawn.wait_stream());
+    assert_eq!(Some(Ok(5)), spawn.wait_stream());
+    assert_eq!(None, spawn.wait_stream());
+}
@@ -1,9 +1,13 @@
 extern crate futures;
 
 use std::any::Any;
+use std::cell::RefCell;
+use std::rc::Rc;
 
 use futures::sync::oneshot;
-use futures::stream::futures_unordered;
+use futures::future::{self, lazy};
+use futures::stream::{futures_unordered, FuturesUnordered};
+use futures::task;
 use futures::prelude::*;
 
 mod support;
@@ -69,3 +73,73 @@ fn finished_future_ok() {
     assert!(spawn.poll_stream_notify(&support::notify_noop(), 0).unwrap().is_not_ready());
     assert!(spawn.poll_stream_notify(&support::notify_noop(), 0).unwrap().is_not_ready());
 }
+
+#[test]
+fn reuse_nodes() {
+    let mut set = FuturesUnordered::new();
+    let stale = Rc::new(RefCell::new(None));
+
+    // A completed future whose task handle is still around, next to plenty
+    // of futures whose nodes can be reused.
+    let stale2 = stale.clone();
+    set.push(Box::new(lazy(move || {
+        *stale2.borrow_mut() = Some(task::current());
+        Ok(0u32)
+    })) as Box<Future<Item = u32, Error = ()>>);
+    let mut spawn = futures::executor::spawn(set);
+    assert_eq!(spawn.wait_stream(), Some(Ok(0)));
+
+    for round in 0..10 {
+        let (tx, rx) = oneshot::channel::<u32>();
+        for i in 0..10 {
+            spawn.get_mut().push(Box::new(future::ok(round * 10 + i)));
+        }
+        spawn.get_mut().push(Box::new(rx.map_err(|_| ())));
+
+        // Notifying the old task doesn't affect the futures reusing nodes.
+        stale.borrow().as_ref().unwrap().notify();
+        let mut sum = 0;
+        for _ in 0..10 {
+            sum += spawn.wait_stream().unwrap().unwrap();
+        }
+        assert_eq!(sum, (0..10).map(|i| round * 10 + i).sum::<u32>());
+        assert!(spawn.poll_stream_notify(&support::notify_noop(), 0).unwrap().is_not_ready());
+        tx.send(1).unwrap();
+        assert_eq!(spawn.wait_stream(), Some(Ok(1)));
+    }
+
+    spawn.get_mut().shrink_to_fit();
+    spawn.get_mut().push(Box::new(future::ok(7)));
+    assert_eq!(spawn.wait_stream(), Some(Ok(7)));
+    assert_eq!(spawn.wait_stream(), None);
+}
+
+#[test]
+fn iter_and_clear() {
+    let (a_tx, a_rx) = oneshot::channel::<u32>();
+    let (b_tx, b_rx) = oneshot::channel::<u32>();
+    let (c_tx, c_rx) = oneshot::channel::<u32>();
+
+    let set = futures_unordered(vec![a_rx, b_rx, c_rx]);
+    assert_eq!(set.iter().len(), 3);
+
+    let mut spawn = futures::executor::spawn(set);
+    b_tx.send(1).unwrap();
+    assert_eq!(spawn.wait_stream(), Some(Ok(1)));
+
+    // Only the pending futures are left to look at.
+    assert_eq!(spawn.get_ref().iter().count(), 2);
+    for rx in spawn.get_mut().iter_mut() {
+        rx.close();
+    }
+    assert!(a_tx.is_canceled());
+    assert!(c_tx.is_canceled());
+
+    let (d_tx, d_rx) = oneshot::channel::<u32>();
+    spawn.get_mut().push(d_rx);
+    spawn.get_mut().clear();
+    assert!(spawn.get_ref().is_empty());
+    assert!(spawn.get_ref().iter().next().is_none());
+    assert!(d_tx.is_canceled());
+    assert_eq!(spawn.wait_stream(), None);
+}
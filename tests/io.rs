@@ -0,0 +1,147 @@
+extern crate futures;
+
+use std::io as std_io;
+
+use futures::prelude::*;
+use futures::io::{self, AsyncRead, AsyncWrite};
+use futures::task;
+
+// Wraps an I/O object, making every other operation on it not ready.
+#[derive(Debug)]
+struct Stutter<T> {
+    inner: T,
+    ready: bool,
+}
+
+fn stutter<T>(inner: T) -> Stutter<T> {
+    Stutter { inner: inner, ready: false }
+}
+
+impl<T> Stutter<T> {
+    fn ready(&mut self) -> bool {
+        self.ready = !self.ready;
+        if !self.ready {
+            task::current().notify();
+        }
+        self.ready
+    }
+}
+
+impl<T: AsyncRead> AsyncRead for Stutter<T> {
+    fn poll_read(&mut self, buf: &mut [u8]) -> Poll<usize, std_io::Error> {
+        if !self.ready() {
+            return Ok(Async::NotReady)
+        }
+        // Only hand out a byte at a time.
+        let len = buf.len().min(1);
+        self.inner.poll_read(&mut buf[..len])
+    }
+}
+
+impl<T: AsyncWrite> AsyncWrite for Stutter<T> {
+    fn poll_write(&mut self, buf: &[u8]) -> Poll<usize, std_io::Error> {
+        if !self.ready() {
+            return Ok(Async::NotReady)
+        }
+        let len = buf.len().min(1);
+        self.inner.poll_write(&buf[..len])
+    }
+
+    fn poll_flush(&mut self) -> Poll<(), std_io::Error> {
+        if !self.ready() {
+            return Ok(Async::NotReady)
+        }
+        self.inner.poll_flush()
+    }
+}
+
+// A writer recording whether it's been flushed and shut down.
+#[derive(Debug, Default)]
+struct Recorder {
+    flushed: bool,
+    shutdown: bool,
+}
+
+impl AsyncWrite for Recorder {
+    fn poll_write(&mut self, buf: &[u8]) -> Poll<usize, std_io::Error> {
+        Ok(Async::Ready(buf.len()))
+    }
+
+    fn poll_flush(&mut self) -> Poll<(), std_io::Error> {
+        self.flushed = true;
+        Ok(Async::Ready(()))
+    }
+
+    fn poll_shutdown(&mut self) -> Poll<(), std_io::Error> {
+        self.shutdown = true;
+        Ok(Async::Ready(()))
+    }
+}
+
+#[test]
+fn read_exact() {
+    let (r, buf) = io::read_exact(stutter(&b"abcdef"[..]), vec![0; 4]).wait().unwrap();
+    assert_eq!(buf, b"abcd");
+    assert_eq!(r.inner, b"ef");
+}
+
+#[test]
+fn read_exact_early_eof() {
+    let err = io::read_exact(stutter(&b"ab"[..]), [0; 4]).wait().unwrap_err();
+    assert_eq!(err.kind(), std_io::ErrorKind::UnexpectedEof);
+}
+
+#[test]
+fn write_all() {
+    let (w, _) = io::write_all(stutter(Vec::new()), "hello").wait().unwrap();
+    assert_eq!(w.inner, b"hello");
+}
+
+#[test]
+fn write_all_write_zero() {
+    let mut buf = [0; 3];
+    let w = std_io::Cursor::new(&mut buf[..]);
+    let err = io::write_all(w, b"hello").wait().unwrap_err();
+    assert_eq!(err.kind(), std_io::ErrorKind::WriteZero);
+    assert_eq!(&buf, b"hel");
+}
+
+#[test]
+fn copy() {
+    let data = (0..20_000).map(|i| i as u8).collect::<Vec<_>>();
+    let (n, _, w) = io::copy(&data[..], Recorder::default()).wait().unwrap();
+    assert_eq!(n, 20_000);
+    assert!(w.flushed);
+    assert!(!w.shutdown);
+
+    let (n, _, w) = io::copy(stutter(&data[..100]), stutter(Vec::new())).wait().unwrap();
+    assert_eq!(n, 100);
+    assert_eq!(w.inner, &data[..100]);
+}
+
+#[test]
+fn flush_and_shutdown() {
+    let w = io::flush(Recorder::default()).wait().unwrap();
+    assert!(w.flushed);
+    assert!(!w.shutdown);
+
+    let w = io::shutdown(Recorder::default()).wait().unwrap();
+    assert!(w.shutdown);
+
+    // The default shutdown only flushes.
+    io::shutdown(stutter(Vec::new())).wait().unwrap();
+}
+
+#[test]
+fn boxed_objects() {
+    let r: Box<AsyncRead> = Box::new(&b"abc"[..]);
+    let (_, buf) = io::read_exact(r, [0; 3]).wait().unwrap();
+    assert_eq!(&buf, b"abc");
+
+    let mut out = Vec::new();
+    {
+        let w: Box<AsyncWrite> = Box::new(&mut out);
+        io::write_all(w, b"abc").wait().unwrap();
+    }
+    assert_eq!(out, b"abc");
+}
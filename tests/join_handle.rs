@@ -0,0 +1,101 @@
+#![cfg(feature = "use_std")]
+
+extern crate futures;
+
+use std::thread;
+
+use futures::prelude::*;
+use futures::executor::LocalPool;
+use futures::future::{self, Executor, ExecuteError, ExecuteErrorKind, SpawnExt, JoinError, JoinTask};
+use futures::sync::oneshot;
+
+#[test]
+fn join_output() {
+    let mut pool = LocalPool::new();
+    let spawner = pool.spawner();
+
+    let ok = spawner.spawn_with_handle(future::ok::<_, u32>(1)).unwrap();
+    let err = spawner.spawn_with_handle(future::err::<i32, _>(2u32)).unwrap();
+    assert!(!ok.is_finished());
+    assert_eq!(pool.run_until(ok), Ok(1));
+    assert!(err.is_finished());
+    assert_eq!(pool.run_until(err), Err(JoinError::Failed(2)));
+}
+
+#[test]
+fn dropping_handle_detaches() {
+    let mut pool = LocalPool::new();
+    let spawner = pool.spawner();
+    let (tx, rx) = oneshot::channel();
+    drop(spawner.spawn_with_handle(future::lazy(move || tx.send(3))).unwrap());
+    pool.run();
+    assert_eq!(rx.wait(), Ok(3));
+}
+
+#[test]
+fn abort() {
+    let mut pool = LocalPool::new();
+    let spawner = pool.spawner();
+    let (tx, rx) = oneshot::channel::<()>();
+    let handle = spawner.spawn_with_handle(rx).unwrap();
+
+    // Run the task once so it's waiting on the channel, then abort it from
+    // another thread.
+    assert_eq!(pool.run_until(future::ok::<_, ()>(())), Ok(()));
+    let t = thread::spawn(move || {
+        handle.abort();
+        handle
+    });
+    let handle = t.join().unwrap();
+    pool.run();
+    assert!(handle.is_finished());
+    assert!(tx.is_canceled());
+    assert_eq!(pool.run_until(handle), Err(JoinError::Aborted));
+
+    // Aborting after completion has no effect.
+    let handle = spawner.spawn_with_handle(future::ok::<_, ()>(4)).unwrap();
+    pool.run();
+    handle.abort();
+    assert_eq!(pool.run_until(handle), Ok(4));
+}
+
+#[test]
+fn abort_on_drop() {
+    let mut pool = LocalPool::new();
+    let spawner = pool.spawner();
+    let (tx, rx) = oneshot::channel::<()>();
+    let handle = spawner.spawn_with_handle(rx).unwrap().abort_on_drop();
+    assert_eq!(pool.run_until(future::ok::<_, ()>(())), Ok(()));
+    assert!(!tx.is_canceled());
+    drop(handle);
+    pool.run();
+    assert!(tx.is_canceled());
+}
+
+struct Reject;
+
+impl<F: Future<Item = (), Error = ()>> Executor<F> for Reject {
+    fn execute(&self, f: F) -> Result<(), ExecuteError<F>> {
+        Err(ExecuteError::new(ExecuteErrorKind::NoCapacity, f))
+    }
+}
+
+struct Forget;
+
+impl<F: Future> Executor<JoinTask<F>> for Forget {
+    fn execute(&self, f: JoinTask<F>) -> Result<(), ExecuteError<JoinTask<F>>> {
+        drop(f);
+        Ok(())
+    }
+}
+
+#[test]
+fn rejected_and_canceled() {
+    let err = Reject.spawn_with_handle(future::ok::<i32, ()>(5)).unwrap_err();
+    assert_eq!(err.kind(), ExecuteErrorKind::NoCapacity);
+    assert_eq!(err.into_future().wait(), Ok(5));
+
+    let handle = Forget.spawn_with_handle(future::ok::<i32, ()>(6)).unwrap();
+    assert!(handle.is_finished());
+    assert_eq!(handle.wait(), Err(JoinError::Canceled));
+}
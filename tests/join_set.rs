@@ -0,0 +1,60 @@
+#![cfg(feature = "use_std")]
+
+extern crate futures;
+
+use futures::prelude::*;
+use futures::executor::{JoinSet, LocalPool};
+use futures::future::{self, JoinError};
+use futures::sync::oneshot;
+
+#[test]
+fn completion_order() {
+    let mut pool = LocalPool::new();
+    let spawner = pool.spawner();
+    let (a_tx, a_rx) = oneshot::channel::<u32>();
+    let (b_tx, b_rx) = oneshot::channel::<u32>();
+
+    let mut set = JoinSet::named("group");
+    assert_eq!(set.name(), Some("group"));
+    set.spawn(&spawner, a_rx.map_err(|_| 0)).unwrap();
+    set.spawn(&spawner, b_rx.map_err(|_| 0)).unwrap();
+    set.spawn(&spawner, future::err(3)).unwrap();
+    assert_eq!(set.len(), 3);
+
+    // The failed future completes right away.
+    let set = match pool.run_until(set.into_future()) {
+        Err((JoinError::Failed(3), set)) => set,
+        _ => panic!("expected the failed future first"),
+    };
+    b_tx.send(2).unwrap();
+    let (item, set) = pool.run_until(set.into_future()).ok().unwrap();
+    assert_eq!(item, Some(2));
+    a_tx.send(1).unwrap();
+    let (item, set) = pool.run_until(set.into_future()).ok().unwrap();
+    assert_eq!(item, Some(1));
+    assert!(set.is_empty());
+}
+
+#[test]
+fn abort_and_join_all() {
+    let mut pool = LocalPool::new();
+    let spawner = pool.spawner();
+    let (tx, rx) = oneshot::channel::<u32>();
+
+    let mut set = JoinSet::new();
+    set.spawn(&spawner, rx.map_err(|_| 0)).unwrap();
+    set.spawn(&spawner, future::ok(2)).unwrap();
+    set.spawn(&spawner, future::err(3)).unwrap();
+    set.abort_all();
+    let results = pool.run_until(set.join_all()).unwrap();
+    assert_eq!(results.len(), 3);
+    assert!(results.iter().all(|r| *r == Err(JoinError::Aborted)));
+    assert!(tx.is_canceled());
+
+    // Futures which complete before being aborted keep their results.
+    let mut set = JoinSet::new();
+    set.spawn(&spawner, future::ok(1)).unwrap();
+    pool.run();
+    set.abort_all();
+    assert_eq!(pool.run_until(set.join_all()), Ok(vec![Ok::<_, JoinError<()>>(1)]));
+}
@@ -0,0 +1,292 @@
+#![cfg(feature = "use_std")]
+
+extern crate futures;
+
+use std::cell::{Cell, RefCell};
+use std::panic::{self, AssertUnwindSafe};
+use std::rc::Rc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use futures::prelude::*;
+use futures::executor::{self, LocalPool, Priority, PriorityExecutor, ExecutePriority, DefaultSpawner,
+                        PanicPolicy, ShutdownReport};
+use futures::future::{self, lazy, loop_fn, Executor, ExecuteErrorKind, Loop, SpawnExt, JoinError};
+use futures::task;
+use futures::sync::oneshot;
+use futures::unsync::mpsc;
+
+#[test]
+fn run_spawned() {
+    let mut pool = LocalPool::new();
+    let spawner = pool.spawner();
+    let order = Rc::new(RefCell::new(Vec::new()));
+
+    let (tx, rx) = mpsc::channel::<i32>(1);
+    let order2 = order.clone();
+    spawner.execute(rx.for_each(move |i| {
+        order2.borrow_mut().push(i);
+        Ok(())
+    })).unwrap();
+
+    let spawner2 = spawner.clone();
+    let order2 = order.clone();
+    spawner.execute(lazy(move || {
+        // Spawning from within a task runs the new task on the same pool.
+        spawner2.execute(lazy(move || {
+            order2.borrow_mut().push(0);
+            Ok(())
+        })).unwrap();
+        tx.send_all(futures::stream::iter_ok(vec![1, 2, 3]))
+            .map(|_| ())
+            .map_err(|_| ())
+    })).unwrap();
+
+    pool.run();
+    let mut order = order.borrow().clone();
+    order.sort();
+    assert_eq!(order, vec![0, 1, 2, 3]);
+}
+
+#[test]
+fn run_until() {
+    let mut pool = LocalPool::new();
+    let spawner = pool.spawner();
+    let (tx, rx) = oneshot::channel();
+
+    thread::spawn(move || tx.send(3).unwrap());
+    let polled = Rc::new(Cell::new(false));
+    let polled2 = polled.clone();
+    spawner.execute(lazy(move || {
+        polled2.set(true);
+        Ok(())
+    })).unwrap();
+    assert_eq!(pool.run_until(rx.map(|i| i + 1)), Ok(4));
+    assert!(polled.get());
+
+    // Tasks which haven't completed are kept around for the next run.
+    let (tx, rx) = mpsc::channel::<()>(1);
+    let done = Rc::new(Cell::new(false));
+    let done2 = done.clone();
+    spawner.execute(rx.for_each(|_| Ok(())).map(move |()| done2.set(true)))
+        .unwrap();
+    assert_eq!(pool.run_until(future::ok::<_, ()>(1)), Ok(1));
+    assert!(!done.get());
+    drop(tx);
+    pool.run();
+    assert!(done.get());
+
+    assert_eq!(pool.run_until(future::err::<(), _>(2)), Err(2));
+}
+
+#[test]
+fn spawn_after_drop() {
+    let pool = LocalPool::new();
+    let spawner = pool.spawner();
+    drop(pool);
+    let err = spawner.execute(future::ok(())).unwrap_err();
+    assert_eq!(err.kind(), ExecuteErrorKind::Shutdown);
+}
+
+#[test]
+fn priorities() {
+    let mut pool = LocalPool::new();
+    let spawner = pool.spawner();
+    let log = Rc::new(RefCell::new(Vec::new()));
+
+    let log2 = log.clone();
+    let high = PriorityExecutor::new(spawner.clone(), Priority::High);
+    spawner.execute_priority(lazy(|| loop_fn(0, move |i| {
+        log2.borrow_mut().push(format!("low{}", i));
+        if i == 0 {
+            // A high priority task becoming ready runs before this one
+            // continues, even though this one was notified first.
+            let log3 = log2.clone();
+            high.execute(lazy(move || {
+                log3.borrow_mut().push("high".to_string());
+                Ok(())
+            })).unwrap();
+        }
+        task::yield_now().map(move |()| {
+            if i == 1 { Loop::Break(()) } else { Loop::Continue(i + 1) }
+        })
+    })), Priority::Low).unwrap();
+
+    let log2 = log.clone();
+    spawner.execute(lazy(move || {
+        log2.borrow_mut().push("normal".to_string());
+        Ok(())
+    })).unwrap();
+
+    pool.run();
+    assert_eq!(*log.borrow(), vec!["normal", "low0", "high", "low1"]);
+}
+
+#[test]
+fn block_on_all() {
+    let (tx, rx) = mpsc::channel::<i32>(1);
+    let sum = Rc::new(Cell::new(0));
+    let sum2 = sum.clone();
+
+    // The spawned tasks are still run after the main future has completed.
+    let res = executor::block_on_all(lazy(move || {
+        DefaultSpawner.execute(rx.for_each(move |i| {
+            sum2.set(sum2.get() + i);
+            Ok(())
+        })).unwrap();
+        DefaultSpawner.execute_priority(lazy(move || {
+            tx.send_all(futures::stream::iter_ok(vec![1, 2, 3]))
+                .map(|_| ())
+                .map_err(|_| ())
+        }), Priority::Low).unwrap();
+        Ok::<_, ()>(7)
+    }));
+    assert_eq!(res, Ok(7));
+    assert_eq!(sum.get(), 6);
+
+    assert_eq!(executor::block_on_all(future::err::<(), _>(1)), Err(1));
+
+    // Outside of a running pool there's nowhere to spawn to.
+    let err = DefaultSpawner.execute(future::ok(())).unwrap_err();
+    assert_eq!(err.kind(), ExecuteErrorKind::Shutdown);
+}
+
+#[test]
+fn shutdown() {
+    let pool = LocalPool::new();
+    let spawner = pool.spawner();
+    let flushed = Rc::new(Cell::new(false));
+
+    // A task which needs a few more polls to flush its data.
+    let flushed2 = flushed.clone();
+    let spawner2 = spawner.clone();
+    spawner.execute(lazy(move || loop_fn(0, move |i| {
+        if i == 2 {
+            // Nothing can be spawned anymore.
+            let err = spawner2.execute(future::ok(())).unwrap_err();
+            assert_eq!(err.kind(), ExecuteErrorKind::Shutdown);
+            let err = DefaultSpawner.execute(future::ok(())).unwrap_err();
+            assert_eq!(err.kind(), ExecuteErrorKind::Shutdown);
+            flushed2.set(true);
+            return future::Either::A(future::ok(Loop::Break(())))
+        }
+        future::Either::B(task::yield_now().map(move |()| Loop::Continue(i + 1)))
+    }))).unwrap();
+
+    // And one which never completes.
+    let (tx, rx) = oneshot::channel::<()>();
+    spawner.execute(rx.map_err(|_| ())).unwrap();
+
+    let start = Instant::now();
+    let report = pool.shutdown(Duration::from_millis(50));
+    assert!(start.elapsed() >= Duration::from_millis(50));
+    assert_eq!(report, ShutdownReport { completed: 1, abandoned: 1 });
+    assert!(flushed.get());
+    assert!(tx.is_canceled());
+    let err = spawner.execute(future::ok(())).unwrap_err();
+    assert_eq!(err.kind(), ExecuteErrorKind::Shutdown);
+
+    // Without pending tasks the pool shuts down right away.
+    let pool = LocalPool::new();
+    pool.spawner().execute(future::ok(())).unwrap();
+    let start = Instant::now();
+    let report = pool.shutdown(Duration::from_secs(10));
+    assert!(start.elapsed() < Duration::from_secs(5));
+    assert_eq!(report, ShutdownReport { completed: 1, abandoned: 0 });
+}
+
+#[test]
+fn metrics() {
+    let mut pool = LocalPool::new();
+    let spawner = pool.spawner();
+    let m = pool.metrics();
+    assert_eq!((m.tasks, m.queued, m.polls), (0, 0, 0));
+    assert_eq!(m.mean_poll_duration(), None);
+
+    let (tx, rx) = oneshot::channel::<()>();
+    let (done_tx, done_rx) = oneshot::channel::<()>();
+    spawner.execute(rx.map_err(|_| ())).unwrap();
+    spawner.execute(lazy(|| {
+        thread::sleep(Duration::from_millis(10));
+        done_tx.send(()).map_err(|_| ())
+    })).unwrap();
+    let m = pool.metrics();
+    assert_eq!((m.tasks, m.queued, m.polls), (2, 2, 0));
+
+    let reports = Rc::new(RefCell::new(Vec::new()));
+    let reports2 = reports.clone();
+    pool.on_metrics(Duration::from_millis(0), move |m| reports2.borrow_mut().push(*m));
+    assert_eq!(pool.run_until(done_rx), Ok(()));
+    let m = pool.metrics();
+    assert_eq!((m.tasks, m.queued, m.polls), (1, 0, 4));
+    assert!(m.busy >= Duration::from_millis(10));
+    assert!(m.mean_poll_duration().unwrap() >= Duration::from_millis(2));
+    assert!(m.polls_per_second() > 0.0);
+    assert_eq!(reports.borrow().iter().map(|m| m.polls).collect::<Vec<_>>(), [1, 2, 3, 4]);
+
+    tx.send(()).unwrap();
+    pool.run();
+    let m = pool.metrics();
+    assert_eq!((m.tasks, m.queued, m.polls), (0, 0, 5));
+}
+
+#[test]
+fn notified_task_queued_once() {
+    let mut pool = LocalPool::new();
+    let polls = Rc::new(Cell::new(0));
+    let handle = Rc::new(RefCell::new(None));
+
+    let (polls2, handle2) = (polls.clone(), handle.clone());
+    pool.spawner().execute(future::poll_fn(move || {
+        polls2.set(polls2.get() + 1);
+        *handle2.borrow_mut() = Some(task::current());
+        Ok::<_, ()>(Async::NotReady)
+    })).unwrap();
+    assert_eq!(pool.run_until(task::yield_now::<()>()), Ok(()));
+    assert_eq!(polls.get(), 1);
+
+    // Notified many times before the pool gets to it, the task is still only
+    // polled once.
+    for _ in 0..10 {
+        handle.borrow().as_ref().unwrap().notify();
+    }
+    assert_eq!(pool.metrics().queued, 1);
+    assert_eq!(pool.run_until(task::yield_now::<()>()), Ok(()));
+    assert_eq!(polls.get(), 2);
+    assert_eq!(pool.metrics().queued, 0);
+}
+
+#[test]
+fn panic_policy() {
+    let mut pool = LocalPool::new();
+    let spawner = pool.spawner();
+    pool.set_panic_policy(PanicPolicy::DropTask);
+
+    let ran = Rc::new(Cell::new(false));
+    let ran2 = ran.clone();
+    spawner.execute(lazy(|| -> Result<(), ()> { panic!("dropped") })).unwrap();
+    spawner.execute(lazy(move || Ok(ran2.set(true)))).unwrap();
+    pool.run();
+    assert!(ran.get());
+
+    // Without forwarding the handle only sees that the task is gone.
+    let handle = spawner.spawn_with_handle(lazy(|| -> Result<(), ()> {
+        panic!("dropped")
+    })).unwrap();
+    pool.run();
+    assert_eq!(handle.wait(), Err(JoinError::Canceled));
+
+    pool.set_panic_policy(PanicPolicy::Forward);
+    let handle = spawner.spawn_with_handle(lazy(|| -> Result<(), ()> {
+        panic!("forwarded")
+    })).unwrap();
+    pool.run();
+    assert!(handle.is_finished());
+    let payload = panic::catch_unwind(AssertUnwindSafe(|| handle.wait())).unwrap_err();
+    assert_eq!(payload.downcast_ref::<&str>(), Some(&"forwarded"));
+
+    // Panics are propagated out of the pool by default.
+    pool.set_panic_policy(PanicPolicy::Propagate);
+    spawner.execute(lazy(|| -> Result<(), ()> { panic!("propagated") })).unwrap();
+    assert!(panic::catch_unwind(AssertUnwindSafe(|| pool.run())).is_err());
+}
@@ -0,0 +1,65 @@
+#[macro_use]
+extern crate futures;
+
+use futures::sync::mpsc;
+use futures::task;
+use futures::test::task::MockTask;
+
+#[test]
+fn counts_notifications() {
+    let mock = MockTask::new();
+    let (tx, mut rx) = mpsc::unbounded::<i32>();
+    assert_not_ready!(mock.poll_stream(&mut rx));
+    assert_eq!(mock.notify_count(), 0);
+
+    tx.unbounded_send(1).unwrap();
+    assert!(mock.is_notified());
+    assert_eq!(assert_ready!(mock.poll_stream(&mut rx)), Some(1));
+    assert!(!mock.is_notified());
+
+    // Clones are the same task.
+    let task = mock.clone().enter(task::current);
+    task.notify();
+    task.notify();
+    assert_eq!(mock.notify_count(), 3);
+
+    drop(tx);
+    assert_eq!(assert_ready!(mock.poll_stream(&mut rx)), None);
+}
+
+#[test]
+fn sinks() {
+    let mock = MockTask::new();
+    let (mut tx, mut rx) = mpsc::channel::<i32>(0);
+    assert_ready!(mock.start_send(&mut tx, 1));
+    assert_not_ready!(mock.start_send(&mut tx, 2));
+    assert_ready!(mock.poll_complete(&mut tx));
+
+    assert_eq!(assert_ready!(mock.poll_stream(&mut rx)), Some(1));
+    assert!(mock.is_notified());
+    assert_ready!(mock.start_send(&mut tx, 2));
+    assert_ready!(mock.close(&mut tx));
+}
+
+#[test]
+fn futures() {
+    let mock = MockTask::new();
+    let mut f = futures::future::ok::<_, ()>(3);
+    assert_eq!(assert_ready!(mock.poll_future(&mut f)), 3);
+}
+
+#[test]
+#[should_panic(expected = "expected ready, got not ready")]
+fn assert_ready_panics() {
+    let mock = MockTask::new();
+    let mut f = futures::future::empty::<(), ()>();
+    assert_ready!(mock.poll_future(&mut f));
+}
+
+#[test]
+#[should_panic(expected = "expected not ready, got error: 4")]
+fn assert_not_ready_panics() {
+    let mock = MockTask::new();
+    let mut f = futures::future::err::<(), _>(4);
+    assert_not_ready!(mock.poll_future(&mut f));
+}
@@ -181,6 +181,53 @@ fn stress_shared_unbounded() {
     t.join().ok().unwrap();
 }
 
+#[test]
+fn stress_unbounded_order() {
+    const AMT: usize = 10000;
+    const NTHREADS: usize = 8;
+    let (tx, rx) = mpsc::unbounded::<(usize, usize)>();
+
+    for i in 0..NTHREADS {
+        let tx = tx.clone();
+
+        thread::spawn(move|| {
+            for j in 0..AMT {
+                tx.unbounded_send((i, j)).unwrap();
+            }
+        });
+    }
+
+    drop(tx);
+
+    // Messages of every sender arrive in the order they were sent.
+    let mut next = vec![0; NTHREADS];
+    for msg in rx.wait() {
+        let (i, j) = msg.unwrap();
+        assert_eq!(next[i], j);
+        next[i] += 1;
+    }
+    assert_eq!(next, vec![AMT; NTHREADS]);
+}
+
+#[test]
+fn unbounded_drop_pending_messages() {
+    let msg = Arc::new(());
+    let (tx, rx) = mpsc::unbounded();
+    for _ in 0..100 {
+        tx.unbounded_send(msg.clone()).unwrap();
+    }
+
+    let mut rx = rx.wait();
+    for _ in 0..50 {
+        rx.next().unwrap().unwrap();
+    }
+    assert_eq!(Arc::strong_count(&msg), 51);
+
+    drop(rx);
+    assert_eq!(Arc::strong_count(&msg), 1);
+    drop(tx);
+}
+
 #[test]
 fn stress_shared_bounded_hard() {
     const AMT: u32 = 10000;
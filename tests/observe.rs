@@ -0,0 +1,166 @@
+extern crate futures;
+
+use std::sync::{Mutex, Once};
+
+use futures::prelude::*;
+use futures::observe::{self, Observer};
+use futures::executor;
+use futures::sink::SinkStats;
+use futures::stream::{self, StreamStats};
+use futures::sync::mpsc;
+
+mod support;
+use support::*;
+
+// Everything reported to the observer, as `(event, name, stats)`.
+static EVENTS: Mutex<Vec<(&'static str, String, StreamStats)>> = Mutex::new(Vec::new());
+static SINK_EVENTS: Mutex<Vec<(&'static str, String, SinkStats)>> = Mutex::new(Vec::new());
+
+struct Recorder;
+
+impl Observer for Recorder {
+    fn stream_polled(&self, name: &str, stats: &StreamStats) {
+        EVENTS.lock().unwrap().push(("polled", name.to_string(), stats.clone()));
+    }
+
+    fn stream_finished(&self, name: &str, stats: &StreamStats) {
+        EVENTS.lock().unwrap().push(("finished", name.to_string(), stats.clone()));
+    }
+
+    fn sink_started(&self, name: &str, stats: &SinkStats) {
+        SINK_EVENTS.lock().unwrap().push(("started", name.to_string(), stats.clone()));
+    }
+
+    fn sink_flushed(&self, name: &str, stats: &SinkStats) {
+        SINK_EVENTS.lock().unwrap().push(("flushed", name.to_string(), stats.clone()));
+    }
+
+    fn sink_finished(&self, name: &str, stats: &SinkStats) {
+        SINK_EVENTS.lock().unwrap().push(("finished", name.to_string(), stats.clone()));
+    }
+}
+
+// The observer can only be registered once per process, so all tests share
+// it and only look at the events of their own streams and sinks.
+fn register() {
+    static INIT: Once = Once::new();
+    INIT.call_once(|| assert!(observe::set_observer(Box::new(Recorder))));
+}
+
+fn events(name: &str) -> Vec<(&'static str, StreamStats)> {
+    register();
+    EVENTS.lock().unwrap().iter()
+        .filter(|e| e.1 == name)
+        .map(|e| (e.0, e.2.clone()))
+        .collect()
+}
+
+fn sink_events(name: &str) -> Vec<(&'static str, SinkStats)> {
+    register();
+    SINK_EVENTS.lock().unwrap().iter()
+        .filter(|e| e.1 == name)
+        .map(|e| (e.0, e.2.clone()))
+        .collect()
+}
+
+#[test]
+fn set_observer_once() {
+    events("set_observer_once");
+    assert!(!observe::set_observer(Box::new(Recorder)));
+    assert!(observe::observer().is_some());
+}
+
+#[test]
+fn instrumented_stream() {
+    events("instrumented_stream");
+    let (tx, rx) = mpsc::unbounded::<i32>();
+    let mut s = rx.instrumented("instrumented_stream");
+    sassert_empty(&mut s);
+    tx.unbounded_send(1).unwrap();
+    sassert_next(&mut s, 1);
+    sassert_empty(&mut s);
+    drop(tx);
+    sassert_done(&mut s);
+
+    let stats = s.stats().clone();
+    assert_eq!(stats.polls(), 4);
+    assert_eq!(stats.not_ready(), 2);
+    assert_eq!(stats.not_ready_ratio(), 0.5);
+    assert_eq!(stats.items(), 1);
+    assert_eq!(stats.errors(), 0);
+    assert!(stats.time_to_first_item().is_some());
+
+    let events = events("instrumented_stream");
+    assert_eq!(events.len(), 5);
+    assert!(events[..4].iter().all(|e| e.0 == "polled"));
+    assert_eq!(events[4].0, "finished");
+    assert_eq!(events[4].1.polls(), 4);
+
+    // Dropping the stream doesn't report it as finished a second time.
+    drop(s);
+    assert_eq!(self::events("instrumented_stream").len(), 5);
+}
+
+#[test]
+fn instrumented_stream_dropped() {
+    events("dropped");
+    let s = stream::iter_result(vec![Ok(1), Err(2)]).instrumented("dropped");
+    let mut s = s.wait();
+    assert_eq!(s.next(), Some(Ok(1)));
+    assert_eq!(s.next(), Some(Err(2)));
+    let s = s.into_inner();
+    assert_eq!(s.stats().errors(), 1);
+    assert!(s.stats().time_to_first_item().is_some());
+    assert!(events("dropped").iter().all(|e| e.0 == "polled"));
+
+    drop(s.into_inner());
+    let events = events("dropped");
+    let last = events.last().unwrap();
+    assert_eq!(last.0, "finished");
+    assert_eq!(last.1.polls(), 2);
+}
+
+#[test]
+fn instrumented_sink() {
+    sink_events("instrumented_sink");
+    let notify = notify_noop();
+    let (tx, rx) = mpsc::channel::<i32>(0);
+    let mut task = executor::spawn(tx.instrumented("instrumented_sink"));
+    assert_eq!(task.start_send_notify(1, &notify, 0), Ok(AsyncSink::Ready));
+    assert_eq!(task.start_send_notify(2, &notify, 0), Ok(AsyncSink::NotReady(2)));
+    assert_eq!(task.poll_flush_notify(&notify, 0), Ok(Async::Ready(())));
+
+    let stats = task.get_ref().stats().clone();
+    assert_eq!(stats.started(), 1);
+    assert_eq!(stats.not_ready(), 1);
+    assert_eq!(stats.flushed(), 1);
+    assert_eq!(stats.flushes(), 1);
+    assert_eq!(stats.errors(), 0);
+    assert!(stats.last_flush_latency().is_some());
+    assert!(stats.max_flush_latency().is_some());
+
+    let kinds = sink_events("instrumented_sink").iter().map(|e| e.0).collect::<Vec<_>>();
+    assert_eq!(kinds, vec!["started", "started", "flushed"]);
+
+    drop(task);
+    let events = sink_events("instrumented_sink");
+    assert_eq!(events.len(), 4);
+    assert_eq!(events[3].0, "finished");
+    assert_eq!(events[3].1.started(), 1);
+    drop(rx);
+}
+
+#[test]
+fn instrumented_sink_closed() {
+    sink_events("closed");
+    let sink = Vec::new().instrumented("closed");
+    let (sink, _) = sink.send_all(stream::iter_ok::<_, ()>(vec![1, 2, 3])).wait().unwrap();
+    assert_eq!(sink.stats().started(), 3);
+    assert_eq!(sink.stats().flushed(), 3);
+    assert_eq!(sink_events("closed").last().unwrap().0, "finished");
+
+    // Dropping the sink doesn't report it as finished a second time.
+    let n = sink_events("closed").len();
+    drop(sink);
+    assert_eq!(sink_events("closed").len(), n);
+}
@@ -0,0 +1,53 @@
+#![cfg(feature = "use_std")]
+
+extern crate futures;
+
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use futures::executor::{self, LocalPool, PollObserver};
+use futures::future::{lazy, Executor};
+use futures::task;
+
+#[derive(Default)]
+struct Log(Mutex<Vec<(&'static str, task::TaskId, Duration)>>);
+
+impl PollObserver for Log {
+    fn enter(&self, task: task::TaskId) {
+        self.0.lock().unwrap().push(("enter", task, Duration::from_secs(0)));
+    }
+
+    fn exit(&self, task: task::TaskId, elapsed: Duration) {
+        self.0.lock().unwrap().push(("exit", task, elapsed));
+    }
+}
+
+#[test]
+fn observes_every_poll() {
+    let log = Arc::new(Log::default());
+    assert!(executor::set_poll_observer(Some(log.clone())).is_none());
+
+    let mut pool = LocalPool::new();
+    pool.spawner().execute(lazy(|| {
+        thread::sleep(Duration::from_millis(20));
+        task::yield_now()
+    })).unwrap();
+    pool.spawner().execute(lazy(|| Ok(()))).unwrap();
+    pool.run();
+
+    assert!(executor::set_poll_observer(None).is_some());
+    pool.spawner().execute(lazy(|| Ok(()))).unwrap();
+    pool.run();
+
+    let log = log.0.lock().unwrap();
+    let events = log.iter().map(|e| e.0).collect::<Vec<_>>();
+    assert_eq!(events, ["enter", "exit", "enter", "exit", "enter", "exit"]);
+
+    // The first task is polled twice, the second one in between.
+    let (first, second) = (log[0].1, log[2].1);
+    assert!(first != second);
+    assert_eq!(log.iter().map(|e| e.1).collect::<Vec<_>>(),
+               [first, first, second, second, first, first]);
+    assert!(log[1].2 >= Duration::from_millis(20));
+}
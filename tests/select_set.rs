@@ -0,0 +1,71 @@
+extern crate futures;
+
+use std::cell::Cell;
+use std::rc::Rc;
+
+use futures::prelude::*;
+use futures::executor;
+use futures::future::{ok, select_set, err, poll_fn};
+use futures::sync::oneshot;
+
+mod support;
+
+#[test]
+fn smoke() {
+    let v: Vec<Box<Future<Item = i32, Error = i32>>> = vec![
+        Box::new(ok(1)),
+        Box::new(err(2)),
+        Box::new(ok(3)),
+    ];
+
+    // Indices are the positions in the original list.
+    let (i, idx, v) = select_set(v).wait().ok().unwrap();
+    assert_eq!((i, idx), (1, 0));
+
+    let (i, idx, v) = v.wait().err().unwrap();
+    assert_eq!((i, idx), (2, 1));
+
+    let (i, idx, v) = v.wait().ok().unwrap();
+    assert_eq!((i, idx), (3, 2));
+
+    assert!(v.is_empty());
+}
+
+#[test]
+fn polls_notified_children() {
+    let polls = Rc::new(Cell::new(0));
+    let mut txs = Vec::new();
+    let mut rxs = Vec::new();
+    for _ in 0..100 {
+        let (tx, mut rx) = oneshot::channel::<usize>();
+        let polls = polls.clone();
+        txs.push(tx);
+        rxs.push(Box::new(poll_fn(move || {
+            polls.set(polls.get() + 1);
+            rx.poll()
+        })) as Box<Future<Item = usize, Error = oneshot::Canceled>>);
+    }
+
+    let mut f = executor::spawn(select_set(rxs));
+    assert!(f.poll_future_notify(&support::notify_noop(), 0).unwrap().is_not_ready());
+    assert_eq!(polls.get(), 100);
+
+    // Only the children which were notified are polled, also by the
+    // remaining set.
+    let mut txs = txs.into_iter().enumerate().rev().collect::<Vec<_>>();
+    for (i, tx) in txs.drain(..2) {
+        tx.send(i).unwrap();
+    }
+    let (i, idx, rest) = f.wait_future().ok().unwrap();
+    assert_eq!((i, idx), (99, 99));
+    let (i, idx, mut rest) = rest.wait().ok().unwrap();
+    assert_eq!((i, idx), (98, 98));
+    assert_eq!(polls.get(), 102);
+
+    // Futures can be added to the remaining set.
+    assert_eq!(rest.push(Box::new(ok(7))), 100);
+    assert_eq!(rest.len(), 99);
+    let (i, idx, rest) = rest.wait().ok().unwrap();
+    assert_eq!((i, idx, rest.len()), (7, 100, 98));
+    assert_eq!(polls.get(), 102);
+}
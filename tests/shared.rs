@@ -202,3 +202,51 @@ fn recursive_poll_with_unpark() {
     drop(tx0);
     core.run(f3).unwrap();
 }
+
+#[test]
+fn clones_dropped_while_waiting() {
+    use futures::test::task::MockTask;
+
+    let (tx, rx) = oneshot::channel::<u32>();
+    let mut f = rx.shared();
+    let task = MockTask::new();
+    assert!(task.poll_future(&mut f).unwrap().is_not_ready());
+
+    // Clones which wait and go away again mustn't pile up or get in the way
+    // of the remaining waiters.
+    for _ in 0..1000 {
+        let mut clone = f.clone();
+        assert!(MockTask::new().poll_future(&mut clone).unwrap().is_not_ready());
+    }
+    assert!(task.poll_future(&mut f).unwrap().is_not_ready());
+
+    tx.send(3).unwrap();
+    assert!(task.is_notified());
+    match task.poll_future(&mut f) {
+        Ok(Async::Ready(n)) => assert_eq!(*n, 3),
+        _ => panic!(),
+    }
+}
+
+#[test]
+fn repolled_on_many_threads() {
+    use futures::sync::mpsc;
+
+    // The clones keep waking each other up while the stream makes progress.
+    let (tx, rx) = mpsc::unbounded::<u32>();
+    let f = rx.for_each(|_| Ok(())).shared();
+    let threads = (0..16).map(|_| {
+        let f = f.clone();
+        thread::spawn(move || {
+            f.wait().unwrap();
+        })
+    }).collect::<Vec<_>>();
+    for i in 0..10000 {
+        tx.unbounded_send(i).unwrap();
+    }
+    drop(tx);
+    f.wait().unwrap();
+    for thread in threads {
+        thread.join().unwrap();
+    }
+}
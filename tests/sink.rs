@@ -1,3 +1,4 @@
+#[macro_use]
 extern crate futures;
 
 use std::mem;
@@ -12,7 +13,7 @@ use futures::stream;
 use futures::sync::{oneshot, mpsc};
 use futures::task::{self, Task};
 use futures::executor::{self, Notify};
-use futures::sink::SinkFromErr;
+use futures::sink::{Fanout, SinkFromErr};
 
 mod support;
 use support::*;
@@ -26,6 +27,38 @@ fn vec_sink() {
     assert_done(move || v.flush(), Ok(vec![0, 1]));
 }
 
+#[test]
+fn vec_deque_sink() {
+    use std::collections::VecDeque;
+
+    let mut v = VecDeque::new();
+    assert_eq!(v.start_send(0), Ok(AsyncSink::Ready));
+    assert_eq!(v.start_send(1), Ok(AsyncSink::Ready));
+    assert_eq!(v, vec![0, 1]);
+    assert_done(move || v.flush(), Ok(vec![0, 1].into_iter().collect()));
+}
+
+#[test]
+fn string_sink() {
+    let mut s = String::new();
+    assert_eq!(s.start_send('a'), Ok(AsyncSink::Ready));
+    assert_eq!(s, "a");
+    let chars = stream::iter_ok::<_, ()>("bc".chars());
+    let (s, _) = s.send_all(chars).wait().unwrap();
+    assert_eq!(s, "abc");
+}
+
+#[test]
+fn drain() {
+    let mut sink = futures::sink::drain();
+    assert_eq!(sink.start_send(0), Ok(AsyncSink::Ready));
+    assert_eq!(sink.poll_complete(), Ok(Async::Ready(())));
+    assert_eq!(sink.close(), Ok(Async::Ready(())));
+
+    let (_, rest) = futures::sink::drain().send_all(stream::iter_ok::<_, ()>(0..100)).wait().unwrap();
+    assert!(rest.collect().wait().unwrap().is_empty());
+}
+
 #[test]
 fn send() {
     let v = Vec::new();
@@ -40,6 +73,26 @@ fn send() {
                 Ok(vec![0, 1, 2]));
 }
 
+#[test]
+fn send_ref_and_feed() {
+    let mut v = Vec::new();
+    v.send_ref(0).wait().unwrap();
+    v.feed(1).wait().unwrap();
+    assert_eq!(v, vec![0, 1]);
+
+    let (mut sink, allow) = manual_allow::<i32>();
+    let flag = Flag::new();
+    {
+        let mut task = executor::spawn(sink.feed(0));
+        assert!(task.poll_future_notify(&flag, 0).unwrap().is_not_ready());
+        allow.start();
+        assert!(flag.get());
+        assert!(task.poll_future_notify(&flag, 0).unwrap().is_ready());
+    }
+    sink.send_ref(1).wait().unwrap();
+    assert_eq!(sink.data, vec![0, 1]);
+}
+
 #[test]
 fn send_all() {
     let v = Vec::new();
@@ -180,6 +233,36 @@ fn with_flat_map() {
     assert_eq!(sink.get_ref(), &[1,2,2,3,3,3]);
 }
 
+#[test]
+// test that dropped items never reach the sink while mapped ones are held
+// until the underlying sink is ready
+fn with_filter_map() {
+    let sink = Vec::new().with_filter_map(|item: i32| {
+        if item % 2 == 0 { None } else { Some(item.to_string()) }
+    });
+    let sink = sink.send(1).wait().unwrap();
+    let sink = sink.send(2).wait().unwrap();
+    let sink = sink.send(3).wait().unwrap();
+    assert_eq!(sink.get_ref(), &["1", "3"]);
+
+    let (sink, allow) = manual_allow::<i32>();
+    let sink = sink.with_filter_map(|item: i32| {
+        if item < 0 { None } else { Some(item * 2) }
+    });
+    let sink = StartSendFut::new(sink, -1).wait().unwrap();
+    let sink = StartSendFut::new(sink, 1).wait().unwrap();
+
+    let flag = Flag::new();
+    let mut task = executor::spawn(sink.send(2));
+    assert!(task.poll_future_notify(&flag, 0).unwrap().is_not_ready());
+    allow.start();
+    assert!(flag.get());
+    match task.poll_future_notify(&flag, 0).unwrap() {
+        Async::Ready(sink) => assert_eq!(sink.get_ref().data, vec![2, 4]),
+        _ => panic!(),
+    }
+}
+
 // Immediately accepts all requests to start pushing, but completion is managed
 // by manually flushing
 struct ManualFlush<T> {
@@ -386,3 +469,629 @@ fn from_err() {
     let tx = mpsc::channel(0).0;
     assert_eq!(tx.sink_from_err().start_send(()), Err(FromErrTest));
 }
+
+#[test]
+fn err_into() {
+    let (tx, _rx) = mpsc::channel(1);
+    let mut tx = tx.sink_err_into::<FromErrTest>();
+    assert_eq!(tx.start_send(()), Ok(AsyncSink::Ready));
+    assert_eq!(tx.poll_complete(), Ok(Async::Ready(())));
+
+    let tx = mpsc::channel(0).0;
+    assert_eq!(tx.sink_err_into::<FromErrTest>().start_send(()), Err(FromErrTest));
+}
+
+#[test]
+fn fanout() {
+    let (a, allow_a) = manual_allow::<i32>();
+    let (b, allow_b) = manual_allow::<i32>();
+    allow_a.start();
+
+    let mut fanout = Fanout::new();
+    let ka = fanout.add(a);
+    let kb = fanout.add(b);
+    let mut task = executor::spawn(fanout);
+    let flag = Flag::new();
+
+    // `b` isn't ready, so it holds on to its copy and the next item has to
+    // wait.
+    assert_eq!(task.start_send_notify(1, &flag, 0), Ok(AsyncSink::Ready));
+    assert_eq!(task.start_send_notify(2, &flag, 0), Ok(AsyncSink::NotReady(2)));
+    assert_eq!(task.poll_flush_notify(&flag, 0), Ok(Async::NotReady));
+    allow_b.start();
+    assert!(flag.get());
+    assert_eq!(task.poll_flush_notify(&flag, 0), Ok(Async::Ready(())));
+    assert_eq!(task.start_send_notify(2, &flag, 0), Ok(AsyncSink::Ready));
+
+    // Sinks added later only see later items.
+    let (c, allow_c) = manual_allow::<i32>();
+    allow_c.start();
+    let kc = task.get_mut().add(c);
+    assert_eq!(task.start_send_notify(3, &flag, 0), Ok(AsyncSink::Ready));
+    assert_eq!(task.close_notify(&flag, 0), Ok(Async::Ready(())));
+
+    let mut fanout = task.into_inner();
+    assert_eq!(fanout.len(), 3);
+    assert_eq!(fanout.get_ref(kb).unwrap().data, vec![1, 2, 3]);
+    assert_eq!(fanout.remove(kc).unwrap().data, vec![3]);
+    assert_eq!(fanout.remove(ka).unwrap().data, vec![1, 2, 3]);
+    assert!(fanout.remove(ka).is_none());
+    assert_eq!(fanout.len(), 1);
+}
+
+#[test]
+fn buffer_with_policy() {
+    use futures::stream::{DropNewest, DropOldest, Evict};
+
+    let flag = Flag::new();
+
+    let (sink, allow) = manual_allow::<i32>();
+    let mut task = executor::spawn(sink.buffer_with_policy(1, DropOldest));
+    for i in 0..5 {
+        assert_eq!(task.start_send_notify(i, &flag, 0), Ok(AsyncSink::Ready));
+    }
+    allow.start();
+    assert_eq!(task.poll_flush_notify(&flag, 0), Ok(Async::Ready(())));
+    assert_eq!(task.get_ref().get_ref().data, vec![3, 4]);
+
+    let (sink, allow) = manual_allow::<i32>();
+    let mut task = executor::spawn(sink.buffer_with_policy(1, DropNewest));
+    for i in 0..5 {
+        assert_eq!(task.start_send_notify(i, &flag, 0), Ok(AsyncSink::Ready));
+    }
+    allow.start();
+    assert_eq!(task.poll_flush_notify(&flag, 0), Ok(Async::Ready(())));
+    assert_eq!(task.get_ref().get_ref().data, vec![0, 1]);
+
+    let evicted = RefCell::new(Vec::new());
+    let (sink, allow) = manual_allow::<i32>();
+    let policy = Evict(|i| evicted.borrow_mut().push(i));
+    let mut task = executor::spawn(sink.buffer_with_policy(1, policy));
+    for i in 0..4 {
+        assert_eq!(task.start_send_notify(i, &flag, 0), Ok(AsyncSink::Ready));
+    }
+    assert_eq!(*evicted.borrow(), vec![0, 1]);
+    allow.start();
+    assert_eq!(task.close_notify(&flag, 0), Ok(Async::Ready(())));
+    assert_eq!(task.get_ref().get_ref().data, vec![2, 3]);
+}
+
+#[test]
+fn flush_timeout() {
+    use std::time::Duration;
+    use futures::sink::FlushTimeoutError;
+    use support::timer::ManualTimer;
+
+    let timer = ManualTimer::new();
+    let flag = Flag::new();
+
+    let (sink, allow) = manual_allow::<i32>();
+    let sink = sink.buffer(1).flush_timeout(Duration::from_secs(10), &timer);
+    let mut task = executor::spawn(sink);
+    assert_eq!(task.start_send_notify(1, &flag, 0), Ok(AsyncSink::Ready));
+    assert_eq!(task.poll_flush_notify(&flag, 0), Ok(Async::NotReady));
+    timer.advance(Duration::from_secs(5));
+    assert!(flag.get());
+    assert_eq!(task.poll_flush_notify(&flag, 0), Ok(Async::NotReady));
+    allow.start();
+    assert_eq!(task.poll_flush_notify(&flag, 0), Ok(Async::Ready(())));
+
+    // A new flush gets the full duration again.
+    timer.advance(Duration::from_secs(8));
+    assert_eq!(task.start_send_notify(2, &flag, 0), Ok(AsyncSink::Ready));
+    assert_eq!(task.close_notify(&flag, 0), Ok(Async::Ready(())));
+    assert_eq!(task.get_ref().get_ref().get_ref().data, vec![1, 2]);
+
+    let (sink, _allow) = manual_allow::<i32>();
+    let sink = sink.buffer(1).flush_timeout(Duration::from_secs(10), &timer);
+    let mut task = executor::spawn(sink);
+    assert_eq!(task.start_send_notify(1, &flag, 0), Ok(AsyncSink::Ready));
+    assert_eq!(task.poll_flush_notify(&flag, 0), Ok(Async::NotReady));
+    flag.set(false);
+    timer.advance(Duration::from_secs(10));
+    assert!(flag.get());
+    assert_eq!(task.poll_flush_notify(&flag, 0), Err(FlushTimeoutError::Elapsed));
+}
+
+#[test]
+fn rate_limit() {
+    use std::time::Duration;
+    use futures::timer::TimerError;
+    use support::timer::ManualTimer;
+
+    let timer = ManualTimer::new();
+    let flag = Flag::new();
+    let ms = Duration::from_millis;
+
+    let sink = Vec::new().sink_map_err(|()| TimerError::new()).rate_limit(2, 2, &timer);
+    let mut task = executor::spawn(sink);
+    assert_eq!(task.start_send_notify(1, &flag, 0), Ok(AsyncSink::Ready));
+    assert_eq!(task.start_send_notify(2, &flag, 0), Ok(AsyncSink::Ready));
+    assert_eq!(task.start_send_notify(3, &flag, 0), Ok(AsyncSink::NotReady(3)));
+    timer.advance(ms(499));
+    assert_eq!(task.start_send_notify(3, &flag, 0), Ok(AsyncSink::NotReady(3)));
+    flag.set(false);
+    timer.advance(ms(1));
+    assert!(flag.get());
+    assert_eq!(task.start_send_notify(3, &flag, 0), Ok(AsyncSink::Ready));
+    assert_eq!(task.start_send_notify(4, &flag, 0), Ok(AsyncSink::NotReady(4)));
+    timer.advance(ms(500));
+    assert_eq!(task.start_send_notify(4, &flag, 0), Ok(AsyncSink::Ready));
+
+    // The bucket never holds more than `burst` tokens.
+    timer.advance(ms(5000));
+    assert_eq!(task.start_send_notify(5, &flag, 0), Ok(AsyncSink::Ready));
+    assert_eq!(task.start_send_notify(6, &flag, 0), Ok(AsyncSink::Ready));
+    assert_eq!(task.start_send_notify(7, &flag, 0), Ok(AsyncSink::NotReady(7)));
+    assert_eq!(task.get_ref().get_ref().get_ref(), &[1, 2, 3, 4, 5, 6]);
+
+    // Heavy items are let through with a full bucket, leaving it in debt.
+    let sink = Vec::new().sink_map_err(|()| TimerError::new())
+        .rate_limit(2, 2, &timer)
+        .weighted(|x: &usize| *x);
+    let mut task = executor::spawn(sink);
+    assert_eq!(task.start_send_notify(5, &flag, 0), Ok(AsyncSink::Ready));
+    assert_eq!(task.start_send_notify(1, &flag, 0), Ok(AsyncSink::NotReady(1)));
+    timer.advance(ms(1999));
+    assert_eq!(task.start_send_notify(1, &flag, 0), Ok(AsyncSink::NotReady(1)));
+    timer.advance(ms(1));
+    assert_eq!(task.start_send_notify(1, &flag, 0), Ok(AsyncSink::Ready));
+}
+
+#[test]
+fn batched() {
+    use std::time::Duration;
+    use futures::timer::TimerError;
+    use support::timer::ManualTimer;
+
+    let timer = ManualTimer::new();
+    let flag = Flag::new();
+    let ms = Duration::from_millis;
+
+    let sink = Vec::new().sink_map_err(|()| TimerError::new())
+        .batched(2, ms(100), &timer);
+    let mut task = executor::spawn(sink);
+
+    // A full batch is sent right away.
+    assert_eq!(task.start_send_notify(1, &flag, 0), Ok(AsyncSink::Ready));
+    assert_eq!(task.start_send_notify(2, &flag, 0), Ok(AsyncSink::Ready));
+    assert_eq!(task.start_send_notify(3, &flag, 0), Ok(AsyncSink::Ready));
+    assert_eq!(task.get_ref().get_ref().get_ref(), &[vec![1, 2]]);
+
+    // Flushing waits for the partial batch to become old enough.
+    timer.advance(ms(50));
+    assert_eq!(task.poll_flush_notify(&flag, 0), Ok(Async::NotReady));
+    flag.set(false);
+    timer.advance(ms(50));
+    assert!(flag.get());
+    assert_eq!(task.poll_flush_notify(&flag, 0), Ok(Async::Ready(())));
+    assert_eq!(task.get_ref().get_ref().get_ref(), &[vec![1, 2], vec![3]]);
+
+    // Closing sends whatever was collected so far.
+    assert_eq!(task.start_send_notify(4, &flag, 0), Ok(AsyncSink::Ready));
+    assert_eq!(task.close_notify(&flag, 0), Ok(Async::Ready(())));
+    assert_eq!(task.get_ref().get_ref().get_ref(), &[vec![1, 2], vec![3], vec![4]]);
+}
+
+#[test]
+fn batched_backpressure() {
+    use std::time::Duration;
+    use futures::timer::TimerError;
+    use support::timer::ManualTimer;
+
+    let timer = ManualTimer::new();
+    let flag = Flag::new();
+
+    let (sink, allow) = manual_allow::<Vec<i32>>();
+    let sink = sink.sink_map_err(|()| TimerError::new())
+        .batched(1, Duration::from_secs(1), &timer);
+    let mut task = executor::spawn(sink);
+
+    // One batch waits for the underlying sink while the next one fills up.
+    assert_eq!(task.start_send_notify(1, &flag, 0), Ok(AsyncSink::Ready));
+    assert_eq!(task.start_send_notify(2, &flag, 0), Ok(AsyncSink::Ready));
+    assert_eq!(task.start_send_notify(3, &flag, 0), Ok(AsyncSink::NotReady(3)));
+    assert_eq!(task.poll_flush_notify(&flag, 0), Ok(Async::NotReady));
+    flag.set(false);
+    allow.start();
+    assert!(flag.get());
+    assert_eq!(task.start_send_notify(3, &flag, 0), Ok(AsyncSink::Ready));
+    assert_eq!(task.poll_flush_notify(&flag, 0), Ok(Async::Ready(())));
+    assert_eq!(task.get_ref().get_ref().get_ref().data, vec![vec![1], vec![2], vec![3]]);
+}
+
+#[test]
+fn unfold() {
+    use futures::future::Either;
+    use futures::sink;
+
+    let flag = Flag::new();
+    let (tx, rx) = oneshot::channel::<()>();
+    let mut rx = Some(rx);
+    let sink = sink::unfold(Vec::new(), move |mut items, item: i32| {
+        items.push(item);
+        // Only the first item is held up until the channel completes.
+        match rx.take() {
+            Some(rx) => Either::A(rx.map(|()| items)),
+            None => Either::B(ok(items)),
+        }
+    });
+    let mut task = executor::spawn(sink);
+    assert_eq!(task.start_send_notify(1, &flag, 0), Ok(AsyncSink::Ready));
+    assert_eq!(task.start_send_notify(2, &flag, 0), Ok(AsyncSink::NotReady(2)));
+    assert_eq!(task.poll_flush_notify(&flag, 0), Ok(Async::NotReady));
+    tx.send(()).unwrap();
+    assert!(flag.get());
+    assert_eq!(task.start_send_notify(2, &flag, 0), Ok(AsyncSink::Ready));
+    assert_eq!(task.close_notify(&flag, 0), Ok(Async::Ready(())));
+    assert_eq!(task.into_inner().into_inner(), Some(vec![1, 2]));
+}
+
+#[test]
+fn either_sink() {
+    use futures::future::Either;
+    use futures::sink::{self, Drain};
+
+    let mut sink = if true {
+        Vec::new().left_sink()
+    } else {
+        sink::drain().right_sink()
+    };
+    assert_eq!(sink.start_send(0), Ok(AsyncSink::Ready));
+    assert_eq!(sink.poll_complete(), Ok(Async::Ready(())));
+    match sink {
+        Either::A(v) => assert_eq!(v, vec![0]),
+        Either::B(_) => panic!(),
+    }
+
+    let sink = Vec::new().right_sink::<Drain<i32>>();
+    let (sink, _) = sink.send_all(stream::iter_ok::<_, ()>(vec![1, 2])).wait().unwrap();
+    match sink {
+        Either::A(_) => panic!(),
+        Either::B(v) => assert_eq!(v, vec![1, 2]),
+    }
+}
+
+#[test]
+fn close_guard() {
+    use futures::future::{Executor, ExecuteError};
+
+    type BoxFuture = Box<Future<Item = (), Error = ()>>;
+
+    #[derive(Clone, Default)]
+    struct Spawned(Rc<RefCell<Vec<BoxFuture>>>);
+
+    impl<F: Future<Item = (), Error = ()> + 'static> Executor<F> for Spawned {
+        fn execute(&self, future: F) -> Result<(), ExecuteError<F>> {
+            self.0.borrow_mut().push(Box::new(future));
+            Ok(())
+        }
+    }
+
+    let spawned = Spawned::default();
+    let flag = Flag::new();
+
+    // Nothing is spawned for a sink which was flushed.
+    let (tx, rx) = mpsc::channel::<i32>(0);
+    let mut task = executor::spawn(tx.buffer(4).close_guard(&spawned));
+    assert_eq!(task.start_send_notify(1, &flag, 0), Ok(AsyncSink::Ready));
+    assert_eq!(task.poll_flush_notify(&flag, 0), Ok(Async::Ready(())));
+    drop(task);
+    assert!(spawned.0.borrow().is_empty());
+    assert_eq!(rx.collect().wait(), Ok(vec![1]));
+
+    // The tail of an unflushed sink is delivered by the spawned future.
+    let (tx, rx) = mpsc::channel::<i32>(0);
+    let mut task = executor::spawn(tx.buffer(4).close_guard(&spawned));
+    for i in 1..4 {
+        assert_eq!(task.start_send_notify(i, &flag, 0), Ok(AsyncSink::Ready));
+    }
+    drop(task);
+    let closing = spawned.0.borrow_mut().pop().unwrap();
+    assert_eq!(closing.join(rx.collect()).wait(), Ok(((), vec![1, 2, 3])));
+}
+
+#[test]
+fn sink2() {
+    use futures::future;
+    use futures::sink::{Sink2, Staged};
+
+    // Items are only created once the sink has room for them.
+    let (sink, allow) = manual_allow::<i32>();
+    let mut sink = Staged::new(sink);
+    let flag = Flag::new();
+    let mut created = 0;
+    let mut task = executor::spawn(future::poll_fn(move || -> Poll<Vec<i32>, ()> {
+        while created < 3 {
+            try_ready!(sink.poll_ready());
+            created += 1;
+            sink.start_send(created)?;
+        }
+        try_ready!(sink.poll_flush());
+        Ok(Async::Ready(mem::replace(&mut sink.get_mut().data, Vec::new())))
+    }));
+    allow.start();
+    assert_eq!(task.poll_future_notify(&flag, 0), Ok(Async::Ready(vec![1, 2, 3])));
+
+    let (sink, allow) = manual_allow::<i32>();
+    let mut sink = Staged::new(sink);
+    let mut task = executor::spawn(future::poll_fn(move || -> Poll<(), ()> {
+        try_ready!(sink.poll_ready());
+        sink.start_send(1)?;
+        sink.poll_ready()
+    }));
+    assert_eq!(task.poll_future_notify(&flag, 0), Ok(Async::NotReady));
+    flag.set(false);
+    allow.start();
+    assert!(flag.get());
+    assert_eq!(task.poll_future_notify(&flag, 0), Ok(Async::Ready(())));
+
+    // A `Sink2` can be driven as a `Sink`.
+    let sink = Vec::new().compat();
+    let (sink, _) = sink.send_all(stream::iter_ok::<_, ()>(vec![1, 2])).wait().unwrap();
+    assert_eq!(sink.into_inner(), vec![1, 2]);
+}
+
+#[test]
+fn spill_buffer() {
+    use std::collections::VecDeque;
+    use futures::sink::Spill;
+
+    #[derive(Clone, Default)]
+    struct Storage(Rc<RefCell<VecDeque<i32>>>);
+
+    impl Spill<i32> for Storage {
+        type Error = ();
+
+        fn spill(&mut self, item: i32) -> Result<(), ()> {
+            self.0.borrow_mut().push_back(item);
+            Ok(())
+        }
+
+        fn unspill(&mut self) -> Result<Option<i32>, ()> {
+            Ok(self.0.borrow_mut().pop_front())
+        }
+    }
+
+    let storage = Storage::default();
+    let (sink, allow) = manual_allow::<i32>();
+    let sink = sink.spill_buffer(2, storage.clone());
+    let flag = Flag::new();
+    let mut task = executor::spawn(sink);
+
+    // Nothing is ever rejected, overflowing items end up in the storage.
+    for i in 0..5 {
+        assert_eq!(task.start_send_notify(i, &flag, 0), Ok(AsyncSink::Ready));
+    }
+    assert_eq!(task.get_ref().spilled(), 3);
+    assert_eq!(*storage.0.borrow(), vec![2, 3, 4]);
+    assert_eq!(task.poll_flush_notify(&flag, 0), Ok(Async::NotReady));
+
+    allow.start();
+    assert!(flag.get());
+    assert_eq!(task.poll_flush_notify(&flag, 0), Ok(Async::Ready(())));
+    assert_eq!(task.get_ref().spilled(), 0);
+    assert!(storage.0.borrow().is_empty());
+    assert_eq!(task.get_ref().get_ref().data, vec![0, 1, 2, 3, 4]);
+}
+
+#[test]
+fn route() {
+    use std::collections::HashMap;
+    use futures::sink::{self, RouteError};
+
+    let flag = Flag::new();
+    let (tx, rx) = mpsc::channel::<i32>(0);
+    let mut sinks = HashMap::new();
+    sinks.insert(0, tx);
+    let router = sink::route(|x: &i32| x % 10, sinks);
+    let handle = router.handle();
+    let mut task = executor::spawn(router);
+
+    assert_eq!(task.start_send_notify(10, &flag, 0), Ok(AsyncSink::Ready));
+    assert_eq!(task.start_send_notify(20, &flag, 0), Ok(AsyncSink::NotReady(20)));
+    assert_eq!(task.start_send_notify(1, &flag, 0), Err(RouteError::NoRoute(1)));
+
+    // Routes can be added and removed while the router is in use, removed
+    // sinks being closed.
+    let (tx1, rx1) = mpsc::channel::<i32>(0);
+    handle.insert(1, tx1);
+    assert_eq!(task.start_send_notify(1, &flag, 0), Ok(AsyncSink::Ready));
+    assert_eq!(task.get_ref().len(), 2);
+    handle.remove(0);
+    assert_eq!(task.poll_flush_notify(&flag, 0), Ok(Async::Ready(())));
+    assert_eq!(task.get_ref().len(), 1);
+    assert_eq!(rx.collect().wait(), Ok(vec![10]));
+    assert_eq!(task.start_send_notify(10, &flag, 0), Err(RouteError::NoRoute(10)));
+
+    drop(task);
+    assert_eq!(rx1.collect().wait(), Ok(vec![1]));
+}
+
+#[test]
+fn route_close_error() {
+    use std::collections::HashMap;
+    use futures::sink::{self, RouteError};
+
+    // A sink whose close fails, counting how often it was closed.
+    struct FailClose(Rc<Cell<usize>>);
+
+    impl Sink for FailClose {
+        type SinkItem = i32;
+        type SinkError = ();
+
+        fn start_send(&mut self, item: i32) -> StartSend<i32, ()> {
+            Ok(AsyncSink::NotReady(item))
+        }
+
+        fn poll_complete(&mut self) -> Poll<(), ()> {
+            Ok(Async::Ready(()))
+        }
+
+        fn close(&mut self) -> Poll<(), ()> {
+            self.0.set(self.0.get() + 1);
+            Err(())
+        }
+    }
+
+    let flag = Flag::new();
+    let closes = Rc::new(Cell::new(0));
+    let router = sink::route(|x: &i32| *x, HashMap::new());
+    let handle = router.handle();
+    let mut task = executor::spawn(router);
+
+    // The removed sink's error is passed on once, after which it's gone.
+    handle.insert(0, FailClose(closes.clone()));
+    handle.remove(0);
+    assert_eq!(task.poll_flush_notify(&flag, 0), Err(RouteError::Inner(())));
+    assert_eq!(task.poll_flush_notify(&flag, 0), Ok(Async::Ready(())));
+    assert_eq!(closes.get(), 1);
+}
+
+#[test]
+fn balance() {
+    use futures::sink::{self, BalanceStrategy};
+
+    let flag = Flag::new();
+
+    // Sinks which aren't ready are skipped.
+    let (a, allow_a) = manual_allow::<i32>();
+    let (b, _allow_b) = manual_allow::<i32>();
+    allow_a.start();
+    let mut task = executor::spawn(sink::balance(vec![a, b]));
+    for i in 0..3 {
+        assert_eq!(task.start_send_notify(i, &flag, 0), Ok(AsyncSink::Ready));
+    }
+    assert_eq!(task.get_ref().get_ref(0).unwrap().data, vec![0, 1, 2]);
+
+    // Only once no sink is ready is the item handed back.
+    let (a, _allow_a) = manual_allow::<i32>();
+    let mut task = executor::spawn(sink::balance(vec![a]));
+    assert_eq!(task.start_send_notify(0, &flag, 0), Ok(AsyncSink::NotReady(0)));
+
+    // The least loaded sink catches up with the others.
+    let (a, allow_a) = manual_allow::<i32>();
+    let (b, allow_b) = manual_allow::<i32>();
+    allow_a.start();
+    let balance = sink::balance_with(vec![a, b], BalanceStrategy::LeastLoaded);
+    let mut task = executor::spawn(balance);
+    for i in 0..2 {
+        assert_eq!(task.start_send_notify(i, &flag, 0), Ok(AsyncSink::Ready));
+    }
+    allow_b.start();
+    for i in 2..6 {
+        assert_eq!(task.start_send_notify(i, &flag, 0), Ok(AsyncSink::Ready));
+    }
+    assert_eq!(task.get_ref().pending(0), Some(3));
+    assert_eq!(task.get_ref().pending(1), Some(3));
+    assert_eq!(task.poll_flush_notify(&flag, 0), Ok(Async::Ready(())));
+    assert_eq!(task.get_ref().pending(0), Some(0));
+    let sinks = task.into_inner().into_inner();
+    assert_eq!(sinks[0].data, vec![0, 1, 4]);
+    assert_eq!(sinks[1].data, vec![2, 3, 5]);
+}
+
+#[test]
+fn retry() {
+    use std::time::Duration;
+    use futures::sink::ExponentialBackoff;
+    use futures::timer::TimerError;
+    use support::timer::ManualTimer;
+
+    #[derive(Debug, PartialEq)]
+    enum Failure {
+        Transient,
+        Fatal,
+        Timer,
+    }
+
+    impl From<TimerError> for Failure {
+        fn from(_: TimerError) -> Failure {
+            Failure::Timer
+        }
+    }
+
+    // Fails with the queued up failures before accepting items.
+    struct Flaky {
+        data: Vec<i32>,
+        failures: Vec<Failure>,
+    }
+
+    impl Sink for Flaky {
+        type SinkItem = i32;
+        type SinkError = Failure;
+
+        fn start_send(&mut self, item: i32) -> StartSend<i32, Failure> {
+            if self.failures.is_empty() {
+                self.data.push(item);
+                Ok(AsyncSink::Ready)
+            } else {
+                Err(self.failures.remove(0))
+            }
+        }
+
+        fn poll_complete(&mut self) -> Poll<(), Failure> {
+            Ok(Async::Ready(()))
+        }
+
+        fn close(&mut self) -> Poll<(), Failure> {
+            Ok(Async::Ready(()))
+        }
+    }
+
+    let backoff = ExponentialBackoff::new(Duration::from_millis(10))
+        .max_delay(Duration::from_millis(25))
+        .max_attempts(4);
+    assert_eq!(backoff.delay(1), Some(Duration::from_millis(10)));
+    assert_eq!(backoff.delay(2), Some(Duration::from_millis(20)));
+    assert_eq!(backoff.delay(3), Some(Duration::from_millis(25)));
+    assert_eq!(backoff.delay(4), None);
+
+    let timer = ManualTimer::new();
+    let flag = Flag::new();
+    let ms = Duration::from_millis;
+
+    let flaky = Flaky {
+        data: Vec::new(),
+        failures: vec![Failure::Transient, Failure::Transient],
+    };
+    let mut task = executor::spawn(flaky.retry(backoff, &timer));
+    assert_eq!(task.start_send_notify(1, &flag, 0), Ok(AsyncSink::Ready));
+    assert_eq!(task.poll_flush_notify(&flag, 0), Ok(Async::NotReady));
+    assert_eq!(task.start_send_notify(2, &flag, 0), Ok(AsyncSink::NotReady(2)));
+    timer.advance(ms(10));
+    assert_eq!(task.poll_flush_notify(&flag, 0), Ok(Async::NotReady));
+    timer.advance(ms(20));
+    assert_eq!(task.poll_flush_notify(&flag, 0), Ok(Async::Ready(())));
+    assert_eq!(task.start_send_notify(2, &flag, 0), Ok(AsyncSink::Ready));
+    assert_eq!(task.get_ref().get_ref().data, vec![1, 2]);
+
+    // Errors which aren't classified as retryable are returned right away.
+    let flaky = Flaky {
+        data: Vec::new(),
+        failures: vec![Failure::Transient, Failure::Fatal],
+    };
+    let policy = |e: &Failure, _attempt: u32| {
+        if *e == Failure::Transient { Some(ms(1)) } else { None }
+    };
+    let mut task = executor::spawn(flaky.retry(policy, &timer));
+    assert_eq!(task.start_send_notify(1, &flag, 0), Ok(AsyncSink::Ready));
+    timer.advance(ms(1));
+    assert_eq!(task.poll_flush_notify(&flag, 0), Err(Failure::Fatal));
+    assert_eq!(task.start_send_notify(2, &flag, 0), Ok(AsyncSink::Ready));
+    assert_eq!(task.get_ref().get_ref().data, vec![2]);
+
+    // A failed delay is reported once, and the item is retried right away on
+    // the next flush.
+    let timer = ManualTimer::new();
+    let flaky = Flaky {
+        data: Vec::new(),
+        failures: vec![Failure::Transient],
+    };
+    let mut task = executor::spawn(flaky.retry(backoff, &timer));
+    assert_eq!(task.start_send_notify(1, &flag, 0), Ok(AsyncSink::Ready));
+    timer.shut_down();
+    assert_eq!(task.poll_flush_notify(&flag, 0), Err(Failure::Timer));
+    assert_eq!(task.poll_flush_notify(&flag, 0), Ok(Async::Ready(())));
+    assert_eq!(task.get_ref().get_ref().data, vec![1]);
+}
@@ -45,3 +45,55 @@ fn test_split() {
     }
     assert_eq!(dest, vec![10, 20, 30]);
 }
+
+#[test]
+fn reunite_mismatched() {
+    let (sink1, stream1) = Join(iter_ok::<_, ()>(vec![1]), Vec::<i32>::new()).split();
+    let (sink2, stream2) = Join(iter_ok::<_, ()>(vec![2]), Vec::<i32>::new()).split();
+    let err = match sink1.reunite(stream2) {
+        Ok(_) => panic!("halves of different objects were reunited"),
+        Err(err) => err,
+    };
+    assert!(err.0.reunite(stream1).is_ok());
+    assert!(err.1.reunite(sink2).is_ok());
+}
+
+#[test]
+fn split_across_threads() {
+    use std::thread;
+    use futures::sync::mpsc;
+
+    let (tx, rx) = mpsc::unbounded::<i32>();
+    let (out_tx, out_rx) = mpsc::unbounded::<i32>();
+    let (sink, stream) = Join(rx, out_tx).split();
+
+    // Both halves are driven at the same time, one of them on a thread of
+    // its own.
+    let writer = thread::spawn(move || {
+        sink.send_all(iter_ok(0..1000)).wait().unwrap().0
+    });
+    for i in 0..1000 {
+        tx.unbounded_send(i).unwrap();
+    }
+    drop(tx);
+    let read = stream.collect().wait().unwrap();
+    let sink = writer.join().unwrap();
+    drop(sink);
+
+    assert_eq!(read, (0..1000).collect::<Vec<_>>());
+    assert_eq!(out_rx.collect().wait().unwrap(), (0..1000).collect::<Vec<_>>());
+}
+
+#[test]
+fn panic_leaves_other_half_usable() {
+    use std::panic::{self, AssertUnwindSafe};
+    use futures::stream::poll_fn;
+
+    let stream = poll_fn::<i32, (), _>(|| panic!("poll failed"));
+    let (mut sink, mut stream) = Join(stream, Vec::new()).split();
+    let res = panic::catch_unwind(AssertUnwindSafe(|| stream.poll()));
+    assert!(res.is_err());
+    assert_eq!(sink.start_send(1), Ok(AsyncSink::Ready));
+    let j = sink.reunite(stream).unwrap();
+    assert_eq!(j.1, vec![1]);
+}
@@ -4,9 +4,10 @@ extern crate futures;
 use futures::prelude::*;
 use futures::executor;
 use futures::future::{err, ok};
-use futures::stream::{empty, iter_ok, poll_fn, Peekable};
+use futures::stream::{self, empty, iter_ok, poll_fn, BatchConfig, Peekable};
 use futures::sync::oneshot;
 use futures::sync::mpsc;
+use futures::test::task::MockTask;
 
 mod support;
 use support::*;
@@ -91,6 +92,28 @@ fn filter() {
     assert_done(|| list().filter(|a| *a % 2 == 0).collect(), Ok(vec![2]));
 }
 
+#[test]
+fn filter_async() {
+    assert_done(|| list().filter_async(|a| ok(*a % 2 == 1)).collect(),
+                Ok(vec![1, 3]));
+    assert_done(|| err_list().filter_async(|_| ok(true)).collect(), Err(3));
+    assert_done(|| list().filter_async(|a| {
+        if *a == 2 { err(7) } else { ok(true) }
+    }).collect(), Err(7));
+
+    let (tx, rx) = oneshot::channel::<bool>();
+    let mut rx = Some(rx);
+    let mut stream = iter_ok::<_, u32>(vec![1, 2])
+        .filter_async(move |_| match rx.take() {
+            Some(rx) => Box::new(rx.map_err(|_| 0u32)) as Box<Future<Item = _, Error = _>>,
+            None => Box::new(ok(true)),
+        });
+    sassert_empty(&mut stream);
+    tx.send(false).unwrap();
+    sassert_next(&mut stream, 2);
+    sassert_done(&mut stream);
+}
+
 #[test]
 fn filter_map() {
     assert_done(|| list().filter_map(|x| {
@@ -129,6 +152,38 @@ fn flatten() {
 
 }
 
+#[test]
+fn flat_map() {
+    assert_done(|| list().flat_map(|i| iter_ok::<_, u32>(vec![i; i as usize])).collect(),
+                Ok(vec![1, 2, 2, 3, 3, 3]));
+    assert_done(|| err_list().flat_map(|_| list()).collect(), Err(3));
+}
+
+#[test]
+fn flat_map_unordered() {
+    let (tx1, rx1) = mpsc::unbounded::<i32>();
+    let (tx2, rx2) = mpsc::unbounded::<i32>();
+    let (tx3, rx3) = mpsc::unbounded::<i32>();
+    let mut inner = vec![rx1, rx2, rx3].into_iter();
+    let mut s = iter_ok::<_, ()>(vec![(); 3])
+        .flat_map_unordered(2, move |()| inner.next().unwrap())
+        .map_err(|_| ());
+
+    // The third stream isn't polled until one of the first two finishes.
+    tx3.unbounded_send(30).unwrap();
+    tx2.unbounded_send(20).unwrap();
+    sassert_next(&mut s, 20);
+    sassert_empty(&mut s);
+    tx1.unbounded_send(10).unwrap();
+    drop(tx1);
+    sassert_next(&mut s, 10);
+    sassert_next(&mut s, 30);
+    sassert_empty(&mut s);
+    drop(tx2);
+    drop(tx3);
+    sassert_done(&mut s);
+}
+
 #[test]
 fn skip() {
     assert_done(|| list().skip(2).collect(), Ok(vec![3]));
@@ -162,6 +217,30 @@ fn take_while() {
                 Ok(vec![1, 2]));
 }
 
+#[test]
+fn while_async_transition() {
+    use futures::stream::Transition;
+
+    assert_done(|| list().skip_while_async(|e| ok(*e < 2), Transition::Keep).collect(),
+                Ok(vec![2, 3]));
+    assert_done(|| list().skip_while_async(|e| ok(*e < 2), Transition::Drop).collect(),
+                Ok(vec![3]));
+
+    let mut taken = iter_ok::<_, u32>(vec![1, 2, 3, 4])
+        .take_while_async(|e| ok(*e < 3), Transition::Drop);
+    sassert_next(&mut taken, 1);
+    sassert_next(&mut taken, 2);
+    sassert_done(&mut taken);
+    assert_done(|| taken.into_remainder().collect(), Ok(vec![4]));
+
+    let mut taken = iter_ok::<_, u32>(vec![1, 2, 3, 4])
+        .take_while_async(|e| ok(*e < 3), Transition::Keep);
+    sassert_next(&mut taken, 1);
+    sassert_next(&mut taken, 2);
+    sassert_done(&mut taken);
+    assert_done(|| taken.into_remainder().collect(), Ok(vec![3, 4]));
+}
+
 #[test]
 fn take_passes_errors_through() {
     let mut s = iter(vec![Err(1), Err(2), Ok(3), Ok(4), Err(4)])
@@ -193,6 +272,19 @@ fn fuse() {
     assert_eq!(stream.next(), None);
 }
 
+#[test]
+fn fuse_is_done() {
+    let mut stream = iter_ok::<_, u32>(vec![1, 2, 3]).fuse();
+    assert!(!stream.is_done());
+    sassert_next(&mut stream, 1);
+    sassert_next(&mut stream, 2);
+    sassert_next(&mut stream, 3);
+    assert!(!stream.is_done());
+    sassert_done(&mut stream);
+    assert!(stream.is_done());
+    sassert_done(&mut stream);
+}
+
 #[test]
 fn buffered() {
     let (tx, rx) = mpsc::channel(1);
@@ -232,6 +324,36 @@ fn buffered() {
     assert_eq!(rx.next(), None);
 }
 
+#[test]
+fn buffered_errors() {
+    let (a, b) = oneshot::channel::<Result<u32, u32>>();
+    let (c, d) = oneshot::channel::<Result<u32, u32>>();
+    let (e, f) = oneshot::channel::<Result<u32, u32>>();
+    let mut rx = iter_ok::<_, u32>(vec![b, d, f])
+        .map(|rx| rx.then(|res| res.unwrap()))
+        .buffered(2);
+    sassert_empty(&mut rx);
+
+    // Errors wait for the results of the futures pulled out before them.
+    c.send(Err(3)).unwrap();
+    e.send(Ok(7)).unwrap();
+    sassert_empty(&mut rx);
+    a.send(Ok(5)).unwrap();
+    sassert_next(&mut rx, 5);
+    sassert_err(&mut rx, 3);
+    sassert_next(&mut rx, 7);
+    sassert_done(&mut rx);
+
+    let mut rx = iter_ok::<_, u32>(vec![Err(1), Ok(2), Err(3), Ok(4)])
+        .map(|res| res.into_future())
+        .buffered(4);
+    sassert_err(&mut rx, 1);
+    sassert_next(&mut rx, 2);
+    sassert_err(&mut rx, 3);
+    sassert_next(&mut rx, 4);
+    sassert_done(&mut rx);
+}
+
 #[test]
 fn unordered() {
     let (tx, rx) = mpsc::channel(1);
@@ -328,12 +450,50 @@ fn chunks() {
     assert_eq!(i, 3);
 }
 
+#[test]
+fn chunks_into_pending() {
+    let (tx, rx) = mpsc::unbounded::<i32>();
+    let mut s = rx.chunks(3);
+    for i in 0..5 {
+        tx.unbounded_send(i).unwrap();
+    }
+    sassert_next(&mut s, vec![0, 1, 2]);
+    sassert_empty(&mut s);
+    assert_eq!(s.into_pending(), (vec![3, 4], None));
+
+    // An error which arrived after a partial chunk is handed back too.
+    let mut s = iter(vec![Ok(1), Err(2), Ok(3)]).chunks(3);
+    sassert_next(&mut s, vec![1]);
+    assert_eq!(s.into_pending(), (vec![], Some(2)));
+}
+
 #[test]
 #[should_panic]
 fn chunks_panic_on_cap_zero() {
     let _ = list().chunks(0);
 }
 
+#[test]
+fn array_chunks() {
+    assert_done(|| list().array_chunks::<[i32; 3]>().collect(), Ok(vec![[1, 2, 3]]));
+    assert_done(|| list().array_chunks::<[i32; 1]>().collect(), Ok(vec![[1], [2], [3]]));
+
+    let mut s = iter(vec![Ok(1), Err(2), Ok(3), Ok(4), Ok(5), Ok(6), Ok(7)])
+        .array_chunks::<[i32; 3]>();
+    assert_eq!(s.size_hint(), (0, None));
+    sassert_err(&mut s, 2);
+    assert_eq!(s.remainder_len(), 1);
+    sassert_next(&mut s, [1, 3, 4]);
+    sassert_next(&mut s, [5, 6, 7]);
+    sassert_done(&mut s);
+    assert_eq!(s.into_remainder().len(), 0);
+
+    let mut s = iter_ok::<_, u32>(vec![String::from("a"); 7]).array_chunks::<[String; 3]>();
+    assert_eq!(s.size_hint(), (2, Some(2)));
+    assert_eq!(s.by_ref().collect().wait().unwrap().len(), 2);
+    assert_eq!(s.into_remainder().collect::<Vec<_>>(), vec![String::from("a")]);
+}
+
 #[test]
 fn select() {
     let a = iter_ok::<_, u32>(vec![1, 2, 3]);
@@ -398,3 +558,775 @@ fn stream_poll_fn() {
 
     assert_eq!(read_stream.wait().count(), 5);
 }
+
+#[test]
+fn cycle() {
+    let a = iter_ok::<_, u32>(vec![1, 2]);
+    assert_done(move || a.cycle().take(5).collect(), Ok(vec![1, 2, 1, 2, 1]));
+
+    let b = iter_ok::<_, u32>(Vec::<i32>::new());
+    assert_done(move || b.cycle().collect(), Ok(vec![]));
+
+    let mut n = 0;
+    let c = futures::stream::cycle_fn(move || {
+        n += 1;
+        iter_ok::<_, u32>(0..n)
+    });
+    assert_done(move || c.take(6).collect(), Ok(vec![0, 0, 1, 0, 1, 2]));
+}
+
+#[test]
+fn unzip() {
+    let pairs = iter_ok::<_, u32>(vec![(1, 'a'), (2, 'b'), (3, 'c')]);
+    let (left, right) = pairs.unzip(1);
+    assert_done(move || left.zip(right).collect(),
+                Ok(vec![(1, 'a'), (2, 'b'), (3, 'c')]));
+
+    // Only one item may be buffered for the right-hand side, so the left-hand
+    // side is blocked after pulling a single pair.
+    let pairs = iter_ok::<_, u32>(vec![(1, 'a'), (2, 'b'), (3, 'c')]);
+    let (mut left, mut right) = pairs.unzip(1);
+    sassert_next(&mut left, 1);
+    sassert_empty(&mut left);
+    sassert_next(&mut right, 'a');
+    sassert_next(&mut left, 2);
+    sassert_next(&mut right, 'b');
+    sassert_next(&mut right, 'c');
+    sassert_next(&mut left, 3);
+    sassert_done(&mut left);
+    sassert_done(&mut right);
+
+    let pairs = iter_ok::<_, u32>(vec![(1, 'a'), (2, 'b'), (3, 'c')]);
+    let (left, right) = pairs.unzip(1);
+    drop(right);
+    assert_done(move || left.collect(), Ok(vec![1, 2, 3]));
+}
+
+#[test]
+fn last() {
+    assert_done(|| list().last(), Ok(Some(3)));
+    assert_done(|| err_list().last(), Err(3));
+    assert_done(|| empty::<i32, u32>().last(), Ok(None));
+}
+
+#[test]
+fn statistics() {
+    assert_done(|| list().count(), Ok(3));
+    assert_done(|| err_list().count(), Err(3));
+    assert_done(|| empty::<i32, u32>().count(), Ok(0));
+
+    assert_done(|| list().sum(), Ok(6));
+    assert_done(|| err_list().sum(), Err(3));
+    assert_done(|| empty::<i32, u32>().sum(), Ok(0));
+
+    let pairs = || iter_ok::<_, u32>(vec![(1, 'a'), (0, 'b'), (1, 'c'), (0, 'd')]);
+    assert_done(|| pairs().min_by_key(|p| p.0), Ok(Some((0, 'b'))));
+    assert_done(|| pairs().max_by_key(|p| p.0), Ok(Some((1, 'c'))));
+    assert_done(|| err_list().min_by_key(|x| *x), Err(3));
+    assert_done(|| empty::<i32, u32>().max_by_key(|x| *x), Ok(None));
+}
+
+#[test]
+fn take_last() {
+    assert_done(|| list().take_last(2).collect(), Ok(vec![2, 3]));
+    assert_done(|| list().take_last(5).collect(), Ok(vec![1, 2, 3]));
+    assert_done(|| list().take_last(0).collect(), Ok(vec![]));
+
+    let mut s = iter(vec![Ok(1), Err(2), Ok(3), Ok(4)]).take_last(2);
+    sassert_err(&mut s, 2);
+    sassert_next(&mut s, 3);
+    sassert_next(&mut s, 4);
+    sassert_done(&mut s);
+}
+
+#[test]
+fn sample() {
+    let (tx, rx) = mpsc::unbounded::<i32>();
+    let (ticks, ticker) = mpsc::unbounded::<()>();
+    let mut s = rx.sample(ticker);
+
+    ticks.unbounded_send(()).unwrap();
+    sassert_empty(&mut s);
+
+    tx.unbounded_send(1).unwrap();
+    tx.unbounded_send(2).unwrap();
+    sassert_empty(&mut s);
+    ticks.unbounded_send(()).unwrap();
+    sassert_next(&mut s, 2);
+
+    ticks.unbounded_send(()).unwrap();
+    sassert_empty(&mut s);
+
+    tx.unbounded_send(3).unwrap();
+    drop(ticks);
+    sassert_done(&mut s);
+
+    let a = iter_ok::<_, u32>(vec![1, 2, 3]);
+    let b = iter_ok(vec![(), ()]);
+    assert_done(move || a.sample(b).collect(), Ok(vec![3]));
+}
+
+#[test]
+fn sample_always_ready() {
+    let (ticks, ticker) = mpsc::unbounded::<()>();
+    let mut s = stream::repeat(1).sample(ticker);
+
+    sassert_empty(&mut s);
+    ticks.unbounded_send(()).unwrap();
+    sassert_next(&mut s, 1);
+    drop(ticks);
+    sassert_done(&mut s);
+}
+
+#[test]
+fn sample_backlog() {
+    let (tx, rx) = mpsc::unbounded::<i32>();
+    let (ticks, ticker) = mpsc::unbounded::<()>();
+    let mut s = rx.sample(ticker);
+    for i in 0..100 {
+        tx.unbounded_send(i).unwrap();
+    }
+
+    // More items are ready than are taken per poll, and the task comes back
+    // for the rest before the next tick.
+    let task = MockTask::new();
+    let mut polls = 0;
+    loop {
+        assert!(task.poll_stream(&mut s).unwrap().is_not_ready());
+        polls += 1;
+        if task.notify_count() < polls {
+            break
+        }
+    }
+    assert!(polls > 1);
+    ticks.unbounded_send(()).unwrap();
+    sassert_next(&mut s, 99);
+}
+
+#[test]
+fn with_latest_from() {
+    let (tx, rx) = mpsc::unbounded::<i32>();
+    let (other_tx, other_rx) = mpsc::unbounded::<char>();
+    let mut s = rx.with_latest_from(other_rx);
+
+    tx.unbounded_send(1).unwrap();
+    sassert_empty(&mut s);
+
+    other_tx.unbounded_send('a').unwrap();
+    other_tx.unbounded_send('b').unwrap();
+    drop(other_tx);
+    tx.unbounded_send(2).unwrap();
+    tx.unbounded_send(3).unwrap();
+    sassert_next(&mut s, (2, 'b'));
+    sassert_next(&mut s, (3, 'b'));
+
+    tx.unbounded_send(4).unwrap();
+    sassert_next(&mut s, (4, 'b'));
+    drop(tx);
+    sassert_done(&mut s);
+
+    assert_done(|| err_list().with_latest_from(iter_ok(vec![0])).collect(), Err(3));
+}
+
+#[test]
+fn with_latest_from_always_ready() {
+    let (tx, rx) = mpsc::unbounded::<i32>();
+    let mut s = rx.with_latest_from(stream::repeat('a'));
+
+    sassert_empty(&mut s);
+    tx.unbounded_send(1).unwrap();
+    sassert_next(&mut s, (1, 'a'));
+    drop(tx);
+    sassert_done(&mut s);
+}
+
+#[test]
+fn combine_latest() {
+    use futures::stream::combine_latest;
+
+    let (tx1, rx1) = mpsc::unbounded::<i32>();
+    let (tx2, rx2) = mpsc::unbounded::<char>();
+    let mut s = combine_latest(rx1, rx2);
+
+    tx1.unbounded_send(1).unwrap();
+    tx1.unbounded_send(2).unwrap();
+    sassert_empty(&mut s);
+
+    tx2.unbounded_send('a').unwrap();
+    drop(tx2);
+    sassert_next(&mut s, (2, 'a'));
+
+    tx1.unbounded_send(3).unwrap();
+    drop(tx1);
+    sassert_next(&mut s, (3, 'a'));
+    sassert_done(&mut s);
+
+    let a = iter_ok::<_, u32>(vec![1, 2]);
+    let b = empty::<char, u32>();
+    assert_done(move || combine_latest(a, b).collect(), Ok(vec![]));
+}
+
+#[test]
+fn share_replay() {
+    let (tx, rx) = mpsc::unbounded::<i32>();
+    let mut a = rx.share_replay(1);
+
+    tx.unbounded_send(1).unwrap();
+    tx.unbounded_send(2).unwrap();
+    sassert_next(&mut a, 1);
+    sassert_next(&mut a, 2);
+
+    // A late subscriber only gets the last item replayed.
+    let mut b = a.clone();
+    sassert_next(&mut b, 2);
+
+    tx.unbounded_send(3).unwrap();
+    sassert_next(&mut b, 3);
+    sassert_next(&mut a, 3);
+
+    drop(tx);
+    sassert_done(&mut a);
+    sassert_done(&mut b);
+
+    let c = a.clone();
+    assert_done(move || c.collect(), Ok(vec![3]));
+}
+
+#[test]
+fn lossy() {
+    use futures::stream::{DropOldest, DropNewest, Coalesce};
+
+    let (tx, rx) = mpsc::unbounded::<i32>();
+    let mut s = rx.lossy(2, DropOldest);
+    for i in 0..4 {
+        tx.unbounded_send(i).unwrap();
+    }
+    drop(tx);
+    sassert_next(&mut s, 2);
+    sassert_next(&mut s, 3);
+    sassert_done(&mut s);
+
+    let (tx, rx) = mpsc::unbounded::<i32>();
+    let mut s = rx.lossy(2, DropNewest);
+    for i in 0..4 {
+        tx.unbounded_send(i).unwrap();
+    }
+    drop(tx);
+    sassert_next(&mut s, 0);
+    sassert_next(&mut s, 1);
+    sassert_done(&mut s);
+
+    let (tx, rx) = mpsc::unbounded::<i32>();
+    let mut s = rx.lossy(1, Coalesce(|a, b| a + b));
+    for i in 1..5 {
+        tx.unbounded_send(i).unwrap();
+    }
+    sassert_next(&mut s, 10);
+    sassert_empty(&mut s);
+}
+
+#[test]
+fn lossy_always_ready() {
+    use futures::stream::DropOldest;
+
+    let mut s = stream::repeat::<_, ()>(1).lossy(2, DropOldest);
+    sassert_next(&mut s, 1);
+    sassert_next(&mut s, 1);
+}
+
+#[test]
+fn iter_blocking() {
+    use std::thread;
+    use std::time::Duration;
+    use futures::stream::iter_blocking;
+
+    let core = support::local_executor::Core::new();
+    let slow = (0..4).inspect(|_| thread::sleep(Duration::from_millis(1)));
+    let rx = iter_blocking::<_, (), _>(slow, &core, 1);
+    assert_eq!(core.run(rx.collect()).unwrap(), [0, 1, 2, 3]);
+}
+
+#[test]
+fn repeat_with() {
+    use futures::stream::{repeat_with, try_repeat_with};
+
+    let mut n = 0;
+    let s = repeat_with::<_, _, u32>(move || { n += 1; n * 10 });
+    assert_done(move || s.take(3).collect(), Ok(vec![10, 20, 30]));
+
+    let mut n = 0;
+    let mut s = try_repeat_with(move || { n += 1; if n == 2 { Err(n) } else { Ok(n) } });
+    sassert_next(&mut s, 1);
+    sassert_err(&mut s, 2);
+    sassert_next(&mut s, 3);
+}
+
+#[test]
+fn forward_with() {
+    use futures::stream::FlushPolicy;
+
+    #[derive(Default)]
+    struct CountFlushes {
+        items: Vec<i32>,
+        flushes: usize,
+    }
+
+    impl Sink for CountFlushes {
+        type SinkItem = i32;
+        type SinkError = ();
+
+        fn start_send(&mut self, item: i32) -> StartSend<i32, ()> {
+            self.items.push(item);
+            Ok(AsyncSink::Ready)
+        }
+
+        fn poll_complete(&mut self) -> Poll<(), ()> {
+            self.flushes += 1;
+            Ok(Async::Ready(()))
+        }
+
+        fn close(&mut self) -> Poll<(), ()> {
+            Ok(Async::Ready(()))
+        }
+    }
+
+    let flushes = |policy| {
+        let items = iter_ok::<_, ()>(vec![1, 2, 3, 4, 5]);
+        let (_, sink) = items.forward_with(CountFlushes::default(), policy)
+                             .wait()
+                             .unwrap();
+        assert_eq!(sink.items, vec![1, 2, 3, 4, 5]);
+        sink.flushes
+    };
+    assert_eq!(flushes(FlushPolicy::Idle), 0);
+    assert_eq!(flushes(FlushPolicy::EveryItem), 5);
+    assert_eq!(flushes(FlushPolicy::Every(2)), 2);
+
+    // Ticks flush the sink only if there's something to flush.
+    let items = iter_ok::<_, ()>(vec![1, 2, 3, 4, 5]);
+    let ticks = stream::repeat(());
+    let (_, sink) = items.forward_with_ticks(CountFlushes::default(), FlushPolicy::Idle, ticks)
+                         .wait()
+                         .unwrap();
+    assert_eq!(sink.items, vec![1, 2, 3, 4, 5]);
+    assert_eq!(sink.flushes, 5);
+
+    let items = iter_ok::<_, ()>(vec![1, 2, 3, 4, 5]);
+    let ticks = stream::repeat(());
+    let policy = FlushPolicy::EveryItem;
+    let (_, sink) = items.forward_with_ticks(CountFlushes::default(), policy, ticks)
+                         .wait()
+                         .unwrap();
+    assert_eq!(sink.flushes, 5);
+}
+
+#[test]
+fn either() {
+    let pick = |left: bool| {
+        if left {
+            list().left_stream()
+        } else {
+            iter_ok(vec![4, 5]).right_stream()
+        }
+    };
+    assert_done(|| pick(true).collect(), Ok(vec![1, 2, 3]));
+    assert_done(|| pick(false).collect(), Ok(vec![4, 5]));
+}
+
+#[test]
+fn size_hint() {
+    let stream = iter_ok::<_, ()>(vec![1, 2, 3, 4, 5]);
+    assert_eq!(stream.size_hint(), (5, Some(5)));
+    assert_eq!(stream.map(|x| x * 2).size_hint(), (5, Some(5)));
+
+    let stream = iter_ok::<_, ()>(vec![1, 2, 3, 4, 5]);
+    assert_eq!(stream.filter(|x| *x > 2).size_hint(), (0, Some(5)));
+
+    let stream = iter_ok::<_, ()>(vec![1, 2, 3, 4, 5]);
+    assert_eq!(stream.skip(2).take(10).size_hint(), (3, Some(3)));
+
+    // Futures of `and_then` may fail, and `then` may turn errors into items.
+    let stream = iter_ok::<_, ()>(vec![1, 2, 3]);
+    assert_eq!(stream.and_then(Ok).size_hint(), (0, Some(3)));
+    let stream = iter_ok::<_, ()>(vec![1, 2, 3]);
+    assert_eq!(stream.then(|x| x).size_hint(), (0, None));
+
+    let stream = iter_ok::<_, ()>(vec![1, 2, 3, 4, 5]);
+    assert_eq!(stream.chunks(2).size_hint(), (3, Some(5)));
+
+    let stream = iter_ok::<_, ()>(vec![1, 2]);
+    assert_eq!(stream.chain(iter_ok(vec![3])).size_hint(), (3, Some(3)));
+
+    let mut stream = iter_ok::<_, ()>(vec![1, 2]).fuse();
+    sassert_next(&mut stream, 1);
+    assert_eq!(stream.size_hint(), (1, Some(1)));
+    sassert_next(&mut stream, 2);
+    sassert_done(&mut stream);
+    assert_eq!(stream.size_hint(), (0, Some(0)));
+
+    assert_eq!(empty::<i32, ()>().size_hint(), (0, Some(0)));
+    assert_eq!(poll_fn(|| Ok::<_, ()>(Async::Ready(Some(1)))).size_hint(), (0, None));
+}
+
+#[test]
+fn size_hint_channel() {
+    let (tx, rx) = mpsc::unbounded();
+    tx.unbounded_send(1).unwrap();
+    tx.unbounded_send(2).unwrap();
+    assert_eq!(rx.size_hint(), (2, None));
+    drop(tx);
+    let mut rx = rx.wait();
+    assert_eq!(rx.next(), Some(Ok(1)));
+    assert_eq!(rx.next(), Some(Ok(2)));
+    assert_eq!(rx.next(), None);
+}
+
+#[test]
+fn scan_async() {
+    let scan = |s: Box<Stream<Item = i32, Error = u32> + Send>| {
+        s.scan_async(0, |acc, x| {
+            match x {
+                Ok(x) => ok::<_, u32>(Some((acc + x, acc + x))),
+                Err(e) => ok(Some((acc, -(e as i32)))),
+            }
+        })
+    };
+    assert_done(|| scan(list()).collect(), Ok(vec![1, 3, 6]));
+    assert_done(|| scan(err_list()).collect(), Ok(vec![1, 3, -3]));
+
+    let mut stream = iter_ok::<_, u32>(vec![1, 2, 3]).scan_async((), |(), x| {
+        if x == Ok(2) { err(7) } else { ok(Some(((), x.unwrap()))) }
+    });
+    sassert_next(&mut stream, 1);
+    sassert_err(&mut stream, 7);
+    sassert_done(&mut stream);
+}
+
+#[test]
+fn try_scan() {
+    let mut stream = iter(vec![Ok(1), Err(5), Ok(2), Ok(3)])
+        .try_scan(10, |acc, x| ok(Some((acc + x, acc + x))));
+    sassert_next(&mut stream, 11);
+    sassert_err(&mut stream, 5);
+    sassert_next(&mut stream, 13);
+    sassert_next(&mut stream, 16);
+    sassert_done(&mut stream);
+
+    assert_done(|| list().try_scan(0, |acc, x| {
+        ok(if x < 3 { Some((acc, x)) } else { None })
+    }).collect(), Ok(vec![1, 2]));
+}
+
+#[test]
+fn batched() {
+    let (tx, rx) = mpsc::unbounded::<i32>();
+    let (ticks_tx, ticks_rx) = mpsc::unbounded::<()>();
+    let config = BatchConfig::new().max_items(3).ticks(ticks_rx);
+    let mut stream = rx.batched(config);
+    let flush = stream.flush_handle();
+
+    // Nothing fires while the batch is small, and flushing an empty batch
+    // does nothing.
+    flush.flush();
+    sassert_empty(&mut stream);
+    tx.unbounded_send(1).unwrap();
+    tx.unbounded_send(2).unwrap();
+    sassert_empty(&mut stream);
+
+    flush.flush();
+    sassert_next(&mut stream, vec![1, 2]);
+    sassert_empty(&mut stream);
+
+    tx.unbounded_send(3).unwrap();
+    ticks_tx.unbounded_send(()).unwrap();
+    sassert_next(&mut stream, vec![3]);
+    sassert_empty(&mut stream);
+
+    for i in 4..9 {
+        tx.unbounded_send(i).unwrap();
+    }
+    drop(tx);
+    sassert_next(&mut stream, vec![4, 5, 6]);
+    sassert_next(&mut stream, vec![7, 8]);
+    sassert_done(&mut stream);
+}
+
+#[test]
+fn batched_interval() {
+    use std::time::Duration;
+    use futures::timer::{Timer, TimerError};
+    use support::timer::ManualTimer;
+
+    let timer = ManualTimer::new();
+    let (tx, rx) = mpsc::unbounded::<i32>();
+    let config = BatchConfig::new().interval(timer.interval(Duration::from_secs(10)));
+    let mut stream = rx.map_err(|()| TimerError::new()).batched(config);
+
+    tx.unbounded_send(1).unwrap();
+    tx.unbounded_send(2).unwrap();
+    sassert_empty(&mut stream);
+    timer.advance(Duration::from_secs(10));
+    sassert_next(&mut stream, vec![1, 2]);
+    sassert_empty(&mut stream);
+
+    // Ticks while the batch is empty don't emit anything.
+    timer.advance(Duration::from_secs(10));
+    sassert_empty(&mut stream);
+    tx.unbounded_send(3).unwrap();
+    drop(tx);
+    sassert_next(&mut stream, vec![3]);
+    sassert_done(&mut stream);
+}
+
+#[test]
+fn batched_always_ready() {
+    let task = MockTask::new();
+
+    let mut stream = stream::repeat::<_, ()>(1).batched(BatchConfig::new());
+    let flush = stream.flush_handle();
+    assert_eq!(task.poll_stream(&mut stream), Ok(Async::NotReady));
+    assert!(task.is_notified());
+    flush.flush();
+    match task.poll_stream(&mut stream) {
+        Ok(Async::Ready(Some(batch))) => assert!(!batch.is_empty()),
+        other => panic!("expected a batch, got {:?}", other.map(|a| a.map(|b| b.map(|b| b.len())))),
+    }
+
+    let task = MockTask::new();
+    let (_tx, rx) = mpsc::unbounded::<i32>();
+    let mut stream = rx.batched(BatchConfig::new().ticks(stream::repeat(())));
+    assert_eq!(task.poll_stream(&mut stream), Ok(Async::NotReady));
+    assert!(task.is_notified());
+}
+
+#[test]
+fn merge_all() {
+    use futures::stream::{iter_result, merge, merge_with, MergeStrategy};
+
+    let streams = vec![iter_result(vec![Ok(1), Err(2), Ok(3)]), iter_result(vec![Ok(4)])];
+    let mut merged = merge(streams);
+    assert_eq!(merged.size_hint(), (0, Some(4)));
+    sassert_next(&mut merged, 1);
+    sassert_next(&mut merged, 4);
+    sassert_err(&mut merged, 2);
+    assert_eq!(merged.len(), 2);
+    sassert_next(&mut merged, 3);
+    assert_eq!(merged.len(), 1);
+    sassert_done(&mut merged);
+    assert!(merged.is_empty());
+
+    let streams = (0..4).map(|i| iter_ok::<_, ()>(vec![i; 3])).collect::<Vec<_>>();
+    let mut items = merge_with(streams, MergeStrategy::Random).collect().wait().unwrap();
+    items.sort();
+    assert_eq!(items, vec![0, 0, 0, 1, 1, 1, 2, 2, 2, 3, 3, 3]);
+
+    let (tx1, rx1) = mpsc::unbounded::<i32>();
+    let (tx2, rx2) = mpsc::unbounded::<i32>();
+    let mut merged = merge_with(vec![rx1, rx2], MergeStrategy::Priority);
+    sassert_empty(&mut merged);
+    tx2.unbounded_send(1).unwrap();
+    tx1.unbounded_send(2).unwrap();
+    tx2.unbounded_send(3).unwrap();
+    drop(tx1);
+    drop(tx2);
+    sassert_next(&mut merged, 2);
+    sassert_next(&mut merged, 1);
+    sassert_next(&mut merged, 3);
+    sassert_done(&mut merged);
+
+    // Streams notified again after being picked are picked again, whichever
+    // strategy is used.
+    for &strategy in &[MergeStrategy::RoundRobin, MergeStrategy::Priority, MergeStrategy::Random] {
+        let (txs, rxs): (Vec<_>, Vec<_>) = (0..50).map(|_| mpsc::unbounded::<i32>()).unzip();
+        let mut merged = merge_with(rxs, strategy);
+        sassert_empty(&mut merged);
+        for round in 0..3 {
+            txs[7].unbounded_send(round).unwrap();
+            txs[42].unbounded_send(round).unwrap();
+            let mut items = vec![];
+            for _ in 0..2 {
+                match MockTask::new().poll_stream(&mut merged) {
+                    Ok(Async::Ready(Some(item))) => items.push(item),
+                    other => panic!("expected an item, got {:?}", other),
+                }
+            }
+            assert_eq!(items, vec![round, round]);
+            sassert_empty(&mut merged);
+        }
+        drop(txs);
+        sassert_done(&mut merged);
+    }
+}
+
+#[test]
+fn delay() {
+    use std::time::Duration;
+    use futures::timer::TimerError;
+    use support::timer::ManualTimer;
+
+    let timer = ManualTimer::new();
+    let (tx, rx) = mpsc::unbounded::<i32>();
+    let mut stream = rx.map_err(|()| TimerError::new())
+        .delay(Duration::from_secs(10), &timer);
+    tx.unbounded_send(1).unwrap();
+    sassert_empty(&mut stream);
+    timer.advance(Duration::from_secs(5));
+    tx.unbounded_send(2).unwrap();
+    sassert_empty(&mut stream);
+    timer.advance(Duration::from_secs(5));
+    sassert_next(&mut stream, 1);
+    sassert_empty(&mut stream);
+    timer.advance(Duration::from_secs(5));
+    sassert_next(&mut stream, 2);
+    sassert_empty(&mut stream);
+    drop(tx);
+    sassert_done(&mut stream);
+
+    // With a full buffer the next item is only received once there's room.
+    let (tx, rx) = mpsc::unbounded::<i32>();
+    let mut stream = rx.map_err(|()| TimerError::new())
+        .delay(Duration::from_secs(10), &timer)
+        .with_capacity(1);
+    tx.unbounded_send(1).unwrap();
+    tx.unbounded_send(2).unwrap();
+    sassert_empty(&mut stream);
+    assert_eq!(stream.size_hint(), (2, None));
+    timer.advance(Duration::from_secs(10));
+    sassert_next(&mut stream, 1);
+    sassert_empty(&mut stream);
+    timer.advance(Duration::from_secs(5));
+    sassert_empty(&mut stream);
+    timer.advance(Duration::from_secs(5));
+    sassert_next(&mut stream, 2);
+    drop(tx);
+    sassert_done(&mut stream);
+}
+
+#[test]
+fn deadline() {
+    use std::time::Duration;
+    use futures::stream::DeadlineError;
+    use support::timer::ManualTimer;
+
+    let timer = ManualTimer::new();
+    let (tx, rx) = mpsc::unbounded::<i32>();
+    let mut stream = rx.deadline(timer.now() + Duration::from_secs(30), &timer);
+    tx.unbounded_send(1).unwrap();
+    sassert_next(&mut stream, 1);
+    sassert_empty(&mut stream);
+
+    timer.advance(Duration::from_secs(10));
+    tx.unbounded_send(2).unwrap();
+    sassert_next(&mut stream, 2);
+    sassert_empty(&mut stream);
+
+    timer.advance(Duration::from_secs(20));
+    tx.unbounded_send(3).unwrap();
+    match executor::spawn(&mut stream).poll_stream_notify(&notify_noop(), 0) {
+        Err(DeadlineError::Elapsed) => {}
+        _ => panic!("deadline should have elapsed"),
+    }
+    sassert_done(&mut stream);
+
+    let mut stream = iter_ok::<_, u32>(vec![1, 2]).deadline(timer.now(), &timer);
+    match executor::spawn(&mut stream).poll_stream_notify(&notify_noop(), 0) {
+        Err(DeadlineError::Elapsed) => {}
+        _ => panic!("deadline should have elapsed"),
+    }
+    sassert_done(&mut stream);
+}
+
+#[test]
+fn debounce() {
+    use std::time::Duration;
+    use futures::timer::TimerError;
+    use support::timer::ManualTimer;
+
+    let timer = ManualTimer::new();
+    let (tx, rx) = mpsc::unbounded::<i32>();
+    let mut stream = rx.map_err(|()| TimerError::new())
+        .debounce(Duration::from_secs(10), &timer);
+    tx.unbounded_send(1).unwrap();
+    sassert_empty(&mut stream);
+    timer.advance(Duration::from_secs(5));
+    // Superseding the first item restarts the quiet period.
+    tx.unbounded_send(2).unwrap();
+    sassert_empty(&mut stream);
+    timer.advance(Duration::from_secs(5));
+    sassert_empty(&mut stream);
+    timer.advance(Duration::from_secs(5));
+    sassert_next(&mut stream, 2);
+    sassert_empty(&mut stream);
+
+    // The last item is yielded right away once the stream ends.
+    tx.unbounded_send(3).unwrap();
+    drop(tx);
+    sassert_next(&mut stream, 3);
+    sassert_done(&mut stream);
+}
+
+#[test]
+fn debounce_always_ready() {
+    use std::time::Duration;
+    use futures::timer::TimerError;
+    use support::timer::ManualTimer;
+
+    let task = MockTask::new();
+    let timer = ManualTimer::new();
+    let mut stream = stream::repeat::<_, TimerError>(1)
+        .debounce(Duration::from_secs(10), &timer);
+    assert_eq!(task.poll_stream(&mut stream), Ok(Async::NotReady));
+    assert!(task.is_notified());
+}
+
+#[test]
+fn throttle() {
+    use std::time::Duration;
+    use futures::timer::TimerError;
+    use support::timer::ManualTimer;
+
+    let timer = ManualTimer::new();
+    let mut stream = iter_ok::<_, TimerError>(vec![1, 2, 3])
+        .throttle(Duration::from_secs(10), &timer);
+    sassert_next(&mut stream, 1);
+    sassert_empty(&mut stream);
+    timer.advance(Duration::from_secs(5));
+    sassert_empty(&mut stream);
+    timer.advance(Duration::from_secs(5));
+    sassert_next(&mut stream, 2);
+    sassert_empty(&mut stream);
+    timer.advance(Duration::from_secs(10));
+    sassert_next(&mut stream, 3);
+    // The end of the stream isn't held back.
+    timer.advance(Duration::from_secs(10));
+    sassert_done(&mut stream);
+}
+
+#[test]
+fn chunks_timeout() {
+    use std::time::Duration;
+    use futures::timer::TimerError;
+    use support::timer::ManualTimer;
+
+    let timer = ManualTimer::new();
+    let (tx, rx) = mpsc::unbounded::<i32>();
+    let mut stream = rx.map_err(|()| TimerError::new())
+        .chunks_timeout(3, Duration::from_secs(10), &timer);
+    for i in 0..4 {
+        tx.unbounded_send(i).unwrap();
+    }
+    sassert_next(&mut stream, vec![0, 1, 2]);
+    sassert_empty(&mut stream);
+    timer.advance(Duration::from_secs(5));
+    tx.unbounded_send(4).unwrap();
+    sassert_empty(&mut stream);
+    // The timeout counts from the first item of the chunk.
+    timer.advance(Duration::from_secs(5));
+    sassert_next(&mut stream, vec![3, 4]);
+    sassert_empty(&mut stream);
+
+    tx.unbounded_send(5).unwrap();
+    drop(tx);
+    sassert_next(&mut stream, vec![5]);
+    sassert_done(&mut stream);
+}
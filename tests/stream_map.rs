@@ -0,0 +1,63 @@
+extern crate futures;
+
+use futures::prelude::*;
+use futures::stream::{self, StreamMap};
+use futures::unsync::mpsc;
+
+mod support;
+
+#[test]
+fn insert_remove_and_finish() {
+    let (a_tx, a_rx) = mpsc::unbounded::<u32>();
+    let (b_tx, b_rx) = mpsc::unbounded::<u32>();
+
+    let mut map = StreamMap::new();
+    map.insert("a", a_rx);
+    map.insert("b", b_rx);
+    let mut spawn = futures::executor::spawn(map);
+    assert!(spawn.poll_stream_notify(&support::notify_noop(), 0).unwrap().is_not_ready());
+
+    b_tx.unbounded_send(1).unwrap();
+    assert_eq!(spawn.wait_stream(), Some(Ok(("b", 1))));
+    a_tx.unbounded_send(2).unwrap();
+    assert_eq!(spawn.wait_stream(), Some(Ok(("a", 2))));
+
+    // A removed stream doesn't yield anything anymore.
+    b_tx.unbounded_send(3).unwrap();
+    assert!(spawn.get_mut().remove("b"));
+    assert!(spawn.poll_stream_notify(&support::notify_noop(), 0).unwrap().is_not_ready());
+    assert!(b_tx.unbounded_send(4).is_err());
+
+    // Streams can be added while polling, and are removed once done.
+    let (c_tx, c_rx) = mpsc::unbounded::<u32>();
+    assert!(!spawn.get_mut().insert("c", c_rx));
+    drop(a_tx);
+    c_tx.unbounded_send(5).unwrap();
+    assert_eq!(spawn.wait_stream(), Some(Ok(("c", 5))));
+    assert!(spawn.poll_stream_notify(&support::notify_noop(), 0).unwrap().is_not_ready());
+    assert!(!spawn.get_ref().contains_key("a"));
+    assert_eq!(spawn.get_ref().len(), 1);
+    drop(c_tx);
+    assert_eq!(spawn.wait_stream(), None);
+    assert!(spawn.get_ref().is_empty());
+}
+
+#[test]
+fn fair() {
+    let mut map = StreamMap::new();
+    map.insert(1, stream::iter_ok::<_, ()>(vec![1, 2, 3]));
+    map.insert(2, stream::iter_ok(vec![1, 2, 3]));
+    let keys = map.map(|(k, _)| k).collect().wait().unwrap();
+    assert!(keys == [1, 2, 1, 2, 1, 2] || keys == [2, 1, 2, 1, 2, 1], "{:?}", keys);
+}
+
+#[test]
+fn errors_carry_key() {
+    let mut map = StreamMap::new();
+    map.insert("a", stream::iter_result(vec![Ok(1), Err(2), Ok(3)]));
+    let mut spawn = futures::executor::spawn(map);
+    assert_eq!(spawn.wait_stream(), Some(Ok(("a", 1))));
+    assert_eq!(spawn.wait_stream(), Some(Err(("a", 2))));
+    assert_eq!(spawn.wait_stream(), Some(Ok(("a", 3))));
+    assert_eq!(spawn.wait_stream(), None);
+}
@@ -0,0 +1,67 @@
+extern crate futures;
+
+use std::cell::Cell;
+use std::rc::Rc;
+
+use futures::prelude::*;
+use futures::stream::{self, StreamUnordered};
+use futures::unsync::mpsc;
+
+mod support;
+
+#[test]
+fn polls_notified_streams() {
+    let polls = Rc::new(Cell::new(0));
+    let mut set = StreamUnordered::new();
+    let mut txs = Vec::new();
+    for _ in 0..10_000 {
+        let (tx, mut rx) = mpsc::unbounded::<u32>();
+        let polls = polls.clone();
+        txs.push(tx);
+        set.push(stream::poll_fn(move || {
+            polls.set(polls.get() + 1);
+            rx.poll()
+        }));
+    }
+    let mut spawn = futures::executor::spawn(set);
+    assert!(spawn.poll_stream_notify(&support::notify_noop(), 0).unwrap().is_not_ready());
+    assert_eq!(polls.get(), 10_000);
+
+    // The stream producing items is polled until it's drained, and nothing
+    // else is.
+    txs[42].unbounded_send(1).unwrap();
+    txs[42].unbounded_send(2).unwrap();
+    assert_eq!(spawn.wait_stream(), Some(Ok((42, 1))));
+    assert_eq!(spawn.wait_stream(), Some(Ok((42, 2))));
+    assert!(spawn.poll_stream_notify(&support::notify_noop(), 0).unwrap().is_not_ready());
+    assert_eq!(polls.get(), 10_003);
+
+    // Finished streams are dropped.
+    txs.remove(7);
+    assert!(spawn.poll_stream_notify(&support::notify_noop(), 0).unwrap().is_not_ready());
+    assert_eq!(spawn.get_ref().len(), 9_999);
+    txs.clear();
+    assert_eq!(spawn.wait_stream(), None);
+}
+
+#[test]
+fn fair_and_errors() {
+    let mut set = StreamUnordered::new();
+    let a = set.push(stream::iter_result(vec![Ok(1), Err(2), Ok(3)]));
+    let b = set.push(stream::iter_result(vec![Ok(4), Ok(5), Ok(6)]));
+    let mut spawn = futures::executor::spawn(set);
+    assert_eq!(spawn.wait_stream(), Some(Ok((a, 1))));
+    assert_eq!(spawn.wait_stream(), Some(Ok((b, 4))));
+    assert_eq!(spawn.wait_stream(), Some(Err((a, 2))));
+    assert_eq!(spawn.wait_stream(), Some(Ok((b, 5))));
+    assert_eq!(spawn.wait_stream(), Some(Ok((a, 3))));
+    assert_eq!(spawn.wait_stream(), Some(Ok((b, 6))));
+    assert_eq!(spawn.wait_stream(), None);
+
+    // More streams can be pushed once the set is empty.
+    let c = spawn.get_mut().push(stream::iter_result(vec![Ok(7)]));
+    assert_eq!(spawn.wait_stream(), Some(Ok((c, 7))));
+    spawn.get_mut().push(stream::iter_result(vec![Ok(8)]));
+    spawn.get_mut().clear();
+    assert_eq!(spawn.wait_stream(), None);
+}
@@ -11,6 +11,7 @@ use futures::executor::{self, NotifyHandle, Notify};
 use futures::task;
 
 pub mod local_executor;
+pub mod timer;
 
 pub fn f_ok(a: i32) -> FutureResult<i32, u32> { Ok(a).into_future() }
 pub fn f_err(a: u32) -> FutureResult<i32, u32> { Err(a).into_future() }
@@ -0,0 +1,115 @@
+//! A timer whose clock only moves when told to, for testing time-based
+//! combinators deterministically.
+
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use futures::{Future, Async, Poll};
+use futures::task::{self, Task};
+use futures::timer::{Delay, DelayInterval, Timer, TimerError};
+
+#[derive(Clone)]
+pub struct ManualTimer {
+    inner: Arc<Mutex<Inner>>,
+}
+
+struct Inner {
+    now: Instant,
+    next_id: usize,
+    waiters: Vec<(usize, Task)>,
+    shut_down: bool,
+}
+
+impl ManualTimer {
+    pub fn new() -> ManualTimer {
+        ManualTimer {
+            inner: Arc::new(Mutex::new(Inner {
+                now: Instant::now(),
+                next_id: 0,
+                waiters: Vec::new(),
+                shut_down: false,
+            })),
+        }
+    }
+
+    pub fn now(&self) -> Instant {
+        self.inner.lock().unwrap().now
+    }
+
+    pub fn advance(&self, duration: Duration) {
+        let waiters = {
+            let mut inner = self.inner.lock().unwrap();
+            inner.now += duration;
+            inner.waiters.drain(..).collect::<Vec<_>>()
+        };
+        for (_, task) in waiters {
+            task.notify();
+        }
+    }
+
+    // Makes all delays fail from now on.
+    pub fn shut_down(&self) {
+        let waiters = {
+            let mut inner = self.inner.lock().unwrap();
+            inner.shut_down = true;
+            inner.waiters.drain(..).collect::<Vec<_>>()
+        };
+        for (_, task) in waiters {
+            task.notify();
+        }
+    }
+}
+
+impl Timer for ManualTimer {
+    type Delay = ManualDelay;
+    type Interval = DelayInterval<ManualDelay>;
+
+    fn delay_until(&self, at: Instant) -> ManualDelay {
+        let id = {
+            let mut inner = self.inner.lock().unwrap();
+            inner.next_id += 1;
+            inner.next_id
+        };
+        ManualDelay { timer: self.clone(), at: at, id: id }
+    }
+
+    fn interval_at(&self, at: Instant, period: Duration) -> DelayInterval<ManualDelay> {
+        DelayInterval::new(self, at, period)
+    }
+
+    fn now(&self) -> Instant {
+        ManualTimer::now(self)
+    }
+}
+
+pub struct ManualDelay {
+    timer: ManualTimer,
+    at: Instant,
+    id: usize,
+}
+
+impl Future for ManualDelay {
+    type Item = ();
+    type Error = TimerError;
+
+    fn poll(&mut self) -> Poll<(), TimerError> {
+        let mut inner = self.timer.inner.lock().unwrap();
+        if inner.shut_down {
+            Err(TimerError::new())
+        } else if inner.now >= self.at {
+            Ok(Async::Ready(()))
+        } else {
+            // Only the most recent task is notified, like real timers do.
+            let id = self.id;
+            inner.waiters.retain(|&(i, _)| i != id);
+            inner.waiters.push((id, task::current()));
+            Ok(Async::NotReady)
+        }
+    }
+}
+
+impl Delay for ManualDelay {
+    fn reset(&mut self, at: Instant) {
+        self.at = at;
+    }
+}
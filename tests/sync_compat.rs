@@ -0,0 +1,78 @@
+extern crate futures;
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+
+use futures::prelude::*;
+use futures::future::{Executor, ExecuteError};
+use futures::sync::compat;
+
+struct Threads;
+
+impl<F> Executor<F> for Threads
+    where F: Future<Item = (), Error = ()> + Send + 'static,
+{
+    fn execute(&self, f: F) -> Result<(), ExecuteError<F>> {
+        thread::spawn(move || f.wait());
+        Ok(())
+    }
+}
+
+#[test]
+fn forwards_messages() {
+    let (tx, rx) = mpsc::channel();
+    let stream = compat::receiver_stream(rx, &Threads).unwrap();
+    let producer = thread::spawn(move || {
+        for i in 0..1000 {
+            tx.send(i).unwrap();
+        }
+    });
+    assert_eq!(stream.collect().wait().unwrap(), (0..1000).collect::<Vec<_>>());
+    producer.join().unwrap();
+}
+
+#[test]
+fn bounded_prefetch() {
+    let (tx, rx) = mpsc::sync_channel(0);
+    let sent = Arc::new(AtomicUsize::new(0));
+    let sent2 = sent.clone();
+    let producer = thread::spawn(move || {
+        for i in 0..100 {
+            if tx.send(i).is_err() {
+                break
+            }
+            sent2.fetch_add(1, Ordering::SeqCst);
+        }
+    });
+
+    let stream = compat::receiver_stream_buffered(rx, &Threads, 2).unwrap();
+    thread::sleep(Duration::from_millis(100));
+    // The buffer, the sender's own slot in it, and a message in flight.
+    assert!(sent.load(Ordering::SeqCst) <= 5);
+
+    let mut items = stream.wait();
+    assert_eq!(items.next(), Some(Ok(0)));
+    assert_eq!(items.next(), Some(Ok(1)));
+
+    // Dropping the stream stops the drain, which disconnects the producer.
+    drop(items);
+    producer.join().unwrap();
+    assert!(sent.load(Ordering::SeqCst) < 100);
+}
+
+#[test]
+fn rejected_by_executor() {
+    struct Reject;
+
+    impl<F: Future<Item = (), Error = ()>> Executor<F> for Reject {
+        fn execute(&self, f: F) -> Result<(), ExecuteError<F>> {
+            Err(ExecuteError::new(futures::future::ExecuteErrorKind::Shutdown, f))
+        }
+    }
+
+    let (_tx, rx) = mpsc::channel::<u32>();
+    assert!(compat::receiver_stream(rx, &Reject).is_err());
+}
@@ -0,0 +1,476 @@
+#![cfg(feature = "use_std")]
+
+extern crate futures;
+
+mod support;
+
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use futures::prelude::*;
+use futures::executor::{self, ArcNotify, LocalPool, Notify, Spawn, ThreadParker};
+use futures::future::{self, lazy, loop_fn, Executor, Loop};
+use futures::stream::futures_unordered;
+use futures::sync::{mpsc, oneshot};
+use futures::task;
+
+use support::*;
+
+// A Notify which records whether it was notified.
+struct Flag(AtomicBool);
+
+impl Flag {
+    fn new() -> Arc<Flag> {
+        Arc::new(Flag(AtomicBool::new(false)))
+    }
+
+    fn get(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+}
+
+impl Notify for Flag {
+    fn notify(&self, _id: usize) {
+        self.0.store(true, Ordering::SeqCst)
+    }
+}
+
+#[test]
+fn yield_now_notifies() {
+    let mut f = executor::spawn(task::yield_now::<()>());
+    let notify = Flag::new();
+    assert!(f.poll_future_notify(&notify, 0).unwrap().is_not_ready());
+    assert!(notify.get());
+    assert!(f.poll_future_notify(&notify_panic(), 0).unwrap().is_ready());
+}
+
+#[test]
+fn with_current() {
+    let mut f = executor::spawn(future::poll_fn(|| -> Poll<(), ()> {
+        // All calls during a poll borrow the same handle.
+        let first = task::with_current(|task| task as *const task::Task);
+        let second = task::with_current(|task| {
+            assert_eq!(task.id(), task::current_id());
+            assert!(task.will_notify_current());
+            task as *const task::Task
+        });
+        assert_eq!(first, second);
+        task::with_current(|task| task.clone()).notify();
+        Ok(Async::NotReady)
+    }));
+    let notify = Flag::new();
+    assert!(f.poll_future_notify(&notify, 0).unwrap().is_not_ready());
+    assert!(notify.get());
+}
+
+#[test]
+fn yield_now_interleaves() {
+    let mut pool = LocalPool::new();
+    let spawner = pool.spawner();
+    let log = Rc::new(Cell::new(0u32));
+
+    for i in 0..2 {
+        let log = log.clone();
+        spawner.execute(loop_fn(0, move |n| {
+            log.set(log.get() * 10 + i + 1);
+            task::yield_now().map(move |()| {
+                if n == 2 { Loop::Break(()) } else { Loop::Continue(n + 1) }
+            })
+        })).unwrap();
+    }
+    pool.run();
+    assert_eq!(log.get(), 121212);
+
+    assert_eq!(pool.run_until(lazy(task::yield_now::<()>)), Ok(()));
+}
+
+#[test]
+fn budget_forces_yield() {
+    let (tx, rx) = mpsc::unbounded();
+    for i in 0..5 {
+        tx.unbounded_send(i).unwrap();
+    }
+    drop(tx);
+
+    let seen = Rc::new(Cell::new(0));
+    let seen2 = seen.clone();
+    let f = rx.for_each(move |_| {
+        seen2.set(seen2.get() + 1);
+        Ok(())
+    });
+    let mut f = executor::spawn(task::budget(2, f));
+
+    let notify = Flag::new();
+    assert!(f.poll_future_notify(&notify, 0).unwrap().is_not_ready());
+    assert_eq!(seen.get(), 2);
+    assert!(notify.get());
+
+    assert!(f.poll_future_notify(&notify_noop(), 0).unwrap().is_not_ready());
+    assert_eq!(seen.get(), 4);
+    assert!(f.poll_future_notify(&notify_noop(), 0).unwrap().is_ready());
+    assert_eq!(seen.get(), 5);
+
+    // Without a budget nothing is limited.
+    let (tx, rx) = mpsc::unbounded();
+    for i in 0..5 {
+        tx.unbounded_send(i).unwrap();
+    }
+    drop(tx);
+    let mut f = executor::spawn(rx.collect());
+    match f.poll_future_notify(&notify_panic(), 0) {
+        Ok(Async::Ready(v)) => assert_eq!(v, vec![0, 1, 2, 3, 4]),
+        _ => panic!("expected the stream to be drained"),
+    }
+}
+
+#[test]
+fn budget_futures_unordered() {
+    let futures = futures_unordered((0..4).map(future::ok::<i32, ()>));
+    let mut f = executor::spawn(task::budget(3, futures.collect()));
+    let notify = Flag::new();
+    assert!(f.poll_future_notify(&notify, 0).unwrap().is_not_ready());
+    assert!(notify.get());
+    match f.poll_future_notify(&notify_noop(), 0) {
+        Ok(Async::Ready(mut v)) => {
+            v.sort();
+            assert_eq!(v, vec![0, 1, 2, 3]);
+        }
+        _ => panic!("expected all futures to complete"),
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct TraceId(u32);
+
+#[test]
+fn with_local_nested() {
+    let read = || lazy(|| Ok::<_, ()>(task::get_local::<TraceId>()));
+
+    let f = read()
+        .join(read().with_local(TraceId(2)))
+        .join(read().with_local(0u8).map(|t| (t, task::get_local::<u8>())))
+        .with_local(TraceId(1));
+    assert_eq!(f.wait(), Ok(((Some(TraceId(1)), Some(TraceId(2))),
+                             (Some(TraceId(1)), None))));
+    assert_eq!(task::get_local::<TraceId>(), None);
+}
+
+#[test]
+fn with_local_across_polls() {
+    let (tx, rx) = mpsc::unbounded::<()>();
+    let f = rx.into_future()
+        .map(|_| task::get_local::<TraceId>())
+        .with_local(TraceId(3));
+    let mut f = executor::spawn(f);
+    assert!(f.poll_future_notify(&notify_noop(), 0).unwrap().is_not_ready());
+    drop(tx);
+    match f.poll_future_notify(&notify_noop(), 0) {
+        Ok(Async::Ready(id)) => assert_eq!(id, Some(TraceId(3))),
+        _ => panic!("expected the future to complete"),
+    }
+
+    // Spawned tasks don't see the values of the task spawning them.
+    let mut pool = LocalPool::new();
+    let spawner = pool.spawner();
+    let seen = Rc::new(Cell::new(true));
+    let seen2 = seen.clone();
+    let spawner2 = spawner.clone();
+    spawner.execute(lazy(move || {
+        spawner2.execute(lazy(move || {
+            seen2.set(task::get_local::<TraceId>().is_some());
+            Ok(())
+        })).unwrap();
+        Ok(())
+    }).with_local(TraceId(4))).unwrap();
+    pool.run();
+    assert!(!seen.get());
+}
+
+#[test]
+fn task_names() {
+    assert!(task::name().is_none());
+
+    let mut pool = LocalPool::new();
+    let spawner = pool.spawner();
+    let names = Rc::new(RefCell::new(Vec::new()));
+    for &name in &["a", "b"] {
+        let names = names.clone();
+        spawner.spawn_named(name, lazy(move || {
+            task::yield_now().map(move |()| {
+                names.borrow_mut().push(task::name().unwrap().to_string());
+            })
+        })).unwrap();
+    }
+    let names2 = names.clone();
+    spawner.execute(lazy(move || {
+        names2.borrow_mut().push(format!("{:?}", task::name()));
+        Ok(())
+    })).unwrap();
+    pool.run();
+    assert_eq!(*names.borrow(), vec!["None", "a", "b"]);
+
+    let f = task::named("outer", lazy(|| Ok::<_, ()>(task::name())))
+        .join(task::named("outer", task::named("inner", lazy(|| Ok(task::name())))));
+    let (outer, inner) = f.wait().unwrap();
+    assert_eq!(&*outer.unwrap(), "outer");
+    assert_eq!(&*inner.unwrap(), "inner");
+    assert_eq!(task::named("x", future::ok::<(), ()>(())).name(), "x");
+}
+
+#[test]
+fn notify_stats() {
+    use std::sync::atomic::AtomicUsize;
+
+    let mut polls = 0;
+    let f = future::poll_fn(move || -> Poll<(), ()> {
+        polls += 1;
+        if polls < 3 {
+            // Wakes itself up without making any progress.
+            task::current().notify();
+            Ok(Async::NotReady)
+        } else {
+            Ok(Async::Ready(()))
+        }
+    });
+    let mut f = executor::spawn(f);
+    assert!(f.notify_stats().is_none());
+
+    let seen = Arc::new(AtomicUsize::new(0));
+    let seen2 = seen.clone();
+    f.on_notify(move |n| seen2.store(n, Ordering::SeqCst));
+
+    assert!(f.poll_future_notify(&notify_noop(), 0).unwrap().is_not_ready());
+    let stats = f.notify_stats().unwrap();
+    assert_eq!((stats.notifies, stats.polls, stats.spurious), (1, 1, 0));
+    assert!(stats.since_last_poll().is_some());
+
+    assert!(f.poll_future_notify(&notify_noop(), 0).unwrap().is_not_ready());
+    assert!(f.poll_future_notify(&notify_noop(), 0).unwrap().is_ready());
+    let stats = f.notify_stats().unwrap();
+    assert_eq!((stats.notifies, stats.polls, stats.spurious), (2, 3, 1));
+    assert_eq!(seen.load(Ordering::SeqCst), 2);
+
+    // Notifications without a poll in between don't count as spurious.
+    let (tx, rx) = mpsc::unbounded::<i32>();
+    let mut rx = executor::spawn(rx);
+    rx.track_notifications();
+    assert!(rx.poll_stream_notify(&notify_noop(), 0).unwrap().is_not_ready());
+    tx.unbounded_send(1).unwrap();
+    assert!(rx.poll_stream_notify(&notify_noop(), 0).unwrap().is_ready());
+    let stats = rx.notify_stats().unwrap();
+    assert_eq!((stats.notifies, stats.polls, stats.spurious), (1, 2, 0));
+}
+
+#[test]
+fn blocking() {
+    use std::sync::Barrier;
+    use std::thread;
+
+    // All of the closures run at the same time, otherwise the barrier would
+    // never be released.
+    let barrier = Arc::new(Barrier::new(4));
+    let futures = (0..4).map(|i| {
+        let barrier = barrier.clone();
+        task::blocking(move || {
+            barrier.wait();
+            (i, thread::current().name().map(|s| s.to_string()))
+        })
+    }).collect::<Vec<_>>();
+    let results = future::join_all(futures).wait().unwrap();
+    for (i, (j, name)) in results.into_iter().enumerate() {
+        assert_eq!(i, j);
+        assert_eq!(name.as_ref().map(|s| &s[..]), Some("futures-blocking"));
+    }
+}
+
+#[test]
+#[should_panic(expected = "boom")]
+fn blocking_panic() {
+    let _ = task::blocking(|| -> () { panic!("boom") }).wait();
+}
+
+#[test]
+fn spawn_blocking() {
+    use futures::thread::{self, JoinError};
+
+    let f = thread::spawn_blocking(|| ::std::thread::current().name().map(|s| s.to_string()));
+    assert_eq!(f.wait().unwrap(), Some("futures-blocking".to_string()));
+
+    // Panics are reported as errors instead of being resumed.
+    match thread::spawn_blocking(|| -> () { panic!("boom") }).wait() {
+        Err(JoinError::Panicked(payload)) => {
+            assert_eq!(payload.downcast_ref::<&str>(), Some(&"boom"));
+        }
+        _ => panic!("the panic should have been reported"),
+    }
+}
+
+#[test]
+#[should_panic(expected = "`wait` was called from within a task")]
+fn wait_in_task() {
+    let (_tx, rx) = mpsc::unbounded::<()>();
+    let _ = lazy(move || rx.into_future().wait().map(|_| ()).map_err(|_| ())).wait();
+}
+
+#[test]
+#[should_panic(expected = "`wait` was called from within a task")]
+fn stream_wait_in_pool() {
+    let mut pool = LocalPool::new();
+    let (_tx, rx) = mpsc::unbounded::<()>();
+    let _ = pool.run_until(lazy(move || {
+        Ok::<_, ()>(rx.wait().next())
+    }));
+}
+
+#[test]
+fn wait_after_task() {
+    // The guard is released once `wait` returns, even if it failed.
+    assert_eq!(future::err::<(), _>(1).wait(), Err(1));
+    assert_eq!(lazy(|| Ok::<_, ()>(2)).wait(), Ok(2));
+    let res = ::std::panic::catch_unwind(|| {
+        lazy(|| future::ok::<(), ()>(()).wait()).wait()
+    });
+    assert!(res.is_err());
+    assert_eq!(future::ok::<_, ()>(3).wait(), Ok(3));
+}
+
+#[test]
+fn current_id() {
+    let mut a = executor::spawn(lazy(|| {
+        let id = task::current_id();
+        assert_eq!(task::current().id(), id);
+        assert!(format!("{:?}", task::current()).contains(&format!("{:?}", id)));
+        Ok::<_, ()>(id)
+    }));
+    let b = executor::spawn(future::empty::<(), ()>());
+    let flag = Flag::new();
+    let id = match a.poll_future_notify(&flag, 0) {
+        Ok(Async::Ready(id)) => id,
+        _ => panic!(),
+    };
+    assert_eq!(a.id(), id);
+    assert!(b.id() != id);
+
+    // Nested tasks have their own id, the outer one is restored afterwards.
+    let mut outer = executor::spawn(lazy(|| {
+        let before = task::current_id();
+        let mut inner = executor::spawn(lazy(|| Ok::<_, ()>(task::current_id())));
+        let inner = inner.poll_future_notify(&Flag::new(), 0).unwrap();
+        assert!(inner != Async::Ready(before));
+        assert_eq!(task::current_id(), before);
+        Ok::<_, ()>(())
+    }));
+    assert!(outer.poll_future_notify(&flag, 0).is_ok());
+}
+
+// A minimal executor task which requeues itself when notified.
+struct ArcTask {
+    spawn: Mutex<Spawn<Box<Future<Item = (), Error = ()> + Send>>>,
+    queue: Arc<Mutex<Vec<Arc<ArcTask>>>>,
+}
+
+impl ArcNotify for ArcTask {
+    fn notify(me: &Arc<ArcTask>, _id: usize) {
+        me.queue.lock().unwrap().push(me.clone());
+    }
+}
+
+impl ArcTask {
+    fn poll(me: &Arc<ArcTask>) -> Async<()> {
+        let handle = ArcNotify::into_notify_handle(me.clone());
+        me.spawn.lock().unwrap().poll_future_notify(&handle, 0).unwrap()
+    }
+}
+
+#[test]
+fn arc_notify() {
+    let queue = Arc::new(Mutex::new(Vec::new()));
+    let (tx, rx) = oneshot::channel::<i32>();
+    let task = Arc::new(ArcTask {
+        spawn: Mutex::new(executor::spawn(Box::new(rx.map(|_| ()).map_err(|_| ())))),
+        queue: queue.clone(),
+    });
+
+    // The receiver holds on to a `Task` handle, keeping a reference.
+    assert_eq!(ArcTask::poll(&task), Async::NotReady);
+    assert_eq!(Arc::strong_count(&task), 2);
+    assert!(queue.lock().unwrap().is_empty());
+
+    tx.send(1).unwrap();
+    let notified = queue.lock().unwrap().pop().unwrap();
+    assert!(Arc::ptr_eq(&notified, &task));
+    assert_eq!(ArcTask::poll(&notified), Async::Ready(()));
+    drop(notified);
+    assert_eq!(Arc::strong_count(&task), 1);
+}
+
+#[test]
+fn thread_parker() {
+    use std::thread;
+    use std::time::{Duration, Instant};
+
+    let parker = ThreadParker::new();
+
+    // Notifications before parking aren't lost, and are only consumed once.
+    parker.notify_handle().notify(0);
+    parker.park();
+    let start = Instant::now();
+    assert!(!parker.park_timeout(Duration::from_millis(20)));
+    assert!(start.elapsed() >= Duration::from_millis(20));
+
+    let remote = parker.clone();
+    let t = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(10));
+        remote.unpark();
+    });
+    assert!(parker.park_timeout(Duration::from_secs(10)));
+    t.join().unwrap();
+
+    // Used to drive a future, the parker is notified through `Task` handles.
+    let (tx, rx) = mpsc::channel::<i32>(1);
+    let t = thread::spawn(move || tx.send(1).wait().map(|_| ()));
+    let mut stream = executor::spawn(rx);
+    let item = loop {
+        match stream.poll_stream_notify(&parker, 0) {
+            Ok(Async::Ready(item)) => break item,
+            Ok(Async::NotReady) => parker.park(),
+            Err(()) => panic!(),
+        }
+    };
+    assert_eq!(item, Some(1));
+    t.join().unwrap().unwrap();
+}
+
+#[test]
+fn enter() {
+    let enter = executor::enter().unwrap();
+    assert!(executor::enter().is_err());
+    drop(enter);
+    assert!(executor::enter().is_ok());
+
+    // Running executors mark the thread as entered, which third-party
+    // blocking APIs can check.
+    let mut pool = LocalPool::new();
+    let res = pool.run_until(lazy(|| Ok::<_, ()>(executor::enter().is_err())));
+    assert_eq!(res, Ok(true));
+    assert!(executor::enter().is_ok());
+}
+
+#[test]
+#[should_panic(expected = "`LocalPool::run` was called from within a task")]
+fn nested_pools() {
+    let mut pool = LocalPool::new();
+    let _ = pool.run_until(lazy(|| {
+        LocalPool::new().run();
+        Ok::<_, ()>(())
+    }));
+}
+
+#[test]
+#[should_panic(expected = "`wait` was called from within a task")]
+fn wait_in_entered_thread() {
+    let _enter = executor::enter().unwrap();
+    let _ = future::ok::<(), ()>(()).wait();
+}
@@ -0,0 +1,115 @@
+extern crate futures;
+
+use std::time::{Duration, Instant};
+
+use futures::prelude::*;
+use futures::executor;
+use futures::future::{self, TimeoutError};
+use futures::timer::{Delay, ThreadTimer, Timer, TimerError};
+
+mod support;
+use support::*;
+use support::timer::ManualTimer;
+
+#[test]
+fn timeout() {
+    let timer = ManualTimer::new();
+    let (tx, rx) = futures::sync::oneshot::channel::<i32>();
+    let mut f = executor::spawn(rx.timeout(Duration::from_secs(10), &timer));
+    assert!(f.poll_future_notify(&notify_noop(), 0).unwrap().is_not_ready());
+    timer.advance(Duration::from_secs(5));
+    tx.send(1).unwrap();
+    assert_eq!(f.poll_future_notify(&notify_noop(), 0), Ok(Async::Ready(1)));
+
+    let mut f = executor::spawn(future::empty::<(), ()>().timeout(Duration::from_secs(10), &timer));
+    assert!(f.poll_future_notify(&notify_noop(), 0).unwrap().is_not_ready());
+    timer.advance(Duration::from_secs(10));
+    assert_eq!(f.poll_future_notify(&notify_noop(), 0), Err(TimeoutError::Elapsed));
+
+    // A future which is ready in time wins over the timeout.
+    let res = future::ok::<_, ()>(2).timeout(Duration::from_secs(0), &timer).wait();
+    assert_eq!(res, Ok(2));
+    let res = future::err::<(), _>(3).timeout(Duration::from_secs(10), &timer).wait();
+    assert_eq!(res, Err(TimeoutError::Inner(3)));
+}
+
+#[test]
+fn delay_interval() {
+    let timer = ManualTimer::new();
+    let start = timer.now();
+    let mut interval = timer.interval(Duration::from_secs(10));
+    sassert_empty(&mut interval);
+    timer.advance(Duration::from_secs(10));
+    sassert_next(&mut interval, start + Duration::from_secs(10));
+    sassert_empty(&mut interval);
+
+    // Missed ticks are caught up on.
+    timer.advance(Duration::from_secs(25));
+    sassert_next(&mut interval, start + Duration::from_secs(20));
+    sassert_next(&mut interval, start + Duration::from_secs(30));
+    sassert_empty(&mut interval);
+}
+
+#[test]
+fn thread_timer() {
+    let timer = ThreadTimer::new();
+    let start = Instant::now();
+    let first = timer.delay(Duration::from_millis(50));
+    let second = timer.delay(Duration::from_millis(10));
+    second.join(first).wait().unwrap();
+    assert!(start.elapsed() >= Duration::from_millis(50));
+
+    let mut delay = timer.delay(Duration::from_secs(3600));
+    delay.reset(Instant::now() + Duration::from_millis(10));
+    delay.wait().unwrap();
+
+    let ticks = timer.interval(Duration::from_millis(10)).take(3).collect().wait().unwrap();
+    assert_eq!(ticks.len(), 3);
+    assert_eq!(ticks[2] - ticks[0], Duration::from_millis(20));
+}
+
+#[test]
+fn thread_timer_shutdown() {
+    let timer = ThreadTimer::new();
+    let delay = timer.delay(Duration::from_secs(3600));
+    drop(timer);
+    assert_eq!(delay.wait(), Err(TimerError::new()));
+}
+
+#[test]
+fn thread_timer_reset_in_place() {
+    fn pending(timer: &ThreadTimer) -> usize {
+        let debug = format!("{:?}", timer);
+        let start = debug.find("pending: ").unwrap() + "pending: ".len();
+        debug[start..].split(' ').next().unwrap().parse().unwrap()
+    }
+
+    let timer = ThreadTimer::new();
+    let start = Instant::now();
+
+    // Pushing the deadline back over and over, as `debounce` does.
+    let mut delay = timer.delay_until(start + Duration::from_secs(3600));
+    for i in 0..10_000 {
+        delay.reset(start + Duration::from_secs(3600 + i));
+    }
+    assert_eq!(pending(&timer), 1);
+
+    // Pulling it in leaves stale entries behind, but only so many.
+    for i in 0..10_000 {
+        delay.reset(start + Duration::from_secs(3600 * 4 - i));
+        assert!(pending(&timer) <= 3);
+    }
+
+    // Dropped delays don't stay around until their deadline either.
+    drop(delay);
+    for _ in 0..10_000 {
+        drop(timer.delay(Duration::from_secs(3600)));
+    }
+    assert!(pending(&timer) <= 1);
+
+    let mut delay = timer.delay(Duration::from_secs(3600));
+    delay.reset(Instant::now() + Duration::from_millis(10));
+    delay.reset(Instant::now() + Duration::from_millis(20));
+    delay.wait().unwrap();
+    assert!(start.elapsed() >= Duration::from_millis(20));
+}
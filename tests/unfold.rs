@@ -50,3 +50,44 @@ fn unfold_err1() {
     // An error was generated by the stream, it will then finish
     sassert_done(&mut stream);
 }
+
+#[test]
+fn try_unfold1() {
+    let mut stream = stream::try_unfold(0, |state| {
+        let res: Result<_, ()> = if state <= 2 {
+            Ok(Some((state * 2, state + 1)))
+        } else {
+            Ok(None)
+        };
+        delay_future(res)
+    });
+    sassert_empty(&mut stream);
+    sassert_next(&mut stream, 0);
+
+    sassert_empty(&mut stream);
+    sassert_next(&mut stream, 2);
+
+    sassert_empty(&mut stream);
+    sassert_next(&mut stream, 4);
+
+    // The future decides the stream is over
+    sassert_empty(&mut stream);
+    sassert_done(&mut stream);
+}
+
+#[test]
+fn try_unfold_err1() {
+    let mut stream = stream::try_unfold(0, |state| {
+        if state <= 1 {
+            Ok(Some((state * 2, state + 1)))
+        } else {
+            Err(-1)
+        }
+    });
+    sassert_next(&mut stream, 0);
+    sassert_next(&mut stream, 2);
+    sassert_err(&mut stream, -1);
+
+    // An error was generated by the stream, it will then finish
+    sassert_done(&mut stream);
+}
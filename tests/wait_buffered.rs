@@ -0,0 +1,80 @@
+extern crate futures;
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::Duration;
+
+use futures::prelude::*;
+use futures::future::{Executor, ExecuteError};
+use futures::stream;
+
+struct Threads;
+
+impl<F> Executor<F> for Threads
+    where F: Future<Item = (), Error = ()> + Send + 'static,
+{
+    fn execute(&self, f: F) -> Result<(), ExecuteError<F>> {
+        thread::spawn(move || f.wait());
+        Ok(())
+    }
+}
+
+#[test]
+fn yields_items_and_errors() {
+    let s = stream::iter_result(vec![Ok(1), Err(2), Ok(3)]);
+    let items = s.wait_buffered(1, &Threads).unwrap().collect::<Vec<_>>();
+    assert_eq!(items, [Ok(1), Err(2), Ok(3)]);
+}
+
+#[test]
+fn runs_ahead_of_consumer() {
+    let produced = Arc::new(AtomicUsize::new(0));
+    let produced2 = produced.clone();
+    let s = stream::iter_ok::<_, ()>(0..100).inspect(move |_| {
+        produced2.fetch_add(1, Ordering::SeqCst);
+    });
+    let mut items = s.wait_buffered(4, &Threads).unwrap();
+    assert_eq!(items.next(), Some(Ok(0)));
+
+    // The items buffered in the channel, plus one waiting to be sent.
+    thread::sleep(Duration::from_millis(100));
+    let n = produced.load(Ordering::SeqCst);
+    assert!(n > 1 && n <= 6, "produced {}", n);
+
+    assert_eq!(items.collect::<Result<Vec<_>, _>>(), Ok((1..100).collect()));
+}
+
+#[test]
+fn drop_stops_stream() {
+    struct Dropped(Arc<AtomicUsize>);
+
+    impl Drop for Dropped {
+        fn drop(&mut self) {
+            self.0.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    let dropped = Arc::new(AtomicUsize::new(0));
+    let guard = Dropped(dropped.clone());
+    let s = stream::repeat::<_, ()>(1).inspect(move |_| {
+        let _ = &guard;
+    });
+    let mut items = s.wait_buffered(2, &Threads).unwrap();
+    assert_eq!(items.next(), Some(Ok(1)));
+    drop(items);
+
+    for _ in 0..100 {
+        if dropped.load(Ordering::SeqCst) == 1 {
+            return
+        }
+        thread::sleep(Duration::from_millis(10));
+    }
+    panic!("stream wasn't dropped");
+}
+
+#[test]
+#[should_panic]
+fn zero_buffer() {
+    drop(stream::empty::<(), ()>().wait_buffered(0, &Threads));
+}